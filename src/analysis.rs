@@ -0,0 +1,2329 @@
+//! Client-side analysis of route and trip geometry
+//!
+//! These helpers operate on fetched track points and never talk to the API,
+//! so they can be used to recompute statistics with different assumptions
+//! than the ones the server applied.
+
+use crate::spatial::SegmentIndex;
+use crate::{
+    AthleteProfile, CoursePoint, CueType, ElevationProvider, LatLng, Result, Route, TrackPoint,
+    Trip, TripTrackPoint,
+};
+use chrono::{DateTime, Utc};
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+
+/// A sample along a track carrying distance and elevation data
+///
+/// Implemented for both route [`TrackPoint`]s and trip [`TripTrackPoint`]s so
+/// the analysis functions work on either.
+pub trait TrackSample {
+    /// Distance along the track in meters
+    fn distance(&self) -> Option<f64>;
+
+    /// Elevation in meters
+    fn elevation(&self) -> Option<f64>;
+
+    /// Replace the elevation of this sample
+    fn set_elevation(&mut self, elevation: Option<f64>);
+
+    /// Position of this sample, if known
+    fn coord(&self) -> Option<LatLng> {
+        None
+    }
+}
+
+impl TrackSample for TrackPoint {
+    fn distance(&self) -> Option<f64> {
+        self.d
+    }
+
+    fn elevation(&self) -> Option<f64> {
+        self.e
+    }
+
+    fn set_elevation(&mut self, elevation: Option<f64>) {
+        self.e = elevation;
+    }
+    fn coord(&self) -> Option<LatLng> {
+        TrackPoint::coord(self)
+    }
+}
+
+impl TrackSample for TripTrackPoint {
+    fn distance(&self) -> Option<f64> {
+        self.d
+    }
+
+    fn elevation(&self) -> Option<f64> {
+        self.e
+    }
+
+    fn set_elevation(&mut self, elevation: Option<f64>) {
+        self.e = elevation;
+    }
+    fn coord(&self) -> Option<LatLng> {
+        TripTrackPoint::coord(self)
+    }
+}
+
+/// Method used by [`smooth_elevation`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmoothingMethod {
+    /// Replace each elevation with the median of the surrounding window
+    ///
+    /// Robust against isolated outliers, at the cost of flattening sharp
+    /// summits slightly.
+    RollingMedian {
+        /// Number of samples in the window (rounded up to an odd number)
+        window: usize,
+    },
+
+    /// Savitzky-Golay filter fitting a quadratic over the surrounding window
+    ///
+    /// Preserves the shape of climbs better than a median, but does not
+    /// remove spikes; combine it with [`remove_elevation_spikes`] for noisy
+    /// barometric data. Samples are assumed to be roughly evenly spaced.
+    SavitzkyGolay {
+        /// Number of samples in the window (rounded up to an odd number)
+        window: usize,
+    },
+}
+
+/// Smooth the elevation channel of a track in place
+///
+/// Samples without an elevation are skipped and left untouched. Near the
+/// ends of the track the window shrinks so that it stays centred.
+///
+/// # Example
+///
+/// ```rust
+/// use ridewithgps_client::analysis::{self, SmoothingMethod};
+/// use ridewithgps_client::TrackPoint;
+///
+/// let mut points: Vec<TrackPoint> = serde_json::from_str(
+///     r#"[{"d": 0, "e": 10}, {"d": 10, "e": 30}, {"d": 20, "e": 11}, {"d": 30, "e": 12}]"#,
+/// ).unwrap();
+///
+/// analysis::smooth_elevation(&mut points, SmoothingMethod::RollingMedian { window: 3 });
+/// assert_eq!(points[1].e, Some(11.0));
+/// ```
+pub fn smooth_elevation<P: TrackSample>(points: &mut [P], method: SmoothingMethod) {
+    let indices: Vec<usize> = (0..points.len())
+        .filter(|&i| points[i].elevation().is_some())
+        .collect();
+    let values: Vec<f64> = indices
+        .iter()
+        .filter_map(|&i| points[i].elevation())
+        .collect();
+
+    let smoothed = match method {
+        SmoothingMethod::RollingMedian { window } => rolling_median(&values, window / 2),
+        SmoothingMethod::SavitzkyGolay { window } => savitzky_golay(&values, window / 2),
+    };
+
+    for (&i, value) in indices.iter().zip(smoothed) {
+        points[i].set_elevation(Some(value));
+    }
+}
+
+fn rolling_median(values: &[f64], half_window: usize) -> Vec<f64> {
+    (0..values.len())
+        .map(|i| {
+            let half = half_window.min(i).min(values.len() - 1 - i);
+            let mut window = values[i - half..=i + half].to_vec();
+            window.sort_by(f64::total_cmp);
+            window[half]
+        })
+        .collect()
+}
+
+fn savitzky_golay(values: &[f64], half_window: usize) -> Vec<f64> {
+    (0..values.len())
+        .map(|i| {
+            let m = half_window.min(i).min(values.len() - 1 - i) as f64;
+            if m < 2.0 {
+                // A quadratic through three points reproduces them exactly
+                return values[i];
+            }
+            let norm = (2.0 * m + 1.0) * (4.0 * m * m + 4.0 * m - 3.0);
+            let base = 3.0 * (3.0 * m * m + 3.0 * m - 1.0);
+            let m = m as isize;
+            (-m..=m)
+                .map(|j| {
+                    let weight = (base - 15.0 * (j * j) as f64) / norm;
+                    weight * values[(i as isize + j) as usize]
+                })
+                .sum()
+        })
+        .collect()
+}
+
+/// Find samples whose elevation is an implausible spike
+///
+/// A sample is considered a spike when the gradient both into and out of it
+/// exceeds `max_gradient` (as a fraction, so `0.3` is 30%) in opposite
+/// directions, i.e. the elevation jumps away and immediately comes back.
+/// Samples without an elevation are ignored.
+///
+/// Returns the indices of the offending samples.
+pub fn find_elevation_spikes<P: TrackSample>(points: &[P], max_gradient: f64) -> Vec<usize> {
+    let samples: Vec<(usize, f64, f64)> = points
+        .iter()
+        .enumerate()
+        .filter_map(|(i, p)| Some((i, p.distance().unwrap_or(i as f64), p.elevation()?)))
+        .collect();
+
+    samples
+        .windows(3)
+        .filter_map(|w| {
+            let (_, d0, e0) = w[0];
+            let (i, d1, e1) = w[1];
+            let (_, d2, e2) = w[2];
+            let rise_in = gradient(d0, e0, d1, e1);
+            let rise_out = gradient(d1, e1, d2, e2);
+            let is_spike = rise_in.abs() > max_gradient
+                && rise_out.abs() > max_gradient
+                && rise_in.signum() != rise_out.signum();
+            is_spike.then_some(i)
+        })
+        .collect()
+}
+
+/// Drop implausible elevation spikes and interpolate over them
+///
+/// Spikes are detected with [`find_elevation_spikes`]; their elevation is
+/// replaced by linear interpolation (by distance) between the nearest
+/// non-spike neighbours.
+///
+/// Returns the indices of the samples that were changed.
+pub fn remove_elevation_spikes<P: TrackSample>(points: &mut [P], max_gradient: f64) -> Vec<usize> {
+    let spikes = find_elevation_spikes(points, max_gradient);
+    for &i in &spikes {
+        points[i].set_elevation(None);
+    }
+
+    for &i in &spikes {
+        let before = (0..i).rev().find(|&j| points[j].elevation().is_some());
+        let after = (i + 1..points.len()).find(|&j| points[j].elevation().is_some());
+        let position = |j: usize| points[j].distance().unwrap_or(j as f64);
+        let value = match (before, after) {
+            (Some(b), Some(a)) => {
+                let (db, eb) = (position(b), points[b].elevation().unwrap_or_default());
+                let (da, ea) = (position(a), points[a].elevation().unwrap_or_default());
+                if da > db {
+                    Some(eb + (ea - eb) * (position(i) - db) / (da - db))
+                } else {
+                    Some(eb)
+                }
+            }
+            (Some(j), None) | (None, Some(j)) => points[j].elevation(),
+            (None, None) => None,
+        };
+        points[i].set_elevation(value);
+    }
+
+    spikes
+}
+
+/// How [`correct_elevation`] corrects barometric elevations
+#[derive(Clone, Copy)]
+pub enum ElevationCorrection<'a> {
+    /// Shift the whole track so that it starts at a known elevation in
+    /// meters
+    ///
+    /// Fixes a barometer that was not calibrated before the start, but not
+    /// drift during the activity.
+    StartElevation(f64),
+
+    /// Follow the elevations of a digital elevation model over longer
+    /// stretches, keeping the barometer's detail over shorter ones
+    ///
+    /// The difference between the model and the barometer is smoothed with
+    /// a rolling median and added to every sample, which removes both the
+    /// offset and slow drift from weather changes.
+    Dem {
+        /// Source of the model elevations
+        provider: &'a dyn ElevationProvider,
+
+        /// Number of samples over which the difference is smoothed
+        window: usize,
+    },
+}
+
+impl std::fmt::Debug for ElevationCorrection<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::StartElevation(elevation) => {
+                f.debug_tuple("StartElevation").field(elevation).finish()
+            }
+            Self::Dem { window, .. } => f.debug_struct("Dem").field("window", window).finish(),
+        }
+    }
+}
+
+/// Correct drift-prone barometric elevations in place
+///
+/// Samples without an elevation are left untouched. With
+/// [`ElevationCorrection::Dem`], samples without a position or without
+/// model data are corrected by the same amount as the nearest sample before
+/// them that has one. Fails without changing any sample if the provider does
+/// not return one entry per location.
+///
+/// # Example
+///
+/// ```rust
+/// use ridewithgps_client::analysis::{self, ElevationCorrection};
+/// use ridewithgps_client::TripTrackPoint;
+///
+/// let mut points: Vec<TripTrackPoint> =
+///     serde_json::from_str(r#"[{"e": 112}, {"e": 140}, {"e": 125}]"#).unwrap();
+///
+/// analysis::correct_elevation(&mut points, ElevationCorrection::StartElevation(12.0)).unwrap();
+/// assert_eq!(points[1].e, Some(40.0));
+/// ```
+pub fn correct_elevation<P: TrackSample>(
+    points: &mut [P],
+    correction: ElevationCorrection,
+) -> Result<()> {
+    let offsets: Vec<(usize, f64)> = match correction {
+        ElevationCorrection::StartElevation(start) => {
+            let Some(first) = points.iter().find_map(|p| p.elevation()) else {
+                return Ok(());
+            };
+            vec![(0, start - first)]
+        }
+        ElevationCorrection::Dem { provider, window } => {
+            let (indexes, locations): (Vec<usize>, Vec<LatLng>) = points
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| p.elevation().is_some())
+                .filter_map(|(i, p)| Some((i, p.coord()?)))
+                .unzip();
+            if locations.is_empty() {
+                return Ok(());
+            }
+            let model = provider.elevations(&locations)?;
+            if model.len() != locations.len() {
+                return Err(crate::Error::ApiError(format!(
+                    "Expected {} elevations, got {}",
+                    locations.len(),
+                    model.len()
+                )));
+            }
+            let (indexes, differences): (Vec<usize>, Vec<f64>) = indexes
+                .into_iter()
+                .zip(model)
+                .filter_map(|(i, m)| Some((i, m? - points[i].elevation()?)))
+                .unzip();
+            indexes
+                .into_iter()
+                .zip(rolling_median(&differences, window / 2))
+                .collect()
+        }
+    };
+    let Some(&(_, first)) = offsets.first() else {
+        return Ok(());
+    };
+
+    let mut next = offsets.iter().peekable();
+    let mut offset = first;
+    for (i, point) in points.iter_mut().enumerate() {
+        while let Some(&(_, o)) = next.next_if(|(j, _)| *j <= i) {
+            offset = o;
+        }
+        if let Some(elevation) = point.elevation() {
+            point.set_elevation(Some(elevation + offset));
+        }
+    }
+    Ok(())
+}
+
+/// A kind of GPS error found by [`clean_track`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyKind {
+    /// Same timestamp as the point before it
+    DuplicateTimestamp,
+
+    /// Far away from both neighbours, which are close to each other
+    Teleport,
+
+    /// Reached faster than the maximum speed from the last good point
+    ImpossibleSpeed,
+}
+
+/// What [`clean_track`] does with anomalous points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CleaningAction {
+    /// Exclude them from metrics by setting their `k` flag
+    #[default]
+    Flag,
+
+    /// Remove them from the track
+    Remove,
+}
+
+/// Thresholds and action for [`clean_track`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CleaningOptions {
+    /// Speed in m/s above which a point is considered impossible to reach
+    pub max_speed: f64,
+
+    /// Distance in meters a point must be from both neighbours to count as
+    /// a teleport
+    pub teleport_distance: f64,
+
+    /// What to do with the anomalous points
+    pub action: CleaningAction,
+}
+
+impl Default for CleaningOptions {
+    fn default() -> Self {
+        Self {
+            max_speed: 30.0,
+            teleport_distance: 200.0,
+            action: CleaningAction::Flag,
+        }
+    }
+}
+
+/// A point found to be anomalous by [`clean_track`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Anomaly {
+    /// Index of the point in the track before cleaning
+    pub index: usize,
+
+    /// What was wrong with it
+    pub kind: AnomalyKind,
+}
+
+/// Number of consecutive points too fast to reach from the last good point,
+/// but plausible from one to the next, after which [`clean_track`] accepts
+/// them as the track carrying on
+const RESEED_POINTS: usize = 5;
+
+/// Find GPS errors in a trip's track points, and flag or remove them
+///
+/// Points sharing the timestamp of the point before them, points far from
+/// both neighbours while those are close together, and points that could
+/// only be reached from the last good point faster than
+/// [`max_speed`](CleaningOptions::max_speed) are anomalous. The first point
+/// has no good point before it, so it is anomalous if it cannot reach the
+/// next point while the two after it agree. After a gap in the recording,
+/// such as a train ride with the GPS off, the track is picked up again once
+/// five points in a row agree with each other. Points without a timestamp
+/// or position are left alone, and distances along the track are not
+/// recomputed.
+///
+/// Returns the anomalies found, in track order.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ridewithgps_client::analysis::{self, CleaningAction, CleaningOptions};
+/// use ridewithgps_client::RideWithGpsClient;
+///
+/// let client = RideWithGpsClient::new(
+///     "https://ridewithgps.com",
+///     "your-api-key",
+///     Some("your-auth-token")
+/// );
+///
+/// let mut trip = client.get_trip(12345).unwrap();
+/// let options = CleaningOptions {
+///     action: CleaningAction::Remove,
+///     ..Default::default()
+/// };
+/// let anomalies = analysis::clean_track(trip.track_points.as_mut().unwrap(), options);
+/// println!("Removed {} points", anomalies.len());
+/// ```
+pub fn clean_track(points: &mut Vec<TripTrackPoint>, options: CleaningOptions) -> Vec<Anomaly> {
+    let located: Vec<(usize, i64, LatLng)> = points
+        .iter()
+        .enumerate()
+        .filter_map(|(i, p)| Some((i, p.t?, p.coord()?)))
+        .collect();
+
+    let plausible = |(t0, a): (i64, LatLng), (t1, b): (i64, LatLng)| {
+        let elapsed = (t1 - t0) as f64;
+        elapsed > 0.0 && a.distance_to(&b) / elapsed <= options.max_speed
+    };
+
+    let mut anomalies: Vec<Anomaly> = Vec::new();
+    let mut last_good: Option<(i64, LatLng)> = None;
+    // Points rejected for their speed in a row that agree with each other,
+    // with their position in `anomalies`
+    let mut rejected: Vec<(usize, i64, LatLng)> = Vec::new();
+    for (n, &(index, t, coord)) in located.iter().enumerate() {
+        let previous = n.checked_sub(1).map(|m| located[m]);
+        let next = located.get(n + 1);
+        let kind = if previous.is_some_and(|(_, pt, _)| pt == t) {
+            Some(AnomalyKind::DuplicateTimestamp)
+        } else if let (Some((_, _, a)), Some((_, _, b))) = (previous, next) {
+            let far = |x: &LatLng| x.distance_to(&coord) > options.teleport_distance;
+            (far(&a) && far(b) && a.distance_to(b) <= options.teleport_distance)
+                .then_some(AnomalyKind::Teleport)
+        } else {
+            None
+        };
+        let kind = kind.or_else(|| {
+            let too_fast = match last_good {
+                Some(good) => !plausible(good, (t, coord)),
+                None => {
+                    let mut after = located[n + 1..].iter().map(|&(_, t, c)| (t, c));
+                    let (a, b) = (after.next()?, after.next()?);
+                    !plausible((t, coord), a) && plausible(a, b)
+                }
+            };
+            too_fast.then_some(AnomalyKind::ImpossibleSpeed)
+        });
+        match kind {
+            None => {
+                last_good = Some((t, coord));
+                rejected.clear();
+            }
+            Some(AnomalyKind::ImpossibleSpeed) => {
+                if rejected
+                    .last()
+                    .is_some_and(|&(_, rt, rc)| !plausible((rt, rc), (t, coord)))
+                {
+                    rejected.clear();
+                }
+                rejected.push((anomalies.len(), t, coord));
+                anomalies.push(Anomaly {
+                    index,
+                    kind: AnomalyKind::ImpossibleSpeed,
+                });
+                if rejected.len() >= RESEED_POINTS {
+                    let mut position = 0;
+                    anomalies.retain(|_| {
+                        let keep = !rejected.iter().any(|&(p, _, _)| p == position);
+                        position += 1;
+                        keep
+                    });
+                    last_good = Some((t, coord));
+                    rejected.clear();
+                }
+            }
+            Some(kind) => anomalies.push(Anomaly { index, kind }),
+        }
+    }
+
+    match options.action {
+        CleaningAction::Flag => {
+            for anomaly in &anomalies {
+                points[anomaly.index].k = Some(true);
+            }
+        }
+        CleaningAction::Remove => {
+            let mut anomalous = anomalies.iter().map(|a| a.index).peekable();
+            let mut i = 0;
+            points.retain(|_| {
+                let keep = anomalous.next_if_eq(&i).is_none();
+                i += 1;
+                keep
+            });
+        }
+    }
+    anomalies
+}
+
+/// Total elevation gain and loss of a track, in meters
+///
+/// Samples without an elevation are skipped. Returns `(gain, loss)` where
+/// both values are non-negative.
+pub fn elevation_gain_loss<P: TrackSample>(points: &[P]) -> (f64, f64) {
+    let mut gain = 0.0;
+    let mut loss = 0.0;
+    let mut previous: Option<f64> = None;
+
+    for elevation in points.iter().filter_map(|p| p.elevation()) {
+        if let Some(prev) = previous {
+            let delta = elevation - prev;
+            if delta > 0.0 {
+                gain += delta;
+            } else {
+                loss -= delta;
+            }
+        }
+        previous = Some(elevation);
+    }
+
+    (gain, loss)
+}
+
+/// Distance and elevation along a track, for statistics on its gradients
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ridewithgps_client::analysis::ElevationProfile;
+/// use ridewithgps_client::RideWithGpsClient;
+///
+/// let client = RideWithGpsClient::new(
+///     "https://ridewithgps.com",
+///     "your-api-key",
+///     None
+/// );
+///
+/// let route = client.get_route(12345).unwrap();
+/// let profile = ElevationProfile::new(route.track_points.as_deref().unwrap_or_default());
+/// for bucket in profile.gradient_histogram(2.0) {
+///     println!(
+///         "{:+.0} to {:+.0} %: {:.1} km",
+///         bucket.min,
+///         bucket.max,
+///         bucket.distance / 1000.0
+///     );
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElevationProfile {
+    /// Distance and elevation of each sample that has both, in meters
+    samples: Vec<(f64, f64)>,
+}
+
+/// Distance covered within a band of gradients, from
+/// [`ElevationProfile::gradient_histogram`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradientBucket {
+    /// Lowest gradient of the band in percent, included
+    pub min: f64,
+
+    /// Highest gradient of the band in percent, excluded
+    pub max: f64,
+
+    /// Distance in meters
+    pub distance: f64,
+}
+
+impl ElevationProfile {
+    /// The profile of a track; samples without a distance or an elevation
+    /// are skipped
+    pub fn new<P: TrackSample>(points: &[P]) -> Self {
+        Self {
+            samples: points
+                .iter()
+                .filter_map(|p| Some((p.distance()?, p.elevation()?)))
+                .collect(),
+        }
+    }
+
+    /// Distance spent in each band of `bucket_size` percent of gradient
+    ///
+    /// Gradients are averaged over stretches of at least 100 m, so noise in
+    /// the elevation data does not show up as short steep ramps. A shorter
+    /// stretch at the end counts towards the band of the stretch before it,
+    /// so the distances add up to the length of the profile.
+    ///
+    /// Every band is `bucket_size` percent wide, starting at a multiple of
+    /// it. Bands run from the lowest gradient to the highest, with empty
+    /// bands in between included; descents have negative gradients.
+    pub fn gradient_histogram(&self, bucket_size: f64) -> Vec<GradientBucket> {
+        if bucket_size <= 0.0 {
+            return Vec::new();
+        }
+        let mut distances: BTreeMap<i64, f64> = BTreeMap::new();
+        let bucket_of =
+            |d0, e0, d, e| (gradient(d0, e0, d, e) * 100.0 / bucket_size).floor() as i64;
+
+        let mut start = self.samples.first().copied();
+        let mut last_bucket = None;
+        for &(d, e) in self.samples.iter().skip(1) {
+            let Some((d0, e0)) = start else { break };
+            let run = d - d0;
+            if run < GRADIENT_SEGMENT {
+                continue;
+            }
+            let bucket = bucket_of(d0, e0, d, e);
+            *distances.entry(bucket).or_default() += run;
+            last_bucket = Some(bucket);
+            start = Some((d, e));
+        }
+        if let (Some((d0, e0)), Some(&(d, e))) = (start, self.samples.last()) {
+            if d > d0 {
+                let bucket = last_bucket.unwrap_or_else(|| bucket_of(d0, e0, d, e));
+                *distances.entry(bucket).or_default() += d - d0;
+            }
+        }
+
+        let (Some(&first), Some(&last)) = (distances.keys().next(), distances.keys().last()) else {
+            return Vec::new();
+        };
+        (first..=last)
+            .map(|bucket| GradientBucket {
+                min: bucket as f64 * bucket_size,
+                max: (bucket + 1) as f64 * bucket_size,
+                distance: distances.get(&bucket).copied().unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+/// Thresholds used to decide whether a rider was moving
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MovingCriteria {
+    /// Minimum speed in m/s below which the rider is considered stopped
+    pub min_speed: f64,
+
+    /// Gap between samples in seconds beyond which the time is counted as
+    /// stopped (e.g. because the recording device auto-paused)
+    pub pause_gap: f64,
+}
+
+impl Default for MovingCriteria {
+    fn default() -> Self {
+        Self {
+            min_speed: 1.0,
+            pause_gap: 30.0,
+        }
+    }
+}
+
+/// A stretch of a trip during which the rider was stopped, such as a coffee
+/// stop or a traffic light
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pause {
+    /// Unix timestamp at which the rider stopped
+    pub start: i64,
+
+    /// Unix timestamp at which the rider moved on
+    pub end: i64,
+
+    /// Where the rider stopped, if the track points have positions
+    pub location: Option<LatLng>,
+}
+
+impl Pause {
+    /// Length of the pause in seconds
+    pub fn duration(&self) -> f64 {
+        (self.end - self.start) as f64
+    }
+}
+
+/// Moving-time statistics recomputed from track points
+#[derive(Debug, Clone, PartialEq)]
+pub struct MovingStats {
+    /// Time spent moving in seconds
+    pub moving_time: f64,
+
+    /// Time spent stopped in seconds
+    pub stopped_time: f64,
+
+    /// Distance covered while moving in meters
+    pub moving_distance: f64,
+
+    /// Average moving speed in m/s, if any time was spent moving
+    pub avg_moving_speed: Option<f64>,
+
+    /// The stretches of stopped time, in order
+    pub pauses: Vec<Pause>,
+}
+
+/// Recompute moving time for a trip using custom thresholds
+///
+/// Each interval between consecutive track points that both carry a
+/// timestamp and a distance is classified as moving or stopped according to
+/// `criteria`; consecutive stopped intervals make up a [`Pause`]. Returns
+/// `None` if the trip has no usable track points, e.g. because it was
+/// fetched from a listing rather than with
+/// [`get_trip`](crate::RideWithGpsClient::get_trip).
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ridewithgps_client::analysis::{self, MovingCriteria};
+/// use ridewithgps_client::RideWithGpsClient;
+///
+/// let client = RideWithGpsClient::new(
+///     "https://ridewithgps.com",
+///     "your-api-key",
+///     Some("your-auth-token")
+/// );
+///
+/// let trip = client.get_trip(12345).unwrap();
+/// let criteria = MovingCriteria {
+///     min_speed: 0.5,
+///     pause_gap: 60.0,
+/// };
+/// if let Some(stats) = analysis::moving_time(&trip, criteria) {
+///     println!("Moving for {:.0} s", stats.moving_time);
+/// }
+/// ```
+pub fn moving_time(trip: &Trip, criteria: MovingCriteria) -> Option<MovingStats> {
+    let samples: Vec<(i64, f64, Option<LatLng>)> = trip
+        .track_points
+        .as_ref()?
+        .iter()
+        .filter_map(|p| Some((p.t?, p.d?, p.coord())))
+        .collect();
+    if samples.len() < 2 {
+        return None;
+    }
+
+    let mut stats = MovingStats {
+        moving_time: 0.0,
+        stopped_time: 0.0,
+        moving_distance: 0.0,
+        avg_moving_speed: None,
+        pauses: Vec::new(),
+    };
+
+    let mut stopped = false;
+    for pair in samples.windows(2) {
+        let (t0, d0, location) = pair[0];
+        let (t1, d1, _) = pair[1];
+        let elapsed = (t1 - t0) as f64;
+        if elapsed <= 0.0 {
+            continue;
+        }
+        let covered = (d1 - d0).max(0.0);
+        if elapsed > criteria.pause_gap || covered / elapsed < criteria.min_speed {
+            stats.stopped_time += elapsed;
+            match stats.pauses.last_mut() {
+                Some(pause) if stopped => pause.end = t1,
+                _ => stats.pauses.push(Pause {
+                    start: t0,
+                    end: t1,
+                    location,
+                }),
+            }
+            stopped = true;
+        } else {
+            stats.moving_time += elapsed;
+            stats.moving_distance += covered;
+            stopped = false;
+        }
+    }
+
+    if stats.moving_time > 0.0 {
+        stats.avg_moving_speed = Some(stats.moving_distance / stats.moving_time);
+    }
+
+    Some(stats)
+}
+
+/// Find the stretches of a trip during which the rider was stopped
+///
+/// Uses the same classification as [`moving_time`], and returns the
+/// [`pauses`](MovingStats::pauses) it finds; empty if the trip has no usable
+/// track points.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ridewithgps_client::analysis::{self, MovingCriteria};
+/// use ridewithgps_client::RideWithGpsClient;
+///
+/// let client = RideWithGpsClient::new(
+///     "https://ridewithgps.com",
+///     "your-api-key",
+///     Some("your-auth-token")
+/// );
+///
+/// let trip = client.get_trip(12345).unwrap();
+/// for pause in analysis::detect_pauses(&trip, MovingCriteria::default()) {
+///     if pause.duration() >= 300.0 {
+///         println!("Stopped for {:.0} min at {:?}", pause.duration() / 60.0, pause.location);
+///     }
+/// }
+/// ```
+pub fn detect_pauses(trip: &Trip, criteria: MovingCriteria) -> Vec<Pause> {
+    moving_time(trip, criteria)
+        .map(|stats| stats.pauses)
+        .unwrap_or_default()
+}
+
+/// Gap between samples in seconds beyond which the rider is assumed to have
+/// paused, for the power and heart rate statistics
+const SAMPLE_PAUSE_GAP: i64 = 30;
+
+/// Fraction of metabolic energy a rider turns into power at the pedals
+const GROSS_EFFICIENCY: f64 = 0.24;
+
+/// Kilojoules per kilocalorie
+const KJ_PER_KCAL: f64 = 4.184;
+
+/// Timed intervals of a trip as `(duration in seconds, point at the start)`,
+/// leaving out pauses and points excluded from metrics
+fn timed_intervals(trip: &Trip) -> impl Iterator<Item = (i64, &TripTrackPoint)> {
+    let points: Vec<&TripTrackPoint> = trip
+        .track_points
+        .iter()
+        .flatten()
+        .filter(|p| p.t.is_some() && p.k != Some(true))
+        .collect();
+    (1..points.len()).filter_map(move |i| {
+        let elapsed = points[i].t? - points[i - 1].t?;
+        (elapsed > 0 && elapsed <= SAMPLE_PAUSE_GAP).then_some((elapsed, points[i - 1]))
+    })
+}
+
+/// Training load of a trip computed from its power data
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrainingLoad {
+    /// Normalized power in watts: the steady power with the same
+    /// physiological cost as the variable power actually ridden
+    pub normalized_power: f64,
+
+    /// Normalized power as a fraction of FTP
+    pub intensity_factor: f64,
+
+    /// Training stress score; an hour at FTP scores 100
+    pub tss: f64,
+}
+
+/// Compute normalized power, intensity factor and TSS for a trip
+///
+/// Uses the power readings of the track points and the athlete's FTP.
+/// Returns `None` without an FTP, or with less than 30 seconds of power
+/// data.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ridewithgps_client::analysis;
+/// use ridewithgps_client::{AthleteProfile, RideWithGpsClient};
+///
+/// let client = RideWithGpsClient::new(
+///     "https://ridewithgps.com",
+///     "your-api-key",
+///     Some("your-auth-token")
+/// );
+///
+/// let trip = client.get_trip(12345).unwrap();
+/// let profile = AthleteProfile::new().ftp(250.0);
+/// if let Some(load) = analysis::training_load(&trip, &profile) {
+///     println!("NP {:.0} W, IF {:.2}, TSS {:.0}", load.normalized_power, load.intensity_factor, load.tss);
+/// }
+/// ```
+pub fn training_load(trip: &Trip, profile: &AthleteProfile) -> Option<TrainingLoad> {
+    let ftp = profile.ftp.filter(|&ftp| ftp > 0.0)?;
+
+    // Power resampled to one value per second
+    let mut power = Vec::new();
+    for (elapsed, point) in timed_intervals(trip) {
+        if let Some(watts) = point.p {
+            power.extend(std::iter::repeat_n(watts, elapsed as usize));
+        }
+    }
+    if power.len() < 30 {
+        return None;
+    }
+
+    let rolling: Vec<f64> = power
+        .windows(30)
+        .map(|w| w.iter().sum::<f64>() / 30.0)
+        .collect();
+    let normalized_power =
+        (rolling.iter().map(|p| p.powi(4)).sum::<f64>() / rolling.len() as f64).powf(0.25);
+    let intensity_factor = normalized_power / ftp;
+    let hours = power.len() as f64 / 3600.0;
+    Some(TrainingLoad {
+        normalized_power,
+        intensity_factor,
+        tss: hours * intensity_factor * intensity_factor * 100.0,
+    })
+}
+
+/// Estimate the calories burned on a trip, in kilocalories
+///
+/// With power data, the mechanical work is converted assuming a gross
+/// efficiency of 24%, which makes a kilojoule of work about one kilocalorie.
+/// Otherwise the estimate uses the athlete's weight and the metabolic
+/// equivalent of cycling at the trip's average speed for its moving time.
+/// Returns `None` if neither is available.
+pub fn estimate_calories(trip: &Trip, profile: &AthleteProfile) -> Option<f64> {
+    let work: f64 = timed_intervals(trip)
+        .filter_map(|(elapsed, point)| Some(point.p? * elapsed as f64))
+        .sum();
+    if work > 0.0 {
+        return Some(work / 1000.0 / GROSS_EFFICIENCY / KJ_PER_KCAL);
+    }
+
+    let weight = profile.weight?;
+    let hours = trip.moving_time.or(trip.duration)? / 3600.0;
+    let kmh = trip.avg_speed? * 3.6;
+    let met = match kmh {
+        s if s < 16.0 => 4.0,
+        s if s < 19.0 => 6.8,
+        s if s < 22.0 => 8.0,
+        s if s < 25.0 => 10.0,
+        s if s < 30.0 => 12.0,
+        _ => 15.8,
+    };
+    Some(met * weight * hours)
+}
+
+/// Time spent in each training zone, in seconds
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ZoneTimes {
+    /// Seconds in each heart rate zone, lowest zone first
+    pub heart_rate: Vec<f64>,
+
+    /// Seconds in each power zone, lowest zone first
+    pub power: Vec<f64>,
+}
+
+/// Compute the time a trip spent in each heart rate and power zone
+///
+/// The zones come from [`AthleteProfile::heart_rate_zones`] and
+/// [`AthleteProfile::power_zones`]; a kind of zone the profile cannot
+/// provide is left empty. Each interval between track points counts toward
+/// the zone of the reading at its start.
+pub fn time_in_zones(trip: &Trip, profile: &AthleteProfile) -> ZoneTimes {
+    let heart_rate = profile.heart_rate_zones();
+    let power = profile.power_zones();
+    let mut times = ZoneTimes {
+        heart_rate: vec![0.0; heart_rate.as_ref().map_or(0, |z| z.len() + 1)],
+        power: vec![0.0; power.as_ref().map_or(0, |z| z.len() + 1)],
+    };
+
+    let zone = |bounds: &[f64], value: f64| bounds.iter().filter(|&&b| value >= b).count();
+    for (elapsed, point) in timed_intervals(trip) {
+        if let (Some(bounds), Some(bpm)) = (&heart_rate, point.h) {
+            times.heart_rate[zone(bounds, bpm)] += elapsed as f64;
+        }
+        if let (Some(bounds), Some(watts)) = (&power, point.p) {
+            times.power[zone(bounds, watts)] += elapsed as f64;
+        }
+    }
+    times
+}
+
+/// Coarse difficulty category derived from a [`DifficultyEstimate`] score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DifficultyRating {
+    /// Score below 30
+    Easy,
+    /// Score from 30 up to 60
+    Moderate,
+    /// Score from 60 up to 100
+    Hard,
+    /// Score from 100 up to 160
+    VeryHard,
+    /// Score of 160 or more
+    Extreme,
+}
+
+/// Difficulty score computed by [`estimate_difficulty`]
+///
+/// The score is expressed in "effort kilometers": the distance of flat,
+/// paved riding that takes a comparable effort.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DifficultyEstimate {
+    /// Overall score, `(distance + climbing + steepness) * unpaved_factor`
+    pub score: f64,
+
+    /// Route distance in kilometers
+    pub distance: f64,
+
+    /// Elevation gain converted to effort kilometers (1 km per 25 m)
+    pub climbing: f64,
+
+    /// Extra effort for steep sections, in effort kilometers
+    pub steepness: f64,
+
+    /// Multiplier for unpaved surface, from 1.0 (fully paved) to 1.5
+    pub unpaved_factor: f64,
+}
+
+impl DifficultyEstimate {
+    /// The category the score falls into
+    pub fn rating(&self) -> DifficultyRating {
+        match self.score {
+            s if s < 30.0 => DifficultyRating::Easy,
+            s if s < 60.0 => DifficultyRating::Moderate,
+            s if s < 100.0 => DifficultyRating::Hard,
+            s if s < 160.0 => DifficultyRating::VeryHard,
+            _ => DifficultyRating::Extreme,
+        }
+    }
+}
+
+/// Length over which gradients are averaged when scoring steepness, in meters
+const GRADIENT_SEGMENT: f64 = 100.0;
+
+/// Estimate how hard a route is from its distance, climbing and surface
+///
+/// The score is computed as
+///
+/// ```text
+/// score = (distance_km + gain_m / 25 + steepness) * (1 + 0.5 * unpaved_pct / 100)
+/// ```
+///
+/// where `steepness` adds, for every kilometer climbed at an average
+/// gradient (over 100 m segments) of 5–8 %, 1 km; of 8–12 %, 3 km; and
+/// above 12 %, 6 km. Distance falls back to the last track point and gain to
+/// [`elevation_gain_loss`] when the route does not report them; steepness is
+/// zero without track points and unpaved percentage zero when unknown. The
+/// route's own `difficulty` field is ignored, so results are reproducible.
+///
+/// Returns `None` if the route's distance cannot be determined.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ridewithgps_client::analysis;
+/// use ridewithgps_client::RideWithGpsClient;
+///
+/// let client = RideWithGpsClient::new(
+///     "https://ridewithgps.com",
+///     "your-api-key",
+///     None
+/// );
+///
+/// let route = client.get_route(12345).unwrap();
+/// if let Some(estimate) = analysis::estimate_difficulty(&route) {
+///     println!("{:?} ({:.0})", estimate.rating(), estimate.score);
+/// }
+/// ```
+pub fn estimate_difficulty(route: &Route) -> Option<DifficultyEstimate> {
+    let points = route.track_points.as_deref().unwrap_or_default();
+
+    let distance = route
+        .distance
+        .or_else(|| points.iter().rev().find_map(|p| p.d))?
+        / 1000.0;
+    let gain = route
+        .elevation_gain
+        .unwrap_or_else(|| elevation_gain_loss(points).0);
+
+    let mut steepness = 0.0;
+    let samples: Vec<(f64, f64)> = points.iter().filter_map(|p| Some((p.d?, p.e?))).collect();
+    let mut start = samples.first().copied();
+    for &(d, e) in samples.iter().skip(1) {
+        let Some((d0, e0)) = start else { break };
+        let run = d - d0;
+        if run < GRADIENT_SEGMENT {
+            continue;
+        }
+        let weight = match gradient(d0, e0, d, e) {
+            g if g > 0.12 => 6.0,
+            g if g > 0.08 => 3.0,
+            g if g > 0.05 => 1.0,
+            _ => 0.0,
+        };
+        steepness += weight * run / 1000.0;
+        start = Some((d, e));
+    }
+
+    let unpaved_factor = 1.0 + 0.5 * route.unpaved_pct.unwrap_or(0.0).clamp(0.0, 100.0) / 100.0;
+    let climbing = gain / 25.0;
+
+    Some(DifficultyEstimate {
+        score: (distance + climbing + steepness) * unpaved_factor,
+        distance,
+        climbing,
+        steepness,
+        unpaved_factor,
+    })
+}
+
+/// Regular breaks and control stops assumed by [`time_plan`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StopSchedule {
+    /// Riding distance between regular breaks in meters, or `None` for no
+    /// regular breaks
+    pub interval: Option<f64>,
+
+    /// Length of each regular break in seconds
+    pub break_duration: f64,
+
+    /// Time spent at each control point in seconds
+    pub control_duration: f64,
+}
+
+/// How fast a rider covers ground, used by [`time_plan`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiderModel {
+    /// Speed on flat ground and gentle slopes in m/s
+    pub flat_speed: f64,
+
+    /// Vertical ascent rate on climbs in meters per hour
+    pub climbing_vam: f64,
+
+    /// Speed on descents in m/s
+    pub descending_speed: f64,
+
+    /// Breaks and control stops
+    pub stop_schedule: StopSchedule,
+}
+
+impl Default for RiderModel {
+    /// 25 km/h on the flat, 700 m/h climbing and 35 km/h descending,
+    /// without stops
+    fn default() -> Self {
+        Self {
+            flat_speed: 25.0 / 3.6,
+            climbing_vam: 700.0,
+            descending_speed: 35.0 / 3.6,
+            stop_schedule: StopSchedule::default(),
+        }
+    }
+}
+
+/// Estimated arrival at a cue of a route, from [`time_plan`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlannedCue {
+    /// Index of the cue in the route's course points
+    pub index: usize,
+
+    /// Distance along the route in meters
+    pub distance: f64,
+
+    /// Time from the start in seconds, including the stops before the cue
+    pub elapsed: f64,
+
+    /// Whether the cue is a control, where the rider stops
+    pub control: bool,
+}
+
+impl PlannedCue {
+    /// Time of arrival for a given start time
+    pub fn eta(&self, start: DateTime<Utc>) -> DateTime<Utc> {
+        start + chrono::Duration::milliseconds((self.elapsed * 1000.0).round() as i64)
+    }
+}
+
+/// Estimated timing of a route, computed by [`time_plan`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimePlan {
+    /// Arrival at each cue with a known position along the route, in the
+    /// order of the course points
+    pub cues: Vec<PlannedCue>,
+
+    /// Riding time for the whole route in seconds
+    pub moving_time: f64,
+
+    /// Time from start to finish in seconds, including stops
+    pub total_time: f64,
+}
+
+/// Gradient beyond which a stretch counts as a climb or a descent
+const CLIMB_GRADIENT: f64 = 0.02;
+
+/// Estimate when a rider reaches each cue and control of a route
+///
+/// Each stretch between track points is ridden at `flat_speed`, except
+/// climbs steeper than 2 %, which take the longer of that and the time to
+/// ascend at `climbing_vam`, and descents steeper than 2 %, ridden at
+/// `descending_speed`. The rider takes a regular break every
+/// `stop_schedule.interval` meters and stops for
+/// `stop_schedule.control_duration` at every [`CueType::Control`] cue;
+/// the arrival time at a cue does not include the stop there.
+///
+/// Cues without a distance are placed by their position on the track, and
+/// left out if they have neither. Returns `None` if the route has no track
+/// points with distances or positions.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use chrono::{TimeZone, Utc};
+/// use ridewithgps_client::analysis::{self, RiderModel, StopSchedule};
+/// use ridewithgps_client::RideWithGpsClient;
+///
+/// let client = RideWithGpsClient::new(
+///     "https://ridewithgps.com",
+///     "your-api-key",
+///     None
+/// );
+///
+/// let route = client.get_route(12345).unwrap();
+/// let model = RiderModel {
+///     stop_schedule: StopSchedule {
+///         interval: Some(80_000.0),
+///         break_duration: 1200.0,
+///         control_duration: 600.0,
+///     },
+///     ..Default::default()
+/// };
+/// let start = Utc.with_ymd_and_hms(2025, 6, 7, 6, 0, 0).unwrap();
+/// if let Some(plan) = analysis::time_plan(&route, model) {
+///     for cue in plan.cues.iter().filter(|c| c.control) {
+///         println!("km {:.0}: {}", cue.distance / 1000.0, cue.eta(start));
+///     }
+/// }
+/// ```
+pub fn time_plan(route: &Route, model: RiderModel) -> Option<TimePlan> {
+    // Distance, riding time and elevation at each track point
+    let mut profile: Vec<(f64, f64, Option<f64>)> = Vec::new();
+    let mut last_coord: Option<LatLng> = None;
+    for point in route.track_points.iter().flatten() {
+        let coord = point.coord();
+        let distance = match (point.d, profile.last(), last_coord.zip(coord)) {
+            (Some(d), _, _) => d,
+            (None, Some(&(d, _, _)), Some((a, b))) => d + a.distance_to(&b),
+            (None, None, _) if coord.is_some() => 0.0,
+            _ => continue,
+        };
+        last_coord = coord.or(last_coord);
+        let time = match profile.last() {
+            Some(&(d0, t0, e0)) => {
+                let run = (distance - d0).max(0.0);
+                let rise = e0.zip(point.e).map_or(0.0, |(e0, e1)| e1 - e0);
+                t0 + stretch_time(run, rise, &model)
+            }
+            None => 0.0,
+        };
+        profile.push((distance, time, point.e.or(profile.last().and_then(|p| p.2))));
+    }
+    let &(total_distance, moving_time, _) = profile.last()?;
+
+    let riding_time = |distance: f64| {
+        let i = profile.partition_point(|&(d, _, _)| d < distance);
+        match (i.checked_sub(1).map(|j| profile[j]), profile.get(i)) {
+            (Some((d0, t0, _)), Some(&(d1, t1, _))) if d1 > d0 => {
+                t0 + (t1 - t0) * (distance - d0) / (d1 - d0)
+            }
+            (_, Some(&(_, t, _))) | (Some((_, t, _)), None) => t,
+            (None, None) => 0.0,
+        }
+    };
+
+    let schedule = model.stop_schedule;
+    let corridor = OnceCell::new();
+    let mut cues: Vec<PlannedCue> = route
+        .course_points
+        .iter()
+        .flatten()
+        .enumerate()
+        .filter_map(|(index, cue)| {
+            let distance = cue.d.or_else(|| {
+                let coord = cue.coord()?;
+                corridor
+                    .get_or_init(|| route.corridor(GRADIENT_SEGMENT))
+                    .project(coord.lat, coord.lng)
+                    .map(|(d, _, _)| d)
+            })?;
+            Some(PlannedCue {
+                index,
+                distance,
+                elapsed: riding_time(distance),
+                control: cue.t == Some(CueType::Control),
+            })
+        })
+        .collect();
+    let stops = |distance: f64, cues: &[PlannedCue]| {
+        let breaks = schedule
+            .interval
+            .filter(|&interval| interval > 0.0)
+            .map_or(0.0, |interval| {
+                ((distance / interval).ceil() - 1.0).max(0.0)
+            });
+        let controls = cues
+            .iter()
+            .filter(|c| c.control && c.distance < distance)
+            .count();
+        breaks * schedule.break_duration + controls as f64 * schedule.control_duration
+    };
+    let waits: Vec<f64> = cues.iter().map(|c| stops(c.distance, &cues)).collect();
+    for (cue, wait) in cues.iter_mut().zip(waits) {
+        cue.elapsed += wait;
+    }
+
+    Some(TimePlan {
+        total_time: moving_time + stops(total_distance, &cues),
+        cues,
+        moving_time,
+    })
+}
+
+/// Time in seconds to ride a stretch of `run` meters climbing `rise` meters
+fn stretch_time(run: f64, rise: f64, model: &RiderModel) -> f64 {
+    let flat = run / model.flat_speed;
+    match gradient(0.0, 0.0, run, rise) {
+        g if g > CLIMB_GRADIENT => flat.max(rise / model.climbing_vam * 3600.0),
+        g if g < -CLIMB_GRADIENT => run / model.descending_speed,
+        _ => flat,
+    }
+}
+
+/// Thresholds used by [`generate_cues`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CueOptions {
+    /// Smallest change of heading in degrees that counts as a turn
+    pub min_turn_angle: f64,
+
+    /// Distance in meters before and after a point over which the incoming
+    /// and outgoing headings are measured, smoothing out GPS jitter
+    pub lookaround: f64,
+
+    /// Minimum distance in meters between two generated cues
+    pub min_spacing: f64,
+}
+
+impl Default for CueOptions {
+    fn default() -> Self {
+        Self {
+            min_turn_angle: 30.0,
+            lookaround: 30.0,
+            min_spacing: 100.0,
+        }
+    }
+}
+
+/// Generate turn cues from the geometry of a track
+///
+/// The heading change at each point is measured between the points
+/// [`lookaround`](CueOptions::lookaround) meters before and after it.
+/// Points turning by at least [`min_turn_angle`](CueOptions::min_turn_angle)
+/// become candidates, and the sharpest candidates are kept as long as they
+/// are at least [`min_spacing`](CueOptions::min_spacing) apart. Turns up to
+/// 45° are "slight", from 120° "sharp", and from 165° U-turns.
+///
+/// Cue distances are taken from the track points where present and
+/// computed from the coordinates otherwise. Points without coordinates are
+/// ignored. The cues have no street names, since those are not part of the
+/// geometry.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ridewithgps_client::analysis::{self, CueOptions};
+/// use ridewithgps_client::RideWithGpsClient;
+///
+/// let client = RideWithGpsClient::new(
+///     "https://ridewithgps.com",
+///     "your-api-key",
+///     Some("your-auth-token")
+/// );
+///
+/// let route = client.get_route(12345).unwrap();
+/// let points = route.track_points.unwrap_or_default();
+/// let cues = analysis::generate_cues(&points, CueOptions::default());
+/// client.update_route_course_points(route.id, &cues).unwrap();
+/// ```
+pub fn generate_cues(points: &[TrackPoint], options: CueOptions) -> Vec<CoursePoint> {
+    let located: Vec<(&TrackPoint, LatLng)> = points
+        .iter()
+        .filter_map(|p| Some((p, p.coord()?)))
+        .collect();
+    if located.len() < 3 {
+        return Vec::new();
+    }
+
+    let mut along = Vec::with_capacity(located.len());
+    let mut total = 0.0;
+    for (i, (_, coord)) in located.iter().enumerate() {
+        if i > 0 {
+            total += located[i - 1].1.distance_to(coord);
+        }
+        along.push(total);
+    }
+
+    let mut candidates = Vec::new();
+    for i in 1..located.len() - 1 {
+        let before = along.partition_point(|&d| d <= along[i] - options.lookaround);
+        let before = before.saturating_sub(1).min(i - 1);
+        let after = along
+            .partition_point(|&d| d < along[i] + options.lookaround)
+            .clamp(i + 1, located.len() - 1);
+
+        let here = located[i].1;
+        let incoming = located[before].1.bearing_to(&here);
+        let outgoing = here.bearing_to(&located[after].1);
+        let turn = (outgoing - incoming + 540.0).rem_euclid(360.0) - 180.0;
+        if turn.abs() >= options.min_turn_angle {
+            candidates.push((i, turn));
+        }
+    }
+
+    candidates.sort_by(|a, b| b.1.abs().total_cmp(&a.1.abs()));
+    let mut kept: Vec<(usize, f64)> = Vec::new();
+    for (i, turn) in candidates {
+        if kept
+            .iter()
+            .all(|&(j, _)| (along[i] - along[j]).abs() >= options.min_spacing)
+        {
+            kept.push((i, turn));
+        }
+    }
+    kept.sort_by_key(|&(i, _)| i);
+
+    kept.into_iter()
+        .map(|(i, turn)| {
+            let (point, coord) = located[i];
+            let (cue_type, text) = turn_cue(turn);
+            CoursePoint {
+                x: Some(coord.lng),
+                y: Some(coord.lat),
+                d: Some(point.d.unwrap_or(along[i])),
+                t: Some(cue_type),
+                n: Some(text.to_string()),
+            }
+        })
+        .collect()
+}
+
+/// Reduce a track to the points needed to follow its shape
+///
+/// Uses the Douglas-Peucker algorithm: a point is dropped if it lies within
+/// `tolerance` meters of the straight line between the points kept around
+/// it. The first and last points are always kept, and points keep their
+/// original distances. Points without coordinates are dropped.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ridewithgps_client::analysis;
+/// use ridewithgps_client::RideWithGpsClient;
+///
+/// let client = RideWithGpsClient::new(
+///     "https://ridewithgps.com",
+///     "your-api-key",
+///     None
+/// );
+///
+/// let route = client.get_route(12345).unwrap();
+/// let points = route.track_points.unwrap_or_default();
+/// let simplified = analysis::simplify_track(&points, 5.0);
+/// println!("{} of {} points kept", simplified.len(), points.len());
+/// ```
+pub fn simplify_track(points: &[TrackPoint], tolerance: f64) -> Vec<TrackPoint> {
+    let located: Vec<(&TrackPoint, LatLng)> = points
+        .iter()
+        .filter_map(|p| Some((p, p.coord()?)))
+        .collect();
+    if located.len() < 3 {
+        return located.into_iter().map(|(p, _)| p.clone()).collect();
+    }
+
+    // Project onto a local plane in meters, which is accurate enough for
+    // the distances involved
+    let cos_lat = located[0].1.lat.to_radians().cos();
+    let xy: Vec<(f64, f64)> = located
+        .iter()
+        .map(|(_, c)| {
+            (
+                c.lng.to_radians() * cos_lat * crate::EARTH_RADIUS,
+                c.lat.to_radians() * crate::EARTH_RADIUS,
+            )
+        })
+        .collect();
+
+    let mut keep = vec![false; xy.len()];
+    keep[0] = true;
+    keep[xy.len() - 1] = true;
+    let mut stack = vec![(0, xy.len() - 1)];
+    while let Some((start, end)) = stack.pop() {
+        let farthest = (start + 1..end)
+            .map(|i| (i, line_distance(xy[i], xy[start], xy[end])))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((i, distance)) = farthest {
+            if distance > tolerance {
+                keep[i] = true;
+                stack.push((start, i));
+                stack.push((i, end));
+            }
+        }
+    }
+
+    located
+        .into_iter()
+        .zip(keep)
+        .filter(|(_, keep)| *keep)
+        .map(|((p, _), _)| p.clone())
+        .collect()
+}
+
+/// Corridor width used by [`overlap`], in meters on either side of a route
+pub const OVERLAP_BUFFER: f64 = 30.0;
+
+/// A stretch of a route that stays away from the route it is compared with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DivergingSection {
+    /// Distance along the route where the section starts, in meters
+    pub start: f64,
+
+    /// Distance along the route where the section ends, in meters
+    pub end: f64,
+}
+
+impl DivergingSection {
+    /// Length of the section in meters
+    pub fn length(&self) -> f64 {
+        self.end - self.start
+    }
+}
+
+/// How much two routes share, from [`overlap`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteOverlap {
+    /// Percentage of the first route's length within the corridor around
+    /// the second
+    pub shared_pct_a: f64,
+
+    /// Percentage of the second route's length within the corridor around
+    /// the first
+    pub shared_pct_b: f64,
+
+    /// Sections of the first route outside the corridor around the second
+    pub diverging_a: Vec<DivergingSection>,
+
+    /// Sections of the second route outside the corridor around the first
+    pub diverging_b: Vec<DivergingSection>,
+}
+
+impl RouteOverlap {
+    /// The lower of the two shared percentages
+    ///
+    /// Near 100 for duplicates; a short route that is part of a longer one
+    /// scores by the share of the longer route it covers.
+    pub fn similarity(&self) -> f64 {
+        self.shared_pct_a.min(self.shared_pct_b)
+    }
+}
+
+/// Compare the geometry of two routes, within [`OVERLAP_BUFFER`] meters
+///
+/// See [`overlap_within`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ridewithgps_client::analysis;
+/// use ridewithgps_client::RideWithGpsClient;
+///
+/// let client = RideWithGpsClient::new(
+///     "https://ridewithgps.com",
+///     "your-api-key",
+///     None
+/// );
+///
+/// let a = client.get_route(12345).unwrap();
+/// let b = client.get_route(67890).unwrap();
+/// if let Some(overlap) = analysis::overlap(&a, &b) {
+///     if overlap.similarity() > 95.0 {
+///         println!("Routes {} and {} are duplicates", a.id, b.id);
+///     }
+///     for section in &overlap.diverging_a {
+///         println!("Only {} from {:.0} to {:.0} m", a.id, section.start, section.end);
+///     }
+/// }
+/// ```
+pub fn overlap(a: &Route, b: &Route) -> Option<RouteOverlap> {
+    overlap_within(a, b, OVERLAP_BUFFER)
+}
+
+/// Compare the geometry of two routes
+///
+/// Each route is walked in steps of at most half the buffer, and every step
+/// whose start lies within `buffer` meters of the other route's track counts
+/// as shared. Direction of travel is ignored, so a route and its reverse
+/// overlap completely. Distances along the routes are taken from the track
+/// points if they all have one, and computed from the coordinates
+/// otherwise. Points without coordinates are ignored.
+///
+/// Returns `None` if either route has fewer than two located track points
+/// or no length.
+pub fn overlap_within(a: &Route, b: &Route, buffer: f64) -> Option<RouteOverlap> {
+    let track_a = located_track(a)?;
+    let track_b = located_track(b)?;
+    let index_a = SegmentIndex::new(&track_a.iter().map(|p| p.0).collect::<Vec<_>>(), buffer);
+    let index_b = SegmentIndex::new(&track_b.iter().map(|p| p.0).collect::<Vec<_>>(), buffer);
+
+    let (shared_pct_a, diverging_a) = compare_track(&track_a, &index_b, buffer)?;
+    let (shared_pct_b, diverging_b) = compare_track(&track_b, &index_a, buffer)?;
+    Some(RouteOverlap {
+        shared_pct_a,
+        shared_pct_b,
+        diverging_a,
+        diverging_b,
+    })
+}
+
+/// Located track points of a route with their distances
+fn located_track(route: &Route) -> Option<Vec<(LatLng, f64)>> {
+    let located: Vec<(LatLng, Option<f64>)> = route
+        .track_points
+        .iter()
+        .flatten()
+        .filter_map(|p| Some((p.coord()?, p.d)))
+        .collect();
+    if located.len() < 2 {
+        return None;
+    }
+
+    if located.iter().all(|(_, d)| d.is_some()) {
+        return Some(
+            located
+                .into_iter()
+                .map(|(c, d)| (c, d.unwrap_or_default()))
+                .collect(),
+        );
+    }
+    let mut total = 0.0;
+    let mut track = Vec::with_capacity(located.len());
+    for (i, (c, _)) in located.iter().enumerate() {
+        if i > 0 {
+            total += located[i - 1].0.distance_to(c);
+        }
+        track.push((*c, total));
+    }
+    Some(track)
+}
+
+/// Shared percentage and diverging sections of `track` against `other`
+fn compare_track(
+    track: &[(LatLng, f64)],
+    other: &SegmentIndex,
+    buffer: f64,
+) -> Option<(f64, Vec<DivergingSection>)> {
+    let step = (buffer / 2.0).max(1.0);
+    let (mut total, mut shared) = (0.0, 0.0);
+    let mut diverging: Vec<DivergingSection> = Vec::new();
+    let mut previous_shared = true;
+
+    for pair in track.windows(2) {
+        let ((c0, d0), (c1, d1)) = (pair[0], pair[1]);
+        let length = (d1 - d0).max(0.0);
+        if length == 0.0 {
+            continue;
+        }
+        let steps = (c0.distance_to(&c1) / step).ceil().max(1.0) as usize;
+        for k in 0..steps {
+            let t = k as f64 / steps as f64;
+            let sample = LatLng::new(
+                c0.lat + t * (c1.lat - c0.lat),
+                c0.lng + t * (c1.lng - c0.lng),
+            );
+            let start = d0 + t * length;
+            let end = start + length / steps as f64;
+            total += end - start;
+            if other.within(&sample, buffer) {
+                shared += end - start;
+                previous_shared = true;
+                continue;
+            }
+            match diverging.last_mut() {
+                Some(section) if !previous_shared => section.end = end,
+                _ => diverging.push(DivergingSection { start, end }),
+            }
+            previous_shared = false;
+        }
+    }
+
+    (total > 0.0).then(|| (shared / total * 100.0, diverging))
+}
+
+/// Distance from `p` to the segment from `a` to `b`, in plane coordinates
+fn line_distance(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_sq = dx * dx + dy * dy;
+    let t = if length_sq > 0.0 {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (p.0 - a.0 - t * dx).hypot(p.1 - a.1 - t * dy)
+}
+
+/// Classify a heading change in degrees (positive is clockwise)
+fn turn_cue(turn: f64) -> (CueType, &'static str) {
+    let right = turn > 0.0;
+    match turn.abs() {
+        a if a >= 165.0 => (CueType::UTurn, "Make a U-turn"),
+        a if a >= 120.0 && right => (CueType::SharpRight, "Sharp right"),
+        a if a >= 120.0 => (CueType::SharpLeft, "Sharp left"),
+        a if a > 45.0 && right => (CueType::Right, "Turn right"),
+        a if a > 45.0 => (CueType::Left, "Turn left"),
+        _ if right => (CueType::SlightRight, "Bear right"),
+        _ => (CueType::SlightLeft, "Bear left"),
+    }
+}
+
+fn gradient(d0: f64, e0: f64, d1: f64, e1: f64) -> f64 {
+    let rise = e1 - e0;
+    let run = d1 - d0;
+    if run > 0.0 {
+        rise / run
+    } else if rise == 0.0 {
+        0.0
+    } else {
+        f64::INFINITY.copysign(rise)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(elevations: &[f64]) -> Vec<TrackPoint> {
+        elevations
+            .iter()
+            .enumerate()
+            .map(|(i, &e)| TrackPoint {
+                x: None,
+                y: None,
+                d: Some(i as f64 * 10.0),
+                e: Some(e),
+                surface: None,
+                highway: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rolling_median_removes_outlier() {
+        let mut track = points(&[100.0, 101.0, 150.0, 102.0, 103.0]);
+        smooth_elevation(&mut track, SmoothingMethod::RollingMedian { window: 3 });
+
+        let elevations: Vec<f64> = track.iter().filter_map(|p| p.e).collect();
+        assert_eq!(elevations, vec![100.0, 101.0, 102.0, 103.0, 103.0]);
+    }
+
+    #[test]
+    fn test_savitzky_golay_preserves_linear_climb() {
+        let mut track = points(&[0.0, 5.0, 10.0, 15.0, 20.0, 25.0, 30.0]);
+        smooth_elevation(&mut track, SmoothingMethod::SavitzkyGolay { window: 5 });
+
+        for (i, point) in track.iter().enumerate() {
+            assert!((point.e.unwrap() - i as f64 * 5.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_smoothing_skips_missing_elevation() {
+        let mut track = points(&[100.0, 101.0, 150.0, 102.0]);
+        track[1].e = None;
+        smooth_elevation(&mut track, SmoothingMethod::RollingMedian { window: 3 });
+
+        assert_eq!(track[1].e, None);
+        assert_eq!(track[2].e, Some(102.0));
+    }
+
+    #[test]
+    fn test_find_and_remove_spikes() {
+        let mut track = points(&[100.0, 101.0, 180.0, 102.0, 103.0]);
+        assert_eq!(find_elevation_spikes(&track, 0.3), vec![2]);
+
+        let removed = remove_elevation_spikes(&mut track, 0.3);
+        assert_eq!(removed, vec![2]);
+        assert_eq!(track[2].e, Some(101.5));
+    }
+
+    #[test]
+    fn test_steady_climb_is_not_a_spike() {
+        let track = points(&[100.0, 110.0, 120.0, 130.0]);
+        assert!(find_elevation_spikes(&track, 0.3).is_empty());
+    }
+
+    #[test]
+    fn test_elevation_gain_loss() {
+        let track = points(&[100.0, 110.0, 105.0, 120.0, 90.0]);
+        assert_eq!(elevation_gain_loss(&track), (25.0, 35.0));
+    }
+
+    fn trip(samples: &[(i64, f64)]) -> Trip {
+        let points: Vec<serde_json::Value> = samples
+            .iter()
+            .map(|(t, d)| serde_json::json!({"t": t, "d": d}))
+            .collect();
+        serde_json::from_value(serde_json::json!({"id": 1, "track_points": points})).unwrap()
+    }
+
+    #[test]
+    fn test_moving_time() {
+        // 10 s moving at 5 m/s, 20 s standing still, 100 s gap, 10 s moving
+        let trip = trip(&[(0, 0.0), (10, 50.0), (30, 50.0), (130, 60.0), (140, 110.0)]);
+
+        let stats = moving_time(&trip, MovingCriteria::default()).unwrap();
+        assert_eq!(stats.moving_time, 20.0);
+        assert_eq!(stats.stopped_time, 120.0);
+        assert_eq!(stats.moving_distance, 100.0);
+        assert_eq!(stats.avg_moving_speed, Some(5.0));
+        assert_eq!(
+            stats.pauses,
+            vec![Pause {
+                start: 10,
+                end: 130,
+                location: None
+            }]
+        );
+    }
+
+    #[test]
+    fn test_detect_pauses() {
+        let track_points: Vec<_> = [(0, 0.0), (10, 50.0), (40, 52.0), (50, 100.0), (60, 100.0)]
+            .iter()
+            .map(|&(t, d)| serde_json::json!({"t": t, "d": d, "x": 4.9, "y": d / 1000.0}))
+            .collect();
+        let trip: Trip =
+            serde_json::from_value(serde_json::json!({"id": 1, "track_points": track_points}))
+                .unwrap();
+
+        let pauses = detect_pauses(&trip, MovingCriteria::default());
+        assert_eq!(pauses.len(), 2);
+        assert_eq!((pauses[0].start, pauses[0].end), (10, 40));
+        assert_eq!(pauses[0].duration(), 30.0);
+        assert_eq!(pauses[0].location, Some(LatLng::new(0.05, 4.9)));
+        assert_eq!(pauses[1].duration(), 10.0);
+
+        let trip: Trip = serde_json::from_str(r#"{"id": 1}"#).unwrap();
+        assert!(detect_pauses(&trip, MovingCriteria::default()).is_empty());
+    }
+
+    #[test]
+    fn test_moving_time_custom_criteria() {
+        let trip = trip(&[(0, 0.0), (10, 50.0), (30, 50.0), (130, 60.0)]);
+        let criteria = MovingCriteria {
+            min_speed: 0.05,
+            pause_gap: 300.0,
+        };
+
+        let stats = moving_time(&trip, criteria).unwrap();
+        assert_eq!(stats.moving_time, 110.0);
+        assert_eq!(stats.stopped_time, 20.0);
+    }
+
+    #[test]
+    fn test_gradient_histogram() {
+        let points: Vec<TrackPoint> = serde_json::from_value(serde_json::json!([
+            {"d": 0.0, "e": 100.0},
+            {"d": 100.0, "e": 100.0},
+            {"d": 150.0},
+            {"d": 200.0, "e": 103.0},
+            {"d": 250.0, "e": 140.0},
+            {"d": 400.0, "e": 127.0},
+            {"d": 500.0, "e": 126.0},
+            {"d": 540.0, "e": 150.0}
+        ]))
+        .unwrap();
+        let histogram = ElevationProfile::new(&points).gradient_histogram(5.0);
+
+        let bands: Vec<(f64, f64, f64)> = histogram
+            .iter()
+            .map(|b| (b.min, b.max, b.distance))
+            .collect();
+        assert_eq!(
+            bands,
+            vec![
+                // Including the last 40 m
+                (-5.0, 0.0, 140.0),
+                (0.0, 5.0, 200.0),
+                (5.0, 10.0, 0.0),
+                (10.0, 15.0, 200.0)
+            ]
+        );
+        assert!(ElevationProfile::new::<TrackPoint>(&[])
+            .gradient_histogram(5.0)
+            .is_empty());
+
+        // A profile shorter than a stretch is a stretch of its own
+        let points: Vec<TrackPoint> = serde_json::from_value(serde_json::json!([
+            {"d": 0.0, "e": 100.0},
+            {"d": 50.0, "e": 101.0}
+        ]))
+        .unwrap();
+        let histogram = ElevationProfile::new(&points).gradient_histogram(5.0);
+        assert_eq!(histogram.len(), 1);
+        assert_eq!((histogram[0].min, histogram[0].distance), (0.0, 50.0));
+    }
+
+    #[test]
+    fn test_time_plan() {
+        // 10 km flat, 1 km climbing 100 m, 1 km descending along the equator
+        let x = |d: f64| d / 111_195.0;
+        let route: Route = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "track_points": [
+                {"x": x(0.0), "y": 0.0, "d": 0.0, "e": 0.0},
+                {"x": x(5000.0), "y": 0.0, "d": 5000.0, "e": 0.0},
+                {"x": x(10000.0), "y": 0.0, "d": 10000.0, "e": 0.0},
+                {"x": x(11000.0), "y": 0.0, "d": 11000.0, "e": 100.0},
+                {"x": x(12000.0), "y": 0.0, "d": 12000.0, "e": 0.0}
+            ],
+            "course_points": [
+                {"d": 5000.0, "t": "control", "n": "Control 1"},
+                {"n": "Nowhere"},
+                {"x": x(11000.0), "y": 0.0, "t": "summit"}
+            ]
+        }))
+        .unwrap();
+        let model = RiderModel {
+            flat_speed: 10.0,
+            climbing_vam: 360.0,
+            descending_speed: 20.0,
+            stop_schedule: StopSchedule {
+                interval: Some(6000.0),
+                break_duration: 300.0,
+                control_duration: 600.0,
+            },
+        };
+
+        let plan = time_plan(&route, model).unwrap();
+        assert!((plan.moving_time - 2050.0).abs() < 1e-6);
+        assert!((plan.total_time - 2950.0).abs() < 1e-6);
+        assert_eq!(plan.cues.len(), 2);
+        assert!(plan.cues[0].control);
+        assert!((plan.cues[0].elapsed - 500.0).abs() < 1e-6);
+        assert_eq!(plan.cues[1].index, 2);
+        assert!((plan.cues[1].distance - 11000.0).abs() < 1.0);
+        assert!((plan.cues[1].elapsed - 2900.0).abs() < 1.0);
+
+        let start = DateTime::parse_from_rfc3339("2025-06-07T06:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            plan.cues[0].eta(start).to_rfc3339(),
+            "2025-06-07T06:08:20+00:00"
+        );
+
+        let empty: Route = serde_json::from_str(r#"{"id": 2}"#).unwrap();
+        assert!(time_plan(&empty, RiderModel::default()).is_none());
+    }
+
+    #[test]
+    fn test_estimate_difficulty() {
+        // 2 km, the second kilometer climbing 100 m at 10 %
+        let track: Vec<TrackPoint> = (0..=20)
+            .map(|i| {
+                let d = i as f64 * 100.0;
+                TrackPoint {
+                    x: None,
+                    y: None,
+                    d: Some(d),
+                    e: Some((d - 1000.0).max(0.0) * 0.1),
+                    surface: None,
+                    highway: None,
+                }
+            })
+            .collect();
+        let route: Route = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "unpaved_pct": 50.0,
+            "track_points": track,
+        }))
+        .unwrap();
+
+        let estimate = estimate_difficulty(&route).unwrap();
+        assert_eq!(estimate.distance, 2.0);
+        assert!((estimate.climbing - 4.0).abs() < 1e-9);
+        assert!((estimate.steepness - 3.0).abs() < 1e-9);
+        assert_eq!(estimate.unpaved_factor, 1.25);
+        assert!((estimate.score - 11.25).abs() < 1e-9);
+        assert_eq!(estimate.rating(), DifficultyRating::Easy);
+
+        let route: Route = serde_json::from_str(r#"{"id": 1}"#).unwrap();
+        assert_eq!(estimate_difficulty(&route), None);
+    }
+
+    #[test]
+    fn test_generate_cues() {
+        // North for ~500 m, right turn to the east, then a gentle bend
+        let mut coords: Vec<(f64, f64)> = (0..=10).map(|i| (i as f64 * 0.00045, 0.0)).collect();
+        coords.extend((1..=10).map(|i| (0.0045, i as f64 * 0.00045)));
+        coords.extend((1..=10).map(|i| (0.0045 + i as f64 * 0.00015, 0.0045 + i as f64 * 0.00045)));
+        let track: Vec<TrackPoint> = coords
+            .iter()
+            .map(|&(lat, lng)| TrackPoint {
+                x: Some(lng),
+                y: Some(lat),
+                d: None,
+                e: None,
+                surface: None,
+                highway: None,
+            })
+            .collect();
+
+        let cues = generate_cues(&track, CueOptions::default());
+        assert_eq!(cues.len(), 1);
+        assert_eq!(cues[0].t, Some(CueType::Right));
+        assert_eq!(cues[0].y, Some(0.0045));
+        assert!((cues[0].d.unwrap() - 500.0).abs() < 5.0);
+
+        let options = CueOptions {
+            min_turn_angle: 15.0,
+            ..Default::default()
+        };
+        let cues = generate_cues(&track, options);
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[1].t, Some(CueType::SlightLeft));
+    }
+
+    #[test]
+    fn test_simplify_track() {
+        // A straight line east with a 1 m wobble, then a corner north
+        let coords = [
+            (0.0, 0.0),
+            (0.00001, 0.001),
+            (0.0, 0.002),
+            (0.0, 0.003),
+            (0.001, 0.003),
+        ];
+        let track: Vec<TrackPoint> = coords
+            .iter()
+            .enumerate()
+            .map(|(i, &(lat, lng))| TrackPoint {
+                x: Some(lng),
+                y: Some(lat),
+                d: Some(i as f64),
+                e: None,
+                surface: None,
+                highway: None,
+            })
+            .collect();
+
+        let kept: Vec<_> = simplify_track(&track, 5.0).iter().map(|p| p.d).collect();
+        assert_eq!(kept, vec![Some(0.0), Some(3.0), Some(4.0)]);
+        assert_eq!(simplify_track(&track, 0.5).len(), 5);
+    }
+
+    #[test]
+    fn test_moving_time_without_track_points() {
+        let trip: Trip = serde_json::from_str(r#"{"id": 1}"#).unwrap();
+        assert_eq!(moving_time(&trip, MovingCriteria::default()), None);
+    }
+    fn route(coords: &[(f64, f64)]) -> Route {
+        let track_points: Vec<_> = coords
+            .iter()
+            .map(|&(lat, lng)| serde_json::json!({"x": lng, "y": lat}))
+            .collect();
+        serde_json::from_value(serde_json::json!({"id": 1, "track_points": track_points})).unwrap()
+    }
+
+    #[test]
+    fn test_overlap() {
+        // About 2.2 km east along the equator, and one that follows its first
+        // half 10 m to the north, then heads away to the north
+        let a = route(&[(0.0, 0.0), (0.0, 0.02)]);
+        let b = route(&[(0.0001, 0.0), (0.0001, 0.01), (0.01, 0.01)]);
+
+        let overlap = overlap(&a, &b).unwrap();
+        assert!((overlap.shared_pct_a - 51.3).abs() < 1.0, "{:?}", overlap);
+        assert_eq!(overlap.diverging_a.len(), 1);
+        let section = overlap.diverging_a[0];
+        assert!((section.start - 1140.0).abs() < 20.0, "{:?}", section);
+        assert!((section.end - 2224.0).abs() < 1.0, "{:?}", section);
+        assert!((overlap.shared_pct_b - 51.3).abs() < 1.0, "{:?}", overlap);
+        assert_eq!(overlap.diverging_b.len(), 1);
+
+        let reversed = route(&[(0.0, 0.02), (0.0, 0.0)]);
+        assert_eq!(super::overlap(&a, &reversed).unwrap().similarity(), 100.0);
+        assert!(super::overlap(&a, &route(&[(0.0, 0.0)])).is_none());
+    }
+
+    fn power_trip(watts: &[(i64, f64, f64)]) -> Trip {
+        let track_points: Vec<_> = watts
+            .iter()
+            .map(|&(t, p, h)| serde_json::json!({"t": t, "p": p, "h": h}))
+            .collect();
+        serde_json::from_value(serde_json::json!({"id": 1, "track_points": track_points})).unwrap()
+    }
+
+    #[test]
+    fn test_training_load() {
+        // An hour at 250 W, sampled every 10 s, with a pause in the middle
+        let mut samples: Vec<_> = (0..=360).map(|i| (i * 10, 250.0, 150.0)).collect();
+        for sample in samples.iter_mut().skip(181) {
+            sample.0 += 600;
+        }
+        let trip = power_trip(&samples);
+
+        let profile = AthleteProfile::new().ftp(250.0);
+        let load = training_load(&trip, &profile).unwrap();
+        assert!((load.normalized_power - 250.0).abs() < 1e-9);
+        assert!((load.intensity_factor - 1.0).abs() < 1e-9);
+        assert!((load.tss - 100.0).abs() < 0.5, "{:?}", load);
+        assert!(training_load(&trip, &AthleteProfile::new()).is_none());
+
+        // 897.5 kJ of work
+        let calories = estimate_calories(&trip, &profile).unwrap();
+        assert!((calories - 893.8).abs() < 0.1, "{}", calories);
+    }
+
+    #[test]
+    fn test_time_in_zones() {
+        let trip = power_trip(&[(0, 100.0, 100.0), (10, 300.0, 170.0), (20, 0.0, 0.0)]);
+        let profile = AthleteProfile::new().ftp(250.0).max_hr(190.0);
+
+        let times = time_in_zones(&trip, &profile);
+        assert_eq!(times.power, vec![10.0, 0.0, 0.0, 0.0, 0.0, 10.0, 0.0]);
+        assert_eq!(times.heart_rate, vec![10.0, 0.0, 0.0, 10.0, 0.0]);
+        assert!(time_in_zones(&trip, &AthleteProfile::new())
+            .power
+            .is_empty());
+    }
+
+    #[test]
+    fn test_estimate_calories_without_power() {
+        let trip: Trip =
+            serde_json::from_str(r#"{"id": 1, "moving_time": 7200, "avg_speed": 6.0}"#).unwrap();
+        let profile = AthleteProfile::new().weight(70.0);
+        // 21.6 km/h for two hours at 8 MET
+        assert_eq!(estimate_calories(&trip, &profile), Some(1120.0));
+        assert_eq!(estimate_calories(&trip, &AthleteProfile::new()), None);
+    }
+
+    #[test]
+    fn test_clean_track() {
+        // About 10 m east every second along the equator
+        let mut samples: Vec<(i64, f64, f64)> =
+            (0..12).map(|i| (i, 0.0, i as f64 * 0.00009)).collect();
+        samples[5].0 = 4;
+        samples[7].1 = 0.01;
+        samples[9].1 = 0.0005;
+        let track: Vec<TripTrackPoint> = samples
+            .iter()
+            .map(|&(t, lat, lng)| {
+                serde_json::from_value(serde_json::json!({"t": t, "y": lat, "x": lng})).unwrap()
+            })
+            .collect();
+
+        let mut flagged = track.clone();
+        let anomalies = clean_track(&mut flagged, CleaningOptions::default());
+        assert_eq!(
+            anomalies,
+            vec![
+                Anomaly {
+                    index: 5,
+                    kind: AnomalyKind::DuplicateTimestamp
+                },
+                Anomaly {
+                    index: 7,
+                    kind: AnomalyKind::Teleport
+                },
+                Anomaly {
+                    index: 9,
+                    kind: AnomalyKind::ImpossibleSpeed
+                },
+            ]
+        );
+        assert_eq!(flagged.len(), 12);
+        assert_eq!(flagged[7].k, Some(true));
+        assert_eq!(flagged[8].k, None);
+
+        let mut removed = track;
+        let options = CleaningOptions {
+            action: CleaningAction::Remove,
+            ..Default::default()
+        };
+        assert_eq!(clean_track(&mut removed, options).len(), 3);
+        let times: Vec<_> = removed.iter().map(|p| p.t.unwrap()).collect();
+        assert_eq!(times, vec![0, 1, 2, 3, 4, 6, 8, 10, 11]);
+    }
+
+    #[test]
+    fn test_clean_track_bad_first_fix() {
+        let mut track: Vec<TripTrackPoint> = (0..12)
+            .map(|i| {
+                let lat = if i == 0 { 0.01 } else { 0.0 };
+                serde_json::from_value(
+                    serde_json::json!({"t": i, "y": lat, "x": i as f64 * 0.00009}),
+                )
+                .unwrap()
+            })
+            .collect();
+        let options = CleaningOptions {
+            action: CleaningAction::Remove,
+            ..Default::default()
+        };
+        assert_eq!(
+            clean_track(&mut track, options),
+            vec![Anomaly {
+                index: 0,
+                kind: AnomalyKind::ImpossibleSpeed
+            }]
+        );
+        assert_eq!(track.len(), 11);
+    }
+
+    #[test]
+    fn test_clean_track_transport_gap() {
+        // Riding at 10 m/s, then 100 km further on half an hour later
+        let mut track: Vec<TripTrackPoint> = (0..16)
+            .map(|i| {
+                let (t, lng) = match i {
+                    0..=5 => (i, i as f64 * 0.00009),
+                    _ => (1800 + i, 0.9 + i as f64 * 0.00009),
+                };
+                serde_json::from_value(serde_json::json!({"t": t, "y": 0.0, "x": lng})).unwrap()
+            })
+            .collect();
+        let options = CleaningOptions {
+            action: CleaningAction::Remove,
+            ..Default::default()
+        };
+        assert_eq!(clean_track(&mut track, options), vec![]);
+        assert_eq!(track.len(), 16);
+    }
+
+    #[test]
+    fn test_correct_elevation() {
+        struct Model;
+
+        impl ElevationProvider for Model {
+            fn elevations(&self, locations: &[LatLng]) -> Result<Vec<Option<f64>>> {
+                Ok(locations
+                    .iter()
+                    .map(|l| (l.lng < 0.5).then_some(l.lng * 100.0))
+                    .collect())
+            }
+        }
+
+        // The barometer reads 50 m high and drifts up by 1 m per sample
+        let mut points: Vec<TripTrackPoint> = (0..7)
+            .map(|i| {
+                let lng = i as f64 / 10.0;
+                serde_json::from_value(serde_json::json!({
+                    "x": lng, "y": 0.0, "e": lng * 100.0 + 50.0 + i as f64
+                }))
+                .unwrap()
+            })
+            .collect();
+        points[3].e = Some(points[3].e.unwrap() + 5.0);
+        points.push(serde_json::from_value(serde_json::json!({})).unwrap());
+
+        let mut calibrated = points.clone();
+        correct_elevation(&mut calibrated, ElevationCorrection::StartElevation(0.0)).unwrap();
+        assert_eq!(calibrated[0].e, Some(0.0));
+        assert_eq!(calibrated[6].e, Some(66.0));
+        assert_eq!(calibrated[7].e, None);
+
+        let correction = ElevationCorrection::Dem {
+            provider: &Model,
+            window: 3,
+        };
+        correct_elevation(&mut points, correction).unwrap();
+        let elevations: Vec<_> = points.iter().map(|p| p.e.map(f64::round)).collect();
+        assert_eq!(
+            elevations,
+            vec![
+                Some(0.0),
+                Some(10.0),
+                Some(20.0),
+                Some(34.0),
+                Some(40.0),
+                Some(51.0),
+                Some(62.0),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_correct_elevation_short_response() {
+        struct Short;
+
+        impl ElevationProvider for Short {
+            fn elevations(&self, locations: &[LatLng]) -> Result<Vec<Option<f64>>> {
+                Ok(vec![Some(0.0); locations.len() - 1])
+            }
+        }
+
+        let mut points: Vec<TripTrackPoint> =
+            serde_json::from_str(r#"[{"x": 1, "y": 1, "e": 5}, {"x": 1, "y": 2, "e": 7}]"#)
+                .unwrap();
+        let correction = ElevationCorrection::Dem {
+            provider: &Short,
+            window: 3,
+        };
+        assert!(matches!(
+            correct_elevation(&mut points, correction),
+            Err(crate::Error::ApiError(_))
+        ));
+        assert_eq!(points[1].e, Some(7.0));
+    }
+}
@@ -0,0 +1,140 @@
+//! Event announcements sent to registered participants
+
+use crate::{Error, Result, RideWithGpsClient};
+use serde::{Deserialize, Serialize};
+
+/// A message sent by an organizer to an event's participants
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EventAnnouncement {
+    /// Announcement ID
+    pub id: u64,
+
+    /// ID of the event
+    pub event_id: Option<u64>,
+
+    /// ID of the sender
+    pub user_id: Option<u64>,
+
+    /// Subject line
+    pub subject: Option<String>,
+
+    /// Message body
+    pub body: Option<String>,
+
+    /// Number of participants the announcement was sent to
+    pub recipients_count: Option<u32>,
+
+    /// Sent timestamp
+    pub sent_at: Option<String>,
+}
+
+impl RideWithGpsClient {
+    /// List the announcements sent for an event
+    ///
+    /// # Arguments
+    ///
+    /// * `event_id` - The event ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// for announcement in client.list_event_announcements(12345).unwrap() {
+    ///     println!("{:?}: {:?}", announcement.sent_at, announcement.subject);
+    /// }
+    /// ```
+    pub fn list_event_announcements(&self, event_id: u64) -> Result<Vec<EventAnnouncement>> {
+        self.get_wrapped(
+            &format!("/api/v1/events/{}/announcements.json", event_id),
+            "announcements",
+        )
+    }
+
+    /// Send an announcement to all registered participants of an event
+    ///
+    /// Note: This endpoint is only available to the event's organizers.
+    ///
+    /// # Arguments
+    ///
+    /// * `event_id` - The event ID
+    /// * `subject` - Subject line
+    /// * `body` - Message body
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let announcement = client
+    ///     .send_event_announcement(
+    ///         12345,
+    ///         "Reroute",
+    ///         "The bridge at mile 40 is closed; follow the detour signs.",
+    ///     )
+    ///     .unwrap();
+    /// println!("Sent to {:?} riders", announcement.recipients_count);
+    /// ```
+    pub fn send_event_announcement(
+        &self,
+        event_id: u64,
+        subject: &str,
+        body: &str,
+    ) -> Result<EventAnnouncement> {
+        #[derive(Serialize)]
+        struct AnnouncementRequest<'a> {
+            subject: &'a str,
+            body: &'a str,
+        }
+
+        if subject.trim().is_empty() {
+            return Err(Error::ValidationError(
+                "Announcement subject must not be empty".to_string(),
+            ));
+        }
+
+        self.post_wrapped(
+            &format!("/api/v1/events/{}/announcements.json", event_id),
+            "announcement",
+            &AnnouncementRequest { subject, body },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_announcement_deserialization() {
+        let json = r#"{
+            "id": 7,
+            "event_id": 12345,
+            "subject": "Weather",
+            "body": "Bring a rain jacket",
+            "recipients_count": 120
+        }"#;
+
+        let announcement: EventAnnouncement = serde_json::from_str(json).unwrap();
+        assert_eq!(announcement.subject.as_deref(), Some("Weather"));
+        assert_eq!(announcement.recipients_count, Some(120));
+
+        let client = RideWithGpsClient::new("https://ridewithgps.com", "key", None);
+        assert!(matches!(
+            client.send_event_announcement(12345, "  ", "Hello"),
+            Err(Error::ValidationError(_))
+        ));
+    }
+}
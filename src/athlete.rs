@@ -0,0 +1,171 @@
+//! The athlete's physiology, for training analytics
+
+use crate::analysis::{self, TrainingLoad, ZoneTimes};
+use crate::{RideWithGpsClient, Trip};
+
+/// Power zone lower bounds as fractions of FTP (Coggan's seven zones)
+const POWER_ZONES: [f64; 6] = [0.55, 0.75, 0.90, 1.05, 1.20, 1.50];
+
+/// Heart rate zone lower bounds as fractions of maximum heart rate
+const HEART_RATE_ZONES: [f64; 4] = [0.60, 0.70, 0.80, 0.90];
+
+/// Custom training zone boundaries
+///
+/// Each list holds the lower bound of every zone but the first, in
+/// ascending order, so four bounds make five zones.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct Zones {
+    /// Heart rate zone bounds in BPM
+    pub heart_rate: Option<Vec<f64>>,
+
+    /// Power zone bounds in watts
+    pub power: Option<Vec<f64>>,
+}
+
+setters!(Zones {
+    heart_rate: Vec<f64>,
+    power: Vec<f64>,
+});
+
+/// Physiological parameters used by the training analytics in
+/// [`analysis`]
+///
+/// Set it once on the client with
+/// [`RideWithGpsClient::set_athlete_profile`] to use the client's
+/// `trip_*` analytics methods.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct AthleteProfile {
+    /// Functional threshold power in watts
+    pub ftp: Option<f64>,
+
+    /// Maximum heart rate in BPM
+    pub max_hr: Option<f64>,
+
+    /// Training zones, instead of the ones derived from FTP and maximum
+    /// heart rate
+    pub zones: Option<Zones>,
+
+    /// Weight in kilograms
+    pub weight: Option<f64>,
+}
+
+setters!(AthleteProfile {
+    ftp: f64,
+    max_hr: f64,
+    zones: Zones,
+    weight: f64,
+});
+
+impl AthleteProfile {
+    /// Heart rate zone bounds in BPM
+    ///
+    /// Taken from [`zones`](Self::zones) if set, and otherwise at 60, 70,
+    /// 80 and 90% of the maximum heart rate.
+    pub fn heart_rate_zones(&self) -> Option<Vec<f64>> {
+        let custom = self.zones.as_ref().and_then(|z| z.heart_rate.clone());
+        custom.or_else(|| {
+            let max_hr = self.max_hr?;
+            Some(HEART_RATE_ZONES.iter().map(|f| f * max_hr).collect())
+        })
+    }
+
+    /// Power zone bounds in watts
+    ///
+    /// Taken from [`zones`](Self::zones) if set, and otherwise at 55, 75,
+    /// 90, 105, 120 and 150% of FTP.
+    pub fn power_zones(&self) -> Option<Vec<f64>> {
+        let custom = self.zones.as_ref().and_then(|z| z.power.clone());
+        custom.or_else(|| {
+            let ftp = self.ftp?;
+            Some(POWER_ZONES.iter().map(|f| f * ftp).collect())
+        })
+    }
+}
+
+impl RideWithGpsClient {
+    /// Set the athlete profile used by the `trip_*` analytics methods
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{AthleteProfile, RideWithGpsClient};
+    ///
+    /// let mut client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    /// client.set_athlete_profile(AthleteProfile::new().ftp(260.0).max_hr(188.0).weight(72.0));
+    ///
+    /// let trip = client.get_trip(12345).unwrap();
+    /// if let Some(load) = client.trip_training_load(&trip) {
+    ///     println!("TSS {:.0}", load.tss);
+    /// }
+    /// ```
+    pub fn set_athlete_profile(&mut self, profile: AthleteProfile) {
+        self.athlete = Some(profile);
+    }
+
+    /// Get the athlete profile
+    pub fn athlete_profile(&self) -> Option<&AthleteProfile> {
+        self.athlete.as_ref()
+    }
+
+    /// Training load of a trip; see [`analysis::training_load`]
+    ///
+    /// Returns `None` if no athlete profile is set.
+    pub fn trip_training_load(&self, trip: &Trip) -> Option<TrainingLoad> {
+        analysis::training_load(trip, self.athlete.as_ref()?)
+    }
+
+    /// Calories burned on a trip; see [`analysis::estimate_calories`]
+    ///
+    /// Returns `None` if no athlete profile is set.
+    pub fn trip_calories(&self, trip: &Trip) -> Option<f64> {
+        analysis::estimate_calories(trip, self.athlete.as_ref()?)
+    }
+
+    /// Time a trip spent in each training zone; see
+    /// [`analysis::time_in_zones`]
+    ///
+    /// Returns `None` if no athlete profile is set.
+    pub fn trip_time_in_zones(&self, trip: &Trip) -> Option<ZoneTimes> {
+        Some(analysis::time_in_zones(trip, self.athlete.as_ref()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zones() {
+        let profile = AthleteProfile::new().ftp(200.0);
+        let zones: Vec<f64> = profile
+            .power_zones()
+            .unwrap()
+            .iter()
+            .map(|z| z.round())
+            .collect();
+        assert_eq!(zones, vec![110.0, 150.0, 180.0, 210.0, 240.0, 300.0]);
+        assert_eq!(profile.heart_rate_zones(), None);
+
+        let profile = profile.zones(Zones::new().power(vec![100.0, 200.0]));
+        assert_eq!(profile.power_zones(), Some(vec![100.0, 200.0]));
+    }
+
+    #[test]
+    fn test_client_profile() {
+        let mut client = RideWithGpsClient::new("https://ridewithgps.com", "key", None);
+        let trip: Trip =
+            serde_json::from_str(r#"{"id": 1, "moving_time": 3600, "avg_speed": 5.0}"#).unwrap();
+        assert_eq!(client.trip_calories(&trip), None);
+
+        client.set_athlete_profile(AthleteProfile::new().weight(80.0));
+        // 18 km/h for an hour at 6.8 MET
+        assert_eq!(client.trip_calories(&trip), Some(544.0));
+        assert_eq!(client.trip_time_in_zones(&trip), Some(ZoneTimes::default()));
+    }
+}
@@ -1,10 +1,15 @@
 //! Authentication-related types and methods
 
-use crate::{Result, RideWithGpsClient, User};
+use crate::{Error, PaginatedResponse, Result, RideWithGpsClient, User};
+use reqwest::Method;
 use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 /// Request to create an authentication token
 #[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
 pub struct CreateAuthTokenRequest {
     /// User email
     pub email: String,
@@ -13,6 +18,16 @@ pub struct CreateAuthTokenRequest {
     pub password: String,
 }
 
+impl CreateAuthTokenRequest {
+    /// Create a request for the given credentials
+    pub fn new(email: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            email: email.into(),
+            password: password.into(),
+        }
+    }
+}
+
 /// Response containing an authentication token
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AuthToken {
@@ -26,6 +41,155 @@ pub struct AuthToken {
     pub user: Option<User>,
 }
 
+/// An active authentication token, as listed by
+/// [`list_auth_tokens`](RideWithGpsClient::list_auth_tokens)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AuthTokenInfo {
+    /// Token ID
+    pub id: u64,
+
+    /// The first few characters of the token, for identification
+    pub token_prefix: Option<String>,
+
+    /// Name of the application or device the token was created for
+    pub name: Option<String>,
+
+    /// User agent of the client that created the token
+    pub user_agent: Option<String>,
+
+    /// Whether this is the token the client is currently using
+    pub current: Option<bool>,
+
+    /// Created timestamp
+    pub created_at: Option<String>,
+
+    /// When the token was last used
+    pub last_used_at: Option<String>,
+}
+
+/// What the configured credentials can do, as reported by
+/// [`verify`](RideWithGpsClient::verify)
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    /// Whether the API key (and auth token, if set) were accepted
+    pub valid_credentials: bool,
+
+    /// The authenticated user, if an auth token was set and accepted
+    pub user: Option<User>,
+
+    /// Whether the credentials can access organization-only endpoints
+    /// (club members and points of interest)
+    pub organization_access: bool,
+
+    /// Rate-limit tier advertised by the API, if any
+    pub rate_limit_tier: Option<String>,
+
+    /// Number of requests allowed per rate-limit window, if advertised
+    pub rate_limit: Option<u64>,
+}
+
+/// Credentials kept between runs, so users only log in once
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct StoredCredentials {
+    /// API base URL the credentials belong to
+    pub base_url: Option<String>,
+
+    /// API key
+    pub api_key: Option<String>,
+
+    /// Authentication token, as returned by
+    /// [`create_auth_token`](RideWithGpsClient::create_auth_token)
+    pub auth_token: Option<String>,
+}
+
+impl StoredCredentials {
+    /// Credentials of a logged-in client
+    pub fn from_client(client: &RideWithGpsClient) -> Self {
+        Self {
+            base_url: Some(client.base_url.to_string()),
+            api_key: Some(client.api_key.clone()),
+            auth_token: client.auth_token.clone(),
+        }
+    }
+}
+
+/// Storage for [`StoredCredentials`] between runs
+///
+/// Implement it on top of the platform keyring or the application's own
+/// settings; [`FileCredentialStore`] keeps them in a file.
+pub trait CredentialStore {
+    /// The saved credentials, or `None` before the first login
+    fn load(&self) -> Result<Option<StoredCredentials>>;
+
+    /// Replace the saved credentials
+    fn save(&self, credentials: &StoredCredentials) -> Result<()>;
+}
+
+/// A [`CredentialStore`] keeping the credentials in a JSON file
+///
+/// On Unix the file is only readable by the current user.
+#[derive(Debug, Clone)]
+pub struct FileCredentialStore {
+    path: PathBuf,
+}
+
+impl FileCredentialStore {
+    /// Keep the credentials in the file at `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Keep the credentials in the default location
+    ///
+    /// That is `$RWGPS_CONFIG` if set, otherwise `rwgps/credentials.json`
+    /// in `$XDG_CONFIG_HOME` (defaulting to `~/.config`). Returns `None`
+    /// if neither that nor `$HOME` is set.
+    pub fn default_location() -> Option<Self> {
+        if let Some(path) = std::env::var_os("RWGPS_CONFIG") {
+            return Some(Self::new(path));
+        }
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(Self::new(config_dir.join("rwgps").join("credentials.json")))
+    }
+
+    /// Path of the credentials file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl CredentialStore for FileCredentialStore {
+    fn load(&self) -> Result<Option<StoredCredentials>> {
+        match std::fs::read(&self.path) {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, credentials: &StoredCredentials) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Restrict the new file before the token is written to it
+        let tmp = self.path.with_extension("tmp");
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&tmp)?;
+        file.write_all(&serde_json::to_vec_pretty(credentials)?)?;
+        drop(file);
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
 impl RideWithGpsClient {
     /// Create an authentication token using email and password
     ///
@@ -49,13 +213,230 @@ impl RideWithGpsClient {
     /// println!("Auth token: {}", auth.auth_token);
     /// ```
     pub fn create_auth_token(&self, email: &str, password: &str) -> Result<AuthToken> {
-        let request = CreateAuthTokenRequest {
-            email: email.to_string(),
-            password: password.to_string(),
-        };
+        let request = CreateAuthTokenRequest::new(email, password);
 
         self.post("/api/v1/auth_tokens", &request)
     }
+
+    /// Check the configured credentials and report what they can do
+    ///
+    /// This performs a cheap authenticated request (and, if that succeeds,
+    /// a probe of an organization-only endpoint), so setup wizards can
+    /// validate credentials before doing real work. Without an auth token
+    /// only organization access can be probed. Rejected credentials
+    /// are reported through [`Capabilities::valid_credentials`] rather than
+    /// as an error; other failures such as network errors are returned as
+    /// errors.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let caps = client.verify().unwrap();
+    /// if !caps.valid_credentials {
+    ///     eprintln!("Invalid API key or auth token");
+    /// } else if let Some(user) = caps.user {
+    ///     println!("Logged in as {:?}", user.name);
+    /// }
+    /// ```
+    pub fn verify(&self) -> Result<Capabilities> {
+        let (user, headers) =
+            match self.get_wrapped_with_headers::<User>("/api/v1/users/current.json", "user") {
+                Ok((user, headers)) => (Some(user), headers),
+                // Without an auth token there is no current user to fetch, so
+                // this response says nothing conclusive about the API key.
+                Err(Error::AuthError(_)) | Err(Error::NotFound(_)) if self.auth_token.is_none() => {
+                    (None, Default::default())
+                }
+                Err(Error::AuthError(_)) | Err(Error::Forbidden(_)) => {
+                    return Ok(Capabilities {
+                        valid_credentials: false,
+                        user: None,
+                        organization_access: false,
+                        rate_limit_tier: None,
+                        rate_limit: None,
+                    });
+                }
+                Err(e) => return Err(e),
+            };
+
+        let organization_access = match self
+            .get_path::<serde_json::Value>("/api/v1/members.json?page_size=1")
+        {
+            Ok(_) => true,
+            Err(Error::AuthError(_)) | Err(Error::Forbidden(_)) | Err(Error::NotFound(_)) => false,
+            Err(e) => return Err(e),
+        };
+
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+
+        Ok(Capabilities {
+            valid_credentials: true,
+            user,
+            organization_access,
+            rate_limit_tier: header("x-rwgps-api-tier"),
+            rate_limit: header("x-ratelimit-limit").and_then(|v| v.parse().ok()),
+        })
+    }
+
+    /// Check that the API is up, and measure how long it takes to answer
+    ///
+    /// Sends a single unauthenticated request to a cheap endpoint, bypassing
+    /// the retry policy and circuit breaker, and returns the round-trip
+    /// time. Since no credentials are sent, a failure means the API is
+    /// unreachable or unhealthy rather than that the credentials are bad;
+    /// combine it with [`verify`](Self::verify) to tell the two apart.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// match client.ping() {
+    ///     Err(e) => eprintln!("RideWithGPS is down: {}", e),
+    ///     Ok(_) if !client.verify().unwrap().valid_credentials => {
+    ///         eprintln!("RideWithGPS is up, but the credentials were rejected")
+    ///     }
+    ///     Ok(latency) => println!("OK in {:?}", latency),
+    /// }
+    /// ```
+    pub fn ping(&self) -> Result<Duration> {
+        let url = self.base_url.join("/api/v1/ping.json")?;
+        let started = Instant::now();
+        let response = self.send(&Method::GET, &url, None, false, None, None)?;
+        let latency = started.elapsed();
+
+        match response.status {
+            200..=299 => Ok(latency),
+            status => Err(self.error_from_status(status, &response.text())),
+        }
+    }
+
+    /// Revoke the authentication token the client is currently using
+    ///
+    /// The token stays set on the client; use [`logout`](Self::logout) to
+    /// revoke and clear it in one step.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// client.revoke_auth_token().unwrap();
+    /// ```
+    pub fn revoke_auth_token(&self) -> Result<()> {
+        if self.auth_token.is_none() {
+            return Err(Error::AuthError("No auth token set".to_string()));
+        }
+        self.delete_path("/api/v1/auth_tokens/current.json")
+    }
+
+    /// List the active authentication tokens of the authenticated user
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let tokens = client.list_auth_tokens().unwrap();
+    /// for token in tokens.results {
+    ///     println!("{} {:?} last used {:?}", token.id, token.name, token.last_used_at);
+    /// }
+    /// ```
+    pub fn list_auth_tokens(&self) -> Result<PaginatedResponse<AuthTokenInfo>> {
+        self.get_path("/api/v1/auth_tokens.json")
+    }
+
+    /// Revoke a specific authentication token
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The token ID, as returned by [`list_auth_tokens`](Self::list_auth_tokens)
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// // Revoke every token except the one in use
+    /// for token in client.list_auth_tokens().unwrap().results {
+    ///     if token.current != Some(true) {
+    ///         client.revoke_auth_token_by_id(token.id).unwrap();
+    ///     }
+    /// }
+    /// ```
+    pub fn revoke_auth_token_by_id(&self, id: u64) -> Result<()> {
+        self.delete_path(&format!("/api/v1/auth_tokens/{}.json", id))
+    }
+
+    /// Revoke the current authentication token and clear it from the client
+    ///
+    /// If the server reports the token as already invalid, it is cleared
+    /// anyway and the call succeeds. Calling this on a client without a
+    /// token is a no-op.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let mut client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// client.logout().unwrap();
+    /// assert!(client.auth_token().is_none());
+    /// ```
+    pub fn logout(&mut self) -> Result<()> {
+        if self.auth_token.is_none() {
+            return Ok(());
+        }
+        match self.revoke_auth_token() {
+            Ok(()) | Err(Error::AuthError(_)) => {
+                self.auth_token = None;
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -73,4 +454,70 @@ mod tests {
         assert!(json.contains("test@example.com"));
         assert!(json.contains("password123"));
     }
+
+    #[test]
+    fn test_auth_token_info_deserialization() {
+        let json = r#"{
+            "results": [
+                {"id": 1, "token_prefix": "ab12", "name": "Garmin sync", "current": false},
+                {"id": 2, "token_prefix": "cd34", "current": true, "last_used_at": "2025-05-01T10:00:00Z"}
+            ],
+            "record_count": 2
+        }"#;
+
+        let tokens: PaginatedResponse<AuthTokenInfo> = serde_json::from_str(json).unwrap();
+        assert_eq!(tokens.results.len(), 2);
+        assert_eq!(tokens.results[0].name.as_deref(), Some("Garmin sync"));
+        assert_eq!(tokens.results[1].current, Some(true));
+    }
+
+    #[test]
+    fn test_logout_without_token() {
+        let mut client = RideWithGpsClient::new("https://ridewithgps.com", "test-api-key", None);
+
+        assert!(matches!(
+            client.revoke_auth_token(),
+            Err(Error::AuthError(_))
+        ));
+        assert!(client.logout().is_ok());
+        assert_eq!(client.auth_token(), None);
+    }
+
+    #[test]
+    fn test_file_credential_store() {
+        let dir = std::env::temp_dir().join(format!("rwgps-credentials-{}", std::process::id()));
+        let store = FileCredentialStore::new(dir.join("rwgps").join("credentials.json"));
+        assert_eq!(store.load().unwrap(), None);
+
+        let client =
+            RideWithGpsClient::new("https://ridewithgps.com", "test-api-key", Some("token"));
+        let credentials = StoredCredentials::from_client(&client);
+        store.save(&credentials).unwrap();
+        assert_eq!(store.load().unwrap(), Some(credentials));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(store.path())
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_ping() {
+        use crate::testing::FakeServer;
+
+        let server = FakeServer::start();
+        let client = server.client();
+        client.ping().unwrap();
+        assert_eq!(server.requests()[0].path, "/api/v1/ping.json");
+        assert_eq!(server.requests()[0].header("x-rwgps-api-key"), None);
+
+        server.mock("GET", "/api/v1/ping.json", 503, serde_json::json!({}));
+        assert!(client.ping().is_err());
+    }
 }
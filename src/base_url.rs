@@ -0,0 +1,155 @@
+//! Well-known API base URLs
+
+use crate::{Error, RideWithGpsClient};
+use std::fmt;
+use std::str::FromStr;
+use url::Url;
+
+/// The RideWithGPS environment a client talks to
+///
+/// Parses from `"production"`, `"staging"` or any absolute URL, so test
+/// suites and tools can switch environments through configuration.
+///
+/// # Example
+///
+/// ```rust
+/// use ridewithgps_client::BaseUrl;
+///
+/// let base_url: BaseUrl = "staging".parse().unwrap();
+/// assert_eq!(base_url, BaseUrl::Staging);
+///
+/// let base_url: BaseUrl = "http://localhost:3000".parse().unwrap();
+/// assert_eq!(base_url.as_str(), "http://localhost:3000");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum BaseUrl {
+    /// The production API at `https://ridewithgps.com`
+    #[default]
+    Production,
+
+    /// The staging API at `https://staging.ridewithgps.com`
+    Staging,
+
+    /// Any other server, such as a local test server
+    Custom(String),
+}
+
+impl BaseUrl {
+    /// Base URL of the production API
+    pub const PRODUCTION: &'static str = "https://ridewithgps.com";
+
+    /// Base URL of the staging API
+    pub const STAGING: &'static str = "https://staging.ridewithgps.com";
+
+    /// The base URL as a string
+    pub fn as_str(&self) -> &str {
+        match self {
+            BaseUrl::Production => Self::PRODUCTION,
+            BaseUrl::Staging => Self::STAGING,
+            BaseUrl::Custom(url) => url,
+        }
+    }
+}
+
+impl AsRef<str> for BaseUrl {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for BaseUrl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BaseUrl {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "production" => return Ok(BaseUrl::Production),
+            "staging" => return Ok(BaseUrl::Staging),
+            _ => {}
+        }
+        let url = s.trim().trim_end_matches('/');
+        Url::parse(url)?;
+        Ok(match url {
+            Self::PRODUCTION => BaseUrl::Production,
+            Self::STAGING => BaseUrl::Staging,
+            _ => BaseUrl::Custom(url.to_string()),
+        })
+    }
+}
+
+impl RideWithGpsClient {
+    /// Create a client for the production API
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - Your API key
+    /// * `auth_token` - Optional authentication token for user-specific operations
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::production("your-api-key", Some("your-auth-token"));
+    /// ```
+    pub fn production(api_key: &str, auth_token: Option<&str>) -> Self {
+        Self::new(BaseUrl::PRODUCTION, api_key, auth_token)
+    }
+
+    /// Create a client for the staging API
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - Your API key for the staging environment
+    /// * `auth_token` - Optional authentication token for user-specific operations
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::staging("your-api-key", None);
+    /// ```
+    pub fn staging(api_key: &str, auth_token: Option<&str>) -> Self {
+        Self::new(BaseUrl::STAGING, api_key, auth_token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            "Production".parse::<BaseUrl>().unwrap(),
+            BaseUrl::Production
+        );
+        assert_eq!(
+            "https://ridewithgps.com/".parse::<BaseUrl>().unwrap(),
+            BaseUrl::Production
+        );
+        assert_eq!(
+            "https://staging.ridewithgps.com"
+                .parse::<BaseUrl>()
+                .unwrap(),
+            BaseUrl::Staging
+        );
+        assert_eq!(
+            "http://127.0.0.1:8080/".parse::<BaseUrl>().unwrap(),
+            BaseUrl::Custom("http://127.0.0.1:8080".to_string())
+        );
+        assert!("ridewithgps.com".parse::<BaseUrl>().is_err());
+    }
+
+    #[test]
+    fn test_constructors() {
+        let client = RideWithGpsClient::staging("key", None);
+        assert_eq!(client.base_url.as_str(), "https://staging.ridewithgps.com/");
+    }
+}
@@ -0,0 +1,373 @@
+//! Running many API calls concurrently
+
+use crate::resource::BATCH_CONCURRENCY;
+use crate::{Error, ItemType, Resource, Result, RideWithGpsClient, Route, Trip, Visibility};
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+type Call<'a, T> = Box<dyn FnOnce(&RideWithGpsClient) -> Result<T> + Send + 'a>;
+
+/// How far a [`Batch`] has got, as passed to its progress callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchProgress {
+    /// Number of calls that have finished, successfully or not
+    pub completed: usize,
+
+    /// Number of finished calls that failed
+    pub failed: usize,
+
+    /// Number of calls in the batch
+    pub total: usize,
+}
+
+/// Queued API calls, run a few at a time by [`run`](Self::run)
+///
+/// Created by [`RideWithGpsClient::batch`]. Every call produces a `T`; to
+/// mix calls returning different types, use an enum with `From`
+/// implementations for them.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ridewithgps_client::{RideWithGpsClient, Trip};
+///
+/// let client = RideWithGpsClient::new(
+///     "https://ridewithgps.com",
+///     "your-api-key",
+///     Some("your-auth-token")
+/// );
+///
+/// let results = client
+///     .batch::<Trip>()
+///     .concurrency(8)
+///     .get_trip(1)
+///     .get_trip(2)
+///     .call(|client| client.get_trip(3))
+///     .on_progress(|p| eprintln!("{}/{} ({} failed)", p.completed, p.total, p.failed))
+///     .run();
+/// for result in results {
+///     match result {
+///         Ok(trip) => println!("{}", trip),
+///         Err(e) => eprintln!("{}", e),
+///     }
+/// }
+/// ```
+pub struct Batch<'a, T> {
+    client: &'a RideWithGpsClient,
+    calls: Vec<Call<'a, T>>,
+    concurrency: usize,
+    progress: Option<Box<dyn Fn(BatchProgress) + Sync + 'a>>,
+}
+
+impl<'a, T: Send> Batch<'a, T> {
+    /// Queue a call
+    pub fn call(mut self, call: impl FnOnce(&RideWithGpsClient) -> Result<T> + Send + 'a) -> Self {
+        self.calls.push(Box::new(call));
+        self
+    }
+
+    /// Queue fetching a resource of any type
+    pub fn get<R>(self, id: R::Id) -> Self
+    where
+        R: Resource + Into<T>,
+        R::Id: Send + 'a,
+    {
+        self.call(move |client| client.get::<R>(id).map(Into::into))
+    }
+
+    /// Queue fetching a route
+    pub fn get_route(self, id: u64) -> Self
+    where
+        Route: Into<T>,
+    {
+        self.call(move |client| client.get_route(id).map(Into::into))
+    }
+
+    /// Queue fetching a trip
+    pub fn get_trip(self, id: u64) -> Self
+    where
+        Trip: Into<T>,
+    {
+        self.call(move |client| client.get_trip(id).map(Into::into))
+    }
+
+    /// Number of calls to run at the same time (default 4)
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Report progress after every finished call
+    ///
+    /// The callback is called from the threads running the calls, one call
+    /// at a time.
+    pub fn on_progress(mut self, progress: impl Fn(BatchProgress) + Sync + 'a) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Number of queued calls
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// Whether no calls are queued
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Run the queued calls, returning their results in the order they were
+    /// queued
+    ///
+    /// A failed call does not affect the others. Rate limiting is handled
+    /// by the client's [`retry`](crate::ClientBuilder::retry) policy, if it
+    /// has one.
+    pub fn run(self) -> Vec<Result<T>> {
+        let total = self.calls.len();
+        let calls: Vec<Mutex<Option<Call<'a, T>>>> = self
+            .calls
+            .into_iter()
+            .map(|c| Mutex::new(Some(c)))
+            .collect();
+        let next = AtomicUsize::new(0);
+        let counts = Mutex::new((0, 0));
+        let client = self.client;
+        let progress = self.progress.as_deref();
+
+        let worker = || {
+            let mut results = Vec::new();
+            loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(call) = calls.get(i) else {
+                    break results;
+                };
+                let call = call.lock().unwrap().take().expect("batch call run twice");
+                let result = call(client);
+
+                let mut counts = counts.lock().unwrap();
+                counts.0 += 1;
+                counts.1 += usize::from(result.is_err());
+                if let Some(progress) = progress {
+                    progress(BatchProgress {
+                        completed: counts.0,
+                        failed: counts.1,
+                        total,
+                    });
+                }
+                results.push((i, result));
+            }
+        };
+
+        let mut results: Vec<(usize, Result<T>)> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.concurrency.min(total))
+                .map(|_| scope.spawn(worker))
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|w| w.join().expect("batch worker panicked"))
+                .collect()
+        });
+        results.sort_by_key(|(i, _)| *i);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+}
+
+impl RideWithGpsClient {
+    /// Start a batch of calls to run concurrently
+    ///
+    /// See [`Batch`].
+    pub fn batch<T: Send>(&self) -> Batch<'_, T> {
+        Batch {
+            client: self,
+            calls: Vec::new(),
+            concurrency: BATCH_CONCURRENCY,
+            progress: None,
+        }
+    }
+
+    /// Change the visibility of many routes or trips
+    ///
+    /// The updates run a few at a time, as a [`Batch`]. A failed update does
+    /// not affect the others; the result of each is returned with its ID, in
+    /// the order of `ids`.
+    ///
+    /// # Arguments
+    ///
+    /// * `item_type` - [`ItemType::Route`] or [`ItemType::Trip`]
+    /// * `ids` - The routes or trips to update
+    /// * `visibility` - The new visibility
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{ItemType, RideWithGpsClient, Route, Visibility};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// // Make every route from before 2020 private
+    /// let old: Vec<u64> = client
+    ///     .paginate::<Route>(None)
+    ///     .unwrap()
+    ///     .filter_map(|route| route.ok())
+    ///     .filter(|route| route.created_at.as_deref().is_some_and(|t| t < "2020"))
+    ///     .map(|route| route.id)
+    ///     .collect();
+    /// for (id, result) in client.set_visibility_bulk(ItemType::Route, &old, Visibility::Private) {
+    ///     if let Err(e) = result {
+    ///         eprintln!("Route {}: {}", id, e);
+    ///     }
+    /// }
+    /// ```
+    pub fn set_visibility_bulk(
+        &self,
+        item_type: ItemType,
+        ids: &[u64],
+        visibility: Visibility,
+    ) -> Vec<(u64, Result<()>)> {
+        #[derive(Serialize)]
+        struct VisibilityUpdate {
+            visibility: Visibility,
+        }
+
+        let collection = match item_type {
+            ItemType::Route => "routes",
+            ItemType::Trip => "trips",
+            _ => {
+                return ids
+                    .iter()
+                    .map(|&id| {
+                        let message = format!("Cannot change the visibility of a {:?}", item_type);
+                        (id, Err(Error::ValidationError(message)))
+                    })
+                    .collect()
+            }
+        };
+
+        let mut batch = self.batch::<()>();
+        for &id in ids {
+            batch = batch.call(move |client| {
+                let _: serde_json::Value = client.put(
+                    &format!("/api/v1/{}/{}.json", collection, id),
+                    &VisibilityUpdate { visibility },
+                )?;
+                Ok(())
+            });
+        }
+        ids.iter().copied().zip(batch.run()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_order_and_progress() {
+        let client = RideWithGpsClient::new("https://ridewithgps.com", "key", None);
+        let seen = Mutex::new(Vec::new());
+
+        let mut batch = client.batch::<u64>().concurrency(3);
+        for i in 0..10 {
+            batch = batch.call(move |_| match i {
+                4 => Err(crate::Error::NotFound("gone".to_string())),
+                i => Ok(i * 10),
+            });
+        }
+        assert_eq!(batch.len(), 10);
+        let results = batch.on_progress(|p| seen.lock().unwrap().push(p)).run();
+
+        assert_eq!(results.len(), 10);
+        assert_eq!(results[3].as_ref().ok(), Some(&30));
+        assert!(results[4].is_err());
+        assert_eq!(results[9].as_ref().ok(), Some(&90));
+
+        let seen = seen.into_inner().unwrap();
+        let completed: Vec<_> = seen.iter().map(|p| p.completed).collect();
+        assert_eq!(completed, (1..=10).collect::<Vec<_>>());
+        assert_eq!(
+            seen.last(),
+            Some(&BatchProgress {
+                completed: 10,
+                failed: 1,
+                total: 10
+            })
+        );
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_batch_fetches() {
+        use crate::testing::FakeServer;
+
+        enum Item {
+            Route(Box<Route>),
+            Trip(Box<Trip>),
+        }
+        impl From<Route> for Item {
+            fn from(route: Route) -> Self {
+                Item::Route(Box::new(route))
+            }
+        }
+        impl From<Trip> for Item {
+            fn from(trip: Trip) -> Self {
+                Item::Trip(Box::new(trip))
+            }
+        }
+
+        let server = FakeServer::start();
+        let client = server.client();
+        let results = client
+            .batch::<Item>()
+            .get_route(1)
+            .get_trip(2)
+            .get::<Trip>(crate::TripId(3))
+            .run();
+
+        assert!(matches!(&results[0], Ok(Item::Route(r)) if r.id == 1));
+        assert!(matches!(&results[1], Ok(Item::Trip(t)) if t.id == 2));
+        assert!(matches!(&results[2], Ok(Item::Trip(t)) if t.id == 3));
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_set_visibility_bulk() {
+        use crate::testing::FakeServer;
+
+        let server = FakeServer::start();
+        let client = server.client();
+        server.mock(
+            "PUT",
+            "/api/v1/trips/{id}.json",
+            200,
+            serde_json::json!({"trip": {"id": 1}}),
+        );
+        server.mock("PUT", "/api/v1/trips/3.json", 404, serde_json::json!({}));
+
+        let results = client.set_visibility_bulk(ItemType::Trip, &[1, 2, 3], Visibility::Private);
+        let ids: Vec<u64> = results.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert!(results[0].1.is_ok());
+        assert!(results[1].1.is_ok());
+        assert!(matches!(results[2].1, Err(crate::Error::NotFound(_))));
+
+        let puts: Vec<_> = server
+            .requests()
+            .into_iter()
+            .filter(|r| r.method == "PUT")
+            .collect();
+        assert_eq!(puts.len(), 3);
+        let body: serde_json::Value = serde_json::from_slice(&puts[0].body).unwrap();
+        assert_eq!(body, serde_json::json!({"visibility": "private"}));
+
+        let results = client.set_visibility_bulk(ItemType::Event, &[4], Visibility::Private);
+        assert!(matches!(
+            results[0].1,
+            Err(crate::Error::ValidationError(_))
+        ));
+    }
+}
@@ -0,0 +1,414 @@
+//! Command-line client for the RideWithGPS API
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use ridewithgps_client::{
+    BaseUrl, CredentialStore, Event, FileCredentialStore, ListEventsParams, ListRoutesParams,
+    ListTripsParams, MirrorContent, MirrorReport, Resource, RideWithGpsClient, Route, RouteId,
+    RouteRequest, StoredCredentials, Trip, TripId, UnitSystem,
+};
+use serde::Serialize;
+use std::error::Error;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+type CliResult<T = ()> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(name = "rwgps", version, about = "Command-line client for RideWithGPS")]
+struct Cli {
+    /// API base URL, or `production` or `staging`
+    #[arg(long, global = true, env = "RWGPS_BASE_URL")]
+    base_url: Option<String>,
+
+    /// API key (defaults to the one saved by `rwgps login`)
+    #[arg(long, global = true, env = "RWGPS_API_KEY", hide_env_values = true)]
+    api_key: Option<String>,
+
+    /// Print raw JSON instead of one-line summaries
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Use miles and feet in summaries
+    #[arg(long, global = true)]
+    imperial: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Log in with email and password and save the auth token
+    Login {
+        /// Account email address
+        #[arg(long)]
+        email: Option<String>,
+    },
+
+    /// Work with your routes
+    #[command(subcommand)]
+    Routes(ListCommand),
+
+    /// Work with a single route
+    #[command(subcommand)]
+    Route(GetCommand),
+
+    /// Work with your trips
+    #[command(subcommand)]
+    Trips(ListCommand),
+
+    /// Work with a single trip
+    #[command(subcommand)]
+    Trip(GetCommand),
+
+    /// Work with events
+    #[command(subcommand)]
+    Events(ListCommand),
+
+    /// Create a route from a JSON file as written by `rwgps route get`
+    Upload {
+        /// Route JSON file
+        file: PathBuf,
+
+        /// Name for the new route (defaults to the name in the file)
+        #[arg(long)]
+        name: Option<String>,
+    },
+
+    /// Download all routes, trips and photos that are new or changed
+    Backup(MirrorArgs),
+
+    /// Apply changes since the last backup or sync, including deletions
+    Sync(MirrorArgs),
+}
+
+#[derive(Args)]
+struct MirrorArgs {
+    /// Directory to store the backup in
+    dir: PathBuf,
+
+    /// What to include; photos are those of the included routes and trips
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "routes,trips,photos"
+    )]
+    include: Vec<Content>,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Content {
+    Routes,
+    Trips,
+    Photos,
+}
+
+impl MirrorArgs {
+    /// What to include; photos are only downloaded along with the routes
+    /// and trips they belong to
+    fn content(&self) -> CliResult<MirrorContent> {
+        let content = MirrorContent {
+            routes: self.include.contains(&Content::Routes),
+            trips: self.include.contains(&Content::Trips),
+            photos: self.include.contains(&Content::Photos),
+        };
+        if content.photos && !content.routes && !content.trips {
+            return Err(
+                "--include photos also needs routes or trips, whose photos are downloaded".into(),
+            );
+        }
+        Ok(content)
+    }
+}
+
+#[derive(Subcommand)]
+enum ListCommand {
+    /// List items, in the order the API returns them
+    List(ListArgs),
+}
+
+#[derive(Args)]
+struct ListArgs {
+    /// Only include items whose name matches
+    #[arg(long)]
+    name: Option<String>,
+
+    /// Maximum number of items to list
+    #[arg(long, default_value_t = 50)]
+    limit: usize,
+}
+
+#[derive(Subcommand)]
+enum GetCommand {
+    /// Fetch an item by ID
+    Get(GetArgs),
+}
+
+#[derive(Args)]
+struct GetArgs {
+    /// ID or web URL of the route or trip
+    id: String,
+
+    /// Write GPX instead of JSON
+    #[arg(long)]
+    gpx: bool,
+
+    /// Write to this file instead of standard output
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(e) = run(cli) {
+        eprintln!("rwgps: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> CliResult {
+    let store = FileCredentialStore::default_location();
+    let credentials = match &store {
+        Some(store) => store.load()?.unwrap_or_default(),
+        None => StoredCredentials::default(),
+    };
+    let base_url = cli
+        .base_url
+        .clone()
+        .or_else(|| credentials.base_url.clone())
+        .map(|s| s.parse::<BaseUrl>())
+        .transpose()?
+        .unwrap_or_default();
+    let api_key = cli
+        .api_key
+        .clone()
+        .or_else(|| credentials.api_key.clone())
+        .ok_or("no API key; pass --api-key or set RWGPS_API_KEY")?;
+    if let Command::Login { email } = &cli.command {
+        let store = store.ok_or("cannot determine config directory; set RWGPS_CONFIG")?;
+        return login(&store, base_url.as_str(), &api_key, email.as_deref());
+    }
+
+    let client = RideWithGpsClient::new(
+        base_url.as_str(),
+        &api_key,
+        credentials.auth_token.as_deref(),
+    );
+    execute(&client, cli)
+}
+
+fn execute(client: &RideWithGpsClient, cli: Cli) -> CliResult {
+    let units = if cli.imperial {
+        UnitSystem::Imperial
+    } else {
+        UnitSystem::Metric
+    };
+
+    match cli.command {
+        Command::Login { .. } => unreachable!(),
+        Command::Routes(ListCommand::List(args)) => {
+            let mut params = ListRoutesParams::new();
+            if let Some(name) = &args.name {
+                params = params.name(name);
+            }
+            let routes = collect::<Route>(client, &params, args.limit)?;
+            print_list(&routes, cli.json, |r| (r.id, r.summary(units)))
+        }
+        Command::Trips(ListCommand::List(args)) => {
+            let mut params = ListTripsParams::new();
+            if let Some(name) = &args.name {
+                params = params.name(name);
+            }
+            let trips = collect::<Trip>(client, &params, args.limit)?;
+            print_list(&trips, cli.json, |t| (t.id, t.summary(units)))
+        }
+        Command::Events(ListCommand::List(args)) => {
+            let mut params = ListEventsParams::new();
+            if let Some(name) = &args.name {
+                params = params.name(name);
+            }
+            let events = collect::<Event>(client, &params, args.limit)?;
+            print_list(&events, cli.json, |e| (e.id, e.summary()))
+        }
+        Command::Route(GetCommand::Get(args)) => {
+            let route = client.get_route(args.id.parse::<RouteId>()?.0)?;
+            write_output(args.output.as_ref(), |mut w| {
+                if args.gpx {
+                    route.write_gpx(&mut w)
+                } else {
+                    write_json(w, &route)
+                }
+            })
+        }
+        Command::Trip(GetCommand::Get(args)) => {
+            let trip = client.get_trip(args.id.parse::<TripId>()?.0)?;
+            write_output(args.output.as_ref(), |mut w| {
+                if args.gpx {
+                    trip.write_gpx(&mut w)
+                } else {
+                    write_json(w, &trip)
+                }
+            })
+        }
+        Command::Upload { file, name } => {
+            let route: Route = serde_json::from_slice(&std::fs::read(&file)?)?;
+            let mut request = RouteRequest::from(&route);
+            if let Some(name) = name {
+                request = request.name(name);
+            }
+            let created = client.create_route(&request)?;
+            println!("Created route {}: {}", created.id, created.summary(units));
+            Ok(())
+        }
+        Command::Backup(args) => {
+            let report = client.mirror(&args.dir).content(args.content()?).backup()?;
+            print_report(&report)
+        }
+        Command::Sync(args) => {
+            let report = client.mirror(&args.dir).content(args.content()?).sync()?;
+            print_report(&report)
+        }
+    }
+}
+
+fn login(
+    store: &FileCredentialStore,
+    base_url: &str,
+    api_key: &str,
+    email: Option<&str>,
+) -> CliResult {
+    let email = match email {
+        Some(email) => email.to_string(),
+        None => prompt("Email: ")?,
+    };
+    let password = match std::env::var("RWGPS_PASSWORD") {
+        Ok(password) => password,
+        Err(_) => rpassword::prompt_password("Password: ")?,
+    };
+
+    let client = RideWithGpsClient::with_credentials(base_url, api_key, &email, &password)?;
+    store.save(&StoredCredentials::from_client(&client))?;
+    println!("Logged in; credentials saved to {}", store.path().display());
+    Ok(())
+}
+
+fn print_report(report: &MirrorReport) -> CliResult {
+    for (item_type, id, error) in &report.failed {
+        eprintln!("failed to download {:?} {}: {}", item_type, id, error);
+    }
+    println!("{}", report);
+    if report.failed.is_empty() {
+        Ok(())
+    } else {
+        Err("some items could not be downloaded; run again to retry".into())
+    }
+}
+
+fn prompt(label: &str) -> CliResult<String> {
+    eprint!("{}", label);
+    std::io::stderr().flush()?;
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Fetch items until `limit` have been collected or the results run out
+fn collect<R: Resource>(
+    client: &RideWithGpsClient,
+    params: &R::ListParams,
+    limit: usize,
+) -> ridewithgps_client::Result<Vec<R>> {
+    client.paginate::<R>(Some(params))?.take(limit).collect()
+}
+
+fn print_list<T: Serialize>(
+    items: &[T],
+    json: bool,
+    line: impl Fn(&T) -> (u64, String),
+) -> CliResult {
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    if json {
+        write_json(&mut out, &items)?;
+    } else {
+        for item in items {
+            let (id, summary) = line(item);
+            writeln!(out, "{:>10}  {}", id, summary)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_json<W: Write + ?Sized, T: Serialize + ?Sized>(
+    writer: &mut W,
+    value: &T,
+) -> std::io::Result<()> {
+    serde_json::to_writer_pretty(&mut *writer, value)?;
+    writeln!(writer)
+}
+
+fn write_output(
+    path: Option<&PathBuf>,
+    write: impl FnOnce(&mut dyn Write) -> std::io::Result<()>,
+) -> CliResult {
+    match path {
+        Some(path) => {
+            let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+            write(&mut file)?;
+            file.flush()?;
+        }
+        None => write(&mut std::io::stdout().lock())?,
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use ridewithgps_client::testing::FakeServer;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from(std::iter::once("rwgps").chain(args.iter().copied())).unwrap()
+    }
+
+    #[test]
+    fn test_backup() {
+        let server = FakeServer::start();
+        let client = server.client();
+        let dir = std::env::temp_dir().join(format!("rwgps-cli-backup-{}", std::process::id()));
+        let dir_arg = dir.to_str().unwrap();
+
+        execute(&client, parse(&["backup", dir_arg])).unwrap();
+        for file in [
+            "routes/101.json",
+            "routes/102.gpx",
+            "trips/201.json",
+            "trips/202.gpx",
+        ] {
+            assert!(dir.join(file).exists(), "{} missing", file);
+        }
+
+        // An incremental run only asks what changed
+        let before = server.requests().len();
+        execute(&client, parse(&["sync", dir_arg])).unwrap();
+        let requests = server.requests();
+        assert!(requests[before].path.starts_with("/api/v1/sync.json"));
+        assert!(dir.join("trips/201.json").exists());
+        assert!(!dir.join("trips/199.json").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_include_photos_only() {
+        let server = FakeServer::start();
+        let dir = std::env::temp_dir().join(format!("rwgps-cli-photos-{}", std::process::id()));
+        let cli = parse(&["backup", dir.to_str().unwrap(), "--include", "photos"]);
+
+        let error = execute(&server.client(), cli).unwrap_err();
+        assert!(error.to_string().contains("routes or trips"));
+        assert!(server.requests().is_empty());
+        assert!(!dir.exists());
+    }
+}
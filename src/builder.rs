@@ -0,0 +1,375 @@
+//! Builder for configuring a client
+
+use crate::circuit::Breaker;
+use crate::drift::DriftHook;
+use crate::telemetry::ErrorHook;
+use crate::{CircuitBreaker, FailedRequest, Result, RetryPolicy, RideWithGpsClient, UnknownFields};
+use reqwest::blocking::Client;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+/// Builder for a [`RideWithGpsClient`] with non-default settings
+///
+/// Created with [`RideWithGpsClient::builder`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ridewithgps_client::RideWithGpsClient;
+///
+/// let client = RideWithGpsClient::builder("https://ridewithgps.com", "your-api-key")
+///     .auth_token("your-auth-token")
+///     .strict(true)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClientBuilder {
+    base_url: String,
+    api_key: String,
+    auth_token: Option<String>,
+    strict: bool,
+    retry: Option<RetryPolicy>,
+    circuit_breaker: Option<CircuitBreaker>,
+    drift_hook: Option<DriftHook>,
+    error_hook: Option<ErrorHook>,
+    slow_request_threshold: Option<Duration>,
+    coalesce_requests: bool,
+    http2_prior_knowledge: bool,
+    pool_idle_timeout: Option<Option<Duration>>,
+    pool_max_idle_per_host: Option<usize>,
+    resolve: Vec<(String, SocketAddr)>,
+    max_response_size: Option<u64>,
+}
+
+impl ClientBuilder {
+    /// Authenticate requests with this auth token
+    pub fn auth_token(mut self, token: impl Into<String>) -> Self {
+        self.auth_token = Some(token.into());
+        self
+    }
+
+    /// Reject responses that do not match the crate's data model
+    ///
+    /// In strict mode, responses containing fields the crate does not know
+    /// about, or fields of an unexpected type, fail with
+    /// [`Error::SchemaMismatch`](crate::Error::SchemaMismatch) instead of
+    /// being silently accepted. This is meant for CI jobs that watch for API
+    /// drift; unknown fields inside flattened structures (such as pagination
+    /// details) are not detected.
+    ///
+    /// Defaults to off, or on when the `strict` feature is enabled.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Retry requests that fail for transient reasons
+    ///
+    /// See [`RetryPolicy`] for what is retried. POST requests get an
+    /// idempotency key so that retrying them cannot create duplicates.
+    ///
+    /// Defaults to no retries.
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Stop sending requests for a while after repeated API failures
+    ///
+    /// Meant for long-running processes such as sync daemons, so that they
+    /// back off during outages. See [`CircuitBreaker`] for the details.
+    ///
+    /// Defaults to no circuit breaker.
+    pub fn circuit_breaker(mut self, settings: CircuitBreaker) -> Self {
+        self.circuit_breaker = Some(settings);
+        self
+    }
+
+    /// Report fields in API responses that the crate's data model lacks
+    ///
+    /// The hook is called once for every successfully decoded response that
+    /// contains unknown fields, so maintainers and power users can notice
+    /// when the API starts returning something worth modelling. Unlike
+    /// [`strict`](Self::strict) mode, the response is still accepted.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::builder("https://ridewithgps.com", "your-api-key")
+    ///     .on_unknown_fields(|unknown| {
+    ///         eprintln!("{} ({}): {:?}", unknown.endpoint, unknown.type_name, unknown.fields);
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn on_unknown_fields(
+        mut self,
+        hook: impl Fn(&UnknownFields) + Send + Sync + 'static,
+    ) -> Self {
+        self.drift_hook = Some(DriftHook::new(hook));
+        self
+    }
+
+    /// Log a warning for every request attempt that takes longer than
+    /// `threshold`
+    ///
+    /// The warning is logged with the `log` crate, and carries the method,
+    /// path, status, duration and payload sizes both in its message and as
+    /// structured key-value pairs, for loggers that support them. Query
+    /// strings are left out of the path.
+    ///
+    /// Defaults to off.
+    pub fn log_slow_requests(mut self, threshold: Duration) -> Self {
+        self.slow_request_threshold = Some(threshold);
+        self
+    }
+
+    /// Fail requests whose response body is larger than `bytes`
+    ///
+    /// Such requests fail with
+    /// [`Error::ResponseTooLarge`](crate::Error::ResponseTooLarge) as soon
+    /// as the limit is passed, without buffering the rest of the body, and
+    /// are not retried. This protects memory-constrained processes from
+    /// unexpectedly large responses, such as trips with very many points.
+    ///
+    /// Defaults to no limit.
+    pub fn max_response_size(mut self, bytes: u64) -> Self {
+        self.max_response_size = Some(bytes);
+        self
+    }
+
+    /// Share one request between identical GETs running at the same time
+    ///
+    /// When several threads fetch the same URL concurrently, as sync code
+    /// following links between resources often does, only the first sends
+    /// a request; the others wait for its response and parse their own
+    /// copy. Requests that start after it finished are sent as usual. If the
+    /// shared request fails without a response, the waiting threads send
+    /// their own.
+    ///
+    /// Defaults to off.
+    pub fn coalesce_requests(mut self, coalesce: bool) -> Self {
+        self.coalesce_requests = coalesce;
+        self
+    }
+
+    /// Speak HTTP/2 from the start of every connection
+    ///
+    /// Over HTTPS, HTTP/2 is already used when the server offers it. This
+    /// skips that negotiation, and also makes plain HTTP connections use
+    /// HTTP/2, so that concurrent requests are multiplexed over a single
+    /// connection. Requests fail if the server does not speak HTTP/2.
+    ///
+    /// Defaults to off.
+    pub fn http2_prior_knowledge(mut self, enabled: bool) -> Self {
+        self.http2_prior_knowledge = enabled;
+        self
+    }
+
+    /// Close idle pooled connections after `timeout`, or never with `None`
+    ///
+    /// Longer timeouts keep connections warm between the bursts of requests
+    /// of a periodic sync.
+    ///
+    /// Defaults to 90 seconds.
+    pub fn pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Keep at most `max` idle connections per host in the pool
+    ///
+    /// Defaults to no limit.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Connect to `addr` for requests to `host`, instead of looking it up
+    ///
+    /// URLs, and so the `Host` header and TLS certificate checks, are left
+    /// unchanged; only the connection goes elsewhere. This lets integration
+    /// tests point a client with the real base URL at a local server, or
+    /// route traffic through an internal gateway. The port of a URL takes
+    /// precedence over the port of `addr`; use port 0 to connect to the
+    /// scheme's default port. May be called more than once.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::builder("https://ridewithgps.com", "your-api-key")
+    ///     .resolve("ridewithgps.com", "10.0.0.5:0".parse().unwrap())
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn resolve(mut self, host: impl Into<String>, addr: SocketAddr) -> Self {
+        self.resolve.push((host.into(), addr));
+        self
+    }
+
+    /// Call `hook` for every request attempt that fails
+    ///
+    /// The hook sees connection failures, timeouts, error responses
+    /// (status 400 and up, including the 404s behind `get_*_opt` methods)
+    /// and requests refused by an open circuit breaker, before the error is
+    /// returned or the request retried. Use it to count and alert on API
+    /// failures in one place.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::builder("https://ridewithgps.com", "your-api-key")
+    ///     .on_error(|failed| {
+    ///         eprintln!(
+    ///             "{} {} failed (status {:?}, attempt {}): {}",
+    ///             failed.method, failed.endpoint, failed.status, failed.attempt, failed.error
+    ///         );
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn on_error(mut self, hook: impl Fn(&FailedRequest) + Send + Sync + 'static) -> Self {
+        self.error_hook = Some(ErrorHook::new(hook));
+        self
+    }
+
+    /// Build the client
+    pub fn build(self) -> Result<RideWithGpsClient> {
+        let mut client = Client::builder();
+        if self.http2_prior_knowledge {
+            client = client.http2_prior_knowledge();
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            client = client.pool_idle_timeout(timeout);
+        }
+        if let Some(max) = self.pool_max_idle_per_host {
+            client = client.pool_max_idle_per_host(max);
+        }
+        for (host, addr) in &self.resolve {
+            client = client.resolve(host, *addr);
+        }
+
+        Ok(RideWithGpsClient {
+            client: client.build()?,
+            base_url: Url::parse(&self.base_url)?,
+            api_key: self.api_key,
+            auth_token: self.auth_token,
+            strict: self.strict,
+            retry: self.retry,
+            breaker: self.circuit_breaker.map(|c| Arc::new(Breaker::new(c))),
+            drift_hook: self.drift_hook,
+            error_hook: self.error_hook,
+            slow_request_threshold: self.slow_request_threshold,
+            max_response_size: self.max_response_size,
+            athlete: None,
+            inflight: self.coalesce_requests.then(Default::default),
+            #[cfg(feature = "replay")]
+            cassette: None,
+        })
+    }
+}
+
+impl RideWithGpsClient {
+    /// Start building a client with non-default settings
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - The base URL for the API
+    /// * `api_key` - Your API key
+    pub fn builder(base_url: &str, api_key: &str) -> ClientBuilder {
+        ClientBuilder {
+            base_url: base_url.to_string(),
+            api_key: api_key.to_string(),
+            auth_token: None,
+            strict: cfg!(feature = "strict"),
+            retry: None,
+            circuit_breaker: None,
+            drift_hook: None,
+            error_hook: None,
+            slow_request_threshold: None,
+            coalesce_requests: false,
+            http2_prior_knowledge: false,
+            pool_idle_timeout: None,
+            pool_max_idle_per_host: None,
+            resolve: Vec::new(),
+            max_response_size: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_connection_pool_options() {
+        use crate::testing::{FakeServer, FAKE_API_KEY};
+        use crate::RideWithGpsClient;
+        use std::time::Duration;
+
+        let server = FakeServer::start();
+        let client = RideWithGpsClient::builder(&server.url(), FAKE_API_KEY)
+            .pool_idle_timeout(Some(Duration::from_secs(300)))
+            .pool_max_idle_per_host(0)
+            .build()
+            .unwrap();
+        assert_eq!(client.get_route(1).unwrap().id, 1);
+        assert_eq!(client.get_route(2).unwrap().id, 2);
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_max_response_size() {
+        use crate::testing::{FakeServer, FAKE_API_KEY};
+        use crate::{Error, RideWithGpsClient};
+
+        let server = FakeServer::start();
+        server.mock(
+            "GET",
+            "/api/v1/trips/{id}.json",
+            200,
+            serde_json::json!({"trip": {"id": 1, "description": "x".repeat(2000)}}),
+        );
+        let client = RideWithGpsClient::builder(&server.url(), FAKE_API_KEY)
+            .max_response_size(1000)
+            .build()
+            .unwrap();
+
+        assert_eq!(client.get_route(1).unwrap().id, 1);
+        match client.get_trip(1) {
+            Err(Error::ResponseTooLarge { endpoint, limit }) => {
+                assert_eq!(endpoint, "/api/v1/trips/1.json");
+                assert_eq!(limit, 1000);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_resolve() {
+        use crate::testing::{FakeServer, FAKE_API_KEY};
+        use crate::RideWithGpsClient;
+
+        let server = FakeServer::start();
+        let base_url = format!("http://api.ridewithgps.test:{}", server.addr().port());
+        let client = RideWithGpsClient::builder(&base_url, FAKE_API_KEY)
+            .resolve("api.ridewithgps.test", server.addr())
+            .build()
+            .unwrap();
+        assert_eq!(client.get_route(1).unwrap().id, 1);
+        let request = server.requests().pop().unwrap();
+        assert_eq!(
+            request.header("host"),
+            Some(format!("api.ridewithgps.test:{}", server.addr().port()).as_str())
+        );
+    }
+}
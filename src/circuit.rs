@@ -0,0 +1,219 @@
+//! Circuit breaker that stops sending requests while the API is failing
+
+use crate::{Error, RawResponse, Result, RideWithGpsClient};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Settings for a circuit breaker that backs off during API outages
+///
+/// After [`failure_threshold`](Self::failure_threshold) consecutive
+/// failures (5xx responses, timeouts and connection errors) the circuit
+/// opens: requests fail immediately with [`Error::CircuitOpen`] without
+/// being sent. Once [`reset_timeout`](Self::reset_timeout) has passed, a
+/// single probe request is let through; if it succeeds the circuit closes
+/// again, otherwise it stays open for another `reset_timeout`.
+///
+/// Enable with [`ClientBuilder::circuit_breaker`](crate::ClientBuilder::circuit_breaker).
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct CircuitBreaker {
+    /// Number of consecutive failures that opens the circuit
+    pub failure_threshold: u32,
+
+    /// How long the circuit stays open before a probe request is allowed
+    pub reset_timeout: Duration,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            reset_timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// Create a circuit breaker with the default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set [`failure_threshold`](Self::failure_threshold)
+    pub fn failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold;
+        self
+    }
+
+    /// Set [`reset_timeout`](Self::reset_timeout)
+    pub fn reset_timeout(mut self, reset_timeout: Duration) -> Self {
+        self.reset_timeout = reset_timeout;
+        self
+    }
+}
+
+/// State of a client's circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests are sent as usual
+    Closed,
+
+    /// Requests fail without being sent
+    Open,
+
+    /// A probe request is deciding whether the circuit closes again
+    HalfOpen,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probing: bool,
+}
+
+/// A circuit breaker together with its current state
+#[derive(Debug)]
+pub(crate) struct Breaker {
+    settings: CircuitBreaker,
+    state: Mutex<BreakerState>,
+}
+
+impl Breaker {
+    pub(crate) fn new(settings: CircuitBreaker) -> Self {
+        Self {
+            settings,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    fn state(&self) -> CircuitState {
+        let state = self.state.lock().unwrap();
+        match state.opened_at {
+            None => CircuitState::Closed,
+            Some(_) if state.probing => CircuitState::HalfOpen,
+            Some(opened_at) if opened_at.elapsed() >= self.settings.reset_timeout => {
+                CircuitState::HalfOpen
+            }
+            Some(_) => CircuitState::Open,
+        }
+    }
+
+    /// Check whether a request may be sent now
+    pub(crate) fn allow(&self) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let Some(opened_at) = state.opened_at else {
+            return Ok(());
+        };
+        let elapsed = opened_at.elapsed();
+        if elapsed >= self.settings.reset_timeout && !state.probing {
+            state.probing = true;
+            return Ok(());
+        }
+        Err(Error::CircuitOpen {
+            retry_in: self.settings.reset_timeout.saturating_sub(elapsed),
+        })
+    }
+
+    /// Update the state with the outcome of a request let through by [`allow`](Self::allow)
+    pub(crate) fn record(&self, result: &Result<RawResponse>) {
+        let failed = match result {
+            Err(Error::Http(e)) => e.is_timeout() || e.is_connect(),
+            Ok(response) => response.status >= 500,
+            Err(_) => false,
+        };
+
+        let mut state = self.state.lock().unwrap();
+        state.probing = false;
+        if failed {
+            state.consecutive_failures += 1;
+            if state.opened_at.is_some()
+                || state.consecutive_failures >= self.settings.failure_threshold
+            {
+                if state.opened_at.is_none() {
+                    log::warn!(
+                        "Opening circuit after {} consecutive failures",
+                        state.consecutive_failures
+                    );
+                }
+                state.opened_at = Some(Instant::now());
+            }
+        } else {
+            if state.opened_at.is_some() {
+                log::info!("Closing circuit after successful probe");
+            }
+            *state = BreakerState::default();
+        }
+    }
+}
+
+impl RideWithGpsClient {
+    /// Current state of the circuit breaker, if one is configured
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{CircuitBreaker, CircuitState, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::builder("https://ridewithgps.com", "your-api-key")
+    ///     .circuit_breaker(CircuitBreaker::new().failure_threshold(3))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// if client.circuit_state() == Some(CircuitState::Open) {
+    ///     println!("RideWithGPS appears to be down");
+    /// }
+    /// ```
+    pub fn circuit_state(&self) -> Option<CircuitState> {
+        self.breaker.as_deref().map(Breaker::state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderMap;
+
+    fn response(status: u16) -> Result<RawResponse> {
+        Ok(RawResponse {
+            endpoint: String::new(),
+            status,
+            headers: HeaderMap::new(),
+            body: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn test_breaker_opens_and_closes() {
+        let breaker = Breaker::new(
+            CircuitBreaker::new()
+                .failure_threshold(2)
+                .reset_timeout(Duration::from_millis(20)),
+        );
+
+        breaker.allow().unwrap();
+        breaker.record(&response(503));
+        breaker.record(&response(404));
+        breaker.record(&response(500));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        breaker.record(&response(502));
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(matches!(breaker.allow(), Err(Error::CircuitOpen { .. })));
+
+        // A failed probe reopens the circuit
+        std::thread::sleep(Duration::from_millis(25));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        breaker.allow().unwrap();
+        // Only one probe at a time
+        assert!(breaker.allow().is_err());
+        breaker.record(&response(503));
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        // A successful probe closes it
+        std::thread::sleep(Duration::from_millis(25));
+        breaker.allow().unwrap();
+        breaker.record(&response(200));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        breaker.allow().unwrap();
+    }
+}
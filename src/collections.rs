@@ -1,6 +1,9 @@
 //! Collection-related types and methods
 
-use crate::{PaginatedResponse, Result, RideWithGpsClient, Route, Trip};
+use crate::{
+    with_query, ItemType, LatLng, PaginatedResponse, Result, ResultExt, RideWithGpsClient, Route,
+    Trip,
+};
 use serde::{Deserialize, Serialize};
 
 /// A collection of routes and trips
@@ -47,10 +50,43 @@ pub struct Collection {
 
     /// Trips in the collection (included when fetching a specific collection)
     pub trips: Option<Vec<Trip>>,
+
+    /// The routes and trips in the collection with their positions
+    /// (included when fetching a specific collection)
+    pub items: Option<Vec<CollectionItem>>,
+}
+
+impl Collection {
+    /// The items of the collection in their intended order
+    ///
+    /// Items without a position come last, in the order the API sent them.
+    pub fn ordered_items(&self) -> Vec<&CollectionItem> {
+        let mut items: Vec<_> = self.items.iter().flatten().collect();
+        items.sort_by_key(|item| item.position.unwrap_or(u32::MAX));
+        items
+    }
+}
+
+/// A route or trip in a collection
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CollectionItem {
+    /// Collection item ID, as passed to
+    /// [`reorder_collection`](RideWithGpsClient::reorder_collection)
+    pub id: u64,
+
+    /// Whether the item is a route or a trip
+    pub item_type: ItemType,
+
+    /// ID of the route or trip
+    pub item_id: u64,
+
+    /// Position in the collection, counting from 0
+    pub position: Option<u32>,
 }
 
 /// Parameters for listing collections
 #[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
 pub struct ListCollectionsParams {
     /// Filter by collection name
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -65,6 +101,60 @@ pub struct ListCollectionsParams {
     pub page_size: Option<u32>,
 }
 
+setters!(ListCollectionsParams {
+    name: impl Into<String>,
+    page: u32,
+    page_size: u32,
+});
+
+/// Parameters for listing ambassador routes
+///
+/// Either a `region` or a center point (`lat`/`lng`, optionally with a
+/// `radius`) selects the area to return curated routes for.
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
+pub struct ListAmbassadorRoutesParams {
+    /// Region name or slug, such as `"oregon"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+
+    /// Latitude of the center of the search area
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lat: Option<f64>,
+
+    /// Longitude of the center of the search area
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lng: Option<f64>,
+
+    /// Search radius around the center (meters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub radius: Option<f64>,
+
+    /// Page number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+
+    /// Page size
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u32>,
+}
+
+setters!(ListAmbassadorRoutesParams {
+    region: impl Into<String>,
+    lat: f64,
+    lng: f64,
+    radius: f64,
+    page: u32,
+    page_size: u32,
+});
+
+impl ListAmbassadorRoutesParams {
+    /// Center the search area on a coordinate
+    pub fn near(self, point: LatLng) -> Self {
+        self.lat(point.lat).lng(point.lng)
+    }
+}
+
 impl RideWithGpsClient {
     /// List collections
     ///
@@ -105,7 +195,7 @@ impl RideWithGpsClient {
             }
         }
 
-        self.get(&url)
+        self.get_path(&url)
     }
 
     /// Get a specific collection by ID
@@ -145,13 +235,77 @@ impl RideWithGpsClient {
     /// }
     /// ```
     pub fn get_collection(&self, id: u64) -> Result<Collection> {
-        #[derive(Deserialize)]
-        struct CollectionWrapper {
-            collection: Collection,
+        self.get::<Collection>(id)
+    }
+
+    /// Get a specific collection by ID, or `None` if it does not exist
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The collection ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// match client.get_collection_opt(12345).unwrap() {
+    ///     Some(collection) => println!("Found: {:?}", collection.name),
+    ///     None => println!("No such collection"),
+    /// }
+    /// ```
+    pub fn get_collection_opt(&self, id: u64) -> Result<Option<Collection>> {
+        self.get_collection(id).optional()
+    }
+
+    /// Put the items of a collection in a new order
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_id` - The collection ID
+    /// * `ordered_item_ids` - IDs of the collection's [`CollectionItem`]s, in
+    ///   their new order
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// // Move the last day of a tour to the front
+    /// let collection = client.get_collection(12345).unwrap();
+    /// let mut ids: Vec<u64> = collection.ordered_items().iter().map(|i| i.id).collect();
+    /// ids.rotate_right(1);
+    /// let collection = client.reorder_collection(12345, &ids).unwrap();
+    /// ```
+    pub fn reorder_collection(
+        &self,
+        collection_id: u64,
+        ordered_item_ids: &[u64],
+    ) -> Result<Collection> {
+        #[derive(Serialize)]
+        struct ReorderRequest<'a> {
+            item_ids: &'a [u64],
         }
 
-        let wrapper: CollectionWrapper = self.get(&format!("/api/v1/collections/{}.json", id))?;
-        Ok(wrapper.collection)
+        self.put_wrapped(
+            &format!("/api/v1/collections/{}/reorder.json", collection_id),
+            "collection",
+            &ReorderRequest {
+                item_ids: ordered_item_ids,
+            },
+        )
     }
 
     /// Get the pinned collection
@@ -171,13 +325,40 @@ impl RideWithGpsClient {
     /// println!("Pinned collection: {:?}", collection);
     /// ```
     pub fn get_pinned_collection(&self) -> Result<Collection> {
-        #[derive(Deserialize)]
-        struct CollectionWrapper {
-            collection: Collection,
-        }
+        self.get_wrapped("/api/v1/collections/pinned.json", "collection")
+    }
 
-        let wrapper: CollectionWrapper = self.get("/api/v1/collections/pinned.json")?;
-        Ok(wrapper.collection)
+    /// List the routes curated by RideWithGPS ambassadors for a region
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The region to search and pagination
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{LatLng, ListAmbassadorRoutesParams, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     None
+    /// );
+    ///
+    /// let params = ListAmbassadorRoutesParams::new()
+    ///     .near(LatLng::new(45.52, -122.68))
+    ///     .radius(50_000.0);
+    /// let routes = client.list_ambassador_routes(&params).unwrap();
+    /// for route in routes.results {
+    ///     println!("{:?}", route.name);
+    /// }
+    /// ```
+    pub fn list_ambassador_routes(
+        &self,
+        params: &ListAmbassadorRoutesParams,
+    ) -> Result<PaginatedResponse<Route>> {
+        let url = with_query("/api/v1/ambassador_routes.json".to_string(), Some(params))?;
+        self.get_path(&url)
     }
 }
 
@@ -218,6 +399,16 @@ mod tests {
         assert!(json.get("page").is_some());
     }
 
+    #[test]
+    fn test_ambassador_routes_params() {
+        let params = ListAmbassadorRoutesParams::new()
+            .near(LatLng::new(45.5, -122.6))
+            .radius(10_000.0);
+
+        let query = serde_urlencoded::to_string(serde_json::to_value(&params).unwrap()).unwrap();
+        assert_eq!(query, "lat=45.5&lng=-122.6&radius=10000.0");
+    }
+
     #[test]
     fn test_collection_wrapper_deserialization() {
         let json = r#"{
@@ -296,4 +487,50 @@ mod tests {
         assert_eq!(trips[0].id, 10);
         assert_eq!(trips[0].name.as_deref(), Some("Trip 1"));
     }
+
+    #[test]
+    fn test_ordered_items() {
+        let json = r#"{
+            "id": 1,
+            "items": [
+                {"id": 10, "item_type": "route", "item_id": 100, "position": 2},
+                {"id": 11, "item_type": "trip", "item_id": 200},
+                {"id": 12, "item_type": "route", "item_id": 101, "position": 0}
+            ]
+        }"#;
+
+        let collection: Collection = serde_json::from_str(json).unwrap();
+        let ids: Vec<u64> = collection.ordered_items().iter().map(|i| i.id).collect();
+        assert_eq!(ids, vec![12, 10, 11]);
+        assert_eq!(collection.ordered_items()[2].item_type, ItemType::Trip);
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_reorder_collection() {
+        use crate::testing::FakeServer;
+
+        let server = FakeServer::start();
+        let client = server.client();
+        server.mock(
+            "PUT",
+            "/api/v1/collections/{id}/reorder.json",
+            200,
+            serde_json::json!({"collection": {
+                "id": 5,
+                "items": [
+                    {"id": 11, "item_type": "route", "item_id": 101, "position": 0},
+                    {"id": 10, "item_type": "route", "item_id": 100, "position": 1}
+                ]
+            }}),
+        );
+
+        let collection = client.reorder_collection(5, &[11, 10]).unwrap();
+        assert_eq!(collection.ordered_items()[0].item_id, 101);
+
+        let put = server.requests().pop().unwrap();
+        assert_eq!(put.path, "/api/v1/collections/5/reorder.json");
+        let body: serde_json::Value = serde_json::from_slice(&put.body).unwrap();
+        assert_eq!(body, serde_json::json!({"item_ids": [11, 10]}));
+    }
 }
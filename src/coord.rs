@@ -0,0 +1,154 @@
+//! Geographic coordinate type shared by track points, cues and POIs
+
+use crate::{CoursePoint, PointOfInterest, TrackPoint, TripTrackPoint};
+use serde::{Deserialize, Serialize};
+
+/// Mean Earth radius in meters, as used for great-circle distances
+pub const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// A WGS84 latitude/longitude pair in degrees
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct LatLng {
+    /// Latitude
+    pub lat: f64,
+
+    /// Longitude
+    pub lng: f64,
+}
+
+impl LatLng {
+    /// Create a new coordinate from a latitude and longitude
+    pub fn new(lat: f64, lng: f64) -> Self {
+        Self { lat, lng }
+    }
+
+    /// Great-circle distance to another coordinate in meters
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ridewithgps_client::LatLng;
+    ///
+    /// let portland = LatLng::new(45.5152, -122.6784);
+    /// let seattle = LatLng::new(47.6062, -122.3321);
+    /// assert!((portland.distance_to(&seattle) / 1000.0 - 234.0).abs() < 1.0);
+    /// ```
+    pub fn distance_to(&self, other: &LatLng) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let dlat = lat2 - lat1;
+        let dlng = (other.lng - self.lng).to_radians();
+
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlng / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS * a.sqrt().asin()
+    }
+
+    /// Initial bearing towards another coordinate in degrees clockwise from
+    /// north, in the range `[0, 360)`
+    pub fn bearing_to(&self, other: &LatLng) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let dlng = (other.lng - self.lng).to_radians();
+
+        let y = dlng.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlng.cos();
+        y.atan2(x).to_degrees().rem_euclid(360.0)
+    }
+}
+
+impl TrackPoint {
+    /// Coordinate of this track point, if both latitude and longitude are set
+    pub fn coord(&self) -> Option<LatLng> {
+        Some(LatLng::new(self.y?, self.x?))
+    }
+}
+
+impl TripTrackPoint {
+    /// Coordinate of this track point, if both latitude and longitude are set
+    pub fn coord(&self) -> Option<LatLng> {
+        Some(LatLng::new(self.y?, self.x?))
+    }
+}
+
+impl CoursePoint {
+    /// Coordinate of this cue, if both latitude and longitude are set
+    pub fn coord(&self) -> Option<LatLng> {
+        Some(LatLng::new(self.y?, self.x?))
+    }
+}
+
+impl PointOfInterest {
+    /// Coordinate of this POI, if both latitude and longitude are set
+    pub fn coord(&self) -> Option<LatLng> {
+        Some(LatLng::new(self.lat?, self.lng?))
+    }
+}
+
+#[cfg(feature = "geo")]
+impl From<LatLng> for geo_types::Coord<f64> {
+    fn from(c: LatLng) -> Self {
+        geo_types::coord! { x: c.lng, y: c.lat }
+    }
+}
+
+#[cfg(feature = "geo")]
+impl From<LatLng> for geo_types::Point<f64> {
+    fn from(c: LatLng) -> Self {
+        geo_types::Point::new(c.lng, c.lat)
+    }
+}
+
+#[cfg(feature = "geo")]
+impl From<geo_types::Coord<f64>> for LatLng {
+    fn from(c: geo_types::Coord<f64>) -> Self {
+        LatLng::new(c.y, c.x)
+    }
+}
+
+#[cfg(feature = "geo")]
+impl From<geo_types::Point<f64>> for LatLng {
+    fn from(p: geo_types::Point<f64>) -> Self {
+        LatLng::new(p.y(), p.x())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_point_coord() {
+        let point: TrackPoint = serde_json::from_str(r#"{"x": -122.4194, "y": 37.7749}"#).unwrap();
+        assert_eq!(point.coord(), Some(LatLng::new(37.7749, -122.4194)));
+
+        let point: TrackPoint = serde_json::from_str(r#"{"x": -122.4194}"#).unwrap();
+        assert_eq!(point.coord(), None);
+    }
+
+    #[test]
+    fn test_poi_coord() {
+        let poi: PointOfInterest =
+            serde_json::from_str(r#"{"id": 1, "latitude": 45.5, "longitude": -122.6}"#).unwrap();
+        assert_eq!(poi.coord(), Some(LatLng::new(45.5, -122.6)));
+    }
+
+    #[test]
+    fn test_distance_and_bearing() {
+        let origin = LatLng::new(0.0, 0.0);
+        let north = LatLng::new(1.0, 0.0);
+        let east = LatLng::new(0.0, 1.0);
+
+        assert!((origin.distance_to(&north) - 111_195.0).abs() < 1.0);
+        assert!((origin.bearing_to(&north) - 0.0).abs() < 1e-9);
+        assert!((origin.bearing_to(&east) - 90.0).abs() < 1e-9);
+        assert!((north.bearing_to(&origin) - 180.0).abs() < 1e-9);
+    }
+
+    #[cfg(feature = "geo")]
+    #[test]
+    fn test_geo_conversions() {
+        let coord = LatLng::new(37.7749, -122.4194);
+        let point: geo_types::Point<f64> = coord.into();
+        assert_eq!(point.x(), -122.4194);
+        assert_eq!(point.y(), 37.7749);
+        assert_eq!(LatLng::from(point), coord);
+    }
+}
@@ -0,0 +1,251 @@
+//! Following routes: corridors for off-course alerts, and progress along
+//! the route
+
+use crate::spatial::SegmentIndex;
+use crate::{LatLng, Route};
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Cell size of the spatial index built by [`Route::project`], in meters
+const PROJECT_CELL_SIZE: f64 = 100.0;
+
+/// The area within a distance of a route's track
+///
+/// Created with [`Route::corridor`]. The corridor is kept as the track and
+/// its radius rather than as a polygon, so checks are exact however the
+/// route turns or crosses itself.
+pub struct RouteCorridor {
+    index: SegmentIndex,
+    radius: f64,
+
+    /// Index in the route's track points of each point in the index
+    track_indices: Vec<usize>,
+
+    /// Distance along the route of each point in the index, in meters
+    distances: Vec<f64>,
+}
+
+impl RouteCorridor {
+    /// Distance in meters on either side of the route
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    /// Whether a position lies within the corridor
+    ///
+    /// Always false for a route without track points.
+    pub fn is_on_course(&self, lat: f64, lng: f64) -> bool {
+        self.index.within(&LatLng::new(lat, lng), self.radius)
+    }
+
+    /// Distance in meters from a position to the route's track, or `None`
+    /// if the route has no track points
+    pub fn distance_off_course(&self, lat: f64, lng: f64) -> Option<f64> {
+        self.index.distance(&LatLng::new(lat, lng))
+    }
+
+    /// Project a position onto the route's track
+    ///
+    /// Returns the distance along the route of the nearest point of the
+    /// track in meters, the distance from the position to that point in
+    /// meters, and the index in the route's track points of the point
+    /// starting the nearest stretch of track. Returns `None` if the route
+    /// has no track points.
+    ///
+    /// Lookups search the corridor's spatial index outwards from the
+    /// position, so they do not scan the whole track, even for positions
+    /// far off the route.
+    pub fn project(&self, lat: f64, lng: f64) -> Option<(f64, f64, usize)> {
+        let (segment, t, offset) = self.index.nearest(&LatLng::new(lat, lng))?;
+        let start = self.distances[segment];
+        let end = self.distances.get(segment + 1).copied().unwrap_or(start);
+        Some((
+            start + t * (end - start),
+            offset,
+            self.track_indices[segment],
+        ))
+    }
+}
+
+/// The corridor [`Route::project`] keeps between calls
+///
+/// Clones start out empty, so routes derived from a clone, such as
+/// [`Route::reversed`], never see the index of the original track.
+#[derive(Default)]
+pub(crate) struct CorridorCache(OnceLock<RouteCorridor>);
+
+impl Clone for CorridorCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl fmt::Debug for CorridorCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CorridorCache").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for RouteCorridor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RouteCorridor")
+            .field("radius", &self.radius)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Route {
+    /// The corridor within `radius_m` meters of the route's track
+    ///
+    /// Navigation apps can check each position against it to warn the
+    /// rider when they leave the route.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let route = client.get_route(12345).unwrap();
+    /// let corridor = route.corridor(50.0);
+    /// let (lat, lng) = (45.52, -122.68);
+    /// if !corridor.is_on_course(lat, lng) {
+    ///     let off = corridor.distance_off_course(lat, lng).unwrap_or_default();
+    ///     println!("Off course by {:.0} m", off);
+    /// }
+    /// ```
+    pub fn corridor(&self, radius_m: f64) -> RouteCorridor {
+        let mut coords: Vec<LatLng> = Vec::new();
+        let mut track_indices = Vec::new();
+        let mut distances: Vec<f64> = Vec::new();
+        for (i, point) in self.track_points.iter().flatten().enumerate() {
+            let Some(coord) = point.coord() else {
+                continue;
+            };
+            // Fill in distances the route does not have from the positions
+            let distance = point.d.unwrap_or_else(|| {
+                coords
+                    .last()
+                    .zip(distances.last())
+                    .map_or(0.0, |(last, d)| d + last.distance_to(&coord))
+            });
+            coords.push(coord);
+            track_indices.push(i);
+            distances.push(distance);
+        }
+        RouteCorridor {
+            index: SegmentIndex::new(&coords, radius_m),
+            radius: radius_m,
+            track_indices,
+            distances,
+        }
+    }
+
+    /// Project a position onto the route's track, for tracking progress
+    /// along it
+    ///
+    /// Returns `(distance_along, lateral_offset, index)`, as described for
+    /// [`RouteCorridor::project`].
+    ///
+    /// The spatial index is built over the track on the first call and
+    /// kept with the route, so following a rider costs a lookup per
+    /// position. Changes made to [`track_points`](Self::track_points) after
+    /// the first call are not seen; project onto a fresh
+    /// [`corridor`](Self::corridor) after editing a route in place.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let route = client.get_route(12345).unwrap();
+    /// if let Some((along, _, _)) = route.project(45.52, -122.68) {
+    ///     println!(
+    ///         "km {:.0} of {:.0}",
+    ///         along / 1000.0,
+    ///         route.distance.unwrap_or_default() / 1000.0
+    ///     );
+    /// }
+    /// ```
+    pub fn project(&self, lat: f64, lng: f64) -> Option<(f64, f64, usize)> {
+        self.projection
+            .0
+            .get_or_init(|| self.corridor(PROJECT_CELL_SIZE))
+            .project(lat, lng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corridor() {
+        let route: Route = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "track_points": [
+                {"x": 0.0, "y": 0.0},
+                {"x": 0.01, "y": 0.0},
+                {"x": 0.01, "y": 0.01}
+            ]
+        }))
+        .unwrap();
+        let corridor = route.corridor(50.0);
+
+        assert!(corridor.is_on_course(0.0003, 0.005));
+        assert!(corridor.is_on_course(0.005, 0.0103));
+        assert!(!corridor.is_on_course(0.005, 0.005));
+        let off = corridor.distance_off_course(0.005, 0.005).unwrap();
+        assert!((off - 556.0).abs() < 2.0, "{}", off);
+
+        let empty: Route = serde_json::from_str(r#"{"id": 2}"#).unwrap();
+        let corridor = empty.corridor(50.0);
+        assert!(!corridor.is_on_course(0.0, 0.0));
+        assert_eq!(corridor.distance_off_course(0.0, 0.0), None);
+        assert_eq!(empty.project(0.0, 0.0), None);
+    }
+
+    #[test]
+    fn test_project() {
+        let route: Route = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "track_points": [
+                {"x": 0.0, "y": 0.0, "d": 0.0},
+                {"d": 500.0},
+                {"x": 0.01, "y": 0.0, "d": 1100.0},
+                {"x": 0.01, "y": 0.01}
+            ]
+        }))
+        .unwrap();
+
+        // A quarter of the way along the first stretch, 33 m to the side
+        let (along, offset, index) = route.project(0.0003, 0.0025).unwrap();
+        assert!((along - 275.0).abs() < 1.0, "{}", along);
+        assert!((offset - 33.4).abs() < 0.5, "{}", offset);
+        assert_eq!(index, 0);
+
+        // Halfway up the second stretch, whose distances are filled in
+        let (along, offset, index) = route.corridor(50.0).project(0.005, 0.0101).unwrap();
+        assert!((along - 1656.0).abs() < 2.0, "{}", along);
+        assert!((offset - 11.1).abs() < 0.5, "{}", offset);
+        assert_eq!(index, 2);
+
+        // The index is kept, but not by routes derived from this one
+        assert!(route.projection.0.get().is_some());
+        let reversed = route.reversed();
+        assert!(reversed.projection.0.get().is_none());
+        let (_, _, index) = reversed.project(0.0003, 0.0025).unwrap();
+        assert_eq!(index, 1);
+    }
+}
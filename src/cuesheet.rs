@@ -0,0 +1,208 @@
+//! Cue sheet generation from route course points
+
+use crate::{CueType, Route};
+use std::io::Write;
+
+/// A single line of a cue sheet
+#[derive(Debug, Clone, PartialEq)]
+pub struct CueSheetEntry {
+    /// Distance from the start in meters
+    pub distance: f64,
+
+    /// Cue type
+    pub cue_type: Option<CueType>,
+
+    /// Cue text/description
+    pub description: Option<String>,
+
+    /// Distance to the next cue (or to the end of the route) in meters
+    pub distance_to_next: Option<f64>,
+}
+
+/// A printable list of turn-by-turn cues
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CueSheet {
+    /// Cue sheet entries, ordered by distance
+    pub entries: Vec<CueSheetEntry>,
+}
+
+impl Route {
+    /// Build a cue sheet from the route's course points
+    ///
+    /// Course points without a distance are skipped. The route must have been
+    /// fetched with [`get_route`](crate::RideWithGpsClient::get_route) for the
+    /// course points to be present; otherwise the cue sheet is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     None
+    /// );
+    ///
+    /// let route = client.get_route(12345).unwrap();
+    /// let cues = route.cue_sheet();
+    /// cues.write_markdown(&mut std::io::stdout()).unwrap();
+    /// ```
+    pub fn cue_sheet(&self) -> CueSheet {
+        let mut cues: Vec<_> = self
+            .course_points
+            .iter()
+            .flatten()
+            .filter_map(|cp| Some((cp.d?, cp)))
+            .collect();
+        cues.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let entries = cues
+            .iter()
+            .enumerate()
+            .map(|(i, (distance, cp))| {
+                let next = cues.get(i + 1).map(|(d, _)| *d).or(self.distance);
+                CueSheetEntry {
+                    distance: *distance,
+                    cue_type: cp.t.clone(),
+                    description: cp.n.clone(),
+                    distance_to_next: next.map(|n| (n - distance).max(0.0)),
+                }
+            })
+            .collect();
+
+        CueSheet { entries }
+    }
+}
+
+impl CueSheet {
+    /// Write the cue sheet as CSV
+    ///
+    /// Distances are written in kilometers. Descriptions starting with `=`,
+    /// `+`, `-` or `@` are prefixed with `'`, so spreadsheets do not run
+    /// them as formulas.
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writeln!(writer, "distance_km,type,description,next_km")?;
+        for entry in &self.entries {
+            writeln!(
+                writer,
+                "{:.2},{},{},{}",
+                entry.distance / 1000.0,
+                csv_field(entry.cue_type.as_ref().map_or("", CueType::as_str)),
+                csv_field(entry.description.as_deref().unwrap_or_default()),
+                entry
+                    .distance_to_next
+                    .map(|d| format!("{:.2}", d / 1000.0))
+                    .unwrap_or_default(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write the cue sheet as a Markdown table
+    ///
+    /// Distances are written in kilometers.
+    pub fn write_markdown<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writeln!(writer, "| km | Type | Description | Next (km) |")?;
+        writeln!(writer, "|---:|------|-------------|----------:|")?;
+        for entry in &self.entries {
+            writeln!(
+                writer,
+                "| {:.2} | {} | {} | {} |",
+                entry.distance / 1000.0,
+                markdown_cell(entry.cue_type.as_ref().map_or("", CueType::as_str)),
+                markdown_cell(entry.description.as_deref().unwrap_or_default()),
+                entry
+                    .distance_to_next
+                    .map(|d| format!("{:.2}", d / 1000.0))
+                    .unwrap_or_default(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Quote a text value for CSV, if needed
+///
+/// Values starting with `=`, `+`, `-` or `@` are prefixed with `'`, so
+/// spreadsheets show them as text instead of running them as formulas.
+pub(crate) fn csv_field(value: &str) -> String {
+    let value = match value.starts_with(['=', '+', '-', '@']) {
+        true => format!("'{}", value),
+        false => value.to_string(),
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route() -> Route {
+        serde_json::from_str(
+            r#"{
+                "id": 1,
+                "distance": 12000.0,
+                "course_points": [
+                    {"d": 5000.0, "t": "right", "n": "Turn right onto Main St, then stop"},
+                    {"d": 0.0, "t": "generic", "n": "Start"},
+                    {"t": "generic", "n": "No distance"},
+                    {"d": 7500.0, "t": "water", "n": "Water | refill"}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cue_sheet_entries() {
+        let sheet = route().cue_sheet();
+        assert_eq!(sheet.entries.len(), 3);
+        assert_eq!(sheet.entries[0].description.as_deref(), Some("Start"));
+        assert_eq!(sheet.entries[0].distance_to_next, Some(5000.0));
+        assert_eq!(sheet.entries[1].distance_to_next, Some(2500.0));
+        assert_eq!(sheet.entries[2].distance_to_next, Some(4500.0));
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let mut out = Vec::new();
+        route().cue_sheet().write_csv(&mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "distance_km,type,description,next_km");
+        assert_eq!(lines[1], "0.00,generic,Start,5.00");
+        assert_eq!(
+            lines[2],
+            "5.00,right,\"Turn right onto Main St, then stop\",2.50"
+        );
+
+        let route: Route = serde_json::from_str(
+            r#"{"id": 1, "course_points": [{"d": 0.0, "t": "generic", "n": "=HYPERLINK(\"x\", \"y\")"}]}"#,
+        )
+        .unwrap();
+        let mut out = Vec::new();
+        route.cue_sheet().write_csv(&mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("0.00,generic,\"'=HYPERLINK(\"\"x\"\", \"\"y\"\")\",")
+        );
+    }
+
+    #[test]
+    fn test_write_markdown() {
+        let mut out = Vec::new();
+        route().cue_sheet().write_markdown(&mut out).unwrap();
+        let markdown = String::from_utf8(out).unwrap();
+        assert!(markdown.contains("| 7.50 | water | Water \\| refill | 4.50 |"));
+    }
+}
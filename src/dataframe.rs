@@ -0,0 +1,148 @@
+//! Conversion of trips to Polars data frames
+//!
+//! Requires the `polars` feature.
+
+use crate::Trip;
+use polars::prelude::{Column, DataFrame};
+
+/// Build a data frame from columns of equal length
+fn data_frame(columns: Vec<Column>) -> DataFrame {
+    DataFrame::new(columns).expect("columns have equal lengths")
+}
+
+/// A column of an optional number per trip or track point
+fn numbers<T>(name: &str, items: &[T], value: impl Fn(&T) -> Option<f64>) -> Column {
+    Column::new(name.into(), items.iter().map(value).collect::<Vec<_>>())
+}
+
+/// A data frame with one row of summary statistics per trip
+///
+/// The columns are `id`, `name`, `departed_at`, `activity_type`,
+/// `locality`, and the numbers `distance` (m), `elevation_gain` (m),
+/// `elevation_loss` (m), `duration` (s), `moving_time` (s), `avg_speed`
+/// (m/s), `max_speed` (m/s), `avg_hr`, `max_hr`, `avg_cad`, `avg_watts`,
+/// `max_watts` and `calories`. Missing values are null.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ridewithgps_client::{trips_to_dataframe, RideWithGpsClient};
+///
+/// let client = RideWithGpsClient::new(
+///     "https://ridewithgps.com",
+///     "your-api-key",
+///     Some("your-auth-token")
+/// );
+///
+/// let trips = client.list_trips(None).unwrap().results;
+/// let df = trips_to_dataframe(&trips);
+/// println!("{}", df);
+/// ```
+pub fn trips_to_dataframe(trips: &[Trip]) -> DataFrame {
+    let text = |name: &str, value: fn(&Trip) -> Option<&str>| {
+        Column::new(name.into(), trips.iter().map(value).collect::<Vec<_>>())
+    };
+    data_frame(vec![
+        Column::new("id".into(), trips.iter().map(|t| t.id).collect::<Vec<_>>()),
+        text("name", |t| t.name.as_deref()),
+        text("departed_at", |t| t.departed_at.as_deref()),
+        text("activity_type", |t| {
+            t.activity_type.as_ref().map(|a| a.as_str())
+        }),
+        text("locality", |t| t.locality.as_deref()),
+        numbers("distance", trips, |t| t.distance),
+        numbers("elevation_gain", trips, |t| t.elevation_gain),
+        numbers("elevation_loss", trips, |t| t.elevation_loss),
+        numbers("duration", trips, |t| t.duration),
+        numbers("moving_time", trips, |t| t.moving_time),
+        numbers("avg_speed", trips, |t| t.avg_speed),
+        numbers("max_speed", trips, |t| t.max_speed),
+        numbers("avg_hr", trips, |t| t.avg_hr),
+        numbers("max_hr", trips, |t| t.max_hr),
+        numbers("avg_cad", trips, |t| t.avg_cad),
+        numbers("avg_watts", trips, |t| t.avg_watts),
+        numbers("max_watts", trips, |t| t.max_watts),
+        numbers("calories", trips, |t| t.calories),
+    ])
+}
+
+/// A data frame with one row per track point of a trip
+///
+/// The columns are `time` (Unix seconds), `lat`, `lng`, `distance` (m),
+/// `elevation` (m), `speed` (km/h), `temperature` (°C), `heart_rate`,
+/// `cadence` and `power`. Missing values are null. The trip must have been
+/// fetched with [`get_trip`](crate::RideWithGpsClient::get_trip) for the
+/// track points to be present; otherwise the data frame is empty.
+pub fn track_points_to_dataframe(trip: &Trip) -> DataFrame {
+    let points = trip.track_points.as_deref().unwrap_or_default();
+    data_frame(vec![
+        Column::new(
+            "time".into(),
+            points.iter().map(|p| p.t).collect::<Vec<_>>(),
+        ),
+        numbers("lat", points, |p| p.y),
+        numbers("lng", points, |p| p.x),
+        numbers("distance", points, |p| p.d),
+        numbers("elevation", points, |p| p.e),
+        numbers("speed", points, |p| p.s),
+        numbers("temperature", points, |p| p.temp),
+        numbers("heart_rate", points, |p| p.h),
+        numbers("cadence", points, |p| p.c),
+        numbers("power", points, |p| p.p),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trips_to_dataframe() {
+        let trips: Vec<Trip> = serde_json::from_str(
+            r#"[
+                {"id": 1, "name": "Commute", "distance": 8000.0, "activity_type": "cycling"},
+                {"id": 2, "avg_hr": 142}
+            ]"#,
+        )
+        .unwrap();
+
+        let df = trips_to_dataframe(&trips);
+        assert_eq!(df.shape(), (2, 18));
+        assert_eq!(df.column("id").unwrap().u64().unwrap().get(1), Some(2));
+        assert_eq!(df.column("name").unwrap().str().unwrap().get(1), None);
+        assert_eq!(
+            df.column("distance").unwrap().f64().unwrap().get(0),
+            Some(8000.0)
+        );
+        assert_eq!(
+            df.column("activity_type").unwrap().str().unwrap().get(0),
+            Some("cycling")
+        );
+    }
+
+    #[test]
+    fn test_track_points_to_dataframe() {
+        let trip: Trip = serde_json::from_str(
+            r#"{"id": 1, "track_points": [
+                {"x": -122.6, "y": 45.5, "t": 1735718400, "h": 120},
+                {"x": -122.59, "y": 45.5, "t": 1735718401, "p": 250}
+            ]}"#,
+        )
+        .unwrap();
+
+        let df = track_points_to_dataframe(&trip);
+        assert_eq!(df.shape(), (2, 10));
+        assert_eq!(
+            df.column("time").unwrap().i64().unwrap().get(1),
+            Some(1735718401)
+        );
+        assert_eq!(df.column("power").unwrap().f64().unwrap().get(0), None);
+        assert_eq!(
+            df.column("power").unwrap().f64().unwrap().get(1),
+            Some(250.0)
+        );
+
+        let empty: Trip = serde_json::from_str(r#"{"id": 2}"#).unwrap();
+        assert_eq!(track_points_to_dataframe(&empty).height(), 0);
+    }
+}
@@ -0,0 +1,171 @@
+//! Snapping waypoints to roads with the RideWithGPS route planner
+
+use crate::{
+    ActivityType, CoursePoint, Error, LatLng, Result, RideWithGpsClient, RouteRequest, TrackPoint,
+};
+use serde::{Deserialize, Serialize};
+
+/// Preferences for [`RideWithGpsClient::plan_route`]
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
+pub struct RoutingOptions {
+    /// Activity to route for, such as road or gravel cycling
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<ActivityType>,
+
+    /// Keep off highways and other major roads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avoid_highways: Option<bool>,
+
+    /// Prefer paved roads over unpaved ones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefer_paved: Option<bool>,
+}
+
+setters!(RoutingOptions {
+    profile: impl Into<ActivityType>,
+    avoid_highways: bool,
+    prefer_paved: bool,
+});
+
+/// A path along roads through a list of waypoints, with turn cues
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Directions {
+    /// Distance in meters
+    pub distance: Option<f64>,
+
+    /// Elevation gain in meters
+    pub elevation_gain: Option<f64>,
+
+    /// Elevation loss in meters
+    pub elevation_loss: Option<f64>,
+
+    /// Track points
+    pub track_points: Option<Vec<TrackPoint>>,
+
+    /// Turn-by-turn cues
+    pub course_points: Option<Vec<CoursePoint>>,
+}
+
+impl From<&Directions> for RouteRequest {
+    fn from(directions: &Directions) -> Self {
+        Self {
+            track_points: directions.track_points.clone(),
+            course_points: directions.course_points.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DirectionsRequest<'a> {
+    waypoints: &'a [LatLng],
+    #[serde(flatten)]
+    options: &'a RoutingOptions,
+}
+
+#[derive(Deserialize)]
+struct DirectionsWrapper {
+    directions: Directions,
+}
+
+impl RideWithGpsClient {
+    /// Plan a route along roads through a list of waypoints
+    ///
+    /// The result is not saved; convert it to a [`RouteRequest`] and pass it
+    /// to [`create_route`](Self::create_route) to keep it.
+    ///
+    /// # Arguments
+    ///
+    /// * `waypoints` - Start, intermediate and end points, at least two
+    /// * `options` - Routing preferences
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{
+    ///     ActivityType, LatLng, RideWithGpsClient, RouteRequest, RoutingOptions,
+    /// };
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let waypoints = [LatLng::new(45.52, -122.68), LatLng::new(45.37, -122.60)];
+    /// let options = RoutingOptions::new()
+    ///     .profile(ActivityType::RoadCycling)
+    ///     .avoid_highways(true);
+    /// let directions = client.plan_route(&waypoints, &options).unwrap();
+    ///
+    /// let route = client
+    ///     .create_route(&RouteRequest::from(&directions).name("To Oregon City"))
+    ///     .unwrap();
+    /// ```
+    pub fn plan_route(&self, waypoints: &[LatLng], options: &RoutingOptions) -> Result<Directions> {
+        if waypoints.len() < 2 {
+            return Err(Error::ValidationError(
+                "A route needs at least two waypoints".to_string(),
+            ));
+        }
+
+        let request = DirectionsRequest { waypoints, options };
+        let wrapper: DirectionsWrapper = self.post("/api/v1/directions.json", &request)?;
+        Ok(wrapper.directions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directions_request() {
+        let waypoints = [LatLng::new(45.5, -122.6), LatLng::new(45.4, -122.5)];
+        let options = RoutingOptions::new()
+            .profile("cycling:gravel")
+            .prefer_paved(false);
+        let json = serde_json::to_value(DirectionsRequest {
+            waypoints: &waypoints,
+            options: &options,
+        })
+        .unwrap();
+
+        assert_eq!(json["waypoints"][1]["lng"], -122.5);
+        assert_eq!(json["profile"], "cycling:gravel");
+        assert_eq!(json["prefer_paved"], false);
+        assert!(json.get("avoid_highways").is_none());
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_plan_route() {
+        use crate::testing::FakeServer;
+
+        let server = FakeServer::start();
+        let client = server.client();
+        server.mock(
+            "POST",
+            "/api/v1/directions.json",
+            200,
+            serde_json::json!({"directions": {
+                "distance": 1200.0,
+                "track_points": [{"x": -122.6, "y": 45.5, "d": 0}, {"x": -122.59, "y": 45.5, "d": 1200}],
+                "course_points": [{"d": 0, "t": "generic", "n": "Start"}]
+            }}),
+        );
+
+        let waypoints = [LatLng::new(45.5, -122.6), LatLng::new(45.5, -122.59)];
+        let directions = client
+            .plan_route(&waypoints, &RoutingOptions::new())
+            .unwrap();
+        assert_eq!(directions.distance, Some(1200.0));
+        let request = RouteRequest::from(&directions);
+        assert_eq!(request.track_points.unwrap().len(), 2);
+
+        assert!(client
+            .plan_route(&waypoints[..1], &RoutingOptions::new())
+            .is_err());
+    }
+}
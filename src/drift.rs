@@ -0,0 +1,87 @@
+//! Reporting fields the API returns but the data model does not know
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Arc;
+
+/// Fields of a response that the crate's data model does not cover
+///
+/// Passed to the hook installed with
+/// [`ClientBuilder::on_unknown_fields`](crate::ClientBuilder::on_unknown_fields).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownFields {
+    /// Path of the endpoint that returned the response
+    pub endpoint: String,
+
+    /// Rust type the response was decoded into
+    pub type_name: &'static str,
+
+    /// Location of each unknown field, such as `route.track_points[0].hr`
+    pub fields: BTreeSet<String>,
+}
+
+/// Callback receiving the unknown fields of each response
+#[derive(Clone)]
+pub(crate) struct DriftHook(Arc<dyn Fn(&UnknownFields) + Send + Sync>);
+
+impl DriftHook {
+    pub(crate) fn new(hook: impl Fn(&UnknownFields) + Send + Sync + 'static) -> Self {
+        DriftHook(Arc::new(hook))
+    }
+
+    pub(crate) fn call(&self, unknown: &UnknownFields) {
+        (self.0)(unknown)
+    }
+}
+
+/// Format a path from `serde_ignored` the way `serde_path_to_error` does,
+/// such as `route.track_points[0].hr`
+pub(crate) fn field_path(path: &serde_ignored::Path) -> String {
+    use serde_ignored::Path;
+
+    match path {
+        Path::Root => String::new(),
+        Path::Seq { parent, index } => format!("{}[{}]", field_path(parent), index),
+        Path::Map { parent, key } => match field_path(parent) {
+            parent if parent.is_empty() => key.clone(),
+            parent => format!("{}.{}", parent, key),
+        },
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => field_path(parent),
+    }
+}
+
+impl fmt::Debug for DriftHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DriftHook")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_ignored::Path;
+
+    #[test]
+    fn test_field_path() {
+        let route = Path::Map {
+            parent: &Path::Root,
+            key: "route".to_string(),
+        };
+        let points = Path::Map {
+            parent: &route,
+            key: "track_points".to_string(),
+        };
+        let some = Path::Some { parent: &points };
+        let first = Path::Seq {
+            parent: &some,
+            index: 0,
+        };
+        let hr = Path::Map {
+            parent: &first,
+            key: "hr".to_string(),
+        };
+        assert_eq!(field_path(&hr), "route.track_points[0].hr");
+    }
+}
@@ -0,0 +1,197 @@
+//! Filling in missing elevation data
+
+use crate::analysis::TrackSample;
+use crate::{LatLng, Result, RideWithGpsClient};
+use serde::Serialize;
+
+/// Number of locations sent to the elevation endpoint per request
+const ELEVATION_BATCH_SIZE: usize = 500;
+
+/// A source of elevation data, such as a digital elevation model
+///
+/// Implemented by [`RideWithGpsClient`] using the RideWithGPS elevation
+/// service; implement it for a local DEM to avoid the API round trips.
+pub trait ElevationProvider {
+    /// Look up the elevation in meters of each location
+    ///
+    /// Returns one entry per location, `None` where no data is available.
+    fn elevations(&self, locations: &[LatLng]) -> Result<Vec<Option<f64>>>;
+}
+
+impl ElevationProvider for RideWithGpsClient {
+    fn elevations(&self, locations: &[LatLng]) -> Result<Vec<Option<f64>>> {
+        #[derive(Serialize)]
+        struct ElevationRequest {
+            locations: Vec<[f64; 2]>,
+        }
+
+        let mut elevations = Vec::with_capacity(locations.len());
+        for batch in locations.chunks(ELEVATION_BATCH_SIZE) {
+            let request = ElevationRequest {
+                locations: batch.iter().map(|l| [l.lat, l.lng]).collect(),
+            };
+            let response: Vec<Option<f64>> =
+                self.post_wrapped("/api/v1/elevation.json", "elevations", &request)?;
+            if response.len() != batch.len() {
+                return Err(crate::Error::ApiError(format!(
+                    "Expected {} elevations, got {}",
+                    batch.len(),
+                    response.len()
+                )));
+            }
+            elevations.extend(response);
+        }
+        Ok(elevations)
+    }
+}
+
+/// Fill in the elevation of points that have a position but no elevation
+///
+/// Points that already have an elevation are left alone, so this is cheap
+/// to run on partially complete tracks. Returns the number of points that
+/// received an elevation, or an error without changing any point if the
+/// provider does not return one entry per location.
+///
+/// # Example
+///
+/// ```rust
+/// use ridewithgps_client::{fill_elevation_with, ElevationProvider, LatLng, Result, TrackPoint};
+///
+/// /// A very flat world
+/// struct SeaLevel;
+///
+/// impl ElevationProvider for SeaLevel {
+///     fn elevations(&self, locations: &[LatLng]) -> Result<Vec<Option<f64>>> {
+///         Ok(vec![Some(0.0); locations.len()])
+///     }
+/// }
+///
+/// let mut points: Vec<TrackPoint> =
+///     serde_json::from_str(r#"[{"x": 4.9, "y": 52.4}, {"x": 4.91, "y": 52.4, "e": 3}]"#).unwrap();
+/// assert_eq!(fill_elevation_with(&mut points, &SeaLevel).unwrap(), 1);
+/// assert_eq!(points[0].e, Some(0.0));
+/// assert_eq!(points[1].e, Some(3.0));
+/// ```
+pub fn fill_elevation_with<P: TrackSample>(
+    points: &mut [P],
+    provider: &(impl ElevationProvider + ?Sized),
+) -> Result<usize> {
+    let (indexes, locations): (Vec<usize>, Vec<LatLng>) = points
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.elevation().is_none())
+        .filter_map(|(i, p)| Some((i, p.coord()?)))
+        .unzip();
+    if locations.is_empty() {
+        return Ok(0);
+    }
+
+    let elevations = provider.elevations(&locations)?;
+    if elevations.len() != locations.len() {
+        return Err(crate::Error::ApiError(format!(
+            "Expected {} elevations, got {}",
+            locations.len(),
+            elevations.len()
+        )));
+    }
+    let mut filled = 0;
+    for (index, elevation) in indexes.into_iter().zip(elevations) {
+        if elevation.is_some() {
+            points[index].set_elevation(elevation);
+            filled += 1;
+        }
+    }
+    Ok(filled)
+}
+
+impl RideWithGpsClient {
+    /// Fill in missing elevations using the RideWithGPS elevation service
+    ///
+    /// Useful for routes built from raw GPS coordinates, so that they have
+    /// proper elevation data before being uploaded. Use
+    /// [`fill_elevation_with`] for another [`ElevationProvider`].
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - Track points to update in place
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{RideWithGpsClient, RouteRequest, TrackPoint};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let mut points: Vec<TrackPoint> = serde_json::from_str(
+    ///     r#"[{"x": 4.90, "y": 52.37}, {"x": 4.91, "y": 52.38}]"#,
+    /// ).unwrap();
+    /// client.fill_elevation(&mut points).unwrap();
+    ///
+    /// let route = client
+    ///     .create_route(&RouteRequest::new().name("Canal loop").track_points(points))
+    ///     .unwrap();
+    /// ```
+    pub fn fill_elevation<P: TrackSample>(&self, points: &mut [P]) -> Result<usize> {
+        fill_elevation_with(points, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TripTrackPoint;
+    use std::cell::Cell;
+
+    struct Counting(Cell<usize>);
+
+    impl ElevationProvider for Counting {
+        fn elevations(&self, locations: &[LatLng]) -> Result<Vec<Option<f64>>> {
+            self.0.set(self.0.get() + locations.len());
+            Ok(locations
+                .iter()
+                .map(|l| (l.lat > 0.0).then_some(l.lat * 10.0))
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_fill_elevation_with() {
+        let mut points: Vec<TripTrackPoint> = serde_json::from_str(
+            r#"[{"x": 1, "y": 1}, {"x": 1, "y": -1}, {"e": 5}, {"x": 1, "y": 2, "e": 7}, {"x": 1, "y": 3}]"#,
+        )
+        .unwrap();
+        let provider = Counting(Cell::new(0));
+
+        assert_eq!(fill_elevation_with(&mut points, &provider).unwrap(), 2);
+        // Only points with a position and without an elevation are looked up
+        assert_eq!(provider.0.get(), 3);
+        let elevations: Vec<_> = points.iter().map(|p| p.e).collect();
+        assert_eq!(
+            elevations,
+            [Some(10.0), None, Some(5.0), Some(7.0), Some(30.0)]
+        );
+    }
+
+    #[test]
+    fn test_fill_elevation_with_short_response() {
+        struct Short;
+
+        impl ElevationProvider for Short {
+            fn elevations(&self, locations: &[LatLng]) -> Result<Vec<Option<f64>>> {
+                Ok(vec![Some(1.0); locations.len() - 1])
+            }
+        }
+
+        let mut points: Vec<TripTrackPoint> =
+            serde_json::from_str(r#"[{"x": 1, "y": 1}, {"x": 1, "y": 2}]"#).unwrap();
+        assert!(matches!(
+            fill_elevation_with(&mut points, &Short),
+            Err(crate::Error::ApiError(_))
+        ));
+        assert!(points.iter().all(|p| p.e.is_none()));
+    }
+}
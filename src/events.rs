@@ -1,6 +1,6 @@
 //! Event-related types and methods
 
-use crate::{PaginatedResponse, Photo, Result, RideWithGpsClient, Visibility};
+use crate::{PaginatedResponse, Photo, Result, ResultExt, RideWithGpsClient, Visibility};
 use serde::{Deserialize, Serialize};
 
 /// Event organizer information
@@ -115,6 +115,7 @@ pub struct Event {
 
 /// Parameters for listing events
 #[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
 pub struct ListEventsParams {
     /// Filter by event name
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -133,8 +134,16 @@ pub struct ListEventsParams {
     pub page_size: Option<u32>,
 }
 
+setters!(ListEventsParams {
+    name: impl Into<String>,
+    visibility: Visibility,
+    page: u32,
+    page_size: u32,
+});
+
 /// Request to create or update an event
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
 pub struct EventRequest {
     /// Event name
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -177,6 +186,19 @@ pub struct EventRequest {
     pub max_attendees: Option<u32>,
 }
 
+setters!(EventRequest {
+    name: impl Into<String>,
+    description: impl Into<String>,
+    location: impl Into<String>,
+    visibility: Visibility,
+    starts_at: impl Into<String>,
+    ends_at: impl Into<String>,
+    registration_opens_at: impl Into<String>,
+    registration_closes_at: impl Into<String>,
+    registration_required: bool,
+    max_attendees: u32,
+});
+
 impl RideWithGpsClient {
     /// List events
     ///
@@ -217,7 +239,58 @@ impl RideWithGpsClient {
             }
         }
 
-        self.get(&url)
+        self.get_path(&url)
+    }
+
+    /// List the events on an organization's calendar
+    ///
+    /// Unlike [`list_events`](Self::list_events), which lists the
+    /// authenticated account's own events, this returns the public calendar
+    /// of a club or other organization.
+    ///
+    /// # Arguments
+    ///
+    /// * `org_id` - The organization ID
+    /// * `params` - Optional parameters for filtering and pagination
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{RideWithGpsClient, ListEventsParams};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     None
+    /// );
+    ///
+    /// let params = ListEventsParams::new().page_size(50);
+    /// let events = client.list_organization_events(42, Some(&params)).unwrap();
+    /// for event in events.results {
+    ///     println!("{}", event);
+    /// }
+    /// ```
+    pub fn list_organization_events(
+        &self,
+        org_id: u64,
+        params: Option<&ListEventsParams>,
+    ) -> Result<PaginatedResponse<Event>> {
+        let mut url = format!("/api/v1/organizations/{}/events.json", org_id);
+
+        if let Some(params) = params {
+            let query = serde_json::to_value(params)?;
+            if let Some(obj) = query.as_object() {
+                if !obj.is_empty() {
+                    let query_str = serde_urlencoded::to_string(obj).map_err(|e| {
+                        crate::Error::ApiError(format!("Failed to encode query: {}", e))
+                    })?;
+                    url.push('?');
+                    url.push_str(&query_str);
+                }
+            }
+        }
+
+        self.get_path(&url)
     }
 
     /// Create a new event
@@ -237,30 +310,20 @@ impl RideWithGpsClient {
     ///     Some("your-auth-token")
     /// );
     ///
-    /// let event_req = EventRequest {
-    ///     name: Some("My Event".to_string()),
-    ///     description: Some("A great ride".to_string()),
-    ///     location: Some("San Francisco, CA".to_string()),
-    ///     visibility: Some(Visibility::Public),
-    ///     starts_at: Some("2025-06-01T09:00:00".to_string()),
-    ///     ends_at: Some("2025-06-01T17:00:00".to_string()),
-    ///     registration_opens_at: None,
-    ///     registration_closes_at: None,
-    ///     registration_required: Some(false),
-    ///     max_attendees: None,
-    /// };
+    /// let event_req = EventRequest::new()
+    ///     .name("My Event")
+    ///     .description("A great ride")
+    ///     .location("San Francisco, CA")
+    ///     .visibility(Visibility::Public)
+    ///     .starts_at("2025-06-01T09:00:00")
+    ///     .ends_at("2025-06-01T17:00:00")
+    ///     .registration_required(false);
     ///
     /// let event = client.create_event(&event_req).unwrap();
     /// println!("Created event: {}", event.id);
     /// ```
     pub fn create_event(&self, event: &EventRequest) -> Result<Event> {
-        #[derive(Deserialize)]
-        struct EventWrapper {
-            event: Event,
-        }
-
-        let wrapper: EventWrapper = self.post("/api/v1/events.json", event)?;
-        Ok(wrapper.event)
+        self.post_wrapped("/api/v1/events.json", "event", event)
     }
 
     /// Get a specific event by ID
@@ -284,13 +347,33 @@ impl RideWithGpsClient {
     /// println!("Event: {:?}", event);
     /// ```
     pub fn get_event(&self, id: u64) -> Result<Event> {
-        #[derive(Deserialize)]
-        struct EventWrapper {
-            event: Event,
-        }
+        self.get::<Event>(id)
+    }
 
-        let wrapper: EventWrapper = self.get(&format!("/api/v1/events/{}.json", id))?;
-        Ok(wrapper.event)
+    /// Get a specific event by ID, or `None` if it does not exist
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The event ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// match client.get_event_opt(12345).unwrap() {
+    ///     Some(event) => println!("Found: {:?}", event.name),
+    ///     None => println!("No such event"),
+    /// }
+    /// ```
+    pub fn get_event_opt(&self, id: u64) -> Result<Option<Event>> {
+        self.get_event(id).optional()
     }
 
     /// Update an event
@@ -311,30 +394,13 @@ impl RideWithGpsClient {
     ///     Some("your-auth-token")
     /// );
     ///
-    /// let event_req = EventRequest {
-    ///     name: Some("Updated Event Name".to_string()),
-    ///     description: None,
-    ///     location: None,
-    ///     visibility: None,
-    ///     starts_at: None,
-    ///     ends_at: None,
-    ///     registration_opens_at: None,
-    ///     registration_closes_at: None,
-    ///     registration_required: None,
-    ///     max_attendees: None,
-    /// };
+    /// let event_req = EventRequest::new().name("Updated Event Name");
     ///
     /// let event = client.update_event(12345, &event_req).unwrap();
     /// println!("Updated event: {:?}", event);
     /// ```
     pub fn update_event(&self, id: u64, event: &EventRequest) -> Result<Event> {
-        #[derive(Deserialize)]
-        struct EventWrapper {
-            event: Event,
-        }
-
-        let wrapper: EventWrapper = self.put(&format!("/api/v1/events/{}.json", id), event)?;
-        Ok(wrapper.event)
+        self.put_wrapped(&format!("/api/v1/events/{}.json", id), "event", event)
     }
 
     /// Delete an event
@@ -357,7 +423,7 @@ impl RideWithGpsClient {
     /// client.delete_event(12345).unwrap();
     /// ```
     pub fn delete_event(&self, id: u64) -> Result<()> {
-        self.delete(&format!("/api/v1/events/{}.json", id))
+        self.delete::<Event>(id)
     }
 }
 
@@ -0,0 +1,12 @@
+//! Exporting routes to files
+//!
+//! [`device`] copies routes to GPS devices mounted as USB mass storage.
+//! With the `zip` feature, `export_routes_zip` bundles them into a ZIP
+//! archive with a manifest.
+
+#[cfg(feature = "zip")]
+mod archive;
+pub mod device;
+
+#[cfg(feature = "zip")]
+pub use archive::*;
@@ -0,0 +1,252 @@
+//! Bulk export of routes to a ZIP archive
+//!
+//! Requires the `zip` feature.
+
+use crate::{Error, ListRoutesParams, Result, RideWithGpsClient, Route};
+use serde::{Deserialize, Serialize};
+use std::io::{Seek, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Name of the manifest file in an export archive
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Longest name part of a GPX file name, in bytes
+const MAX_NAME_LEN: usize = 60;
+
+/// Which routes [`RideWithGpsClient::export_routes_zip`] exports
+#[derive(Debug, Clone, Copy)]
+pub enum RouteSelection<'a> {
+    /// These routes
+    Ids(&'a [u64]),
+
+    /// Every route of the listing, across all pages
+    Matching(Option<&'a ListRoutesParams>),
+}
+
+/// A route in an export archive, as listed in its manifest
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ExportedRoute {
+    /// Route ID
+    pub id: u64,
+
+    /// Route name
+    pub name: Option<String>,
+
+    /// Path of the GPX file in the archive, if the route was exported
+    pub file: Option<String>,
+
+    /// Why the route could not be exported
+    pub error: Option<String>,
+}
+
+/// Contents of an export archive, also stored in it as [`MANIFEST_FILE`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ExportManifest {
+    /// Every selected route, in export order
+    pub routes: Vec<ExportedRoute>,
+}
+
+impl ExportManifest {
+    /// Routes that could not be exported
+    pub fn failed(&self) -> impl Iterator<Item = &ExportedRoute> {
+        self.routes.iter().filter(|r| r.error.is_some())
+    }
+}
+
+impl RideWithGpsClient {
+    /// Export routes as GPX files into a ZIP archive
+    ///
+    /// Routes are fetched and written one at a time, so memory use does not
+    /// grow with the size of the library. Each GPX file is named after the
+    /// route's ID and name, reduced to characters that are safe on any file
+    /// system, and a [`MANIFEST_FILE`] lists every selected route.
+    ///
+    /// A route that cannot be fetched is recorded in the manifest with its
+    /// error and skipped, rather than aborting the export; failing to list
+    /// the routes does abort it. To ride out rate limiting on large
+    /// libraries, build the client with a
+    /// [`retry`](crate::ClientBuilder::retry) policy, which waits as long as
+    /// `429` responses ask.
+    ///
+    /// # Arguments
+    ///
+    /// * `routes` - The routes to export
+    /// * `writer` - Where to write the archive, such as a file
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{RetryPolicy, RideWithGpsClient, RouteSelection};
+    ///
+    /// let client = RideWithGpsClient::builder("https://ridewithgps.com", "your-api-key")
+    ///     .auth_token("your-auth-token")
+    ///     .retry(RetryPolicy::new())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let file = std::fs::File::create("routes.zip").unwrap();
+    /// let manifest = client
+    ///     .export_routes_zip(RouteSelection::Matching(None), file)
+    ///     .unwrap();
+    /// for route in manifest.failed() {
+    ///     eprintln!("Skipped route {}: {:?}", route.id, route.error);
+    /// }
+    /// ```
+    pub fn export_routes_zip<W: Write + Seek>(
+        &self,
+        routes: RouteSelection<'_>,
+        writer: W,
+    ) -> Result<ExportManifest> {
+        let mut archive = ZipExport::new(writer);
+
+        match routes {
+            RouteSelection::Ids(ids) => {
+                for &id in ids {
+                    archive.add(id, None, self.get_route(id))?;
+                }
+            }
+            RouteSelection::Matching(params) => {
+                for listed in self.paginate::<Route>(params)? {
+                    let listed = listed?;
+                    archive.add(listed.id, listed.name, self.get_route(listed.id))?;
+                }
+            }
+        }
+
+        archive.finish()
+    }
+}
+
+/// An export archive being written
+struct ZipExport<W: Write + Seek> {
+    zip: ZipWriter<W>,
+    manifest: ExportManifest,
+}
+
+impl<W: Write + Seek> ZipExport<W> {
+    fn new(writer: W) -> Self {
+        Self {
+            zip: ZipWriter::new(writer),
+            manifest: ExportManifest::default(),
+        }
+    }
+
+    /// Add a fetched route, or record why it could not be fetched
+    ///
+    /// `name` is the route's name from a listing, used in the manifest if
+    /// the route could not be fetched.
+    fn add(&mut self, id: u64, name: Option<String>, route: Result<Route>) -> Result<()> {
+        let route = match route {
+            Ok(route) => route,
+            Err(e) => {
+                self.manifest.routes.push(ExportedRoute {
+                    id,
+                    name,
+                    file: None,
+                    error: Some(e.to_string()),
+                });
+                return Ok(());
+            }
+        };
+
+        let file = gpx_file_name(id, route.name.as_deref());
+        let mut gpx = Vec::new();
+        route.write_gpx(&mut gpx)?;
+        self.zip
+            .start_file(file.as_str(), deflated())
+            .map_err(zip_error)?;
+        self.zip.write_all(&gpx)?;
+
+        self.manifest.routes.push(ExportedRoute {
+            id,
+            name: route.name,
+            file: Some(file),
+            error: None,
+        });
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<ExportManifest> {
+        self.zip
+            .start_file(MANIFEST_FILE, deflated())
+            .map_err(zip_error)?;
+        serde_json::to_writer_pretty(&mut self.zip, &self.manifest)?;
+        self.zip.finish().map_err(zip_error)?;
+        Ok(self.manifest)
+    }
+}
+
+fn deflated() -> SimpleFileOptions {
+    SimpleFileOptions::default().compression_method(CompressionMethod::Deflated)
+}
+
+fn zip_error(e: zip::result::ZipError) -> Error {
+    Error::Io(e.into())
+}
+
+/// File name for a route's GPX, such as `12345-Morning-Loop.gpx`
+fn gpx_file_name(id: u64, name: Option<&str>) -> String {
+    let mut safe = String::new();
+    for c in name.unwrap_or_default().chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            safe.push(c);
+        } else if !safe.is_empty() && !safe.ends_with('-') {
+            safe.push('-');
+        }
+        if safe.len() >= MAX_NAME_LEN {
+            break;
+        }
+    }
+    match safe.trim_end_matches('-') {
+        "" => format!("{}.gpx", id),
+        safe => format!("{}-{}.gpx", id, safe),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_gpx_file_name() {
+        assert_eq!(gpx_file_name(1, Some("Morning Loop")), "1-Morning-Loop.gpx");
+        assert_eq!(
+            gpx_file_name(2, Some("  Col du Galibier / Télégraphe ")),
+            "2-Col-du-Galibier-T-l-graphe.gpx"
+        );
+        assert_eq!(gpx_file_name(3, Some("../..")), "3.gpx");
+        assert_eq!(gpx_file_name(4, None), "4.gpx");
+        assert!(gpx_file_name(5, Some(&"x".repeat(200))).len() <= 70);
+    }
+
+    #[test]
+    fn test_zip_export() {
+        let route: Route = serde_json::from_str(
+            r#"{"id": 7, "name": "Dune ride", "track_points": [{"x": 4.5, "y": 52.3}]}"#,
+        )
+        .unwrap();
+
+        let mut buffer = Cursor::new(Vec::new());
+        let mut archive = ZipExport::new(&mut buffer);
+        archive.add(7, None, Ok(route)).unwrap();
+        let gone = Err(Error::NotFound("gone".to_string()));
+        archive.add(8, Some("Old".to_string()), gone).unwrap();
+        let manifest = archive.finish().unwrap();
+
+        assert_eq!(manifest.routes[0].file.as_deref(), Some("7-Dune-ride.gpx"));
+        let failed: Vec<_> = manifest.failed().map(|r| r.name.as_deref()).collect();
+        assert_eq!(failed, vec![Some("Old")]);
+
+        let mut zip = zip::ZipArchive::new(buffer).unwrap();
+        assert_eq!(zip.len(), 2);
+        let stored: ExportManifest =
+            serde_json::from_reader(zip.by_name(MANIFEST_FILE).unwrap()).unwrap();
+        assert_eq!(stored, manifest);
+        let mut gpx = String::new();
+        std::io::Read::read_to_string(&mut zip.by_name("7-Dune-ride.gpx").unwrap(), &mut gpx)
+            .unwrap();
+        assert!(gpx.contains("<name>Dune ride</name>"));
+    }
+}
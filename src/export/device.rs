@@ -0,0 +1,283 @@
+//! Copying routes to GPS devices mounted as USB mass storage
+
+use crate::{Error, Result, RideWithGpsClient, Route};
+use std::path::{Path, PathBuf};
+
+/// Longest name part of a route file name, in bytes
+const MAX_NAME_LEN: usize = 40;
+
+/// File format written to a device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceFileFormat {
+    /// FIT course, see [`Route::write_fit_course`]
+    Fit,
+
+    /// GPX document, see [`Route::write_gpx`]
+    Gpx,
+}
+
+impl DeviceFileFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            DeviceFileFormat::Fit => "fit",
+            DeviceFileFormat::Gpx => "gpx",
+        }
+    }
+}
+
+/// Where and how a device expects route files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceLayout {
+    /// Garmin devices: FIT courses in `Garmin/NewFiles`, which the device
+    /// imports as courses when it is next unplugged
+    Garmin,
+
+    /// Wahoo ELEMNT devices: FIT routes in `routes`, which show up in the
+    /// device's route list
+    Wahoo,
+
+    /// Hammerhead Karoo devices: GPX routes in `Download`, which the
+    /// device offers to import from its routes screen
+    Hammerhead,
+
+    /// Files of a format in a directory relative to the mount point, for
+    /// other devices
+    Directory {
+        /// Directory relative to the mount point
+        path: PathBuf,
+
+        /// Format of the files
+        format: DeviceFileFormat,
+    },
+}
+
+impl DeviceLayout {
+    /// Recognize the device mounted at `mount_path`
+    ///
+    /// Garmin devices are recognized by their `Garmin` directory, Wahoo
+    /// devices by their `exports` and `routes` directories. Hammerhead
+    /// devices look like any other Android device and are not recognized.
+    pub fn detect(mount_path: impl AsRef<Path>) -> Option<DeviceLayout> {
+        let mount_path = mount_path.as_ref();
+        if mount_path.join("Garmin").is_dir() {
+            Some(DeviceLayout::Garmin)
+        } else if mount_path.join("exports").is_dir() && mount_path.join("routes").is_dir() {
+            Some(DeviceLayout::Wahoo)
+        } else {
+            None
+        }
+    }
+
+    /// Directory route files go in, relative to the mount point
+    pub fn directory(&self) -> PathBuf {
+        match self {
+            DeviceLayout::Garmin => Path::new("Garmin").join("NewFiles"),
+            DeviceLayout::Wahoo => PathBuf::from("routes"),
+            DeviceLayout::Hammerhead => PathBuf::from("Download"),
+            DeviceLayout::Directory { path, .. } => path.clone(),
+        }
+    }
+
+    /// Format of the route files
+    pub fn format(&self) -> DeviceFileFormat {
+        match self {
+            DeviceLayout::Garmin | DeviceLayout::Wahoo => DeviceFileFormat::Fit,
+            DeviceLayout::Hammerhead => DeviceFileFormat::Gpx,
+            DeviceLayout::Directory { format, .. } => *format,
+        }
+    }
+
+    /// Write a route to the device mounted at `mount_path`, returning the
+    /// path of the new file
+    ///
+    /// The directory is created if missing. Existing files for the same
+    /// route are replaced, including ones named after an earlier name of
+    /// the route.
+    pub fn write_route(&self, mount_path: impl AsRef<Path>, route: &Route) -> Result<PathBuf> {
+        let dir = mount_path.as_ref().join(self.directory());
+        std::fs::create_dir_all(&dir)?;
+
+        let format = self.format();
+        let name = route_file_name(route, format);
+        let path = dir.join(&name);
+        let mut data = Vec::new();
+        match format {
+            DeviceFileFormat::Fit => route.write_fit_course(&mut data)?,
+            DeviceFileFormat::Gpx => route.write_gpx(&mut data)?,
+        }
+        std::fs::write(&path, data)?;
+
+        let extension = format!(".{}", format.extension());
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let Some(file_name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            let Some(stem) = file_name.strip_suffix(&extension) else {
+                continue;
+            };
+            let stale = stem == route.id.to_string() || stem.starts_with(&format!("{}-", route.id));
+            if stale && file_name != name {
+                std::fs::remove_file(entry.path())?;
+            }
+        }
+        Ok(path)
+    }
+}
+
+impl RideWithGpsClient {
+    /// Copy routes to a GPS device mounted as USB mass storage
+    ///
+    /// Each route is fetched and written with
+    /// [`DeviceLayout::write_route`]. Returns the paths of the new files.
+    ///
+    /// # Arguments
+    ///
+    /// * `mount_path` - Where the device is mounted
+    /// * `layout` - Where and how the device expects route files; see
+    ///   [`DeviceLayout::detect`]
+    /// * `route_ids` - The routes to copy
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{DeviceLayout, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let mount = "/media/GARMIN";
+    /// let layout = DeviceLayout::detect(mount).expect("no Garmin device mounted");
+    /// for path in client
+    ///     .sync_routes_to_mounted_device(mount, &layout, &[12345, 67890])
+    ///     .unwrap()
+    /// {
+    ///     println!("Wrote {}", path.display());
+    /// }
+    /// ```
+    pub fn sync_routes_to_mounted_device(
+        &self,
+        mount_path: impl AsRef<Path>,
+        layout: &DeviceLayout,
+        route_ids: &[u64],
+    ) -> Result<Vec<PathBuf>> {
+        let mount_path = mount_path.as_ref();
+        if !mount_path.is_dir() {
+            return Err(Error::ValidationError(format!(
+                "No device mounted at {}",
+                mount_path.display()
+            )));
+        }
+
+        route_ids
+            .iter()
+            .map(|&id| layout.write_route(mount_path, &self.get_route(id)?))
+            .collect()
+    }
+}
+
+/// File name for a route: its ID and name, reduced to characters every
+/// device file system accepts
+fn route_file_name(route: &Route, format: DeviceFileFormat) -> String {
+    let mut safe = String::new();
+    for c in route.name.as_deref().unwrap_or_default().chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            safe.push(c);
+        } else if !safe.is_empty() && !safe.ends_with('-') {
+            safe.push('-');
+        }
+        if safe.len() >= MAX_NAME_LEN {
+            break;
+        }
+    }
+    match safe.trim_end_matches('-') {
+        "" => format!("{}.{}", route.id, format.extension()),
+        safe => format!("{}-{}.{}", route.id, safe, format.extension()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_file_name() {
+        let route: Route =
+            serde_json::from_str(r#"{"id": 1, "name": "Col du Galibier / Télégraphe"}"#).unwrap();
+        assert_eq!(
+            route_file_name(&route, DeviceFileFormat::Fit),
+            "1-Col-du-Galibier-T-l-graphe.fit"
+        );
+        let route: Route = serde_json::from_str(r#"{"id": 2}"#).unwrap();
+        assert_eq!(route_file_name(&route, DeviceFileFormat::Gpx), "2.gpx");
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_sync_routes_to_mounted_device() {
+        use crate::testing::FakeServer;
+
+        let server = FakeServer::start();
+        let client = server.client();
+        let mount = std::env::temp_dir().join(format!("rwgps-device-{}", std::process::id()));
+        std::fs::create_dir_all(mount.join("Garmin")).unwrap();
+
+        let layout = DeviceLayout::detect(&mount).unwrap();
+        assert_eq!(layout, DeviceLayout::Garmin);
+        let paths = client
+            .sync_routes_to_mounted_device(&mount, &layout, &[1, 2])
+            .unwrap();
+        assert_eq!(paths.len(), 2);
+        assert!(paths[0].starts_with(mount.join("Garmin/NewFiles")));
+        assert_eq!(&std::fs::read(&paths[1]).unwrap()[8..12], b".FIT");
+
+        let directory = DeviceLayout::Directory {
+            path: PathBuf::from("routes"),
+            format: DeviceFileFormat::Gpx,
+        };
+        let paths = client
+            .sync_routes_to_mounted_device(&mount, &directory, &[3])
+            .unwrap();
+        assert_eq!(paths[0].extension().unwrap(), "gpx");
+        assert!(paths[0].starts_with(mount.join("routes")));
+
+        // A renamed route replaces the file named after its old name
+        let mut route = client.get_route(3).unwrap();
+        route.name = Some("Renamed".to_string());
+        std::fs::write(mount.join("routes/31-Other.gpx"), "").unwrap();
+        let path = directory.write_route(&mount, &route).unwrap();
+        assert_eq!(path, mount.join("routes/3-Renamed.gpx"));
+        let mut files: Vec<_> = std::fs::read_dir(mount.join("routes"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        files.sort();
+        assert_eq!(files, ["3-Renamed.gpx", "31-Other.gpx"]);
+
+        let paths = client
+            .sync_routes_to_mounted_device(&mount, &DeviceLayout::Hammerhead, &[4])
+            .unwrap();
+        assert!(paths[0].starts_with(mount.join("Download")));
+        assert_eq!(paths[0].extension().unwrap(), "gpx");
+
+        std::fs::remove_dir_all(&mount).unwrap();
+        assert!(DeviceLayout::detect(&mount).is_none());
+
+        std::fs::create_dir_all(mount.join("exports")).unwrap();
+        std::fs::create_dir_all(mount.join("routes")).unwrap();
+        let layout = DeviceLayout::detect(&mount).unwrap();
+        assert_eq!(layout, DeviceLayout::Wahoo);
+        let paths = client
+            .sync_routes_to_mounted_device(&mount, &layout, &[5])
+            .unwrap();
+        assert!(paths[0].starts_with(mount.join("routes")));
+        assert_eq!(paths[0].extension().unwrap(), "fit");
+        std::fs::remove_dir_all(&mount).unwrap();
+        assert!(client
+            .sync_routes_to_mounted_device(&mount, &layout, &[1])
+            .is_err());
+    }
+}
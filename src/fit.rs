@@ -0,0 +1,370 @@
+//! FIT course export of routes
+
+use crate::{CueType, LatLng, Route};
+use chrono::DateTime;
+use std::io::Write;
+
+/// Seconds between the Unix epoch and the FIT epoch (1989-12-31)
+const FIT_EPOCH: i64 = 631_065_600;
+
+/// Speed in m/s used to give course points timestamps, which devices use
+/// for the virtual partner
+const COURSE_SPEED: f64 = 20.0 / 3.6;
+
+/// FIT profile version written in the file header (21.32)
+const PROFILE_VERSION: u16 = 2132;
+
+/// Size in bytes of name fields, including the terminating null
+const NAME_SIZE: u8 = 32;
+
+// FIT base types
+const ENUM: u8 = 0x00;
+const UINT16: u8 = 0x84;
+const SINT32: u8 = 0x85;
+const UINT32: u8 = 0x86;
+const STRING: u8 = 0x07;
+
+// Global message numbers
+const FILE_ID: u16 = 0;
+const LAP: u16 = 19;
+const RECORD: u16 = 20;
+const EVENT: u16 = 21;
+const COURSE: u16 = 31;
+const COURSE_POINT: u16 = 32;
+
+impl Route {
+    /// Write the route as a FIT course file
+    ///
+    /// FIT is the native format of Garmin devices, and is read by most other
+    /// bike computers. Track points become records and course points with a
+    /// position become course points. Devices need timestamps for their
+    /// virtual partner, so points are timed at a steady 20 km/h from the
+    /// route's last update.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let route = client.get_route(12345).unwrap();
+    /// let mut file = std::fs::File::create("route.fit").unwrap();
+    /// route.write_fit_course(&mut file).unwrap();
+    /// ```
+    pub fn write_fit_course<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let start = self
+            .updated_at
+            .iter()
+            .chain(&self.created_at)
+            .find_map(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map_or(0, |t| (t.timestamp() - FIT_EPOCH).max(0) as u32);
+        let time_at = |distance: f64| start + (distance / COURSE_SPEED).round() as u32;
+
+        // Fill in distances the route does not have from the positions
+        let mut points = Vec::new();
+        let mut previous: Option<(LatLng, f64)> = None;
+        for point in self.track_points.iter().flatten() {
+            let Some(coord) = point.coord() else {
+                continue;
+            };
+            let distance = point
+                .d
+                .unwrap_or_else(|| previous.map_or(0.0, |(last, d)| d + last.distance_to(&coord)));
+            points.push((coord, distance, point.e));
+            previous = Some((coord, distance));
+        }
+        let total_distance = points.last().map_or(0.0, |&(_, d, _)| d);
+        let end = time_at(total_distance);
+
+        let mut fit = FitWriter::default();
+        fit.define(
+            0,
+            FILE_ID,
+            &[(0, 1, ENUM), (1, 2, UINT16), (2, 2, UINT16), (4, 4, UINT32)],
+        );
+        fit.record(0);
+        fit.u8(6); // course
+        fit.u16(255); // development
+        fit.u16(0);
+        fit.u32(start);
+
+        fit.define(1, COURSE, &[(4, 1, ENUM), (5, NAME_SIZE, STRING)]);
+        fit.record(1);
+        fit.u8(2); // cycling
+        fit.string(self.name.as_deref().unwrap_or("Route"));
+
+        let first = points.first().map(|&(c, _, _)| c);
+        let last = points.last().map(|&(c, _, _)| c);
+        fit.define(
+            2,
+            LAP,
+            &[
+                (253, 4, UINT32),
+                (2, 4, UINT32),
+                (3, 4, SINT32),
+                (4, 4, SINT32),
+                (5, 4, SINT32),
+                (6, 4, SINT32),
+                (7, 4, UINT32),
+                (8, 4, UINT32),
+                (9, 4, UINT32),
+            ],
+        );
+        fit.record(2);
+        fit.u32(end);
+        fit.u32(start);
+        fit.position(first);
+        fit.position(last);
+        fit.u32((end - start) * 1000);
+        fit.u32((end - start) * 1000);
+        fit.u32((total_distance * 100.0).round() as u32);
+
+        fit.define(3, EVENT, &[(253, 4, UINT32), (0, 1, ENUM), (1, 1, ENUM)]);
+        fit.record(3);
+        fit.u32(start);
+        fit.u8(0); // timer
+        fit.u8(0); // start
+
+        fit.define(
+            4,
+            RECORD,
+            &[
+                (253, 4, UINT32),
+                (0, 4, SINT32),
+                (1, 4, SINT32),
+                (2, 2, UINT16),
+                (5, 4, UINT32),
+            ],
+        );
+        for &(coord, distance, elevation) in &points {
+            fit.record(4);
+            fit.u32(time_at(distance));
+            fit.position(Some(coord));
+            fit.u16(elevation.map_or(u16::MAX, |e| ((e + 500.0) * 5.0).round() as u16));
+            fit.u32((distance * 100.0).round() as u32);
+        }
+
+        fit.record(3);
+        fit.u32(end);
+        fit.u8(0); // timer
+        fit.u8(4); // stop all
+
+        fit.define(
+            5,
+            COURSE_POINT,
+            &[
+                (254, 2, UINT16),
+                (1, 4, UINT32),
+                (2, 4, SINT32),
+                (3, 4, SINT32),
+                (4, 4, UINT32),
+                (5, 1, ENUM),
+                (6, NAME_SIZE, STRING),
+            ],
+        );
+        let cues = self
+            .course_points
+            .iter()
+            .flatten()
+            .filter_map(|cue| Some((cue, cue.coord()?)));
+        for (index, (cue, coord)) in cues.enumerate() {
+            let distance = cue.d.unwrap_or_default();
+            fit.record(5);
+            fit.u16(index as u16);
+            fit.u32(time_at(distance));
+            fit.position(Some(coord));
+            fit.u32((distance * 100.0).round() as u32);
+            fit.u8(course_point_type(cue.t.as_ref()));
+            fit.string(cue.n.as_deref().unwrap_or_default());
+        }
+
+        writer.write_all(&fit.finish())
+    }
+}
+
+/// FIT `course_point` type of a cue
+fn course_point_type(cue_type: Option<&CueType>) -> u8 {
+    match cue_type {
+        Some(CueType::Summit) => 1,
+        Some(CueType::Valley) => 2,
+        Some(CueType::Water) => 3,
+        Some(CueType::Food) => 4,
+        Some(CueType::Danger) => 5,
+        Some(CueType::Left) => 6,
+        Some(CueType::Right) => 7,
+        Some(CueType::Straight) => 8,
+        Some(CueType::FirstAid) => 9,
+        Some(CueType::SlightLeft) => 19,
+        Some(CueType::SharpLeft) => 20,
+        Some(CueType::SlightRight) => 21,
+        Some(CueType::SharpRight) => 22,
+        Some(CueType::UTurn) => 23,
+        _ => 0,
+    }
+}
+
+/// Builds the records of a FIT file, little-endian
+#[derive(Default)]
+struct FitWriter {
+    records: Vec<u8>,
+}
+
+impl FitWriter {
+    /// Define the fields of a local message type as `(number, size, base type)`
+    fn define(&mut self, local: u8, global: u16, fields: &[(u8, u8, u8)]) {
+        self.records.extend([0x40 | local, 0, 0]);
+        self.u16(global);
+        self.u8(fields.len() as u8);
+        for &(number, size, base_type) in fields {
+            self.records.extend([number, size, base_type]);
+        }
+    }
+
+    /// Start a data message of a local message type
+    fn record(&mut self, local: u8) {
+        self.u8(local);
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.records.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.records.extend(value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.records.extend(value.to_le_bytes());
+    }
+
+    /// Latitude and longitude in semicircles, or invalid values if unknown
+    fn position(&mut self, coord: Option<LatLng>) {
+        let semicircles = |degrees: f64| (degrees * (2f64.powi(31) / 180.0)).round() as i32;
+        let (lat, lng) = coord.map_or((i32::MAX, i32::MAX), |c| {
+            (semicircles(c.lat), semicircles(c.lng))
+        });
+        self.records.extend(lat.to_le_bytes());
+        self.records.extend(lng.to_le_bytes());
+    }
+
+    /// A null-padded string field of [`NAME_SIZE`] bytes, cut at a character
+    /// boundary if too long
+    fn string(&mut self, value: &str) {
+        let mut end = value.len().min(NAME_SIZE as usize - 1);
+        while !value.is_char_boundary(end) {
+            end -= 1;
+        }
+        let mut field = value.as_bytes()[..end].to_vec();
+        field.resize(NAME_SIZE as usize, 0);
+        self.records.extend(field);
+    }
+
+    /// The complete file: header, records and CRC
+    fn finish(self) -> Vec<u8> {
+        let mut file = vec![14, 0x20];
+        file.extend(PROFILE_VERSION.to_le_bytes());
+        file.extend((self.records.len() as u32).to_le_bytes());
+        file.extend(b".FIT");
+        file.extend(crc(&file).to_le_bytes());
+        file.extend(self.records);
+        file.extend(crc(&file).to_le_bytes());
+        file
+    }
+}
+
+/// CRC-16 as used by FIT files
+fn crc(data: &[u8]) -> u16 {
+    const TABLE: [u16; 16] = [
+        0x0000, 0xCC01, 0xD801, 0x1400, 0xF001, 0x3C00, 0x2800, 0xE401, 0xA001, 0x6C00, 0x7800,
+        0xB401, 0x5000, 0x9C01, 0x8801, 0x4400,
+    ];
+    data.iter().fold(0, |mut crc, &byte| {
+        for nibble in [byte & 0xF, byte >> 4] {
+            let tmp = TABLE[(crc & 0xF) as usize];
+            crc = ((crc >> 4) & 0x0FFF) ^ tmp ^ TABLE[nibble as usize];
+        }
+        crc
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Count the data messages of a FIT file by global message number
+    fn count_messages(file: &[u8]) -> HashMap<u16, usize> {
+        let mut definitions = HashMap::new();
+        let mut counts = HashMap::new();
+        let end = file.len() - 2;
+        let mut i = file[0] as usize;
+        while i < end {
+            let header = file[i];
+            let local = header & 0x0F;
+            if header & 0x40 != 0 {
+                let global = u16::from_le_bytes([file[i + 3], file[i + 4]]);
+                let fields = file[i + 5] as usize;
+                let size: usize = (0..fields).map(|f| file[i + 7 + 3 * f] as usize).sum();
+                definitions.insert(local, (global, size));
+                i += 6 + 3 * fields;
+            } else {
+                let (global, size) = definitions[&local];
+                *counts.entry(global).or_default() += 1;
+                i += 1 + size;
+            }
+        }
+        assert_eq!(i, end);
+        counts
+    }
+
+    #[test]
+    fn test_write_fit_course() {
+        let route: Route = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "name": "A rather long route name that does not fit",
+            "updated_at": "2025-06-01T08:00:00Z",
+            "track_points": [
+                {"x": -122.6, "y": 45.5, "e": 10.0},
+                {"x": -122.59, "y": 45.5, "e": 12.0},
+                {"d": 2000.0},
+                {"x": -122.58, "y": 45.5}
+            ],
+            "course_points": [
+                {"x": -122.59, "y": 45.5, "d": 780.0, "t": "left", "n": "Turn left onto Main St"},
+                {"d": 900.0, "t": "right"}
+            ]
+        }))
+        .unwrap();
+
+        let mut file = Vec::new();
+        route.write_fit_course(&mut file).unwrap();
+
+        assert_eq!(&file[8..12], b".FIT");
+        assert_eq!(crc(&file[..14]), 0);
+        assert_eq!(crc(&file), 0);
+        let size = u32::from_le_bytes(file[4..8].try_into().unwrap()) as usize;
+        assert_eq!(size, file.len() - 16);
+
+        let counts = count_messages(&file);
+        assert_eq!(counts[&FILE_ID], 1);
+        assert_eq!(counts[&COURSE], 1);
+        assert_eq!(counts[&LAP], 1);
+        assert_eq!(counts[&EVENT], 2);
+        assert_eq!(counts[&RECORD], 3);
+        assert_eq!(counts[&COURSE_POINT], 1);
+
+        let name = b"A rather long route name that d\0";
+        assert!(file.windows(name.len()).any(|w| w == name));
+    }
+
+    #[test]
+    fn test_crc() {
+        assert_eq!(crc(b""), 0);
+        assert_eq!(crc(b"123456789"), 0xBB3D);
+    }
+}
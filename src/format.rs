@@ -0,0 +1,58 @@
+//! Formatting quantities for display in a unit system
+//!
+//! These produce short strings such as `"42.3 km"` or `"26.3 mi"`, so CLI and
+//! GUI consumers display quantities consistently. The API does not expose
+//! the user's preferred unit system, so callers choose one, for example from
+//! a command line flag.
+
+use crate::{Meters, MetersPerSecond, UnitSystem};
+
+/// Format a distance, such as `"42.3 km"` or `"26.3 mi"`
+pub fn distance(distance: Meters, units: UnitSystem) -> String {
+    match units {
+        UnitSystem::Metric => format!("{:.1} km", distance.km()),
+        UnitSystem::Imperial => format!("{:.1} mi", distance.miles()),
+    }
+}
+
+/// Format an elevation or elevation gain, such as `"512 m"` or `"1680 ft"`
+pub fn elevation(elevation: Meters, units: UnitSystem) -> String {
+    match units {
+        UnitSystem::Metric => format!("{:.0} m", elevation.0),
+        UnitSystem::Imperial => format!("{:.0} ft", elevation.feet()),
+    }
+}
+
+/// Format a speed, such as `"24.5 km/h"` or `"15.2 mph"`
+pub fn speed(speed: MetersPerSecond, units: UnitSystem) -> String {
+    match units {
+        UnitSystem::Metric => format!("{:.1} km/h", speed.kmh()),
+        UnitSystem::Imperial => format!("{:.1} mph", speed.mph()),
+    }
+}
+
+/// Format a temperature given in Celsius, such as `"18 °C"` or `"64 °F"`
+pub fn temperature(celsius: f64, units: UnitSystem) -> String {
+    match units {
+        UnitSystem::Metric => format!("{:.0} °C", celsius),
+        UnitSystem::Imperial => format!("{:.0} °F", celsius * 9.0 / 5.0 + 32.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_quantities() {
+        assert_eq!(distance(Meters(42300.0), UnitSystem::Metric), "42.3 km");
+        assert_eq!(distance(Meters(42300.0), UnitSystem::Imperial), "26.3 mi");
+        assert_eq!(elevation(Meters(512.0), UnitSystem::Imperial), "1680 ft");
+        assert_eq!(
+            speed(MetersPerSecond::from_kmh(24.5), UnitSystem::Metric),
+            "24.5 km/h"
+        );
+        assert_eq!(temperature(18.0, UnitSystem::Imperial), "64 °F");
+        assert_eq!(temperature(-3.4, UnitSystem::Metric), "-3 °C");
+    }
+}
@@ -0,0 +1,285 @@
+//! Goal-related types and methods
+
+use crate::{PaginatedResponse, Result, RideWithGpsClient};
+use serde::{Deserialize, Serialize};
+
+/// Period over which a goal is measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GoalPeriod {
+    /// Weekly goal
+    Week,
+
+    /// Monthly goal
+    Month,
+
+    /// Yearly goal
+    Year,
+
+    /// Goal with explicit start and end dates
+    Custom,
+}
+
+/// Progress made towards a goal
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GoalProgress {
+    /// Distance covered in meters
+    pub distance: Option<f64>,
+
+    /// Elevation gained in meters
+    pub elevation_gain: Option<f64>,
+
+    /// Moving time in seconds
+    pub duration: Option<f64>,
+
+    /// Number of trips counted towards the goal
+    pub trip_count: Option<u32>,
+
+    /// Overall completion percentage
+    pub percent_complete: Option<f64>,
+}
+
+/// A training goal
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Goal {
+    /// Goal ID
+    pub id: u64,
+
+    /// Goal name
+    pub name: Option<String>,
+
+    /// Goal description
+    pub description: Option<String>,
+
+    /// User ID of the goal owner
+    pub user_id: Option<u64>,
+
+    /// Goal period
+    pub period: Option<GoalPeriod>,
+
+    /// Target distance in meters
+    pub target_distance: Option<f64>,
+
+    /// Target elevation gain in meters
+    pub target_elevation_gain: Option<f64>,
+
+    /// Target moving time in seconds
+    pub target_duration: Option<f64>,
+
+    /// Start date
+    pub starts_on: Option<String>,
+
+    /// End date
+    pub ends_on: Option<String>,
+
+    /// Whether the goal has been reached
+    pub completed: Option<bool>,
+
+    /// Progress towards the goal
+    pub progress: Option<GoalProgress>,
+
+    /// API URL
+    pub url: Option<String>,
+
+    /// Created timestamp
+    pub created_at: Option<String>,
+
+    /// Updated timestamp
+    pub updated_at: Option<String>,
+}
+
+/// Request to create or update a goal
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
+pub struct GoalRequest {
+    /// Goal name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Goal description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Goal period
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<GoalPeriod>,
+
+    /// Target distance in meters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_distance: Option<f64>,
+
+    /// Target elevation gain in meters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_elevation_gain: Option<f64>,
+
+    /// Target moving time in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_duration: Option<f64>,
+
+    /// Start date (for custom goals)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starts_on: Option<String>,
+
+    /// End date (for custom goals)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ends_on: Option<String>,
+}
+
+setters!(GoalRequest {
+    name: impl Into<String>,
+    description: impl Into<String>,
+    period: GoalPeriod,
+    target_distance: f64,
+    target_elevation_gain: f64,
+    target_duration: f64,
+    starts_on: impl Into<String>,
+    ends_on: impl Into<String>,
+});
+
+impl RideWithGpsClient {
+    /// List goals for the authenticated user
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let goals = client.list_goals().unwrap();
+    /// for goal in goals.results {
+    ///     println!("{:?}: {:?}", goal.name, goal.progress);
+    /// }
+    /// ```
+    pub fn list_goals(&self) -> Result<PaginatedResponse<Goal>> {
+        self.get_path("/api/v1/goals.json")
+    }
+
+    /// Create a new goal
+    ///
+    /// # Arguments
+    ///
+    /// * `goal` - The goal data
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{GoalPeriod, GoalRequest, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let goal_req = GoalRequest::new()
+    ///     .name("10,000 km this year")
+    ///     .period(GoalPeriod::Year)
+    ///     .target_distance(10_000_000.0);
+    ///
+    /// let goal = client.create_goal(&goal_req).unwrap();
+    /// println!("Created goal: {}", goal.id);
+    /// ```
+    pub fn create_goal(&self, goal: &GoalRequest) -> Result<Goal> {
+        self.post_wrapped("/api/v1/goals.json", "goal", goal)
+    }
+
+    /// Update a goal
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The goal ID
+    /// * `goal` - The updated goal data
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{GoalRequest, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let goal_req = GoalRequest::new().target_distance(12_000_000.0);
+    ///
+    /// let goal = client.update_goal(12345, &goal_req).unwrap();
+    /// println!("Updated goal: {:?}", goal);
+    /// ```
+    pub fn update_goal(&self, id: u64, goal: &GoalRequest) -> Result<Goal> {
+        self.put_wrapped(&format!("/api/v1/goals/{}.json", id), "goal", goal)
+    }
+
+    /// Delete a goal
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The goal ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// client.delete_goal(12345).unwrap();
+    /// ```
+    pub fn delete_goal(&self, id: u64) -> Result<()> {
+        self.delete_path(&format!("/api/v1/goals/{}.json", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_goal_deserialization() {
+        let json = r#"{
+            "id": 12,
+            "name": "Yearly distance",
+            "period": "year",
+            "target_distance": 10000000.0,
+            "completed": false,
+            "progress": {
+                "distance": 2500000.0,
+                "trip_count": 57,
+                "percent_complete": 25.0
+            }
+        }"#;
+
+        let goal: Goal = serde_json::from_str(json).unwrap();
+        assert_eq!(goal.id, 12);
+        assert_eq!(goal.period, Some(GoalPeriod::Year));
+        assert_eq!(goal.target_distance, Some(10000000.0));
+        let progress = goal.progress.unwrap();
+        assert_eq!(progress.trip_count, Some(57));
+        assert_eq!(progress.percent_complete, Some(25.0));
+    }
+
+    #[test]
+    fn test_goal_request_serialization() {
+        let req = GoalRequest {
+            name: Some("Climb Everest".to_string()),
+            period: Some(GoalPeriod::Month),
+            target_elevation_gain: Some(8849.0),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_value(&req).unwrap();
+        assert_eq!(json.get("name").unwrap(), "Climb Everest");
+        assert_eq!(json.get("period").unwrap(), "month");
+        assert_eq!(json.get("target_elevation_gain").unwrap(), 8849.0);
+        assert!(json.get("target_distance").is_none());
+    }
+}
@@ -0,0 +1,167 @@
+//! GPX export of routes and trips
+
+use crate::{Route, Trip};
+use chrono::DateTime;
+use std::io::Write;
+
+impl Route {
+    /// Write the route as a GPX 1.1 document
+    ///
+    /// Track points become a single track segment and course points are
+    /// written as waypoints, with the cue type as the waypoint type.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let route = client.get_route(12345).unwrap();
+    /// let mut file = std::fs::File::create("route.gpx").unwrap();
+    /// route.write_gpx(&mut file).unwrap();
+    /// ```
+    pub fn write_gpx<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        write_header(writer, self.name.as_deref())?;
+        for cue in self.course_points.iter().flatten() {
+            let (Some(lon), Some(lat)) = (cue.x, cue.y) else {
+                continue;
+            };
+            writeln!(writer, "  <wpt lat=\"{}\" lon=\"{}\">", lat, lon)?;
+            if let Some(name) = &cue.n {
+                writeln!(writer, "    <name>{}</name>", escape(name))?;
+            }
+            if let Some(cue_type) = &cue.t {
+                writeln!(writer, "    <type>{}</type>", escape(cue_type.as_str()))?;
+            }
+            writeln!(writer, "  </wpt>")?;
+        }
+        write_track(
+            writer,
+            self.name.as_deref(),
+            self.track_points
+                .iter()
+                .flatten()
+                .map(|p| (p.y, p.x, p.e, None)),
+        )
+    }
+}
+
+impl Trip {
+    /// Write the trip as a GPX 1.1 document
+    ///
+    /// Track points become a single track segment, including timestamps
+    /// where they were recorded.
+    pub fn write_gpx<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        write_header(writer, self.name.as_deref())?;
+        write_track(
+            writer,
+            self.name.as_deref(),
+            self.track_points
+                .iter()
+                .flatten()
+                .map(|p| (p.y, p.x, p.e, p.t)),
+        )
+    }
+}
+
+fn write_header<W: Write>(writer: &mut W, name: Option<&str>) -> std::io::Result<()> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<gpx version="1.1" creator="ridewithgps-client" xmlns="http://www.topografix.com/GPX/1/1">"#
+    )?;
+    if let Some(name) = name {
+        writeln!(
+            writer,
+            "  <metadata><name>{}</name></metadata>",
+            escape(name)
+        )?;
+    }
+    Ok(())
+}
+
+/// Write a track from (latitude, longitude, elevation, unix time) tuples
+fn write_track<W: Write>(
+    writer: &mut W,
+    name: Option<&str>,
+    points: impl Iterator<Item = (Option<f64>, Option<f64>, Option<f64>, Option<i64>)>,
+) -> std::io::Result<()> {
+    writeln!(writer, "  <trk>")?;
+    if let Some(name) = name {
+        writeln!(writer, "    <name>{}</name>", escape(name))?;
+    }
+    writeln!(writer, "    <trkseg>")?;
+    for (lat, lon, ele, time) in points {
+        let (Some(lat), Some(lon)) = (lat, lon) else {
+            continue;
+        };
+        write!(writer, "      <trkpt lat=\"{}\" lon=\"{}\">", lat, lon)?;
+        if let Some(ele) = ele {
+            write!(writer, "<ele>{}</ele>", ele)?;
+        }
+        if let Some(time) = time.and_then(|t| DateTime::from_timestamp(t, 0)) {
+            write!(writer, "<time>{}</time>", time.format("%Y-%m-%dT%H:%M:%SZ"))?;
+        }
+        writeln!(writer, "</trkpt>")?;
+    }
+    writeln!(writer, "    </trkseg>")?;
+    writeln!(writer, "  </trk>")?;
+    writeln!(writer, "</gpx>")
+}
+
+pub(crate) fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_gpx() {
+        let route: Route = serde_json::from_str(
+            r#"{
+                "id": 1,
+                "name": "Coffee & cake",
+                "track_points": [
+                    {"x": -122.6, "y": 45.5, "e": 30.0},
+                    {"x": -122.7},
+                    {"x": -122.8, "y": 45.6}
+                ],
+                "course_points": [{"x": -122.7, "y": 45.55, "t": "left", "n": "Turn <left>"}]
+            }"#,
+        )
+        .unwrap();
+
+        let mut out = Vec::new();
+        route.write_gpx(&mut out).unwrap();
+        let gpx = String::from_utf8(out).unwrap();
+        assert!(gpx.contains("<metadata><name>Coffee &amp; cake</name></metadata>"));
+        assert!(gpx.contains(r#"<trkpt lat="45.5" lon="-122.6"><ele>30</ele></trkpt>"#));
+        assert!(gpx.contains("<name>Turn &lt;left&gt;</name>"));
+        assert_eq!(gpx.matches("<trkpt").count(), 2);
+        assert!(gpx.ends_with("</gpx>\n"));
+    }
+
+    #[test]
+    fn test_trip_gpx_has_timestamps() {
+        let trip: Trip = serde_json::from_str(
+            r#"{"id": 2, "track_points": [{"x": 5.0, "y": 52.0, "t": 1700000000}]}"#,
+        )
+        .unwrap();
+
+        let mut out = Vec::new();
+        trip.write_gpx(&mut out).unwrap();
+        let gpx = String::from_utf8(out).unwrap();
+        assert!(gpx.contains("<time>2023-11-14T22:13:20Z</time>"));
+    }
+}
@@ -0,0 +1,161 @@
+//! Personal heatmap types and methods
+
+use crate::{ActivityType, Result, RideWithGpsClient};
+use serde::{Deserialize, Serialize};
+
+/// Aggregated heatmap of where the authenticated user has ridden
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Heatmap {
+    /// Raster tile URL template with `{z}`, `{x}` and `{y}` placeholders
+    pub tile_url: Option<String>,
+
+    /// Minimum zoom level tiles are available for
+    pub min_zoom: Option<u8>,
+
+    /// Maximum zoom level tiles are available for
+    pub max_zoom: Option<u8>,
+
+    /// Encoded polylines of the aggregated geometry
+    pub polylines: Option<Vec<String>>,
+
+    /// Number of trips included in the heatmap
+    pub trip_count: Option<u64>,
+
+    /// Total distance included in the heatmap in meters
+    pub distance: Option<f64>,
+
+    /// Southwest corner latitude (bounding box)
+    pub sw_lat: Option<f64>,
+
+    /// Southwest corner longitude (bounding box)
+    pub sw_lng: Option<f64>,
+
+    /// Northeast corner latitude (bounding box)
+    pub ne_lat: Option<f64>,
+
+    /// Northeast corner longitude (bounding box)
+    pub ne_lng: Option<f64>,
+}
+
+impl Heatmap {
+    /// Build the URL of a specific heatmap tile from the tile URL template
+    ///
+    /// Returns `None` if the heatmap has no tile URL.
+    pub fn tile_url_for(&self, z: u8, x: u32, y: u32) -> Option<String> {
+        self.tile_url.as_ref().map(|template| {
+            template
+                .replace("{z}", &z.to_string())
+                .replace("{x}", &x.to_string())
+                .replace("{y}", &y.to_string())
+        })
+    }
+}
+
+/// Parameters for fetching a heatmap
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
+pub struct HeatmapParams {
+    /// Only include trips of this activity type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activity_type: Option<ActivityType>,
+
+    /// Only include trips departing on or after this date (YYYY-MM-DD)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<String>,
+
+    /// Only include trips departing on or before this date (YYYY-MM-DD)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<String>,
+
+    /// Include planned routes in addition to trips
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_routes: Option<bool>,
+}
+
+setters!(HeatmapParams {
+    activity_type: impl Into<ActivityType>,
+    start_date: impl Into<String>,
+    end_date: impl Into<String>,
+    include_routes: bool,
+});
+
+impl RideWithGpsClient {
+    /// Get the authenticated user's personal heatmap
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Optional parameters for filtering the included activities
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{HeatmapParams, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let params = HeatmapParams::new().start_date("2025-01-01");
+    ///
+    /// let heatmap = client.get_heatmap(Some(&params)).unwrap();
+    /// println!("Tile: {:?}", heatmap.tile_url_for(10, 163, 357));
+    /// ```
+    pub fn get_heatmap(&self, params: Option<&HeatmapParams>) -> Result<Heatmap> {
+        let mut url = "/api/v1/users/current/heatmap.json".to_string();
+
+        if let Some(params) = params {
+            let query = serde_json::to_value(params)?;
+            if let Some(obj) = query.as_object() {
+                if !obj.is_empty() {
+                    let query_str = serde_urlencoded::to_string(obj).map_err(|e| {
+                        crate::Error::ApiError(format!("Failed to encode query: {}", e))
+                    })?;
+                    url.push('?');
+                    url.push_str(&query_str);
+                }
+            }
+        }
+
+        self.get_wrapped(&url, "heatmap")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heatmap_deserialization() {
+        let json = r#"{
+            "tile_url": "https://heatmap.example.com/{z}/{x}/{y}.png",
+            "min_zoom": 3,
+            "max_zoom": 16,
+            "trip_count": 812,
+            "polylines": ["_p~iF~ps|U_ulLnnqC"]
+        }"#;
+
+        let heatmap: Heatmap = serde_json::from_str(json).unwrap();
+        assert_eq!(heatmap.trip_count, Some(812));
+        assert_eq!(heatmap.max_zoom, Some(16));
+        assert_eq!(
+            heatmap.tile_url_for(10, 163, 357).as_deref(),
+            Some("https://heatmap.example.com/10/163/357.png")
+        );
+    }
+
+    #[test]
+    fn test_heatmap_params_serialization() {
+        let params = HeatmapParams {
+            activity_type: Some(ActivityType::Cycling),
+            include_routes: Some(false),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json.get("activity_type").unwrap(), "cycling");
+        assert_eq!(json.get("include_routes").unwrap(), false);
+        assert!(json.get("start_date").is_none());
+    }
+}
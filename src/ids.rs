@@ -0,0 +1,148 @@
+//! Typed IDs that can be parsed from web URLs
+
+use crate::{Error, Result};
+use std::fmt;
+use std::str::FromStr;
+use url::Url;
+
+macro_rules! id_type {
+    ($(#[$meta:meta])* $name:ident, $segment:literal, $example:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name(pub u64);
+
+        impl $name {
+            #[doc = concat!("Parse the ID from a web URL such as `", $example, "`")]
+            ///
+            /// The host is not checked, and slugs after the ID, query
+            /// strings and fragments are ignored.
+            pub fn from_url(url: &str) -> Result<Self> {
+                id_from_url(url, $segment).map(Self)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Error;
+
+            /// Parse either a bare ID or a web URL
+            fn from_str(s: &str) -> Result<Self> {
+                match s.trim().parse() {
+                    Ok(id) => Ok(Self(id)),
+                    Err(_) => Self::from_url(s),
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl From<u64> for $name {
+            fn from(id: u64) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<$name> for u64 {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+    };
+}
+
+id_type!(
+    /// ID of a route
+    RouteId,
+    "routes",
+    "https://ridewithgps.com/routes/12345-name"
+);
+id_type!(
+    /// ID of a trip
+    TripId,
+    "trips",
+    "https://ridewithgps.com/trips/12345"
+);
+id_type!(
+    /// ID of an event
+    EventId,
+    "events",
+    "https://ridewithgps.com/events/12345-club-ride"
+);
+id_type!(
+    /// ID of a collection
+    CollectionId,
+    "collections",
+    "https://ridewithgps.com/collections/12345"
+);
+
+/// Extract the numeric ID following the `segment` path segment of a URL
+fn id_from_url(url: &str, segment: &str) -> Result<u64> {
+    let invalid = || Error::ValidationError(format!("Not a RideWithGPS {} URL: {}", segment, url));
+
+    let trimmed = url.trim();
+    // Accept URLs pasted without a scheme, such as "ridewithgps.com/routes/1"
+    let parsed = Url::parse(trimmed)
+        .or_else(|_| Url::parse(&format!("https://{}", trimmed)))
+        .map_err(|_| invalid())?;
+
+    let mut segments = parsed.path_segments().ok_or_else(invalid)?;
+    segments.find(|s| *s == segment).ok_or_else(invalid)?;
+    let item = segments.next().ok_or_else(invalid)?;
+    let digits: &str = &item[..item
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(item.len())];
+    // The ID is followed by nothing, a slug, or a format extension
+    match item[digits.len()..].chars().next() {
+        None | Some('-') | Some('.') => digits.parse().map_err(|_| invalid()),
+        Some(_) => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_url() {
+        for url in [
+            "https://ridewithgps.com/routes/12345",
+            "https://ridewithgps.com/routes/12345-morning-loop",
+            "https://ridewithgps.com/routes/12345?privacy_code=abc#map",
+            "http://www.ridewithgps.com/routes/12345/",
+            "ridewithgps.com/routes/12345.gpx",
+            "  https://ridewithgps.com/routes/12345-name  ",
+        ] {
+            assert_eq!(RouteId::from_url(url).unwrap(), RouteId(12345), "{}", url);
+        }
+        assert_eq!(
+            EventId::from_url("https://ridewithgps.com/events/7-club-ride").unwrap(),
+            EventId(7)
+        );
+
+        for url in [
+            "https://ridewithgps.com/trips/12345",
+            "https://ridewithgps.com/routes/",
+            "https://ridewithgps.com/routes/abc",
+            "https://ridewithgps.com/routes/12x",
+            "not a url",
+        ] {
+            assert!(RouteId::from_url(url).is_err(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("42".parse::<TripId>().unwrap(), TripId(42));
+        assert_eq!(
+            "https://ridewithgps.com/trips/42"
+                .parse::<TripId>()
+                .unwrap(),
+            TripId(42)
+        );
+        assert_eq!(u64::from(CollectionId(3)), 3);
+        assert_eq!(CollectionId(3).to_string(), "3");
+    }
+}
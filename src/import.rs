@@ -0,0 +1,289 @@
+//! Creating routes from local GPX and TCX files
+//!
+//! Requires the `gpx` feature.
+
+use crate::analysis::{self, CueOptions};
+use crate::{Error, Result, RideWithGpsClient, Route, RouteRequest, TrackPoint, Visibility};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::path::Path;
+
+/// Track read from a GPX or TCX file
+#[derive(Debug, Clone, Default)]
+pub struct TrackFile {
+    /// Name of the track, route or course, if the file has one
+    pub name: Option<String>,
+
+    /// Points of the track, with distances filled in
+    pub track_points: Vec<TrackPoint>,
+}
+
+impl TrackFile {
+    /// Read a GPX or TCX file
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse the contents of a GPX or TCX file
+    ///
+    /// The format is detected from the content. Points of GPX tracks and
+    /// routes (`trkpt` and `rtept`) and of TCX activities and courses are
+    /// read in order. Distances missing from the file are computed from the
+    /// coordinates.
+    pub fn parse(content: &str) -> Result<Self> {
+        let invalid =
+            |e: quick_xml::Error| Error::ValidationError(format!("Invalid track file: {}", e));
+        let mut reader = Reader::from_str(content);
+        reader.config_mut().trim_text(true);
+
+        let mut file = TrackFile::default();
+        let mut path: Vec<String> = Vec::new();
+        let mut point: Option<TrackPoint> = None;
+
+        loop {
+            match reader.read_event().map_err(invalid)? {
+                Event::Start(e) => {
+                    let name = local_name(&e);
+                    if name == "trkpt" || name == "rtept" || name == "Trackpoint" {
+                        point = Some(gpx_point(&e)?);
+                    }
+                    path.push(name);
+                }
+                Event::Empty(e) => {
+                    let name = local_name(&e);
+                    if name == "trkpt" || name == "rtept" {
+                        file.track_points.push(gpx_point(&e)?);
+                    }
+                }
+                Event::End(_) => {
+                    let name = path.pop().unwrap_or_default();
+                    if name == "trkpt" || name == "rtept" || name == "Trackpoint" {
+                        file.track_points
+                            .extend(point.take().filter(|p| p.coord().is_some()));
+                    }
+                }
+                Event::Text(text) => {
+                    let text = text.unescape().map_err(invalid)?;
+                    let parent = path.len().checked_sub(2).map(|i| path[i].as_str());
+                    let element = path.last().map(String::as_str);
+                    match (element, parent, point.as_mut()) {
+                        (Some("ele" | "AltitudeMeters"), _, Some(p)) => p.e = text.parse().ok(),
+                        (Some("LatitudeDegrees"), _, Some(p)) => p.y = text.parse().ok(),
+                        (Some("LongitudeDegrees"), _, Some(p)) => p.x = text.parse().ok(),
+                        (Some("DistanceMeters"), Some("Trackpoint"), Some(p)) => {
+                            p.d = text.parse().ok()
+                        }
+                        (Some("name"), Some("trk" | "rte" | "metadata"), None)
+                        | (Some("Name"), Some("Course"), None)
+                            if file.name.is_none() =>
+                        {
+                            file.name = Some(text.into_owned())
+                        }
+                        _ => {}
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        if file.track_points.is_empty() {
+            return Err(Error::ValidationError(
+                "Track file contains no points".to_string(),
+            ));
+        }
+        fill_distances(&mut file.track_points);
+        Ok(file)
+    }
+}
+
+/// Settings for [`RideWithGpsClient::create_route_from_file`]
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct ImportOptions {
+    /// Route name, instead of the name in the file or the file name
+    pub name: Option<String>,
+
+    /// Route description
+    pub description: Option<String>,
+
+    /// Route visibility
+    pub visibility: Option<Visibility>,
+
+    /// Drop points within this many meters of the simplified line, see
+    /// [`analysis::simplify_track`]
+    pub simplify: Option<f64>,
+
+    /// Generate turn cues from the geometry, see [`analysis::generate_cues`]
+    pub generate_cues: Option<bool>,
+}
+
+setters!(ImportOptions {
+    name: impl Into<String>,
+    description: impl Into<String>,
+    visibility: Visibility,
+    simplify: f64,
+    generate_cues: bool,
+});
+
+impl RideWithGpsClient {
+    /// Create a route from a local GPX or TCX file
+    ///
+    /// Cues are generated from the full-resolution track before it is
+    /// simplified. The route is named after `options.name`, the name in the
+    /// file, or the file name, in that order.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the GPX or TCX file
+    /// * `options` - How to convert the track
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{ImportOptions, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let options = ImportOptions::new().simplify(2.0).generate_cues(true);
+    /// let route = client
+    ///     .create_route_from_file("Marmotte.gpx", &options)
+    ///     .unwrap();
+    /// println!("Created route {}", route.id);
+    /// ```
+    pub fn create_route_from_file(
+        &self,
+        path: impl AsRef<Path>,
+        options: &ImportOptions,
+    ) -> Result<Route> {
+        let path = path.as_ref();
+        let file = TrackFile::read(path)?;
+        let name = options
+            .name
+            .clone()
+            .or(file.name)
+            .or_else(|| Some(path.file_stem()?.to_string_lossy().into_owned()));
+
+        let mut request = RouteRequest::new();
+        request.name = name;
+        request.description = options.description.clone();
+        request.visibility = options.visibility;
+        if options.generate_cues == Some(true) {
+            request.course_points = Some(analysis::generate_cues(
+                &file.track_points,
+                CueOptions::default(),
+            ));
+        }
+        request.track_points = Some(match options.simplify {
+            Some(tolerance) => analysis::simplify_track(&file.track_points, tolerance),
+            None => file.track_points,
+        });
+
+        self.create_route(&request)
+    }
+}
+
+fn local_name(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).into_owned()
+}
+
+/// A point from a GPX `trkpt` or `rtept` element's attributes
+///
+/// TCX trackpoints have no attributes, and get their position from child
+/// elements instead.
+fn gpx_point(e: &BytesStart) -> Result<TrackPoint> {
+    let coordinate = |name: &str| -> Result<Option<f64>> {
+        let attribute = e
+            .try_get_attribute(name)
+            .map_err(|e| Error::ValidationError(format!("Invalid track file: {}", e)))?;
+        Ok(attribute
+            .and_then(|a| a.unescape_value().ok())
+            .and_then(|v| v.trim().parse().ok()))
+    };
+    Ok(TrackPoint {
+        x: coordinate("lon")?,
+        y: coordinate("lat")?,
+        d: None,
+        e: None,
+        surface: None,
+        highway: None,
+    })
+}
+
+/// Compute distances along the track, unless the file provided all of them
+fn fill_distances(points: &mut [TrackPoint]) {
+    if points.iter().all(|p| p.d.is_some()) {
+        return;
+    }
+    let mut total = 0.0;
+    let mut previous = None;
+    for point in points.iter_mut() {
+        let coord = point.coord();
+        if let (Some(a), Some(b)) = (previous, coord) {
+            total += crate::LatLng::distance_to(&a, &b);
+        }
+        previous = coord.or(previous);
+        point.d = Some(total);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gpx() {
+        let gpx = r#"<?xml version="1.0"?>
+            <gpx version="1.1" xmlns="http://www.topografix.com/GPX/1/1">
+              <metadata><name>Dunes &amp; dikes</name></metadata>
+              <trk><name>Track</name><trkseg>
+                <trkpt lat="52.0" lon="4.0"><ele>1.5</ele></trkpt>
+                <trkpt lat="52.001" lon="4.0"/>
+                <trkpt lat="52.002" lon="4.0"><ele>3</ele></trkpt>
+              </trkseg></trk>
+            </gpx>"#;
+
+        let file = TrackFile::parse(gpx).unwrap();
+        assert_eq!(file.name.as_deref(), Some("Dunes & dikes"));
+        assert_eq!(file.track_points.len(), 3);
+        assert_eq!(file.track_points[0].e, Some(1.5));
+        assert_eq!(file.track_points[1].e, None);
+        let d = file.track_points[2].d.unwrap();
+        assert!((d - 222.4).abs() < 0.5, "{}", d);
+    }
+
+    #[test]
+    fn test_parse_tcx() {
+        let tcx = r#"<?xml version="1.0"?>
+            <TrainingCenterDatabase xmlns="http://www.garmin.com/xmlschemas/TrainingCenterDatabase/v2">
+              <Courses><Course><Name>Loop</Name><Track>
+                <Trackpoint>
+                  <Position><LatitudeDegrees>52.0</LatitudeDegrees><LongitudeDegrees>4.0</LongitudeDegrees></Position>
+                  <AltitudeMeters>2.0</AltitudeMeters><DistanceMeters>0</DistanceMeters>
+                </Trackpoint>
+                <Trackpoint><DistanceMeters>50</DistanceMeters></Trackpoint>
+                <Trackpoint>
+                  <Position><LatitudeDegrees>52.001</LatitudeDegrees><LongitudeDegrees>4.0</LongitudeDegrees></Position>
+                  <DistanceMeters>111.2</DistanceMeters>
+                </Trackpoint>
+              </Track></Course></Courses>
+            </TrainingCenterDatabase>"#;
+
+        let file = TrackFile::parse(tcx).unwrap();
+        assert_eq!(file.name.as_deref(), Some("Loop"));
+        assert_eq!(file.track_points.len(), 2);
+        assert_eq!(file.track_points[0].e, Some(2.0));
+        assert_eq!(file.track_points[1].d, Some(111.2));
+        assert_eq!(file.track_points[1].y, Some(52.001));
+    }
+
+    #[test]
+    fn test_parse_rejects_empty() {
+        assert!(TrackFile::parse("<gpx></gpx>").is_err());
+        assert!(TrackFile::parse("<gpx><trk>").is_err());
+    }
+}
@@ -0,0 +1,231 @@
+//! Typed enums for string-valued API fields
+//!
+//! Each enum parses case-insensitively (treating spaces, hyphens and
+//! underscores alike), so a known value is always written back in the
+//! API's own lowercase spelling, whatever spelling it was read in: `"Left"`
+//! is read as [`CueType::Left`] and written as `"left"`. Values an enum does
+//! not know are kept in its `Other` variant and round-trip unchanged.
+
+macro_rules! string_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $($(#[$vmeta:meta])* $variant:ident => $value:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+        #[serde(from = "String", into = "String")]
+        pub enum $name {
+            $($(#[$vmeta])* $variant,)*
+            /// Any other value, as sent by the API
+            Other(String),
+        }
+
+        impl $name {
+            /// The API's string for this value
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Other(value) => value,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                let key = $crate::kinds::normalize(value);
+                $(if key == $crate::kinds::normalize($value) {
+                    return $name::$variant;
+                })*
+                $name::Other(value.to_string())
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                match $name::from(value.as_str()) {
+                    $name::Other(_) => $name::Other(value),
+                    known => known,
+                }
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                match value {
+                    $name::Other(value) => value,
+                    known => known.as_str().to_string(),
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+    };
+}
+
+pub(crate) use string_enum;
+
+/// Lowercase a value and unify word separators for comparison
+pub(crate) fn normalize(value: &str) -> String {
+    value
+        .trim()
+        .chars()
+        .map(|c| match c {
+            ' ' | '-' => '_',
+            c => c.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+string_enum!(
+    /// Type of a course point (turn-by-turn cue)
+    CueType {
+        /// Turn left
+        Left => "left",
+        /// Turn right
+        Right => "right",
+        /// Continue straight
+        Straight => "straight",
+        /// Bear left
+        SlightLeft => "slight left",
+        /// Bear right
+        SlightRight => "slight right",
+        /// Sharp turn left
+        SharpLeft => "sharp left",
+        /// Sharp turn right
+        SharpRight => "sharp right",
+        /// Turn around
+        UTurn => "u turn",
+        /// Food stop
+        Food => "food",
+        /// Water stop
+        Water => "water",
+        /// Summit of a climb
+        Summit => "summit",
+        /// Bottom of a valley
+        Valley => "valley",
+        /// Hazard
+        Danger => "danger",
+        /// First aid station
+        FirstAid => "first aid",
+        /// Control or checkpoint
+        Control => "control",
+        /// Generic cue without a specific meaning
+        Generic => "generic",
+    }
+);
+
+impl CueType {
+    /// The cue for riding through the same point in the opposite direction
+    ///
+    /// Left and right turns are swapped; for [`CueType::Other`] values,
+    /// "left" and "right" are swapped in the text, preserving capitalization.
+    pub fn mirrored(&self) -> CueType {
+        match self {
+            CueType::Left => CueType::Right,
+            CueType::Right => CueType::Left,
+            CueType::SlightLeft => CueType::SlightRight,
+            CueType::SlightRight => CueType::SlightLeft,
+            CueType::SharpLeft => CueType::SharpRight,
+            CueType::SharpRight => CueType::SharpLeft,
+            CueType::Other(value) => CueType::Other(mirror_left_right(value)),
+            other => other.clone(),
+        }
+    }
+}
+
+/// Swap "left" and "right" in a text, preserving its capitalization
+fn mirror_left_right(text: &str) -> String {
+    [("left", "right"), ("Left", "Right"), ("LEFT", "RIGHT")]
+        .iter()
+        .fold(text.to_string(), |acc, (left, right)| {
+            acc.split(left)
+                .map(|part| part.replace(right, left))
+                .collect::<Vec<_>>()
+                .join(right)
+        })
+}
+
+string_enum!(
+    /// Activity a route is planned for or a trip was recorded as
+    ActivityType {
+        /// Cycling of any kind
+        Cycling => "cycling",
+        /// Road cycling
+        RoadCycling => "cycling:road",
+        /// Gravel cycling
+        GravelCycling => "cycling:gravel",
+        /// Mountain biking
+        MountainBiking => "cycling:mountain",
+        /// Running
+        Running => "running",
+        /// Walking
+        Walking => "walking",
+        /// Hiking
+        Hiking => "hiking",
+    }
+);
+
+string_enum!(
+    /// Overall shape of a route or trip
+    TrackType {
+        /// Ends where it starts
+        Loop => "loop",
+        /// Returns along the outbound path
+        OutAndBack => "out_and_back",
+        /// Ends somewhere other than the start
+        PointToPoint => "point_to_point",
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_round_trip() {
+        let cues: Vec<CueType> =
+            serde_json::from_str(r#"["Left", "Slight_Right", "first-aid", "Roundabout"]"#).unwrap();
+        assert_eq!(
+            cues,
+            vec![
+                CueType::Left,
+                CueType::SlightRight,
+                CueType::FirstAid,
+                CueType::Other("Roundabout".to_string())
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&cues).unwrap(),
+            r#"["left","slight right","first aid","Roundabout"]"#
+        );
+
+        // Known values come back in the API's spelling, and stay that way
+        let json = serde_json::to_string(&CueType::from("Sharp-LEFT")).unwrap();
+        assert_eq!(json, r#""sharp left""#);
+        let cue: CueType = serde_json::from_str(&json).unwrap();
+        assert_eq!(cue, CueType::SharpLeft);
+        assert_eq!(serde_json::to_string(&cue).unwrap(), json);
+
+        assert_eq!(TrackType::from("Out and back"), TrackType::OutAndBack);
+        assert_eq!(
+            ActivityType::from("cycling:gravel").to_string(),
+            "cycling:gravel"
+        );
+    }
+
+    #[test]
+    fn test_mirrored() {
+        assert_eq!(CueType::SharpLeft.mirrored(), CueType::SharpRight);
+        assert_eq!(CueType::Water.mirrored(), CueType::Water);
+        assert_eq!(
+            CueType::from("Keep Left at fork").mirrored(),
+            CueType::Other("Keep Right at fork".to_string())
+        );
+    }
+}
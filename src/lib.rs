@@ -20,32 +20,181 @@
 //! println!("User: {:?}", user);
 //! ```
 
-use log::trace;
+use log::{debug, trace, warn};
 use reqwest::blocking::Client;
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+/// HTTP method, for [`RideWithGpsClient::request`]
+pub use reqwest::Method;
+use serde::de::DeserializeSeed;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::io::Read;
+use std::marker::PhantomData;
+use std::sync::Arc;
 use url::Url;
 
+/// Implement `new()` and fluent setters for a parameter or request struct
+///
+/// Every listed field must be an `Option`; its setter wraps the value in
+/// `Some`. Fields declared as `impl Into<T>` get setters accepting anything
+/// convertible into `T`.
+macro_rules! setters {
+    ($ty:ident { $($body:tt)* }) => {
+        impl $ty {
+            #[doc = concat!("Create an empty [`", stringify!($ty), "`]")]
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            setters!(@items $($body)*);
+        }
+    };
+    (@items) => {};
+    (@items $field:ident: impl Into<$fty:ty> $(, $($rest:tt)*)?) => {
+        #[doc = concat!("Set [`", stringify!($field), "`](Self::", stringify!($field), ")")]
+        pub fn $field(mut self, $field: impl Into<$fty>) -> Self {
+            self.$field = Some($field.into());
+            self
+        }
+
+        setters!(@items $($($rest)*)?);
+    };
+    (@items $field:ident: $fty:ty $(, $($rest:tt)*)?) => {
+        #[doc = concat!("Set [`", stringify!($field), "`](Self::", stringify!($field), ")")]
+        pub fn $field(mut self, $field: $fty) -> Self {
+            self.$field = Some($field);
+            self
+        }
+
+        setters!(@items $($($rest)*)?);
+    };
+}
+
+pub mod analysis;
+mod announcements;
+mod athlete;
 mod auth;
+mod base_url;
+mod batch;
+mod builder;
+mod circuit;
 mod collections;
+mod coord;
+mod corridor;
+mod cuesheet;
+#[cfg(feature = "polars")]
+mod dataframe;
+mod directions;
+mod drift;
+mod elevation;
 mod events;
+pub mod export;
+mod fit;
+pub mod format;
+mod goals;
+mod gpx;
+mod heatmap;
+mod ids;
+#[cfg(feature = "gpx")]
+mod import;
+mod kinds;
+mod likes;
+mod live;
 mod members;
+mod mirror;
+mod naming;
+mod options;
+mod organizations;
+mod pages;
+mod participants;
 mod poi;
+mod raw;
+#[cfg(feature = "replay")]
+mod replay;
+mod resource;
+mod retry;
+mod reviews;
+mod roster;
 mod routes;
+mod segments;
+mod singleflight;
+mod spatial;
+#[cfg(feature = "staticmap")]
+mod staticmap;
+mod summary;
+mod surface;
+mod svg;
 mod sync;
+mod telemetry;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod trackcsv;
 mod trips;
+mod units;
 mod users;
+mod waves;
+#[cfg(feature = "weather")]
+mod weather;
+pub mod webhook;
 
+pub use announcements::*;
+pub use athlete::*;
 pub use auth::*;
+pub use base_url::*;
+pub use batch::*;
+pub use builder::*;
+pub use circuit::*;
 pub use collections::*;
+pub use coord::*;
+pub use corridor::*;
+pub use cuesheet::*;
+#[cfg(feature = "polars")]
+pub use dataframe::*;
+pub use directions::*;
+pub use drift::UnknownFields;
+pub use elevation::*;
 pub use events::*;
+pub use export::device::{DeviceFileFormat, DeviceLayout};
+#[cfg(feature = "zip")]
+pub use export::{ExportManifest, ExportedRoute, RouteSelection, MANIFEST_FILE};
+pub use goals::*;
+pub use heatmap::*;
+pub use ids::*;
+#[cfg(feature = "gpx")]
+pub use import::*;
+pub use kinds::*;
+pub use likes::*;
+pub use live::*;
 pub use members::*;
+pub use mirror::*;
+pub use naming::*;
+pub use options::*;
+pub use organizations::*;
+pub use pages::*;
+pub use participants::*;
 pub use poi::*;
+pub use raw::*;
+#[cfg(feature = "replay")]
+pub use replay::*;
+pub use resource::*;
+pub use retry::*;
+pub use reviews::*;
+pub use roster::*;
 pub use routes::*;
+pub use segments::*;
+#[cfg(feature = "staticmap")]
+pub use staticmap::*;
+pub use surface::*;
+pub use svg::*;
 pub use sync::*;
+pub use telemetry::FailedRequest;
+pub use trackcsv::*;
 pub use trips::*;
+pub use units::*;
 pub use users::*;
+pub use waves::*;
+#[cfg(feature = "weather")]
+pub use weather::*;
 
 /// Error type for RideWithGPS API operations
 #[derive(Debug)]
@@ -74,8 +223,72 @@ pub enum Error {
     /// Forbidden
     Forbidden(String),
 
+    /// Conflict with the current state of the resource
+    Conflict(String),
+
     /// Validation error
     ValidationError(String),
+
+    /// Server error (status 500 and up), usually transient
+    ServerError {
+        /// HTTP status of the response
+        status: u16,
+
+        /// Body of the response
+        body: String,
+    },
+
+    /// I/O error while reading or writing local files
+    Io(std::io::Error),
+
+    /// Response did not match the expected schema (strict mode only)
+    SchemaMismatch {
+        /// Location of the mismatch in the response, such as `route.track_points[3].x`
+        path: String,
+
+        /// What was wrong
+        detail: String,
+    },
+
+    /// Response body could not be decoded into the expected type
+    Decode {
+        /// Path of the endpoint that returned the body
+        endpoint: String,
+
+        /// Location of the failure in the response, such as `trip.track_points[812].t`
+        path: String,
+
+        /// Part of the body around the failure, truncated to a few hundred bytes
+        snippet: String,
+
+        /// The underlying error
+        source: serde_json::Error,
+    },
+
+    /// Request not sent because the circuit breaker is open
+    CircuitOpen {
+        /// Time until the circuit breaker lets a probe request through
+        retry_in: std::time::Duration,
+    },
+
+    /// An HTML page instead of an API response, such as a maintenance page
+    /// or a Cloudflare challenge
+    Unavailable {
+        /// HTTP status of the response
+        status: u16,
+
+        /// What the page appears to be
+        hint: String,
+    },
+
+    /// Response body larger than the client's maximum response size
+    ResponseTooLarge {
+        /// Path of the endpoint that returned the body
+        endpoint: String,
+
+        /// The maximum response size in bytes
+        limit: u64,
+    },
 }
 
 impl std::fmt::Display for Error {
@@ -89,7 +302,36 @@ impl std::fmt::Display for Error {
             Error::NotFound(s) => write!(f, "Resource not found: {}", s),
             Error::BadRequest(s) => write!(f, "Bad request: {}", s),
             Error::Forbidden(s) => write!(f, "Forbidden: {}", s),
+            Error::Conflict(s) => write!(f, "Conflict: {}", s),
             Error::ValidationError(s) => write!(f, "Validation error: {}", s),
+            Error::ServerError { status, body } => write!(f, "Server error {}: {}", status, body),
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::SchemaMismatch { path, detail } => {
+                write!(f, "Schema mismatch at {}: {}", path, detail)
+            }
+            Error::Decode {
+                endpoint,
+                path,
+                snippet,
+                source,
+            } => write!(
+                f,
+                "Failed to decode response from {} at {}: {} (near `{}`)",
+                endpoint, path, source, snippet
+            ),
+            Error::CircuitOpen { retry_in } => write!(
+                f,
+                "Circuit breaker open after repeated API failures; retry in {}s",
+                retry_in.as_secs()
+            ),
+            Error::Unavailable { status, hint } => {
+                write!(f, "RideWithGPS unavailable (HTTP {}): {}", status, hint)
+            }
+            Error::ResponseTooLarge { endpoint, limit } => write!(
+                f,
+                "Response from {} is larger than the limit of {} bytes",
+                endpoint, limit
+            ),
         }
     }
 }
@@ -114,9 +356,47 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
 /// Result type for RideWithGPS API operations
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Extension methods for [`Result`]
+pub trait ResultExt<T> {
+    /// Turn [`Error::NotFound`] into `Ok(None)`
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{ResultExt, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// if client.get_segment(12345).optional().unwrap().is_none() {
+    ///     println!("Segment does not exist");
+    /// }
+    /// ```
+    fn optional(self) -> Result<Option<T>>;
+}
+
+impl<T> ResultExt<T> for Result<T> {
+    fn optional(self) -> Result<Option<T>> {
+        match self {
+            Ok(value) => Ok(Some(value)),
+            Err(Error::NotFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
 /// Pagination information for list responses
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Pagination {
@@ -144,12 +424,44 @@ pub struct PaginatedResponse<T> {
     pub pagination: Pagination,
 }
 
+/// A buffered HTTP response
+#[derive(Clone)]
+struct RawResponse {
+    /// Path of the requested URL, for error messages
+    endpoint: String,
+    status: u16,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl RawResponse {
+    /// The body as text, for error messages
+    fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
 /// Main client for the RideWithGPS API
+///
+/// Cloning is cheap: clones share the connection pool, circuit breaker and
+/// cassette.
+#[derive(Clone)]
 pub struct RideWithGpsClient {
     client: Client,
     base_url: Url,
     api_key: String,
     auth_token: Option<String>,
+    strict: bool,
+    retry: Option<RetryPolicy>,
+    breaker: Option<Arc<circuit::Breaker>>,
+    drift_hook: Option<drift::DriftHook>,
+    error_hook: Option<telemetry::ErrorHook>,
+    slow_request_threshold: Option<std::time::Duration>,
+    max_response_size: Option<u64>,
+    athlete: Option<AthleteProfile>,
+    inflight: Option<Arc<singleflight::InFlight>>,
+    #[cfg(feature = "replay")]
+    cassette: Option<Arc<Cassette>>,
 }
 
 impl RideWithGpsClient {
@@ -178,6 +490,17 @@ impl RideWithGpsClient {
             base_url: Url::parse(base_url).expect("Invalid base URL"),
             api_key: api_key.to_string(),
             auth_token: auth_token.map(|s| s.to_string()),
+            strict: cfg!(feature = "strict"),
+            retry: None,
+            breaker: None,
+            drift_hook: None,
+            error_hook: None,
+            slow_request_threshold: None,
+            max_response_size: None,
+            athlete: None,
+            inflight: None,
+            #[cfg(feature = "replay")]
+            cassette: None,
         }
     }
 
@@ -236,89 +559,564 @@ impl RideWithGpsClient {
         Ok(headers)
     }
 
+    /// Send a request and buffer the response
+    ///
+    /// All requests go through here. API credentials are only attached when
+    /// `authenticated` is set.
+    fn execute(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<Vec<u8>>,
+        authenticated: bool,
+    ) -> Result<RawResponse> {
+        self.execute_with(method, url, body, authenticated, &RequestOptions::default())
+    }
+
+    /// Execute a request with per-call options
+    ///
+    /// Identical GETs running at the same time share one request if the
+    /// client coalesces requests. Requests are only identical if they are
+    /// sent with the same credentials and options, as clones of a client
+    /// with different auth tokens share the requests in flight.
+    fn execute_with(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<Vec<u8>>,
+        authenticated: bool,
+        options: &RequestOptions,
+    ) -> Result<RawResponse> {
+        match &self.inflight {
+            Some(inflight) if method == Method::GET => {
+                let credentials =
+                    authenticated.then_some((self.api_key.as_str(), self.auth_token.as_deref()));
+                let key = singleflight::request_key(credentials, options, &url);
+                inflight.run(key, || {
+                    self.execute_uncoalesced(method, url, body, authenticated, options)
+                })
+            }
+            _ => self.execute_uncoalesced(method, url, body, authenticated, options),
+        }
+    }
+
+    /// Execute a request, retrying it as the retry policy allows
+    fn execute_uncoalesced(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<Vec<u8>>,
+        authenticated: bool,
+        options: &RequestOptions,
+    ) -> Result<RawResponse> {
+        trace!("{} {}", method, url);
+
+        #[cfg(feature = "replay")]
+        if let Some(cassette) = self.cassette.as_ref().filter(|c| c.is_replaying()) {
+            return cassette.replay_interaction(&method, &url, &self.base_url, body.as_deref());
+        }
+
+        // Retried POSTs carry the same key, so the server can recognize a
+        // creation it already performed
+        let retry = self.retry.as_ref().filter(|_| options.retry != Some(false));
+        let idempotency_key =
+            (retry.is_some() && method == Method::POST).then(retry::new_idempotency_key);
+
+        let started = std::time::Instant::now();
+        let mut attempt = 0;
+        let raw = loop {
+            if let Some(breaker) = &self.breaker {
+                if let Err(e) = breaker.allow() {
+                    self.report_failure(&method, &url, attempt, false, None, &e);
+                    return Err(e);
+                }
+            }
+            let result = self.send(
+                &method,
+                &url,
+                body.as_deref(),
+                authenticated,
+                idempotency_key.as_deref(),
+                options.timeout,
+            );
+            if let Some(breaker) = &self.breaker {
+                breaker.record(&result);
+            }
+            let delay = retry.and_then(|policy| {
+                policy.retry_delay(&method, attempt, started.elapsed(), &result)
+            });
+            match &result {
+                Err(e) => self.report_failure(&method, &url, attempt, delay.is_some(), None, e),
+                Ok(response) if response.status >= 400 && self.error_hook.is_some() => {
+                    let e = self.error_from_status(response.status, &response.text());
+                    let status = Some(response.status);
+                    self.report_failure(&method, &url, attempt, delay.is_some(), status, &e);
+                }
+                Ok(_) => {}
+            }
+            match delay {
+                Some(delay) => {
+                    debug!(
+                        "Retrying {} {} in {:?} (attempt {})",
+                        method,
+                        url,
+                        delay,
+                        attempt + 2
+                    );
+                    std::thread::sleep(delay);
+                    attempt += 1;
+                }
+                None => break result?,
+            }
+        };
+
+        #[cfg(feature = "replay")]
+        if let Some(cassette) = &self.cassette {
+            let secrets = [Some(self.api_key.as_str()), self.auth_token.as_deref()];
+            cassette.record_interaction(
+                &method,
+                &url,
+                &self.base_url,
+                body.as_deref(),
+                &raw,
+                &secrets,
+            )?;
+        }
+
+        Ok(raw)
+    }
+
+    /// Pass a failed attempt (counting from 0) to the error hook, if any
+    fn report_failure(
+        &self,
+        method: &Method,
+        url: &Url,
+        attempt: u32,
+        retrying: bool,
+        status: Option<u16>,
+        error: &Error,
+    ) {
+        if let Some(hook) = &self.error_hook {
+            hook.call(&FailedRequest {
+                method,
+                endpoint: url.path(),
+                status,
+                attempt: attempt + 1,
+                retrying,
+                error,
+            });
+        }
+    }
+
+    /// Send a single request, without retrying
+    fn send(
+        &self,
+        method: &Method,
+        url: &Url,
+        body: Option<&[u8]>,
+        authenticated: bool,
+        idempotency_key: Option<&str>,
+        timeout: Option<std::time::Duration>,
+    ) -> Result<RawResponse> {
+        let mut request = self.client.request(method.clone(), url.clone());
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+        if authenticated {
+            request = request.headers(self.build_headers()?);
+        }
+        if let Some(key) = idempotency_key {
+            request = request.header(retry::IDEMPOTENCY_KEY_HEADER, key);
+        }
+        if let Some(body) = body {
+            request = request.body(body.to_vec());
+        }
+        let started = std::time::Instant::now();
+        let response = request.send()?;
+        let status = response.status().as_u16();
+        let headers = response.headers().clone();
+        let response_body = match self.max_response_size {
+            Some(limit) => read_limited(response, url, limit)?,
+            None => response.bytes()?.to_vec(),
+        };
+        let raw = RawResponse {
+            endpoint: url.path().to_string(),
+            status,
+            headers,
+            body: response_body,
+        };
+
+        let elapsed = started.elapsed();
+        if self.slow_request_threshold.is_some_and(|t| elapsed > t) {
+            let sent = body.map_or(0, <[u8]>::len);
+            warn!(
+                method = method.as_str(),
+                path = url.path(),
+                status = raw.status,
+                duration_ms = elapsed.as_millis() as u64,
+                request_bytes = sent,
+                response_bytes = raw.body.len();
+                "Slow request: {} {} took {:?} ({} bytes sent, {} bytes received)",
+                method,
+                url.path(),
+                elapsed,
+                sent,
+                raw.body.len()
+            );
+        }
+        Ok(raw)
+    }
+
     /// Execute a GET request
-    fn get<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<T> {
-        let url = self.base_url.join(path)?;
-        trace!("GET {}", url);
+    fn get_path<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<T> {
+        self.get_path_with(path, &RequestOptions::default())
+    }
 
-        let headers = self.build_headers()?;
-        let response = self.client.get(url).headers(headers).send()?;
+    /// Execute a GET request with per-call options
+    fn get_path_with<T: for<'de> Deserialize<'de>>(
+        &self,
+        path: &str,
+        options: &RequestOptions,
+    ) -> Result<T> {
+        let url = self.base_url.join(path)?;
+        let response = self.execute_with(Method::GET, url, None, true, options)?;
 
         self.handle_response(response)
     }
 
+    /// Execute a GET request for a model wrapped in an object under `key`,
+    /// such as `{"route": {...}}`
+    fn get_wrapped<T: for<'de> Deserialize<'de>>(
+        &self,
+        path: &str,
+        key: &'static str,
+    ) -> Result<T> {
+        self.get_wrapped_with(path, key, &RequestOptions::default())
+    }
+
+    /// Execute a GET request for a wrapped model with per-call options
+    fn get_wrapped_with<T: for<'de> Deserialize<'de>>(
+        &self,
+        path: &str,
+        key: &'static str,
+        options: &RequestOptions,
+    ) -> Result<T> {
+        let url = self.base_url.join(path)?;
+        let response = self.execute_with(Method::GET, url, None, true, options)?;
+
+        self.handle_wrapped(response, key)
+    }
+
+    /// Execute a GET request for a wrapped model, also returning the
+    /// response headers
+    fn get_wrapped_with_headers<T: for<'de> Deserialize<'de>>(
+        &self,
+        path: &str,
+        key: &'static str,
+    ) -> Result<(T, HeaderMap)> {
+        let url = self.base_url.join(path)?;
+        let response = self.execute(Method::GET, url, None, true)?;
+        let response_headers = response.headers.clone();
+
+        Ok((self.handle_wrapped(response, key)?, response_headers))
+    }
+
+    /// Download the raw body of a URL, such as a photo
+    ///
+    /// No API credentials are sent, since the URL may point to another host.
+    fn download(&self, url: &str) -> Result<Vec<u8>> {
+        let url = self.base_url.join(url)?;
+        let response = self.execute(Method::GET, url, None, false)?;
+
+        match response.status {
+            200 => Ok(response.body),
+            status => Err(self.error_from_status(status, &response.text())),
+        }
+    }
+
     /// Execute a POST request
     fn post<T: for<'de> Deserialize<'de>, B: Serialize>(&self, path: &str, body: &B) -> Result<T> {
         let url = self.base_url.join(path)?;
-        trace!("POST {}", url);
-
-        let headers = self.build_headers()?;
-        let response = self.client.post(url).headers(headers).json(body).send()?;
+        let body = serde_json::to_vec(body)?;
+        let response = self.execute(Method::POST, url, Some(body), true)?;
 
         self.handle_response(response)
     }
 
+    /// Execute a POST request answered with a model wrapped under `key`
+    fn post_wrapped<T: for<'de> Deserialize<'de>, B: Serialize>(
+        &self,
+        path: &str,
+        key: &'static str,
+        body: &B,
+    ) -> Result<T> {
+        let url = self.base_url.join(path)?;
+        let body = serde_json::to_vec(body)?;
+        let response = self.execute(Method::POST, url, Some(body), true)?;
+
+        self.handle_wrapped(response, key)
+    }
+
     /// Execute a PUT request
     fn put<T: for<'de> Deserialize<'de>, B: Serialize>(&self, path: &str, body: &B) -> Result<T> {
         let url = self.base_url.join(path)?;
-        trace!("PUT {}", url);
-
-        let headers = self.build_headers()?;
-        let response = self.client.put(url).headers(headers).json(body).send()?;
+        let body = serde_json::to_vec(body)?;
+        let response = self.execute(Method::PUT, url, Some(body), true)?;
 
         self.handle_response(response)
     }
 
-    /// Execute a DELETE request
-    fn delete(&self, path: &str) -> Result<()> {
+    /// Execute a PUT request answered with a model wrapped under `key`
+    fn put_wrapped<T: for<'de> Deserialize<'de>, B: Serialize>(
+        &self,
+        path: &str,
+        key: &'static str,
+        body: &B,
+    ) -> Result<T> {
         let url = self.base_url.join(path)?;
-        trace!("DELETE {}", url);
+        let body = serde_json::to_vec(body)?;
+        let response = self.execute(Method::PUT, url, Some(body), true)?;
 
-        let headers = self.build_headers()?;
-        let response = self.client.delete(url).headers(headers).send()?;
+        self.handle_wrapped(response, key)
+    }
 
-        match response.status().as_u16() {
+    /// Execute a DELETE request
+    fn delete_path(&self, path: &str) -> Result<()> {
+        let url = self.base_url.join(path)?;
+        let response = self.execute(Method::DELETE, url, None, true)?;
+
+        match response.status {
             204 => Ok(()),
-            _ => {
-                let status = response.status();
-                let text = response.text().unwrap_or_default();
-                Err(self.error_from_status(status.as_u16(), &text))
-            }
+            status => Err(self.error_from_status(status, &response.text())),
         }
     }
 
     /// Handle API response and convert to typed result
-    fn handle_response<T: for<'de> Deserialize<'de>>(
+    fn handle_response<T: for<'de> Deserialize<'de>>(&self, response: RawResponse) -> Result<T> {
+        self.handle_response_seed(response, PhantomData::<T>, std::any::type_name::<T>())
+    }
+
+    /// Handle an API response holding a model wrapped under `key`
+    fn handle_wrapped<T: for<'de> Deserialize<'de>>(
         &self,
-        response: reqwest::blocking::Response,
+        response: RawResponse,
+        key: &'static str,
     ) -> Result<T> {
-        let status = response.status();
+        self.handle_response_seed(
+            response,
+            resource::Envelope::<T>::new(key),
+            std::any::type_name::<T>(),
+        )
+    }
 
-        match status.as_u16() {
-            200 | 201 => {
-                let text = response.text()?;
-                serde_json::from_str(&text).map_err(Error::Json)
-            }
-            _ => {
-                let text = response.text().unwrap_or_default();
-                Err(self.error_from_status(status.as_u16(), &text))
-            }
+    /// Handle an API response, decoding its body with `seed`
+    ///
+    /// `type_name` is the model the body holds, for drift reports.
+    fn handle_response_seed<S, T>(
+        &self,
+        response: RawResponse,
+        seed: S,
+        type_name: &'static str,
+    ) -> Result<T>
+    where
+        S: for<'de> DeserializeSeed<'de, Value = T>,
+    {
+        match response.status {
+            200 | 201 => match html_hint(&response.body) {
+                Some(hint) => Err(Error::Unavailable {
+                    status: response.status,
+                    hint,
+                }),
+                None => self.decode_seed(&response.endpoint, &response.body, seed, type_name),
+            },
+            status => Err(self.error_from_status(status, &response.text())),
+        }
+    }
+
+    /// Deserialize a response body, checking it against the schema in strict
+    /// mode and reporting unknown fields to the drift hook
+    fn decode<T: for<'de> Deserialize<'de>>(&self, endpoint: &str, body: &[u8]) -> Result<T> {
+        self.decode_seed(endpoint, body, PhantomData::<T>, std::any::type_name::<T>())
+    }
+
+    /// Deserialize a response body with `seed`, like [`decode`](Self::decode)
+    ///
+    /// `type_name` is reported to the drift hook: the model in the body
+    /// rather than an envelope around it.
+    fn decode_seed<S, T>(
+        &self,
+        endpoint: &str,
+        body: &[u8],
+        seed: S,
+        type_name: &'static str,
+    ) -> Result<T>
+    where
+        S: for<'de> DeserializeSeed<'de, Value = T>,
+    {
+        let decode_error = |path: String, source: serde_json::Error| Error::Decode {
+            endpoint: endpoint.to_string(),
+            path,
+            snippet: snippet(body, source.line(), source.column()),
+            source,
+        };
+
+        if !self.strict && self.drift_hook.is_none() {
+            let mut json = serde_json::Deserializer::from_slice(body);
+            let mut track = serde_path_to_error::Track::new();
+            return seed
+                .deserialize(serde_path_to_error::Deserializer::new(
+                    &mut json, &mut track,
+                ))
+                .map_err(|e| decode_error(track.path().to_string(), e));
+        }
+
+        let mut json = serde_json::Deserializer::from_slice(body);
+        let mut track = serde_path_to_error::Track::new();
+        let mut unknown = std::collections::BTreeSet::new();
+        let value = seed
+            .deserialize(serde_ignored::Deserializer::new(
+                serde_path_to_error::Deserializer::new(&mut json, &mut track),
+                &mut |path: serde_ignored::Path| {
+                    unknown.insert(drift::field_path(&path));
+                },
+            ))
+            .map_err(|e| match self.strict {
+                true => Error::SchemaMismatch {
+                    path: track.path().to_string(),
+                    detail: e.to_string(),
+                },
+                false => decode_error(track.path().to_string(), e),
+            })?;
+
+        if unknown.is_empty() {
+            return Ok(value);
+        }
+        if let Some(hook) = &self.drift_hook {
+            hook.call(&drift::UnknownFields {
+                endpoint: endpoint.to_string(),
+                type_name,
+                fields: unknown.clone(),
+            });
+        }
+        match unknown.into_iter().next() {
+            Some(path) if self.strict => Err(Error::SchemaMismatch {
+                path,
+                detail: "unknown field".to_string(),
+            }),
+            _ => Ok(value),
         }
     }
 
     /// Convert HTTP status code to Error
+    ///
+    /// HTML pages served with a status that outages and blocks use become
+    /// [`Error::Unavailable`].
     fn error_from_status(&self, status: u16, body: &str) -> Error {
+        if matches!(status, 403 | 429 | 500..) {
+            if let Some(hint) = html_hint(body.as_bytes()) {
+                return Error::Unavailable { status, hint };
+            }
+        }
         match status {
             400 => Error::BadRequest(body.to_string()),
             401 => Error::AuthError(body.to_string()),
             403 => Error::Forbidden(body.to_string()),
             404 => Error::NotFound(body.to_string()),
+            409 => Error::Conflict(body.to_string()),
             422 => Error::ValidationError(body.to_string()),
+            500.. => Error::ServerError {
+                status,
+                body: body.to_string(),
+            },
             _ => Error::ApiError(format!("HTTP {}: {}", status, body)),
         }
     }
 }
 
+/// Append the serialized parameters to a URL as a query string
+pub(crate) fn with_query<P: Serialize>(mut url: String, params: Option<&P>) -> Result<String> {
+    if let Some(params) = params {
+        let query = serde_json::to_value(params)?;
+        if let Some(obj) = query.as_object() {
+            if !obj.is_empty() {
+                let query_str = serde_urlencoded::to_string(obj)
+                    .map_err(|e| Error::ApiError(format!("Failed to encode query: {}", e)))?;
+                url.push('?');
+                url.push_str(&query_str);
+            }
+        }
+    }
+    Ok(url)
+}
+
+/// Bytes of context on either side of a decoding failure in [`Error::Decode`]
+const SNIPPET_CONTEXT: usize = 100;
+
+/// The part of `body` around a 1-based line and column reported by serde_json
+fn snippet(body: &[u8], line: usize, column: usize) -> String {
+    let line_start: usize = body
+        .split(|&b| b == b'\n')
+        .take(line.saturating_sub(1))
+        .map(|l| l.len() + 1)
+        .sum();
+    let offset = (line_start + column.saturating_sub(1)).min(body.len());
+    let start = offset.saturating_sub(SNIPPET_CONTEXT);
+    let end = (offset + SNIPPET_CONTEXT).min(body.len());
+
+    let mut snippet = String::from_utf8_lossy(&body[start..end]).into_owned();
+    if start > 0 {
+        snippet.insert(0, '…');
+    }
+    if end < body.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+/// What an HTML body appears to be, or `None` if it is not HTML
+fn html_hint(body: &[u8]) -> Option<String> {
+    if body.iter().find(|b| !b.is_ascii_whitespace()) != Some(&b'<') {
+        return None;
+    }
+    let html = String::from_utf8_lossy(body);
+    let lower = html.to_ascii_lowercase();
+    if lower.contains("challenge-platform") || lower.contains("cf-chl") {
+        return Some("Cloudflare challenge page; the request was blocked".to_string());
+    }
+    if lower.contains("maintenance") {
+        return Some("maintenance page".to_string());
+    }
+    let title = lower
+        .find("<title")
+        .and_then(|start| Some(start + lower[start..].find('>')? + 1))
+        .and_then(|start| Some(html[start..start + lower[start..].find("</title")?].trim()))
+        .filter(|title| !title.is_empty());
+    Some(match title {
+        Some(title) => format!("HTML page \"{}\"", title),
+        None => "HTML page instead of JSON".to_string(),
+    })
+}
+
+/// Read a response body, failing as soon as it exceeds `limit` bytes
+fn read_limited(response: reqwest::blocking::Response, url: &Url, limit: u64) -> Result<Vec<u8>> {
+    let too_large = || Error::ResponseTooLarge {
+        endpoint: url.path().to_string(),
+        limit,
+    };
+    if response
+        .content_length()
+        .is_some_and(|length| length > limit)
+    {
+        return Err(too_large());
+    }
+    let mut body = Vec::new();
+    response.take(limit + 1).read_to_end(&mut body)?;
+    if body.len() as u64 > limit {
+        return Err(too_large());
+    }
+    Ok(body)
+}
+
 impl fmt::Debug for RideWithGpsClient {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RideWithGpsClient")
@@ -355,4 +1153,305 @@ mod tests {
         client.set_auth_token("new-token");
         assert_eq!(client.auth_token(), Some("new-token"));
     }
+
+    #[test]
+    fn test_optional() {
+        assert_eq!(Ok::<_, Error>(1).optional().unwrap(), Some(1));
+        let missing: Result<u64> = Err(Error::NotFound("gone".to_string()));
+        assert_eq!(missing.optional().unwrap(), None);
+        let forbidden: Result<u64> = Err(Error::Forbidden("no".to_string()));
+        assert!(matches!(forbidden.optional(), Err(Error::Forbidden(_))));
+    }
+
+    #[test]
+    fn test_error_from_status() {
+        let client = RideWithGpsClient::new("https://ridewithgps.com", "key", None);
+        assert!(matches!(
+            client.error_from_status(409, "taken"),
+            Error::Conflict(body) if body == "taken"
+        ));
+        assert!(matches!(
+            client.error_from_status(503, "down"),
+            Error::ServerError { status: 503, body } if body == "down"
+        ));
+        assert!(matches!(
+            client.error_from_status(429, "slow down"),
+            Error::ApiError(_)
+        ));
+    }
+
+    #[test]
+    fn test_html_responses() {
+        let client = RideWithGpsClient::new("https://ridewithgps.com", "key", None);
+        let maintenance = "<!DOCTYPE html><html><body>Down for Maintenance</body></html>";
+        assert!(matches!(
+            client.error_from_status(503, maintenance),
+            Error::Unavailable { status: 503, hint } if hint == "maintenance page"
+        ));
+        let challenge = "\n<html><head><title>Just a moment...</title>\
+            <script src=\"/cdn-cgi/challenge-platform/orchestrate.js\"></script></head></html>";
+        assert!(matches!(
+            client.error_from_status(403, challenge),
+            Error::Unavailable { status: 403, hint } if hint.starts_with("Cloudflare")
+        ));
+        assert!(matches!(
+            client.error_from_status(404, "<html></html>"),
+            Error::NotFound(_)
+        ));
+
+        let page = RawResponse {
+            endpoint: "/api/v1/routes/1.json".to_string(),
+            status: 200,
+            headers: HeaderMap::new(),
+            body: b"<html><head><TITLE> Bad Gateway </TITLE></head></html>".to_vec(),
+        };
+        match client.handle_response::<serde_json::Value>(page) {
+            Err(Error::Unavailable { status, hint }) => {
+                assert_eq!(status, 200);
+                assert_eq!(hint, "HTML page \"Bad Gateway\"");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_request_timeout() {
+        // Accepts connections but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let client = RideWithGpsClient::new(&url, "test-api-key", None);
+
+        let options = RequestOptions::new().timeout(std::time::Duration::from_millis(100));
+        match client.get_trip_with(1, &options) {
+            Err(Error::Http(e)) => assert!(e.is_timeout()),
+            other => panic!("expected timeout, got {:?}", other.map(|t| t.id)),
+        }
+        drop(listener);
+    }
+
+    #[test]
+    fn test_coalescing_keeps_users_apart() {
+        use std::io::{BufRead, BufReader, Write};
+
+        // Answers each request slowly, with the user of its auth token
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                std::thread::spawn(move || {
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut token = String::new();
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).unwrap() > 2 {
+                        if let Some(value) = line.to_lowercase().strip_prefix("x-rwgps-auth-token:")
+                        {
+                            token = value.trim().to_string();
+                        }
+                        line.clear();
+                    }
+                    std::thread::sleep(std::time::Duration::from_millis(200));
+                    let body = format!(r#"{{"user": {{"id": {}}}}}"#, token);
+                    let mut stream = stream;
+                    write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                    .unwrap();
+                });
+            }
+        });
+
+        let alice = RideWithGpsClient::builder(&url, "test-api-key")
+            .auth_token("1")
+            .coalesce_requests(true)
+            .build()
+            .unwrap();
+        let mut bob = alice.clone();
+        bob.set_auth_token("2");
+
+        let (a, b) = std::thread::scope(|scope| {
+            let a = scope.spawn(|| alice.get_current_user().unwrap().id);
+            let b = scope.spawn(|| bob.get_current_user().unwrap().id);
+            (a.join().unwrap(), b.join().unwrap())
+        });
+        assert_eq!((a, b), (1, 2));
+    }
+
+    #[test]
+    fn test_strict_decoding() {
+        #[derive(Debug, Deserialize)]
+        struct RouteWrapper {
+            #[allow(dead_code)]
+            route: Route,
+        }
+
+        let lenient = RideWithGpsClient::builder("https://ridewithgps.com", "test-api-key")
+            .strict(false)
+            .build()
+            .unwrap();
+        let strict = RideWithGpsClient::builder("https://ridewithgps.com", "test-api-key")
+            .strict(true)
+            .build()
+            .unwrap();
+
+        let unknown = br#"{"route": {"id": 1, "colour": "red"}}"#;
+        assert!(lenient
+            .decode::<RouteWrapper>("/api/v1/routes/1.json", unknown)
+            .is_ok());
+        match strict.decode::<RouteWrapper>("/api/v1/routes/1.json", unknown) {
+            Err(Error::SchemaMismatch { path, .. }) => assert_eq!(path, "route.colour"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let mistyped = br#"{"route": {"id": 1, "track_points": [{"x": "east"}]}}"#;
+        match strict.decode::<RouteWrapper>("/api/v1/routes/1.json", mistyped) {
+            Err(Error::SchemaMismatch { path, .. }) => {
+                assert_eq!(path, "route.track_points[0].x")
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unknown_fields_hook() {
+        let route = |body: &[u8]| RawResponse {
+            endpoint: "/api/v1/routes/1.json".to_string(),
+            status: 200,
+            headers: HeaderMap::new(),
+            body: body.to_vec(),
+        };
+
+        let reported = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = reported.clone();
+        let client = RideWithGpsClient::builder("https://ridewithgps.com", "test-api-key")
+            .strict(false)
+            .on_unknown_fields(move |unknown| sink.lock().unwrap().push(unknown.clone()))
+            .build()
+            .unwrap();
+
+        let body =
+            br#"{"route": {"id": 1, "colour": "red", "track_points": [{"x": 5.0, "hr": 120}]}}"#;
+        let decoded: Route = client.handle_wrapped(route(body), "route").unwrap();
+        assert_eq!(decoded.id, 1);
+        client
+            .handle_wrapped::<Route>(route(br#"{"route": {"id": 2}}"#), "route")
+            .unwrap();
+
+        let reported = reported.lock().unwrap();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].endpoint, "/api/v1/routes/1.json");
+        // The model, not the envelope around it
+        assert_eq!(reported[0].type_name, std::any::type_name::<Route>());
+        assert_eq!(
+            reported[0].fields.iter().collect::<Vec<_>>(),
+            vec!["route.colour", "route.track_points[0].hr"]
+        );
+    }
+
+    #[test]
+    fn test_decode_error_context() {
+        #[derive(Debug, Deserialize)]
+        struct TripWrapper {
+            #[allow(dead_code)]
+            trip: Trip,
+        }
+
+        let client = RideWithGpsClient::builder("https://ridewithgps.com", "test-api-key")
+            .strict(false)
+            .build()
+            .unwrap();
+        let points = r#"{"x": 5.0, "y": 52.0, "t": 1700000000}, "#.repeat(50);
+        let body = format!(
+            r#"{{"trip": {{"id": 1, "track_points": [{}{{"x": 5.0, "t": "noon"}}]}}}}"#,
+            points
+        );
+
+        match client.decode::<TripWrapper>("/api/v1/trips/1.json", body.as_bytes()) {
+            Err(Error::Decode {
+                endpoint,
+                path,
+                snippet,
+                ..
+            }) => {
+                assert_eq!(endpoint, "/api/v1/trips/1.json");
+                assert_eq!(path, "trip.track_points[50].t");
+                assert!(snippet.starts_with('…'));
+                assert!(snippet.contains(r#""t": "noon""#));
+                assert!(snippet.len() < body.len());
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_log_slow_requests() {
+        use crate::testing::{FakeServer, FAKE_API_KEY};
+        use std::sync::Mutex;
+
+        /// Collects the key-value pairs of slow-request warnings
+        struct SlowLog(Mutex<Vec<Vec<(String, String)>>>);
+
+        impl log::Log for SlowLog {
+            fn enabled(&self, _: &log::Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, record: &log::Record) {
+                struct Collect(Vec<(String, String)>);
+                impl<'kvs> log::kv::VisitSource<'kvs> for Collect {
+                    fn visit_pair(
+                        &mut self,
+                        key: log::kv::Key<'kvs>,
+                        value: log::kv::Value<'kvs>,
+                    ) -> std::result::Result<(), log::kv::Error> {
+                        self.0.push((key.to_string(), value.to_string()));
+                        Ok(())
+                    }
+                }
+
+                if record.args().to_string().starts_with("Slow request") {
+                    let mut pairs = Collect(Vec::new());
+                    record.key_values().visit(&mut pairs).unwrap();
+                    self.0.lock().unwrap().push(pairs.0);
+                }
+            }
+
+            fn flush(&self) {}
+        }
+
+        static LOG: SlowLog = SlowLog(Mutex::new(Vec::new()));
+        log::set_logger(&LOG).unwrap();
+        log::set_max_level(log::LevelFilter::Warn);
+
+        let server = FakeServer::start();
+        let client = RideWithGpsClient::builder(&server.url(), FAKE_API_KEY)
+            .log_slow_requests(std::time::Duration::ZERO)
+            .build()
+            .unwrap();
+        client.get_route(1234).unwrap();
+
+        let logged = LOG.0.lock().unwrap();
+        let pairs = logged
+            .iter()
+            .find(|pairs| {
+                pairs.contains(&("path".to_string(), "/api/v1/routes/1234.json".to_string()))
+            })
+            .expect("no slow request warning");
+        let keys: Vec<_> = pairs.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "method",
+                "path",
+                "status",
+                "duration_ms",
+                "request_bytes",
+                "response_bytes"
+            ]
+        );
+        assert!(pairs.contains(&("status".to_string(), "200".to_string())));
+    }
 }
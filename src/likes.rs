@@ -0,0 +1,106 @@
+//! Liking routes and trips
+
+use crate::{Result, RideWithGpsClient, RouteId, TripId};
+use reqwest::Method;
+
+/// A route or trip that can be liked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Parent {
+    /// A route
+    Route(u64),
+
+    /// A trip
+    Trip(u64),
+}
+
+impl Parent {
+    /// API path of the parent, without the `.json` suffix
+    fn path(&self) -> String {
+        match self {
+            Parent::Route(id) => format!("/api/v1/routes/{}", id),
+            Parent::Trip(id) => format!("/api/v1/trips/{}", id),
+        }
+    }
+}
+
+impl From<RouteId> for Parent {
+    fn from(id: RouteId) -> Self {
+        Parent::Route(id.0)
+    }
+}
+
+impl From<TripId> for Parent {
+    fn from(id: TripId) -> Self {
+        Parent::Trip(id.0)
+    }
+}
+
+impl RideWithGpsClient {
+    /// Like a route or trip
+    ///
+    /// Liking something that is already liked has no effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent` - The route or trip to like
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{Parent, RideWithGpsClient, TripId};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// client.like(Parent::Route(12345)).unwrap();
+    /// client.like(TripId(67890)).unwrap();
+    /// ```
+    pub fn like(&self, parent: impl Into<Parent>) -> Result<()> {
+        let url = self
+            .base_url
+            .join(&format!("{}/like.json", parent.into().path()))?;
+        let response = self.execute(Method::POST, url, None, true)?;
+
+        match response.status {
+            200 | 201 | 204 => Ok(()),
+            status => Err(self.error_from_status(status, &response.text())),
+        }
+    }
+
+    /// Remove a like from a route or trip
+    ///
+    /// # Arguments
+    ///
+    /// * `parent` - The route or trip to unlike
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{Parent, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// client.unlike(Parent::Route(12345)).unwrap();
+    /// ```
+    pub fn unlike(&self, parent: impl Into<Parent>) -> Result<()> {
+        self.delete_path(&format!("{}/like.json", parent.into().path()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parent_path() {
+        assert_eq!(Parent::from(RouteId(5)).path(), "/api/v1/routes/5");
+        assert_eq!(Parent::from(TripId(6)).path(), "/api/v1/trips/6");
+    }
+}
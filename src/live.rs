@@ -0,0 +1,309 @@
+//! Live log (live ride tracking) types and methods
+
+use crate::{LatLng, Result, RideWithGpsClient, TripTrackPoint};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// A live log of an in-progress ride
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LiveLog {
+    /// Live log ID
+    pub id: u64,
+
+    /// User ID of the rider
+    pub user_id: Option<u64>,
+
+    /// Name of the ride
+    pub name: Option<String>,
+
+    /// Whether the ride is still in progress
+    pub active: Option<bool>,
+
+    /// When the ride started
+    pub started_at: Option<String>,
+
+    /// When the live log was last updated
+    pub updated_at: Option<String>,
+
+    /// Distance covered so far in meters
+    pub distance: Option<f64>,
+
+    /// HTML/web URL of the public tracking page
+    pub html_url: Option<String>,
+
+    /// Track points recorded so far
+    pub track_points: Option<Vec<TripTrackPoint>>,
+}
+
+impl LiveLog {
+    /// Most recent position of the rider, if any has been recorded
+    ///
+    /// This is the position of the point with the newest timestamp, as
+    /// points are not necessarily listed in order; points without a
+    /// timestamp only count if none has one.
+    pub fn last_position(&self) -> Option<LatLng> {
+        self.track_points
+            .iter()
+            .flatten()
+            .filter_map(|p| Some((p.t, p.coord()?)))
+            .max_by_key(|&(t, _)| t)
+            .map(|(_, coord)| coord)
+    }
+}
+
+/// An update produced by [`LiveTracker`]
+#[derive(Debug, Clone)]
+pub struct LiveUpdate {
+    /// Whether the ride is still in progress
+    pub active: bool,
+
+    /// Track points recorded since the previous update
+    pub new_points: Vec<TripTrackPoint>,
+
+    /// Most recent position of the rider
+    pub position: Option<LatLng>,
+
+    /// Distance covered so far in meters
+    pub distance: Option<f64>,
+}
+
+/// Polls a user's live log and yields new positions as they arrive
+///
+/// Created with [`RideWithGpsClient::live_tracker`]. Each call to
+/// [`Iterator::next`] waits for the poll interval (except the first) and
+/// fetches the live log again, yielding only the track points that were not
+/// seen before. Points newer than the newest one seen are new; of the
+/// points sharing the newest timestamp, as many as were already seen are
+/// skipped. After the first update, points without a timestamp are never
+/// yielded. Iteration ends after the update in which the ride is reported as
+/// no longer active. Errors are yielded as they occur and polling continues
+/// on the next call.
+pub struct LiveTracker<'a> {
+    client: &'a RideWithGpsClient,
+    user_id: u64,
+    interval: Duration,
+    last_timestamp: Option<i64>,
+    /// Number of points seen with `last_timestamp`
+    seen_at_last: usize,
+    started: bool,
+    finished: bool,
+}
+
+impl LiveTracker<'_> {
+    fn poll(&mut self) -> Result<LiveUpdate> {
+        let log = self.client.get_live_log(self.user_id)?;
+        let active = log.active.unwrap_or(false);
+        let position = log.last_position();
+
+        let mut at_last = 0;
+        let mut new_points: Vec<TripTrackPoint> = log
+            .track_points
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|p| match (p.t, self.last_timestamp) {
+                (Some(t), Some(last)) if t == last => {
+                    at_last += 1;
+                    at_last > self.seen_at_last
+                }
+                (Some(t), Some(last)) => t > last,
+                (_, None) => true,
+                (None, Some(_)) => false,
+            })
+            .collect();
+        new_points.sort_by_key(|p| p.t);
+
+        if let Some(t) = new_points.iter().filter_map(|p| p.t).max() {
+            let count = new_points.iter().filter(|p| p.t == Some(t)).count();
+            if self.last_timestamp == Some(t) {
+                self.seen_at_last += count;
+            } else {
+                self.last_timestamp = Some(t);
+                self.seen_at_last = count;
+            }
+        }
+
+        Ok(LiveUpdate {
+            active,
+            new_points,
+            position,
+            distance: log.distance,
+        })
+    }
+}
+
+impl Iterator for LiveTracker<'_> {
+    type Item = Result<LiveUpdate>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        if self.started {
+            std::thread::sleep(self.interval);
+        }
+        self.started = true;
+
+        let update = self.poll();
+        if let Ok(update) = &update {
+            self.finished = !update.active;
+        }
+        Some(update)
+    }
+}
+
+impl RideWithGpsClient {
+    /// Get the live log of a user's in-progress ride
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The user ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let log = client.get_live_log(12345).unwrap();
+    /// println!("Last seen at {:?}", log.last_position());
+    /// ```
+    pub fn get_live_log(&self, user_id: u64) -> Result<LiveLog> {
+        self.get_wrapped(
+            &format!("/api/v1/users/{}/live_log.json", user_id),
+            "live_log",
+        )
+    }
+
+    /// Track a user's in-progress ride by polling their live log
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The user ID
+    /// * `interval` - Time to wait between polls
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    /// use std::time::Duration;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// for update in client.live_tracker(12345, Duration::from_secs(30)) {
+    ///     let update = update.unwrap();
+    ///     println!("{} new points, now at {:?}", update.new_points.len(), update.position);
+    /// }
+    /// ```
+    pub fn live_tracker(&self, user_id: u64, interval: Duration) -> LiveTracker<'_> {
+        LiveTracker {
+            client: self,
+            user_id,
+            interval,
+            last_timestamp: None,
+            seen_at_last: 0,
+            started: false,
+            finished: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_log_deserialization() {
+        let json = r#"{
+            "live_log": {
+                "id": 99,
+                "user_id": 12345,
+                "name": "Saturday ride",
+                "active": true,
+                "distance": 15300.0,
+                "track_points": [
+                    {"x": -122.6, "y": 45.5, "t": 1700000000},
+                    {"x": -122.7, "y": 45.6, "t": 1700000060},
+                    {"t": 1700000090}
+                ]
+            }
+        }"#;
+
+        #[derive(Deserialize)]
+        struct LiveLogWrapper {
+            live_log: LiveLog,
+        }
+
+        let wrapper: LiveLogWrapper = serde_json::from_str(json).unwrap();
+        let log = wrapper.live_log;
+        assert_eq!(log.id, 99);
+        assert_eq!(log.active, Some(true));
+        assert_eq!(log.track_points.as_ref().unwrap().len(), 3);
+        assert_eq!(log.last_position(), Some(LatLng::new(45.6, -122.7)));
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_live_tracker() {
+        use crate::testing::FakeServer;
+
+        let server = FakeServer::start();
+        let client = server.client();
+        let log = |active: bool, times: &[i64]| {
+            let points: Vec<_> = times
+                .iter()
+                .map(|&t| serde_json::json!({"x": t as f64 / 1000.0, "y": 45.0, "t": t}))
+                .collect();
+            serde_json::json!({"live_log": {
+                "id": 99, "active": active, "distance": times.len() as f64 * 100.0, "track_points": points
+            }})
+        };
+        // Later mocks are used first
+        let path = "/api/v1/users/{id}/live_log.json";
+        server.mock_once("GET", path, 200, log(false, &[1, 2, 3, 3, 4]));
+        server.mock_once("GET", path, 200, log(true, &[1, 2, 3]));
+        server.mock_once("GET", path, 404, serde_json::json!({"error": "gone"}));
+        server.mock_once("GET", path, 200, log(true, &[2, 1]));
+
+        let updates: Vec<_> = client.live_tracker(12345, Duration::ZERO).collect();
+        assert_eq!(updates.len(), 4);
+        let times = |update: &Result<LiveUpdate>| -> Vec<Option<i64>> {
+            update
+                .as_ref()
+                .unwrap()
+                .new_points
+                .iter()
+                .map(|p| p.t)
+                .collect()
+        };
+
+        // Points come in order, and only once
+        assert_eq!(times(&updates[0]), [Some(1), Some(2)]);
+        assert_eq!(
+            updates[0].as_ref().unwrap().position,
+            Some(LatLng::new(45.0, 0.002))
+        );
+        // An error does not end the polling
+        assert!(matches!(updates[1], Err(crate::Error::NotFound(_))));
+        assert_eq!(times(&updates[2]), [Some(3)]);
+        let last = updates[3].as_ref().unwrap();
+        assert!(!last.active);
+        // A late point with the same timestamp as one seen before is new
+        assert_eq!(times(&updates[3]), [Some(3), Some(4)]);
+        assert_eq!(last.distance, Some(500.0));
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 4);
+        assert!(requests
+            .iter()
+            .all(|r| r.path == "/api/v1/users/12345/live_log.json"));
+    }
+}
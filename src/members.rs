@@ -1,6 +1,8 @@
 //! Club member related types and methods
 //!
-//! Note: These endpoints are only available to organization accounts.
+//! Note: Most of these endpoints are only available to organization accounts.
+//! [`RideWithGpsClient::request_membership`] and
+//! [`RideWithGpsClient::leave_organization`] are called by the member instead.
 
 use crate::{PaginatedResponse, Result, RideWithGpsClient};
 use serde::{Deserialize, Serialize};
@@ -17,6 +19,9 @@ pub struct Member {
     /// Organization ID
     pub organization_id: Option<u64>,
 
+    /// ID of the [`Chapter`](crate::Chapter) the member belongs to
+    pub chapter_id: Option<u64>,
+
     /// API URL
     pub url: Option<String>,
 
@@ -84,6 +89,7 @@ pub struct MemberPermissions {
 
 /// Parameters for listing members
 #[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
 pub struct ListMembersParams {
     /// Filter by member name
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -106,8 +112,17 @@ pub struct ListMembersParams {
     pub page_size: Option<u32>,
 }
 
+setters!(ListMembersParams {
+    name: impl Into<String>,
+    role: impl Into<String>,
+    status: impl Into<String>,
+    page: u32,
+    page_size: u32,
+});
+
 /// Request to update member permissions/status
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
 pub struct UpdateMemberRequest {
     /// Member role
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -122,6 +137,12 @@ pub struct UpdateMemberRequest {
     pub permissions: Option<MemberPermissions>,
 }
 
+setters!(UpdateMemberRequest {
+    role: impl Into<String>,
+    status: impl Into<String>,
+    permissions: MemberPermissions,
+});
+
 impl RideWithGpsClient {
     /// List club members
     ///
@@ -164,7 +185,7 @@ impl RideWithGpsClient {
             }
         }
 
-        self.get(&url)
+        self.get_path(&url)
     }
 
     /// Get a specific member by ID
@@ -190,13 +211,7 @@ impl RideWithGpsClient {
     /// println!("Member: {:?}", member);
     /// ```
     pub fn get_member(&self, id: u64) -> Result<Member> {
-        #[derive(Deserialize)]
-        struct MemberWrapper {
-            member: Member,
-        }
-
-        let wrapper: MemberWrapper = self.get(&format!("/api/v1/members/{}.json", id))?;
-        Ok(wrapper.member)
+        self.get_wrapped(&format!("/api/v1/members/{}.json", id), "member")
     }
 
     /// Update a member's permissions or status
@@ -219,28 +234,75 @@ impl RideWithGpsClient {
     ///     Some("your-auth-token")
     /// );
     ///
-    /// let member_req = UpdateMemberRequest {
-    ///     role: Some("admin".to_string()),
-    ///     status: Some("active".to_string()),
-    ///     permissions: Some(MemberPermissions {
+    /// let member_req = UpdateMemberRequest::new()
+    ///     .role("admin")
+    ///     .status("active")
+    ///     .permissions(MemberPermissions {
     ///         manage_routes: Some(true),
     ///         manage_events: Some(true),
     ///         manage_members: Some(true),
     ///         view_analytics: Some(true),
-    ///     }),
-    /// };
+    ///     });
     ///
     /// let member = client.update_member(12345, &member_req).unwrap();
     /// println!("Updated member: {:?}", member);
     /// ```
     pub fn update_member(&self, id: u64, member: &UpdateMemberRequest) -> Result<Member> {
-        #[derive(Deserialize)]
-        struct MemberWrapper {
-            member: Member,
-        }
+        self.put_wrapped(&format!("/api/v1/members/{}.json", id), "member", member)
+    }
 
-        let wrapper: MemberWrapper = self.put(&format!("/api/v1/members/{}.json", id), member)?;
-        Ok(wrapper.member)
+    /// Ask to join an organization as the authenticated user
+    ///
+    /// The returned membership stays pending until an admin of the
+    /// organization approves it.
+    ///
+    /// # Arguments
+    ///
+    /// * `org_id` - The organization ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let member = client.request_membership(42).unwrap();
+    /// println!("Membership status: {:?}", member.status);
+    /// ```
+    pub fn request_membership(&self, org_id: u64) -> Result<Member> {
+        self.post_wrapped(
+            &format!("/api/v1/organizations/{}/membership.json", org_id),
+            "member",
+            &serde_json::json!({}),
+        )
+    }
+
+    /// Leave an organization, or withdraw a pending membership request
+    ///
+    /// # Arguments
+    ///
+    /// * `org_id` - The organization ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// client.leave_organization(42).unwrap();
+    /// ```
+    pub fn leave_organization(&self, org_id: u64) -> Result<()> {
+        self.delete_path(&format!("/api/v1/organizations/{}/membership.json", org_id))
     }
 }
 
@@ -0,0 +1,1381 @@
+//! Local mirror of an account's routes, trips and photos
+
+use crate::resource::BATCH_CONCURRENCY;
+use crate::{
+    BatchProgress, Error, FileSyncStateStore, ItemType, ListRoutesParams, ListTripsParams,
+    PaginatedResponse, Photo, Result, RideWithGpsClient, SyncItem, SyncOptions, SyncStateStore,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const STATE_FILE: &str = ".rwgps-mirror.json";
+
+/// Which items refer to each stored photo, kept in the `photos/` directory
+const PHOTO_INDEX_FILE: &str = "index.json";
+
+/// What [`Mirror::export_changed_gpx`] exported, kept in the export directory
+const GPX_EXPORT_STATE_FILE: &str = ".rwgps-gpx-export.json";
+
+/// Where a [`SyncRunner`] keeps its checkpoint unless given another store
+const SYNC_STATE_FILE: &str = ".rwgps-sync.json";
+
+/// Which kinds of data a [`Mirror`] downloads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MirrorContent {
+    /// Routes, as JSON and GPX
+    pub routes: bool,
+
+    /// Trips, as JSON and GPX
+    pub trips: bool,
+
+    /// Photos attached to the downloaded routes and trips
+    pub photos: bool,
+}
+
+impl Default for MirrorContent {
+    fn default() -> Self {
+        Self {
+            routes: true,
+            trips: true,
+            photos: true,
+        }
+    }
+}
+
+/// Summary of what a [`Mirror`] run did
+#[derive(Debug, Clone, Default)]
+pub struct MirrorReport {
+    /// Number of routes downloaded
+    pub routes_written: usize,
+
+    /// Number of trips downloaded
+    pub trips_written: usize,
+
+    /// Number of photos written; a photo identical to one already stored
+    /// is not written again
+    pub photos_written: usize,
+
+    /// Number of routes and trips skipped because they had not changed
+    pub unchanged: usize,
+
+    /// Number of routes and trips removed because they were deleted upstream
+    pub deleted: usize,
+
+    /// Items that could not be downloaded, with the error message
+    pub failed: Vec<(ItemType, u64, String)>,
+
+    /// Number of failed items that no longer hold back the sync checkpoint,
+    /// because they failed in too many runs of a [`SyncRunner`]
+    pub quarantined: usize,
+}
+
+impl fmt::Display for MirrorReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} routes, {} trips and {} photos written; {} unchanged, {} deleted, {} failed",
+            self.routes_written,
+            self.trips_written,
+            self.photos_written,
+            self.unchanged,
+            self.deleted,
+            self.failed.len()
+        )
+    }
+}
+
+/// Version of the mirror layout; version 0 stored photos by photo ID
+const STATE_VERSION: u32 = 1;
+
+/// What the mirror has downloaded so far, stored in the mirror directory
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct MirrorState {
+    #[serde(default)]
+    version: u32,
+    last_sync: Option<DateTime<Utc>>,
+    #[serde(default)]
+    routes: BTreeMap<u64, Option<String>>,
+    #[serde(default)]
+    trips: BTreeMap<u64, Option<String>>,
+    #[serde(default)]
+    photos: BTreeMap<u64, StoredPhoto>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    failing: Vec<FailingItem>,
+}
+
+/// An item that a [`SyncRunner`] could not download, by [`MirrorState`]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+struct FailingItem {
+    item_type: ItemType,
+    item_id: u64,
+    updated_at: Option<String>,
+    /// Number of runs in a row in which the download failed
+    runs: u32,
+}
+
+/// Version of each item last exported by [`Mirror::export_changed_gpx`]
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct GpxExportState {
+    #[serde(default)]
+    routes: BTreeMap<u64, Option<String>>,
+    #[serde(default)]
+    trips: BTreeMap<u64, Option<String>>,
+}
+
+/// A photo of a downloaded item, by photo ID in [`MirrorState`]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+struct StoredPhoto {
+    /// Name of the file in `photos/`, from the SHA-256 of its content
+    file: String,
+    item_type: ItemType,
+    item_id: u64,
+}
+
+/// An entry of the photo index: a photo referring to a stored file
+#[derive(Debug, Serialize)]
+struct PhotoReference {
+    photo_id: u64,
+    item_type: ItemType,
+    item_id: u64,
+}
+
+/// Photos of a downloaded item
+#[derive(Debug, Default)]
+struct DownloadedPhotos {
+    /// Stored file of each photo, by photo ID
+    files: Vec<(u64, String)>,
+
+    /// Number of files added to the photo store
+    written: usize,
+}
+
+/// Keeps a local directory in sync with an account's routes, trips and photos
+///
+/// Created with [`RideWithGpsClient::mirror`]. The directory gets a
+/// `routes/` and `trips/` subdirectory with a JSON and a GPX file per item,
+/// and a `photos/` subdirectory. Photos are stored by content, so an image
+/// attached to several routes and trips is only stored once; `photos/index.json`
+/// lists the photos and items that refer to each file; photos stored by ID
+/// by older versions are moved into it on the first run. A state file
+/// records what has been downloaded, so repeated runs only fetch what
+/// changed.
+pub struct Mirror<'a> {
+    client: &'a RideWithGpsClient,
+    dir: PathBuf,
+    content: MirrorContent,
+}
+
+impl Mirror<'_> {
+    /// Restrict which kinds of data are downloaded
+    pub fn content(mut self, content: MirrorContent) -> Self {
+        self.content = content;
+        self
+    }
+
+    /// Download every route and trip that is new or changed since the last run
+    ///
+    /// This lists the whole account, so it also picks up items a previous
+    /// run failed to download. Items deleted upstream are left alone, so
+    /// the point [`sync`](Self::sync) continues from is not moved.
+    pub fn backup(&self) -> Result<MirrorReport> {
+        let mut state = self.load_state()?;
+        let mut report = MirrorReport::default();
+
+        if self.content.routes {
+            let routes = list_all(|page| {
+                self.client
+                    .list_routes(Some(&ListRoutesParams::new().page(page)))
+            })?;
+            for route in routes {
+                self.update(
+                    &mut state,
+                    &mut report,
+                    ItemType::Route,
+                    route.id,
+                    route.updated_at,
+                );
+            }
+        }
+
+        if self.content.trips {
+            let trips = list_all(|page| {
+                self.client
+                    .list_trips(Some(&ListTripsParams::new().page(page)))
+            })?;
+            for trip in trips {
+                self.update(
+                    &mut state,
+                    &mut report,
+                    ItemType::Trip,
+                    trip.id,
+                    trip.updated_at,
+                );
+            }
+        }
+
+        self.save_state(&state)?;
+        Ok(report)
+    }
+
+    /// Apply the changes reported by the sync endpoint since the last run
+    ///
+    /// Items deleted upstream are removed locally. The first run asks for
+    /// every change in the account's history. The next run starts from the
+    /// `server_datetime` of this one, so the local clock is never used.
+    pub fn sync(&self) -> Result<MirrorReport> {
+        let mut state = self.load_state()?;
+        let mut report = MirrorReport::default();
+
+        let changes = self.client.sync(&state.last_sync.unwrap_or_default())?;
+        for item in changes.items {
+            if !self.includes(item.item_type) {
+                continue;
+            }
+
+            if item.deleted == Some(true) {
+                self.remove(&mut state, item.item_type, item.id)?;
+                report.deleted += 1;
+            } else {
+                self.update(
+                    &mut state,
+                    &mut report,
+                    item.item_type,
+                    item.id,
+                    Some(item.updated_at.to_rfc3339()),
+                );
+            }
+        }
+
+        if report.failed.is_empty() {
+            state.last_sync = Some(changes.server_datetime);
+        }
+        self.save_state(&state)?;
+        Ok(report)
+    }
+
+    /// Copy the GPX files of the routes and trips that changed since the
+    /// last export to another directory
+    ///
+    /// Meant to be called after each [`sync`](Self::sync), to keep a folder
+    /// of GPX files, such as one on a shared drive, up to date without
+    /// exporting everything again. The files go in `routes/` and `trips/`
+    /// subdirectories, named by ID; files of items no longer in the mirror
+    /// are removed. Returns the paths of the files written.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory to export to; created if missing
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let mirror = client.mirror("rwgps-backup");
+    /// mirror.sync().unwrap();
+    /// for path in mirror.export_changed_gpx("/mnt/shared/gpx").unwrap() {
+    ///     println!("Updated {}", path.display());
+    /// }
+    /// ```
+    pub fn export_changed_gpx(&self, dir: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        let state = self.load_state()?;
+        let state_path = dir.join(GPX_EXPORT_STATE_FILE);
+        let mut exported: GpxExportState = match std::fs::read(&state_path) {
+            Ok(data) => serde_json::from_slice(&data)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => GpxExportState::default(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut written = Vec::new();
+        for (item_type, current, exported) in [
+            (ItemType::Route, &state.routes, &mut exported.routes),
+            (ItemType::Trip, &state.trips, &mut exported.trips),
+        ] {
+            let gone: Vec<u64> = exported
+                .keys()
+                .filter(|id| !current.contains_key(id))
+                .copied()
+                .collect();
+            for id in gone {
+                match std::fs::remove_file(item_file(dir, item_type, id, "gpx")) {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                    _ => {}
+                }
+                exported.remove(&id);
+            }
+
+            for (&id, updated_at) in current {
+                let target = item_file(dir, item_type, id, "gpx");
+                if updated_at.is_some() && exported.get(&id) == Some(updated_at) && target.exists()
+                {
+                    continue;
+                }
+                write_file(
+                    &target,
+                    &std::fs::read(self.item_path(item_type, id, "gpx"))?,
+                )?;
+                exported.insert(id, updated_at.clone());
+                written.push(target);
+            }
+        }
+
+        write_file(&state_path, &serde_json::to_vec_pretty(&exported)?)?;
+        Ok(written)
+    }
+
+    /// Download an item unless the stored copy is already up to date
+    fn update(
+        &self,
+        state: &mut MirrorState,
+        report: &mut MirrorReport,
+        item_type: ItemType,
+        id: u64,
+        updated_at: Option<String>,
+    ) {
+        let updated_at = updated_at.map(normalize_timestamp);
+        if self.is_current(state, item_type, id, &updated_at) {
+            report.unchanged += 1;
+            return;
+        }
+
+        let result = self
+            .download(&state.photos, item_type, id)
+            .and_then(|photos| {
+                let released = self.link_photos(state, item_type, id, photos.files);
+                self.prune_photos(state, released)?;
+                Ok(photos.written)
+            });
+        match result {
+            Ok(written) => {
+                report.photos_written += written;
+                record(state, report, item_type, id, updated_at);
+            }
+            Err(e) => report.failed.push((item_type, id, e.to_string())),
+        }
+    }
+
+    /// Whether the stored copy of an item was downloaded at `updated_at`
+    fn is_current(
+        &self,
+        state: &MirrorState,
+        item_type: ItemType,
+        id: u64,
+        updated_at: &Option<String>,
+    ) -> bool {
+        let known = match item_type {
+            ItemType::Route => &state.routes,
+            _ => &state.trips,
+        };
+        updated_at.is_some()
+            && known.get(&id) == Some(updated_at)
+            && self.item_path(item_type, id, "json").exists()
+    }
+
+    /// Download an item and the photos not stored yet
+    ///
+    /// `stored` are the photos already in the photo store, which are not
+    /// downloaded again.
+    fn download(
+        &self,
+        stored: &BTreeMap<u64, StoredPhoto>,
+        item_type: ItemType,
+        id: u64,
+    ) -> Result<DownloadedPhotos> {
+        let mut json = Vec::new();
+        let mut gpx = Vec::new();
+        let photos = match item_type {
+            ItemType::Route => {
+                let route = self.client.get_route(id)?;
+                serde_json::to_writer_pretty(&mut json, &route)?;
+                route.write_gpx(&mut gpx)?;
+                route.photos
+            }
+            _ => {
+                let trip = self.client.get_trip(id)?;
+                serde_json::to_writer_pretty(&mut json, &trip)?;
+                trip.write_gpx(&mut gpx)?;
+                trip.photos
+            }
+        };
+
+        write_file(&self.item_path(item_type, id, "json"), &json)?;
+        write_file(&self.item_path(item_type, id, "gpx"), &gpx)?;
+
+        let mut downloaded = DownloadedPhotos::default();
+        if !self.content.photos {
+            return Ok(downloaded);
+        }
+        for photo in photos.iter().flatten() {
+            let known = stored
+                .get(&photo.id)
+                .filter(|s| self.photo_path(&s.file).exists());
+            let file = match (known, &photo.url) {
+                (Some(stored), _) => stored.file.clone(),
+                (None, Some(url)) => {
+                    let data = self.client.download(url)?;
+                    let file = photo_file_name(photo, &data);
+                    let path = self.photo_path(&file);
+                    if !path.exists() {
+                        write_file(&path, &data)?;
+                        downloaded.written += 1;
+                    }
+                    file
+                }
+                (None, None) => continue,
+            };
+            downloaded.files.push((photo.id, file));
+        }
+        Ok(downloaded)
+    }
+
+    /// Replace the photos an item refers to, returning the files it no
+    /// longer refers to
+    ///
+    /// The files are left in place: another item downloaded at the same
+    /// time may use them too. Nothing changes if photos are not mirrored.
+    fn link_photos(
+        &self,
+        state: &mut MirrorState,
+        item_type: ItemType,
+        id: u64,
+        files: Vec<(u64, String)>,
+    ) -> Vec<String> {
+        if !self.content.photos {
+            return Vec::new();
+        }
+
+        let mut released = Vec::new();
+        state.photos.retain(|_, photo| {
+            let linked = photo.item_type == item_type && photo.item_id == id;
+            if linked {
+                released.push(photo.file.clone());
+            }
+            !linked
+        });
+        for (photo_id, file) in files {
+            let photo = StoredPhoto {
+                file,
+                item_type,
+                item_id: id,
+            };
+            state.photos.insert(photo_id, photo);
+        }
+        released
+    }
+
+    /// Delete the released files that no photo refers to any more
+    ///
+    /// Only call this once every item downloaded so far has been linked,
+    /// so that a file an item has just started using is not deleted.
+    fn prune_photos(&self, state: &MirrorState, released: Vec<String>) -> Result<()> {
+        for file in released {
+            if state.photos.values().any(|photo| photo.file == file) {
+                continue;
+            }
+            match std::fs::remove_file(self.photo_path(&file)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether changes to items of this type are mirrored
+    fn includes(&self, item_type: ItemType) -> bool {
+        match item_type {
+            ItemType::Route => self.content.routes,
+            ItemType::Trip => self.content.trips,
+            _ => false,
+        }
+    }
+
+    fn remove(&self, state: &mut MirrorState, item_type: ItemType, id: u64) -> Result<()> {
+        match item_type {
+            ItemType::Route => state.routes.remove(&id),
+            _ => state.trips.remove(&id),
+        };
+        state
+            .failing
+            .retain(|f| (f.item_type, f.item_id) != (item_type, id));
+        let released = self.link_photos(state, item_type, id, Vec::new());
+        self.prune_photos(state, released)?;
+        for extension in ["json", "gpx"] {
+            match std::fs::remove_file(self.item_path(item_type, id, extension)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn item_path(&self, item_type: ItemType, id: u64, extension: &str) -> PathBuf {
+        item_file(&self.dir, item_type, id, extension)
+    }
+
+    fn photo_path(&self, file: &str) -> PathBuf {
+        self.dir.join("photos").join(file)
+    }
+
+    fn load_state(&self) -> Result<MirrorState> {
+        let mut state: MirrorState = match std::fs::read(self.dir.join(STATE_FILE)) {
+            Ok(data) => serde_json::from_slice(&data)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(MirrorState {
+                    version: STATE_VERSION,
+                    ..Default::default()
+                })
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if state.version < STATE_VERSION {
+            self.migrate_photos(&mut state)?;
+            state.version = STATE_VERSION;
+            self.save_state(&state)?;
+        }
+        Ok(state)
+    }
+
+    /// Move photos stored by photo ID, as older versions did, into the
+    /// photo store
+    ///
+    /// The photos of each item are taken from its JSON file. Files that are
+    /// already in the store are deleted instead of moved.
+    fn migrate_photos(&self, state: &mut MirrorState) -> Result<()> {
+        #[derive(Deserialize)]
+        struct Photos {
+            #[serde(default)]
+            photos: Option<Vec<Photo>>,
+        }
+
+        let items: Vec<(ItemType, u64)> = state
+            .routes
+            .keys()
+            .map(|&id| (ItemType::Route, id))
+            .chain(state.trips.keys().map(|&id| (ItemType::Trip, id)))
+            .collect();
+        for (item_type, id) in items {
+            let Ok(json) = std::fs::read(self.item_path(item_type, id, "json")) else {
+                continue;
+            };
+            let Ok(Photos { photos }) = serde_json::from_slice(&json) else {
+                continue;
+            };
+            for photo in photos.iter().flatten() {
+                let legacy = self.photo_path(&format!("{}.{}", photo.id, photo_extension(photo)));
+                let Ok(data) = std::fs::read(&legacy) else {
+                    continue;
+                };
+                let file = photo_file_name(photo, &data);
+                if self.photo_path(&file).exists() {
+                    std::fs::remove_file(&legacy)?;
+                } else {
+                    std::fs::rename(&legacy, self.photo_path(&file))?;
+                }
+                let stored = StoredPhoto {
+                    file,
+                    item_type,
+                    item_id: id,
+                };
+                state.photos.insert(photo.id, stored);
+            }
+        }
+        Ok(())
+    }
+
+    fn save_state(&self, state: &MirrorState) -> Result<()> {
+        if !state.photos.is_empty() || self.photo_path(PHOTO_INDEX_FILE).exists() {
+            let mut index: BTreeMap<&str, Vec<PhotoReference>> = BTreeMap::new();
+            for (&photo_id, photo) in &state.photos {
+                index.entry(&photo.file).or_default().push(PhotoReference {
+                    photo_id,
+                    item_type: photo.item_type,
+                    item_id: photo.item_id,
+                });
+            }
+            write_file(
+                &self.photo_path(PHOTO_INDEX_FILE),
+                &serde_json::to_vec_pretty(&index)?,
+            )?;
+        }
+        write_file(
+            &self.dir.join(STATE_FILE),
+            &serde_json::to_vec_pretty(state)?,
+        )
+    }
+}
+
+/// Progress of a [`SyncRunner`], as passed to its progress callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncEvent {
+    /// The changes since the last checkpoint were fetched
+    Fetched {
+        /// Number of changed items, including ones not mirrored
+        changes: usize,
+    },
+
+    /// Downloading the changed items progressed
+    Downloading(BatchProgress),
+
+    /// Items that failed to download are being tried again
+    Retrying {
+        /// Number of items tried again
+        items: usize,
+
+        /// Attempt number, counting from 1
+        attempt: u32,
+    },
+}
+
+/// Keeps a [`Mirror`] up to date in a single resumable call
+///
+/// Created with [`RideWithGpsClient::sync_runner`]. Each [`run`](Self::run)
+/// fetches the changes since the last checkpoint with
+/// [`sync_with_store`](RideWithGpsClient::sync_with_store), downloads the
+/// changed items a few at a time, tries failed downloads again, and removes
+/// deleted items. The checkpoint only moves forward once every item has been
+/// handled; an interrupted or partly failed run is picked up by the next
+/// one, which skips what was already downloaded.
+///
+/// An item that keeps failing would hold the checkpoint back forever, so
+/// once it has failed in [`quarantine_after`](Self::quarantine_after) runs
+/// in a row it is quarantined: the checkpoint moves on without it, and it
+/// is tried again in every later run until it downloads or is deleted.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ridewithgps_client::{RideWithGpsClient, SyncEvent};
+///
+/// let client = RideWithGpsClient::new(
+///     "https://ridewithgps.com",
+///     "your-api-key",
+///     Some("your-auth-token")
+/// );
+///
+/// let report = client
+///     .sync_runner("rwgps-backup")
+///     .concurrency(8)
+///     .on_progress(|event| {
+///         if let SyncEvent::Downloading(p) = event {
+///             eprintln!("{}/{}", p.completed, p.total);
+///         }
+///     })
+///     .run()
+///     .unwrap();
+/// println!("{}", report);
+/// ```
+pub struct SyncRunner<'a> {
+    mirror: Mirror<'a>,
+    store: Option<Box<dyn SyncStateStore + 'a>>,
+    options: SyncOptions,
+    concurrency: usize,
+    attempts: u32,
+    quarantine_after: u32,
+    progress: Option<Box<dyn Fn(SyncEvent) + Sync + 'a>>,
+}
+
+impl<'a> SyncRunner<'a> {
+    /// Restrict which kinds of data are downloaded
+    pub fn content(mut self, content: MirrorContent) -> Self {
+        self.mirror.content = content;
+        self
+    }
+
+    /// Keep the checkpoint in `store`, instead of a file in the mirror
+    /// directory
+    pub fn store(mut self, store: impl SyncStateStore + 'a) -> Self {
+        self.store = Some(Box::new(store));
+        self
+    }
+
+    /// Overlap and starting point of the sync
+    pub fn options(mut self, options: SyncOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Number of items to download at the same time (default 4)
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Number of times to try downloading each item in a run (default 3)
+    ///
+    /// This comes on top of the client's own
+    /// [`retry`](crate::ClientBuilder::retry) policy for single requests.
+    pub fn attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts.max(1);
+        self
+    }
+
+    /// Number of runs in a row an item may fail before it no longer holds
+    /// back the checkpoint (default 3)
+    pub fn quarantine_after(mut self, runs: u32) -> Self {
+        self.quarantine_after = runs.max(1);
+        self
+    }
+
+    /// Report progress while running
+    ///
+    /// The callback may be called from several threads, one call at a time.
+    pub fn on_progress(mut self, progress: impl Fn(SyncEvent) + Sync + 'a) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Bring the mirror up to date
+    ///
+    /// Returns an error if the changes could not be fetched or the mirror or
+    /// checkpoint could not be saved. Items that still failed after every
+    /// attempt are listed in the report, and fetched again next run.
+    /// Unless all of them are quarantined, the checkpoint stays put.
+    pub fn run(&self) -> Result<MirrorReport> {
+        let default_store;
+        let store: &dyn SyncStateStore = match &self.store {
+            Some(store) => store.as_ref(),
+            None => {
+                default_store = FileSyncStateStore::new(self.mirror.dir.join(SYNC_STATE_FILE));
+                &default_store
+            }
+        };
+
+        let mut report = None;
+        let mut held_back = false;
+        let result = self
+            .mirror
+            .client
+            .sync_with_store(store, &self.options, |items| {
+                let applied = self.apply(items)?;
+                let failed = applied.failed.len() - applied.quarantined;
+                report = Some(applied);
+                if failed > 0 {
+                    // Keep the checkpoint, so the next run tries them again
+                    held_back = true;
+                    return Err(Error::ApiError(format!("{} items failed", failed)));
+                }
+                Ok(())
+            });
+        match (result, report) {
+            (Ok(_), report) => Ok(report.unwrap_or_default()),
+            (Err(_), Some(report)) if held_back => Ok(report),
+            (Err(e), _) => Err(e),
+        }
+    }
+
+    fn apply(&self, items: &[SyncItem]) -> Result<MirrorReport> {
+        self.emit(SyncEvent::Fetched {
+            changes: items.len(),
+        });
+        let mirror = &self.mirror;
+        let progress = self.progress.as_deref();
+        let mut state = mirror.load_state()?;
+        let mut report = MirrorReport::default();
+
+        let mut pending = Vec::new();
+        for item in items.iter().filter(|i| mirror.includes(i.item_type)) {
+            let updated_at = Some(item.updated_at.to_rfc3339());
+            if item.deleted == Some(true) {
+                mirror.remove(&mut state, item.item_type, item.id)?;
+                report.deleted += 1;
+            } else if mirror.is_current(&state, item.item_type, item.id, &updated_at) {
+                report.unchanged += 1;
+            } else {
+                pending.push((item.item_type, item.id, updated_at));
+            }
+        }
+        // Quarantined items are no longer part of the changes
+        for failing in &state.failing {
+            let key = (failing.item_type, failing.item_id);
+            if failing.runs >= self.quarantine_after
+                && !items.iter().any(|i| (i.item_type, i.id) == key)
+            {
+                pending.push((key.0, key.1, failing.updated_at.clone()));
+            }
+        }
+
+        for attempt in 1..=self.attempts {
+            if pending.is_empty() {
+                break;
+            }
+            if attempt > 1 {
+                self.emit(SyncEvent::Retrying {
+                    items: pending.len(),
+                    attempt,
+                });
+            }
+
+            let stored = &state.photos;
+            let mut batch = mirror
+                .client
+                .batch::<DownloadedPhotos>()
+                .concurrency(self.concurrency)
+                .on_progress(|p| {
+                    if let Some(progress) = progress {
+                        progress(SyncEvent::Downloading(p));
+                    }
+                });
+            for &(item_type, id, _) in &pending {
+                batch = batch.call(move |_| mirror.download(stored, item_type, id));
+            }
+            let results = batch.run();
+
+            let mut failed = Vec::new();
+            let mut released = Vec::new();
+            for (item, result) in pending.into_iter().zip(results) {
+                let result = result.map(|photos| {
+                    released.extend(mirror.link_photos(&mut state, item.0, item.1, photos.files));
+                    photos.written
+                });
+                match result {
+                    Ok(written) => {
+                        report.photos_written += written;
+                        record(&mut state, &mut report, item.0, item.1, item.2);
+                    }
+                    Err(e) if attempt == self.attempts => {
+                        let runs = note_failure(&mut state, item.0, item.1, item.2);
+                        if runs >= self.quarantine_after {
+                            report.quarantined += 1;
+                        }
+                        report.failed.push((item.0, item.1, e.to_string()))
+                    }
+                    Err(_) => failed.push(item),
+                }
+            }
+            mirror.prune_photos(&state, released)?;
+            pending = failed;
+        }
+
+        mirror.save_state(&state)?;
+        Ok(report)
+    }
+
+    fn emit(&self, event: SyncEvent) {
+        if let Some(progress) = &self.progress {
+            progress(event);
+        }
+    }
+}
+
+impl RideWithGpsClient {
+    /// Mirror the authenticated user's routes, trips and photos to a directory
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory to store the mirror in; created if missing
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let report = client.mirror("rwgps-backup").sync().unwrap();
+    /// println!("{}", report);
+    /// ```
+    pub fn mirror(&self, dir: impl Into<PathBuf>) -> Mirror<'_> {
+        Mirror {
+            client: self,
+            dir: dir.into(),
+            content: MirrorContent::default(),
+        }
+    }
+
+    /// Keep a mirror of the authenticated user's routes, trips and photos
+    /// up to date with a [`SyncRunner`]
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory to store the mirror in; created if missing
+    pub fn sync_runner(&self, dir: impl Into<PathBuf>) -> SyncRunner<'_> {
+        SyncRunner {
+            mirror: self.mirror(dir),
+            store: None,
+            options: SyncOptions::default(),
+            concurrency: BATCH_CONCURRENCY,
+            attempts: 3,
+            quarantine_after: 3,
+            progress: None,
+        }
+    }
+}
+
+/// Timestamps from listings and from the sync endpoint are formatted
+/// differently, so they are stored in a common form
+fn normalize_timestamp(timestamp: String) -> String {
+    DateTime::parse_from_rfc3339(&timestamp)
+        .map(|t| t.with_timezone(&Utc).to_rfc3339())
+        .unwrap_or(timestamp)
+}
+
+/// Path of a route or trip file in a mirror or export directory
+fn item_file(dir: &Path, item_type: ItemType, id: u64, extension: &str) -> PathBuf {
+    let subdir = match item_type {
+        ItemType::Route => "routes",
+        _ => "trips",
+    };
+    dir.join(subdir).join(format!("{}.{}", id, extension))
+}
+
+/// Note that an item was downloaded
+fn record(
+    state: &mut MirrorState,
+    report: &mut MirrorReport,
+    item_type: ItemType,
+    id: u64,
+    updated_at: Option<String>,
+) {
+    state
+        .failing
+        .retain(|f| (f.item_type, f.item_id) != (item_type, id));
+    match item_type {
+        ItemType::Route => {
+            state.routes.insert(id, updated_at);
+            report.routes_written += 1;
+        }
+        _ => {
+            state.trips.insert(id, updated_at);
+            report.trips_written += 1;
+        }
+    }
+}
+
+/// Note that an item failed in a run, returning the number of runs in a row
+/// it failed in
+fn note_failure(
+    state: &mut MirrorState,
+    item_type: ItemType,
+    id: u64,
+    updated_at: Option<String>,
+) -> u32 {
+    let index = match state
+        .failing
+        .iter()
+        .position(|f| (f.item_type, f.item_id) == (item_type, id))
+    {
+        Some(index) => index,
+        None => {
+            state.failing.push(FailingItem {
+                item_type,
+                item_id: id,
+                updated_at: None,
+                runs: 0,
+            });
+            state.failing.len() - 1
+        }
+    };
+    let failing = &mut state.failing[index];
+    failing.updated_at = updated_at;
+    failing.runs += 1;
+    failing.runs
+}
+
+/// Fetch every page of a paginated listing
+pub(crate) fn list_all<T>(
+    mut fetch: impl FnMut(u32) -> Result<PaginatedResponse<T>>,
+) -> Result<Vec<T>> {
+    let mut items = Vec::new();
+    for page in 1.. {
+        let response = fetch(page)?;
+        let done = response.results.is_empty() || response.pagination.next_page_url.is_none();
+        items.extend(response.results);
+        if done {
+            break;
+        }
+    }
+    Ok(items)
+}
+
+/// Write a file atomically, creating its directory if needed
+fn write_file(path: &Path, data: &[u8]) -> Result<()> {
+    // Identical photos may be written from several threads at once
+    static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension(format!(
+        "{}.tmp",
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&tmp, data)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// File name of a photo in the photo store: the SHA-256 of its content, with
+/// the extension of its URL
+fn photo_file_name(photo: &Photo, data: &[u8]) -> String {
+    let hash: String = Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("{}.{}", hash, photo_extension(photo))
+}
+
+/// Extension of a photo's URL, or `jpg` if it has none
+fn photo_extension(photo: &Photo) -> String {
+    photo
+        .url
+        .as_deref()
+        .and_then(|url| url.split(['?', '#']).next())
+        .and_then(|path| path.rsplit('/').next())
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .filter(|ext| !ext.is_empty() && ext.len() <= 4)
+        .unwrap_or_else(|| "jpg".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_photo_file_name() {
+        let photo: Photo = serde_json::from_str(
+            r#"{"id": 7, "url": "https://photos.example.com/a/b/IMG_1.PNG?size=large"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            photo_file_name(&photo, b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad.png"
+        );
+
+        let photo: Photo =
+            serde_json::from_str(r#"{"id": 8, "url": "https://photos.example.com/8"}"#).unwrap();
+        assert!(photo_file_name(&photo, b"").ends_with(".jpg"));
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let json = r#"{"last_sync": "2025-01-15T12:00:00Z", "routes": {"12": "2025-01-10"}}"#;
+        let state: MirrorState = serde_json::from_str(json).unwrap();
+        assert_eq!(state.routes.get(&12), Some(&Some("2025-01-10".to_string())));
+        assert!(state.trips.is_empty());
+
+        let again: MirrorState =
+            serde_json::from_slice(&serde_json::to_vec(&state).unwrap()).unwrap();
+        assert_eq!(again.last_sync, state.last_sync);
+    }
+
+    #[test]
+    fn test_report_display() {
+        let report = MirrorReport {
+            routes_written: 2,
+            unchanged: 5,
+            ..Default::default()
+        };
+        assert_eq!(
+            report.to_string(),
+            "2 routes, 0 trips and 0 photos written; 5 unchanged, 0 deleted, 0 failed"
+        );
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_sync_runner() {
+        use crate::testing::FakeServer;
+        use std::sync::Mutex;
+
+        let server = FakeServer::start();
+        let client = server.client();
+        let dir = std::env::temp_dir().join(format!("rwgps-sync-runner-{}", std::process::id()));
+        let events = Mutex::new(Vec::new());
+
+        // The trip fails, so the checkpoint stays put
+        server.mock_once("GET", "/api/v1/trips/{id}.json", 404, serde_json::json!({}));
+        let report = client.sync_runner(&dir).attempts(1).run().unwrap();
+        assert_eq!(report.routes_written, 1);
+        assert_eq!(report.deleted, 1);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].1, 201);
+        assert!(!dir.join(SYNC_STATE_FILE).exists());
+
+        // The next run skips the route, and tries the trip again until it works
+        server.mock_once("GET", "/api/v1/trips/{id}.json", 404, serde_json::json!({}));
+        let report = client
+            .sync_runner(&dir)
+            .on_progress(|e| events.lock().unwrap().push(e))
+            .run()
+            .unwrap();
+        assert_eq!(report.unchanged, 1);
+        assert_eq!(report.trips_written, 1);
+        assert!(report.failed.is_empty());
+        assert!(dir.join("trips/201.gpx").exists());
+        let checkpoint = std::fs::read_to_string(dir.join(SYNC_STATE_FILE)).unwrap();
+        assert!(checkpoint.contains("2025-01-04T00:00:00Z"));
+
+        let events = events.into_inner().unwrap();
+        assert_eq!(events[0], SyncEvent::Fetched { changes: 3 });
+        assert!(events.contains(&SyncEvent::Retrying {
+            items: 1,
+            attempt: 2
+        }));
+        assert_eq!(events.len(), 4);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_sync_runner_quarantine() {
+        use crate::testing::FakeServer;
+
+        let server = FakeServer::start();
+        let client = server.client();
+        let dir = std::env::temp_dir().join(format!("rwgps-quarantine-{}", std::process::id()));
+        let runner = || client.sync_runner(&dir).attempts(1).quarantine_after(2);
+
+        // The trip always fails; the second failed run no longer holds back
+        // the checkpoint
+        server.mock("GET", "/api/v1/trips/{id}.json", 404, serde_json::json!({}));
+        let report = runner().run().unwrap();
+        assert_eq!((report.failed.len(), report.quarantined), (1, 0));
+        assert!(!dir.join(SYNC_STATE_FILE).exists());
+        let report = runner().run().unwrap();
+        assert_eq!((report.failed.len(), report.quarantined), (1, 1));
+        assert!(dir.join(SYNC_STATE_FILE).exists());
+
+        // Once the trip is no longer among the changes, it is still tried
+        server.mock(
+            "GET",
+            "/api/v1/trips/{id}.json",
+            200,
+            serde_json::json!({"trip": {"id": 201}}),
+        );
+        let report = runner().run().unwrap();
+        assert_eq!(report.trips_written, 1);
+        assert!(report.failed.is_empty());
+        assert!(dir.join("trips/201.json").exists());
+        let state: MirrorState =
+            serde_json::from_slice(&std::fs::read(dir.join(STATE_FILE)).unwrap()).unwrap();
+        assert!(state.failing.is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_sync_runner_checkpoint_error() {
+        use crate::testing::FakeServer;
+        use crate::SyncState;
+
+        struct Unwritable;
+
+        impl SyncStateStore for Unwritable {
+            fn load(&self) -> Result<Option<SyncState>> {
+                Ok(None)
+            }
+
+            fn save(&self, _: &SyncState) -> Result<()> {
+                Err(std::io::Error::other("read-only").into())
+            }
+        }
+
+        let server = FakeServer::start();
+        let client = server.client();
+        let dir = std::env::temp_dir().join(format!("rwgps-unwritable-{}", std::process::id()));
+        server.mock("GET", "/api/v1/trips/{id}.json", 404, serde_json::json!({}));
+
+        // With the failed trip quarantined the checkpoint is saved, and
+        // failing to save it is an error
+        let result = client
+            .sync_runner(&dir)
+            .store(Unwritable)
+            .attempts(1)
+            .quarantine_after(1)
+            .run();
+        assert!(matches!(result, Err(Error::Io(_))), "{:?}", result);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_photo_store() {
+        use crate::testing::FakeServer;
+
+        let server = FakeServer::start();
+        let client = server.client();
+        let dir = std::env::temp_dir().join(format!("rwgps-photo-store-{}", std::process::id()));
+        server.mock(
+            "GET",
+            "/api/v1/routes/{id}.json",
+            200,
+            serde_json::json!({"route": {"id": 101, "photos": [
+                {"id": 1, "url": "/photos/a.jpg"},
+                {"id": 2, "url": "/photos/c.png"}
+            ]}}),
+        );
+        server.mock(
+            "GET",
+            "/api/v1/trips/{id}.json",
+            200,
+            serde_json::json!({"trip": {"id": 201, "photos": [
+                {"id": 3, "url": "/photos/b.jpg"}
+            ]}}),
+        );
+        server.mock_raw("GET", "/photos/a.jpg", 200, "same image");
+        server.mock_raw("GET", "/photos/b.jpg", 200, "same image");
+        server.mock_raw("GET", "/photos/c.png", 200, "other image");
+
+        let report = client.sync_runner(&dir).run().unwrap();
+        assert_eq!(report.photos_written, 2);
+        let shared = photo_file_name(
+            &serde_json::from_str(r#"{"id": 1}"#).unwrap(),
+            b"same image",
+        );
+        assert_eq!(
+            std::fs::read(dir.join("photos").join(&shared)).unwrap(),
+            b"same image"
+        );
+        let index: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(dir.join("photos/index.json")).unwrap()).unwrap();
+        assert_eq!(index.as_object().unwrap().len(), 2);
+        assert_eq!(
+            index[&shared],
+            serde_json::json!([
+                {"photo_id": 1, "item_type": "route", "item_id": 101},
+                {"photo_id": 3, "item_type": "trip", "item_id": 201}
+            ])
+        );
+
+        // Files are deleted once nothing refers to them
+        let mirror = client.mirror(&dir);
+        let mut state = mirror.load_state().unwrap();
+        mirror.remove(&mut state, ItemType::Trip, 201).unwrap();
+        assert!(dir.join("photos").join(&shared).exists());
+        mirror.remove(&mut state, ItemType::Route, 101).unwrap();
+        assert!(!dir.join("photos").join(&shared).exists());
+        assert!(state.photos.is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_photo_moved_between_items_in_one_batch() {
+        use crate::testing::FakeServer;
+
+        let server = FakeServer::start();
+        let client = server.client();
+        let dir = std::env::temp_dir().join(format!("rwgps-photo-batch-{}", std::process::id()));
+
+        // The route used to have the photo; now only the new trip has it
+        let shared = photo_file_name(
+            &serde_json::from_str(r#"{"id": 1}"#).unwrap(),
+            b"same image",
+        );
+        write_file(&dir.join("photos").join(&shared), b"same image").unwrap();
+        let state = serde_json::json!({
+            "version": STATE_VERSION,
+            "last_sync": null,
+            "routes": {"101": "2020-01-01T00:00:00+00:00"},
+            "photos": {"1": {"file": shared, "item_type": "route", "item_id": 101}}
+        });
+        write_file(&dir.join(STATE_FILE), state.to_string().as_bytes()).unwrap();
+        server.mock(
+            "GET",
+            "/api/v1/routes/{id}.json",
+            200,
+            serde_json::json!({"route": {"id": 101, "photos": []}}),
+        );
+        server.mock(
+            "GET",
+            "/api/v1/trips/{id}.json",
+            200,
+            serde_json::json!({"trip": {"id": 201, "photos": [{"id": 3, "url": "/photos/b.jpg"}]}}),
+        );
+        server.mock_raw("GET", "/photos/b.jpg", 200, "same image");
+
+        let report = client.sync_runner(&dir).run().unwrap();
+        assert_eq!(report.routes_written, 1);
+        assert_eq!(report.trips_written, 1);
+        assert!(dir.join("photos").join(&shared).exists());
+        let state = client.mirror(&dir).load_state().unwrap();
+        assert_eq!(state.photos.len(), 1);
+        assert_eq!(state.photos[&3].file, shared);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_photos() {
+        let client = RideWithGpsClient::new("https://ridewithgps.com", "key", None);
+        let dir = std::env::temp_dir().join(format!("rwgps-photo-migrate-{}", std::process::id()));
+        let route = serde_json::json!({"id": 5, "photos": [
+            {"id": 1, "url": "https://photos.example.com/1.png"},
+            {"id": 2, "url": "https://photos.example.com/2.jpg"}
+        ]});
+        write_file(&dir.join("routes/5.json"), route.to_string().as_bytes()).unwrap();
+        write_file(&dir.join("photos/1.png"), b"image").unwrap();
+        write_file(
+            &dir.join(STATE_FILE),
+            br#"{"last_sync": null, "routes": {"5": "2025-01-10"}}"#,
+        )
+        .unwrap();
+
+        let mirror = client.mirror(&dir);
+        let state = mirror.load_state().unwrap();
+        assert_eq!(state.version, STATE_VERSION);
+        let file = &state.photos[&1].file;
+        assert_eq!(
+            std::fs::read(dir.join("photos").join(file)).unwrap(),
+            b"image"
+        );
+        assert!(!dir.join("photos/1.png").exists());
+        assert_eq!(state.photos.len(), 1);
+        assert!(dir.join("photos/index.json").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_export_changed_gpx() {
+        use crate::testing::FakeServer;
+
+        let server = FakeServer::start();
+        let client = server.client();
+        let dir = std::env::temp_dir().join(format!("rwgps-gpx-mirror-{}", std::process::id()));
+        let export = std::env::temp_dir().join(format!("rwgps-gpx-export-{}", std::process::id()));
+        let mirror = client.mirror(&dir);
+        mirror.backup().unwrap();
+
+        let written = mirror.export_changed_gpx(&export).unwrap();
+        assert_eq!(written.len(), 4);
+        assert!(export.join("routes/101.gpx").exists());
+        assert!(mirror.export_changed_gpx(&export).unwrap().is_empty());
+
+        // A changed route is exported again, a removed one is deleted
+        let mut state = mirror.load_state().unwrap();
+        state
+            .routes
+            .insert(101, Some("2025-02-01T00:00:00+00:00".to_string()));
+        mirror.remove(&mut state, ItemType::Route, 102).unwrap();
+        mirror.save_state(&state).unwrap();
+        let written = mirror.export_changed_gpx(&export).unwrap();
+        assert_eq!(written, vec![export.join("routes/101.gpx")]);
+        assert!(!export.join("routes/102.gpx").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        std::fs::remove_dir_all(&export).unwrap();
+    }
+}
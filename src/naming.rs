@@ -0,0 +1,333 @@
+//! Descriptive names for untitled trips
+
+use crate::{ActivityType, LatLng, Result, RideWithGpsClient, Trip};
+use chrono::{DateTime, Timelike};
+use serde::Serialize;
+
+/// Distance in meters the far point must be from the start before a trip
+/// is described by its direction or destination
+const MIN_FAR_DISTANCE: f64 = 2000.0;
+
+/// A reverse geocoder naming the place at a location
+///
+/// Used by [`suggest_trip_name_with`] to name the far point of a trip.
+/// Implement it on top of any geocoding service, or a local gazetteer.
+pub trait Geocoder {
+    /// Name of the place at `location`, such as a town or summit, if known
+    fn place_name(&self, location: &LatLng) -> Result<Option<String>>;
+}
+
+/// Whether a trip name counts as untitled
+///
+/// Missing and blank names count, as do names starting with "Untitled".
+pub fn is_untitled(name: Option<&str>) -> bool {
+    let name = name.unwrap_or_default().trim();
+    name.is_empty() || name.to_lowercase().starts_with("untitled")
+}
+
+/// Suggest a name for a trip, such as "Morning Ride north of Portland"
+///
+/// The time of day comes from the local time in `departed_at`, and the kind
+/// of activity from `activity_type`. If the point farthest from the start is
+/// at least 2 km away, the name gives its compass direction from the start
+/// locality; otherwise the trip is "in" that locality. The administrative
+/// area stands in for a missing locality. The far point is taken from the
+/// track points if the trip has them, and is the last point otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// use ridewithgps_client::{suggest_trip_name, Trip};
+///
+/// let trip: Trip = serde_json::from_str(
+///     r#"{
+///         "id": 1,
+///         "departed_at": "2025-06-01T07:30:00-07:00",
+///         "locality": "Mill Valley",
+///         "first_lat": 37.906, "first_lng": -122.545,
+///         "last_lat": 37.929, "last_lng": -122.578
+///     }"#,
+/// )
+/// .unwrap();
+/// assert_eq!(suggest_trip_name(&trip), "Morning Ride northwest of Mill Valley");
+/// ```
+pub fn suggest_trip_name(trip: &Trip) -> String {
+    describe(trip, None)
+}
+
+/// Suggest a name for a trip, naming its far point with a geocoder
+///
+/// Like [`suggest_trip_name`], but a far point at least 2 km from the start
+/// is named by `geocoder`, as in "Morning Ride to Mt. Tam". Falls back to
+/// the compass direction if the geocoder knows no name for it, or returns
+/// the start locality.
+pub fn suggest_trip_name_with(trip: &Trip, geocoder: &(impl Geocoder + ?Sized)) -> Result<String> {
+    let destination = match far_point(trip) {
+        Some(point) => geocoder
+            .place_name(&point)?
+            .filter(|name| Some(name.as_str()) != locality(trip)),
+        None => None,
+    };
+    Ok(describe(trip, destination.as_deref()))
+}
+
+fn describe(trip: &Trip, destination: Option<&str>) -> String {
+    let mut name = match time_of_day(trip) {
+        Some(time) => format!("{} {}", time, activity_noun(trip.activity_type.as_ref())),
+        None => activity_noun(trip.activity_type.as_ref()).to_string(),
+    };
+
+    let direction = start_point(trip)
+        .zip(far_point(trip))
+        .map(|(start, far)| compass_point(start.bearing_to(&far)));
+    match (destination, direction, locality(trip)) {
+        (Some(destination), Some(_), _) => name += &format!(" to {}", destination),
+        (_, Some(direction), Some(locality)) => name += &format!(" {} of {}", direction, locality),
+        (_, Some(direction), None) => name += &format!(" heading {}", direction),
+        (_, None, Some(locality)) => name += &format!(" in {}", locality),
+        (_, None, None) => {}
+    }
+    name
+}
+
+/// The first located point of the trip
+fn start_point(trip: &Trip) -> Option<LatLng> {
+    let first = trip.track_points.iter().flatten().find_map(|p| p.coord());
+    first.or_else(|| Some(LatLng::new(trip.first_lat?, trip.first_lng?)))
+}
+
+/// The point of the trip farthest from its start, if far enough to mention
+fn far_point(trip: &Trip) -> Option<LatLng> {
+    let start = start_point(trip)?;
+    let last = || Some(LatLng::new(trip.last_lat?, trip.last_lng?));
+    let far = trip
+        .track_points
+        .iter()
+        .flatten()
+        .filter_map(|p| p.coord())
+        .max_by(|a, b| start.distance_to(a).total_cmp(&start.distance_to(b)))
+        .or_else(last)?;
+    (start.distance_to(&far) >= MIN_FAR_DISTANCE).then_some(far)
+}
+
+fn locality(trip: &Trip) -> Option<&str> {
+    [&trip.locality, &trip.administrative_area]
+        .into_iter()
+        .filter_map(|s| s.as_deref().map(str::trim))
+        .find(|s| !s.is_empty())
+}
+
+/// "Morning", "Lunch", ... from the local hour of departure
+fn time_of_day(trip: &Trip) -> Option<&'static str> {
+    let departed = DateTime::parse_from_rfc3339(trip.departed_at.as_deref()?).ok()?;
+    Some(match departed.hour() {
+        4..=10 => "Morning",
+        11..=13 => "Lunch",
+        14..=17 => "Afternoon",
+        18..=21 => "Evening",
+        _ => "Night",
+    })
+}
+
+fn activity_noun(activity: Option<&ActivityType>) -> &'static str {
+    match activity {
+        Some(ActivityType::GravelCycling) => "Gravel Ride",
+        Some(ActivityType::MountainBiking) => "Mountain Bike Ride",
+        Some(ActivityType::Running) => "Run",
+        Some(ActivityType::Walking) => "Walk",
+        Some(ActivityType::Hiking) => "Hike",
+        _ => "Ride",
+    }
+}
+
+/// Eight-point compass direction of a bearing in degrees
+fn compass_point(bearing: f64) -> &'static str {
+    const POINTS: [&str; 8] = [
+        "north",
+        "northeast",
+        "east",
+        "southeast",
+        "south",
+        "southwest",
+        "west",
+        "northwest",
+    ];
+    POINTS[((bearing.rem_euclid(360.0) + 22.5) / 45.0) as usize % 8]
+}
+
+impl RideWithGpsClient {
+    /// Rename a trip
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The trip ID
+    /// * `name` - The new name
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let trip = client.rename_trip(12345, "Sunday club ride").unwrap();
+    /// ```
+    pub fn rename_trip(&self, id: u64, name: &str) -> Result<Trip> {
+        #[derive(Serialize)]
+        struct RenameRequest<'a> {
+            name: &'a str,
+        }
+
+        self.put_wrapped(
+            &format!("/api/v1/trips/{}.json", id),
+            "trip",
+            &RenameRequest { name },
+        )
+    }
+
+    /// Give every untitled trip a suggested name
+    ///
+    /// Trips are untitled as decided by [`is_untitled`]. Each one is fetched
+    /// with its track points and renamed with [`suggest_trip_name`], or
+    /// [`suggest_trip_name_with`] if a geocoder is given. Returns the renamed
+    /// trips.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// for trip in client.rename_untitled_trips(None).unwrap() {
+    ///     println!("{}: {}", trip.id, trip.name.unwrap_or_default());
+    /// }
+    /// ```
+    pub fn rename_untitled_trips(&self, geocoder: Option<&dyn Geocoder>) -> Result<Vec<Trip>> {
+        let mut renamed = Vec::new();
+        for trip in self.paginate::<Trip>(None)? {
+            let trip = trip?;
+            if !is_untitled(trip.name.as_deref()) {
+                continue;
+            }
+            let trip = self.get_trip(trip.id)?;
+            let name = match geocoder {
+                Some(geocoder) => suggest_trip_name_with(&trip, geocoder)?,
+                None => suggest_trip_name(&trip),
+            };
+            renamed.push(self.rename_trip(trip.id, &name)?);
+        }
+        Ok(renamed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Summits;
+
+    impl Geocoder for Summits {
+        fn place_name(&self, location: &LatLng) -> Result<Option<String>> {
+            Ok((location.lat > 45.52).then(|| "Council Crest".to_string()))
+        }
+    }
+
+    fn trip(json: serde_json::Value) -> Trip {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_suggest_trip_name() {
+        let out_and_back = trip(serde_json::json!({
+            "id": 1,
+            "departed_at": "2025-01-03T18:15:00-08:00",
+            "activity_type": "running",
+            "administrative_area": "Oregon",
+            "track_points": [
+                {"x": -122.6, "y": 45.5},
+                {"x": -122.6, "y": 45.53},
+                {"x": -122.6, "y": 45.5}
+            ]
+        }));
+        assert_eq!(
+            suggest_trip_name(&out_and_back),
+            "Evening Run north of Oregon"
+        );
+        assert_eq!(
+            suggest_trip_name_with(&out_and_back, &Summits).unwrap(),
+            "Evening Run to Council Crest"
+        );
+
+        let short = trip(serde_json::json!({
+            "id": 2,
+            "departed_at": "2025-01-03T12:00:00Z",
+            "locality": "Portland",
+            "first_lat": 45.5, "first_lng": -122.6,
+            "last_lat": 45.501, "last_lng": -122.6
+        }));
+        assert_eq!(suggest_trip_name(&short), "Lunch Ride in Portland");
+        assert_eq!(
+            suggest_trip_name_with(&short, &Summits).unwrap(),
+            "Lunch Ride in Portland"
+        );
+
+        assert_eq!(
+            suggest_trip_name(&trip(serde_json::json!({"id": 3}))),
+            "Ride"
+        );
+    }
+
+    #[test]
+    fn test_is_untitled() {
+        assert!(is_untitled(None));
+        assert!(is_untitled(Some("  ")));
+        assert!(is_untitled(Some("Untitled trip")));
+        assert!(!is_untitled(Some("Morning Ride")));
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_rename_untitled_trips() {
+        use crate::testing::FakeServer;
+
+        let server = FakeServer::start();
+        let client = server.client();
+        server.mock(
+            "GET",
+            "/api/v1/trips.json",
+            200,
+            serde_json::json!({
+                "results": [{"id": 1, "name": "Untitled"}, {"id": 2, "name": "Commute"}],
+                "record_count": 2,
+                "page_count": 1
+            }),
+        );
+        server.mock(
+            "PUT",
+            "/api/v1/trips/{id}.json",
+            200,
+            serde_json::json!({"trip": {"id": 1, "name": "Renamed"}}),
+        );
+
+        let renamed = client.rename_untitled_trips(None).unwrap();
+        assert_eq!(renamed.len(), 1);
+
+        let put = server
+            .requests()
+            .into_iter()
+            .find(|r| r.method == "PUT")
+            .unwrap();
+        assert_eq!(put.path, "/api/v1/trips/1.json");
+        let body: serde_json::Value = serde_json::from_slice(&put.body).unwrap();
+        assert_eq!(body["name"], "Morning Ride heading northeast");
+    }
+}
@@ -0,0 +1,38 @@
+//! Per-call request settings
+
+use std::time::Duration;
+
+/// Settings that override the client's defaults for a single call
+///
+/// Passed to the `_with` variants of client methods, such as
+/// [`get_trip_with`](crate::RideWithGpsClient::get_trip_with).
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ridewithgps_client::{RequestOptions, RideWithGpsClient};
+/// use std::time::Duration;
+///
+/// let client = RideWithGpsClient::new(
+///     "https://ridewithgps.com",
+///     "your-api-key",
+///     Some("your-auth-token")
+/// );
+///
+/// let options = RequestOptions::new().timeout(Duration::from_secs(120));
+/// let trip = client.get_trip_with(12345, &options).unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct RequestOptions {
+    /// Timeout for each attempt, instead of the HTTP client's default
+    pub timeout: Option<Duration>,
+
+    /// Whether to apply the client's retry policy (defaults to yes)
+    pub retry: Option<bool>,
+}
+
+setters!(RequestOptions {
+    timeout: Duration,
+    retry: bool,
+});
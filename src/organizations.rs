@@ -0,0 +1,184 @@
+//! Organization chapters (sub-clubs)
+//!
+//! Large clubs can partition their members and routes into chapters, for
+//! example one per city in a statewide club.
+
+use crate::{
+    with_query, ListMembersParams, ListRoutesParams, Member, PaginatedResponse, Result,
+    RideWithGpsClient, Route,
+};
+use serde::{Deserialize, Serialize};
+
+/// A chapter of an organization
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Chapter {
+    /// Chapter ID
+    pub id: u64,
+
+    /// ID of the parent organization
+    pub organization_id: Option<u64>,
+
+    /// Chapter name
+    pub name: Option<String>,
+
+    /// Description
+    pub description: Option<String>,
+
+    /// Locality, such as a city or region
+    pub locality: Option<String>,
+
+    /// Number of members in the chapter
+    pub members_count: Option<u32>,
+
+    /// Number of routes in the chapter
+    pub routes_count: Option<u32>,
+
+    /// Created timestamp
+    pub created_at: Option<String>,
+
+    /// Updated timestamp
+    pub updated_at: Option<String>,
+}
+
+impl RideWithGpsClient {
+    /// List the chapters of an organization
+    ///
+    /// # Arguments
+    ///
+    /// * `org_id` - The organization ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     None
+    /// );
+    ///
+    /// for chapter in client.list_chapters(42).unwrap() {
+    ///     println!("{:?} ({:?} members)", chapter.name, chapter.members_count);
+    /// }
+    /// ```
+    pub fn list_chapters(&self, org_id: u64) -> Result<Vec<Chapter>> {
+        self.get_wrapped(
+            &format!("/api/v1/organizations/{}/chapters.json", org_id),
+            "chapters",
+        )
+    }
+
+    /// List the members of a chapter
+    ///
+    /// Note: This endpoint is only available to organization accounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `org_id` - The organization ID
+    /// * `chapter_id` - The chapter ID
+    /// * `params` - Optional parameters for filtering and pagination
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let members = client.list_chapter_members(42, 7, None).unwrap();
+    /// println!("Found {} members", members.results.len());
+    /// ```
+    pub fn list_chapter_members(
+        &self,
+        org_id: u64,
+        chapter_id: u64,
+        params: Option<&ListMembersParams>,
+    ) -> Result<PaginatedResponse<Member>> {
+        let url = with_query(
+            format!(
+                "/api/v1/organizations/{}/chapters/{}/members.json",
+                org_id, chapter_id
+            ),
+            params,
+        )?;
+        self.get_path(&url)
+    }
+
+    /// List the routes of a chapter
+    ///
+    /// # Arguments
+    ///
+    /// * `org_id` - The organization ID
+    /// * `chapter_id` - The chapter ID
+    /// * `params` - Optional parameters for filtering and pagination
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{ListRoutesParams, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     None
+    /// );
+    ///
+    /// let params = ListRoutesParams::new().page_size(50);
+    /// let routes = client.list_chapter_routes(42, 7, Some(&params)).unwrap();
+    /// for route in routes.results {
+    ///     println!("{:?}", route.name);
+    /// }
+    /// ```
+    pub fn list_chapter_routes(
+        &self,
+        org_id: u64,
+        chapter_id: u64,
+        params: Option<&ListRoutesParams>,
+    ) -> Result<PaginatedResponse<Route>> {
+        let url = with_query(
+            format!(
+                "/api/v1/organizations/{}/chapters/{}/routes.json",
+                org_id, chapter_id
+            ),
+            params,
+        )?;
+        self.get_path(&url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chapter_deserialization() {
+        let json = r#"{"chapters": [{"id": 7, "organization_id": 42, "name": "Portland", "members_count": 310}]}"#;
+
+        #[derive(Deserialize)]
+        struct ChaptersWrapper {
+            chapters: Vec<Chapter>,
+        }
+
+        let wrapper: ChaptersWrapper = serde_json::from_str(json).unwrap();
+        assert_eq!(wrapper.chapters[0].name.as_deref(), Some("Portland"));
+        assert_eq!(wrapper.chapters[0].members_count, Some(310));
+    }
+
+    #[test]
+    fn test_with_query() {
+        let params = ListMembersParams::new().role("admin");
+        assert_eq!(
+            with_query("/members.json".to_string(), Some(&params)).unwrap(),
+            "/members.json?role=admin"
+        );
+        assert_eq!(
+            with_query::<ListMembersParams>("/members.json".to_string(), None).unwrap(),
+            "/members.json"
+        );
+    }
+}
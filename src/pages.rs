@@ -0,0 +1,576 @@
+//! Lazy iteration over every page of a listing, with resumable cursors
+
+use crate::{
+    ListRoutesParams, ListTripsParams, PaginatedResponse, RequestOptions, Resource, Result,
+    RideWithGpsClient, Route, Trip,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Position of a [`Paginator`] in a listing
+///
+/// Points at the next item the paginator will yield. Serialize it to
+/// checkpoint a long export, and pass it to [`RideWithGpsClient::resume`] to
+/// continue exactly where the export left off.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PageCursor {
+    /// Path of the listing, including any filter query but not the page
+    pub path: String,
+
+    /// Page containing the next item
+    pub page: u32,
+
+    /// Number of items of `page` already yielded
+    pub offset: usize,
+
+    /// Whether the listing has been exhausted
+    pub finished: bool,
+}
+
+impl PageCursor {
+    fn page_path(&self) -> String {
+        let separator = if self.path.contains('?') { '&' } else { '?' };
+        format!("{}{}page={}", self.path, separator, self.page)
+    }
+}
+
+/// Items fetched by [`Paginator::fetch_all_within`] before it stopped
+#[derive(Debug, Clone)]
+pub struct PartialResults<T> {
+    /// The items fetched, in listing order
+    pub items: Vec<T>,
+
+    /// Where to resume with [`RideWithGpsClient::resume`] to fetch the rest
+    pub cursor: PageCursor,
+
+    /// Whether the whole listing was fetched
+    pub complete: bool,
+}
+
+/// Starts fetching a page on a background thread
+type SpawnFetch<T> =
+    fn(RideWithGpsClient, String, RequestOptions) -> JoinHandle<Result<PaginatedResponse<T>>>;
+
+fn spawn_fetch<T: DeserializeOwned + Send + 'static>(
+    client: RideWithGpsClient,
+    path: String,
+    options: RequestOptions,
+) -> JoinHandle<Result<PaginatedResponse<T>>> {
+    std::thread::spawn(move || client.get_path_with(&path, &options))
+}
+
+/// Iterator over the items of every page of a listing
+///
+/// Pages are fetched as the iterator advances. A failed request is yielded
+/// as an error without moving the cursor, so calling `next` again retries it.
+pub struct Paginator<'a, T> {
+    client: &'a RideWithGpsClient,
+    cursor: PageCursor,
+    buffer: VecDeque<T>,
+    last_page: bool,
+    deadline: Option<Instant>,
+    spawn_fetch: Option<SpawnFetch<T>>,
+    prefetched: Option<(u32, JoinHandle<Result<PaginatedResponse<T>>>)>,
+}
+
+impl<T: DeserializeOwned + Send + 'static> Paginator<'_, T> {
+    /// Fetch the next page in the background while the current one is
+    /// being consumed
+    ///
+    /// This hides the latency of page requests when each item takes a while
+    /// to process. At most one page is fetched ahead. A prefetch that fails
+    /// is yielded as an error when its page is reached, and retried by the
+    /// next call to `next` as usual.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{RideWithGpsClient, Trip};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// for trip in client.paginate::<Trip>(None).unwrap().prefetch() {
+    ///     let trip = client.get_trip(trip.unwrap().id).unwrap();
+    ///     println!("{}: {} points", trip.id, trip.track_points.unwrap_or_default().len());
+    /// }
+    /// ```
+    pub fn prefetch(mut self) -> Self {
+        self.spawn_fetch = Some(spawn_fetch::<T>);
+        self
+    }
+}
+
+impl<T: DeserializeOwned> Paginator<'_, T> {
+    /// The position of the next item to be yielded
+    pub fn cursor(&self) -> &PageCursor {
+        &self.cursor
+    }
+
+    /// Stop iterating once `deadline` has passed
+    ///
+    /// After the deadline the iterator ends, leaving the cursor at the next
+    /// item so the listing can be resumed later. Page requests are given no
+    /// longer than the time left as their timeout.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Stop iterating once `budget` has passed from now; see
+    /// [`deadline`](Self::deadline)
+    pub fn within(self, budget: Duration) -> Self {
+        self.deadline(Instant::now() + budget)
+    }
+
+    /// Whether the iterator stopped, or will stop, because its deadline
+    /// passed before the listing was exhausted
+    pub fn deadline_reached(&self) -> bool {
+        !self.cursor.finished && self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+
+    /// Collect items for at most `budget`, then stop cleanly
+    ///
+    /// Returns the items fetched so far, and a cursor to resume from in a
+    /// later run. A page request that fails because it ran into the
+    /// deadline ends the collection like the deadline itself; other
+    /// failures are returned as errors.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{PageCursor, RideWithGpsClient, Trip};
+    /// use std::time::Duration;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let trips = match std::fs::read_to_string("trips.cursor") {
+    ///     Ok(saved) => client.resume::<Trip>(serde_json::from_str(&saved).unwrap()),
+    ///     Err(_) => client.paginate::<Trip>(None).unwrap(),
+    /// };
+    /// let fetched = trips.fetch_all_within(Duration::from_secs(600)).unwrap();
+    /// println!("Fetched {} trips", fetched.items.len());
+    /// if !fetched.complete {
+    ///     let checkpoint = serde_json::to_string(&fetched.cursor).unwrap();
+    ///     std::fs::write("trips.cursor", checkpoint).unwrap();
+    /// }
+    /// ```
+    pub fn fetch_all_within(self, budget: Duration) -> Result<PartialResults<T>> {
+        let mut pages = self.within(budget);
+        let mut items = Vec::new();
+        loop {
+            match pages.next() {
+                Some(Ok(item)) => items.push(item),
+                Some(Err(_)) if pages.deadline_reached() => break,
+                Some(Err(e)) => return Err(e),
+                None => break,
+            }
+        }
+        Ok(PartialResults {
+            items,
+            complete: pages.cursor.finished,
+            cursor: pages.cursor,
+        })
+    }
+
+    /// Move past the item just yielded from the buffer
+    fn advance(&mut self) {
+        self.cursor.offset += 1;
+        if self.buffer.is_empty() {
+            self.next_page();
+        }
+    }
+
+    fn next_page(&mut self) {
+        if self.last_page {
+            self.cursor.finished = true;
+        } else {
+            self.cursor.page += 1;
+            self.cursor.offset = 0;
+        }
+    }
+}
+
+impl<T: DeserializeOwned> Iterator for Paginator<'_, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        if self.deadline_reached() {
+            return None;
+        }
+        while self.buffer.is_empty() {
+            if self.cursor.finished {
+                return None;
+            }
+            let options = RequestOptions {
+                timeout: self
+                    .deadline
+                    .map(|d| d.saturating_duration_since(Instant::now())),
+                ..Default::default()
+            };
+            let fetched = match self.prefetched.take() {
+                Some((page, handle)) if page == self.cursor.page => handle
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e)),
+                _ => self
+                    .client
+                    .get_path_with(&self.cursor.page_path(), &options),
+            };
+            let response = match fetched {
+                Ok(response) => response,
+                Err(e) => return Some(Err(e)),
+            };
+            self.last_page =
+                response.results.is_empty() || response.pagination.next_page_url.is_none();
+            if let Some(spawn_fetch) = self.spawn_fetch.filter(|_| !self.last_page) {
+                let next = PageCursor {
+                    page: self.cursor.page + 1,
+                    ..self.cursor.clone()
+                };
+                let handle = spawn_fetch(self.client.clone(), next.page_path(), options);
+                self.prefetched = Some((next.page, handle));
+            }
+            self.buffer = response
+                .results
+                .into_iter()
+                .skip(self.cursor.offset)
+                .collect();
+            if self.buffer.is_empty() {
+                self.next_page();
+            }
+        }
+
+        let item = self.buffer.pop_front()?;
+        self.advance();
+        Some(Ok(item))
+    }
+}
+
+impl RideWithGpsClient {
+    /// Iterate over every resource of a listing, fetching pages lazily
+    ///
+    /// Iteration starts at the page set in `params`, or the first page.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Optional parameters for filtering and pagination
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{RideWithGpsClient, Trip};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let mut trips = client.paginate::<Trip>(None).unwrap();
+    /// while let Some(trip) = trips.next() {
+    ///     println!("{}", trip.unwrap());
+    ///     let checkpoint = serde_json::to_string(trips.cursor()).unwrap();
+    ///     std::fs::write("export.cursor", checkpoint).unwrap();
+    /// }
+    /// ```
+    pub fn paginate<R: Resource>(
+        &self,
+        params: Option<&R::ListParams>,
+    ) -> Result<Paginator<'_, R>> {
+        let mut path = format!("{}.json", R::PATH);
+        let mut page = 1;
+
+        if let Some(params) = params {
+            let mut query = serde_json::to_value(params)?;
+            if let Some(obj) = query.as_object_mut() {
+                if let Some(start) = obj.remove("page").and_then(|p| p.as_u64()) {
+                    page = start as u32;
+                }
+                if !obj.is_empty() {
+                    let query_str = serde_urlencoded::to_string(&*obj).map_err(|e| {
+                        crate::Error::ApiError(format!("Failed to encode query: {}", e))
+                    })?;
+                    path.push('?');
+                    path.push_str(&query_str);
+                }
+            }
+        }
+
+        Ok(self.resume(PageCursor {
+            path,
+            page,
+            offset: 0,
+            finished: false,
+        }))
+    }
+
+    /// Call `visit` with every resource of a listing, one page at a time
+    ///
+    /// Only the current page is held in memory, so listings of any size can
+    /// be traversed. An error from fetching a page or from `visit` stops the
+    /// traversal and is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Optional parameters for filtering and pagination
+    /// * `visit` - Called with each resource, in listing order
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{RideWithGpsClient, Route};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let mut distance = 0.0;
+    /// client
+    ///     .for_each::<Route>(None, |route| {
+    ///         distance += route.distance.unwrap_or_default();
+    ///         Ok(())
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn for_each<R: Resource>(
+        &self,
+        params: Option<&R::ListParams>,
+        mut visit: impl FnMut(R) -> Result<()>,
+    ) -> Result<()> {
+        for item in self.paginate::<R>(params)? {
+            visit(item?)?;
+        }
+        Ok(())
+    }
+
+    /// Call `visit` with every trip, one page at a time
+    ///
+    /// See [`for_each`](Self::for_each).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{ListTripsParams, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let params = ListTripsParams::new().min_distance_km(100);
+    /// client
+    ///     .for_each_trip(Some(&params), |trip| {
+    ///         println!("{}", trip);
+    ///         Ok(())
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn for_each_trip(
+        &self,
+        params: Option<&ListTripsParams>,
+        visit: impl FnMut(Trip) -> Result<()>,
+    ) -> Result<()> {
+        self.for_each::<Trip>(params, visit)
+    }
+
+    /// Call `visit` with every route, one page at a time
+    ///
+    /// See [`for_each`](Self::for_each).
+    pub fn for_each_route(
+        &self,
+        params: Option<&ListRoutesParams>,
+        visit: impl FnMut(Route) -> Result<()>,
+    ) -> Result<()> {
+        self.for_each::<Route>(params, visit)
+    }
+
+    /// Continue iterating over a listing from a saved cursor
+    ///
+    /// # Arguments
+    ///
+    /// * `cursor` - A cursor from [`Paginator::cursor`]
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{PageCursor, RideWithGpsClient, Trip};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let saved = std::fs::read_to_string("export.cursor").unwrap();
+    /// let cursor: PageCursor = serde_json::from_str(&saved).unwrap();
+    /// for trip in client.resume::<Trip>(cursor) {
+    ///     println!("{}", trip.unwrap());
+    /// }
+    /// ```
+    pub fn resume<T: DeserializeOwned>(&self, cursor: PageCursor) -> Paginator<'_, T> {
+        Paginator {
+            client: self,
+            cursor,
+            buffer: VecDeque::new(),
+            last_page: false,
+            deadline: None,
+            spawn_fetch: None,
+            prefetched: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_path() {
+        let mut cursor = PageCursor {
+            path: "/api/v1/routes.json".to_string(),
+            page: 2,
+            offset: 0,
+            finished: false,
+        };
+        assert_eq!(cursor.page_path(), "/api/v1/routes.json?page=2");
+
+        cursor.path.push_str("?name=loop");
+        assert_eq!(cursor.page_path(), "/api/v1/routes.json?name=loop&page=2");
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_resume_mid_page() {
+        use crate::testing::FakeServer;
+        use crate::{ListRoutesParams, Route};
+
+        let server = FakeServer::start();
+        let client = server.client();
+
+        let params = ListRoutesParams::new().name("loop");
+        let mut routes = client.paginate::<Route>(Some(&params)).unwrap();
+        assert_eq!(routes.next().unwrap().unwrap().id, 101);
+        let saved = serde_json::to_string(routes.cursor()).unwrap();
+
+        let cursor: PageCursor = serde_json::from_str(&saved).unwrap();
+        assert_eq!(cursor.offset, 1);
+        let rest: Vec<u64> = client
+            .resume::<Route>(cursor)
+            .map(|r| r.unwrap().id)
+            .collect();
+        assert_eq!(rest, vec![102]);
+        assert_eq!(
+            server.requests().last().unwrap().path,
+            "/api/v1/routes.json?name=loop&page=1"
+        );
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_fetch_all_within() {
+        use crate::testing::FakeServer;
+        use crate::Route;
+
+        let server = FakeServer::start();
+        let client = server.client();
+
+        let fetched = client
+            .paginate::<Route>(None)
+            .unwrap()
+            .fetch_all_within(Duration::from_secs(60))
+            .unwrap();
+        assert!(fetched.complete);
+        assert_eq!(fetched.items.len(), 2);
+
+        let mut routes = client.paginate::<Route>(None).unwrap();
+        assert_eq!(routes.next().unwrap().unwrap().id, 101);
+        let fetched = routes.fetch_all_within(Duration::ZERO).unwrap();
+        assert!(!fetched.complete);
+        assert!(fetched.items.is_empty());
+        assert_eq!(fetched.cursor.offset, 1);
+
+        let rest: Vec<u64> = client
+            .resume::<Route>(fetched.cursor)
+            .map(|r| r.unwrap().id)
+            .collect();
+        assert_eq!(rest, vec![102]);
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_for_each() {
+        use crate::testing::FakeServer;
+
+        let server = FakeServer::start();
+        let client = server.client();
+
+        let mut ids = Vec::new();
+        client
+            .for_each_route(None, |route| {
+                ids.push(route.id);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(ids, vec![101, 102]);
+
+        let mut visited = 0;
+        let result = client.for_each_trip(None, |_| {
+            visited += 1;
+            Err(crate::Error::ApiError("stop".to_string()))
+        });
+        assert!(result.is_err());
+        assert_eq!(visited, 1);
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_prefetch() {
+        use crate::testing::FakeServer;
+        use crate::Route;
+
+        let server = FakeServer::start();
+        let client = server.client();
+        server.mock_once(
+            "GET",
+            "/api/v1/routes.json",
+            200,
+            serde_json::json!({
+                "results": [{"id": 1}, {"id": 2}],
+                "record_count": 4,
+                "page_count": 2,
+                "next_page_url": "/api/v1/routes.json?page=2"
+            }),
+        );
+
+        let mut routes = client.paginate::<Route>(None).unwrap().prefetch();
+        assert_eq!(routes.next().unwrap().unwrap().id, 1);
+        let prefetched = || {
+            server
+                .requests()
+                .iter()
+                .any(|r| r.path == "/api/v1/routes.json?page=2")
+        };
+        let started = Instant::now();
+        while !prefetched() && started.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(prefetched());
+
+        let rest: Vec<u64> = routes.map(|r| r.unwrap().id).collect();
+        assert_eq!(rest, vec![2, 101, 102]);
+        assert_eq!(server.requests().len(), 2);
+    }
+}
@@ -0,0 +1,294 @@
+//! Event participants and the registration fields they fill in
+
+use crate::{PaginatedResponse, Result, RideWithGpsClient};
+use serde::{Deserialize, Serialize};
+
+/// A custom question asked when registering for an event
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RegistrationField {
+    /// Field ID
+    pub id: u64,
+
+    /// Question shown to riders
+    pub label: Option<String>,
+
+    /// Kind of input, such as `"text"`, `"select"` or `"checkbox"`
+    pub field_type: Option<String>,
+
+    /// Whether an answer is required to register
+    pub required: Option<bool>,
+
+    /// Choices for select fields
+    pub options: Option<Vec<String>>,
+
+    /// Position of the field on the registration form
+    pub position: Option<u32>,
+}
+
+/// A participant's answer to a [`RegistrationField`]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RegistrationAnswer {
+    /// ID of the field answered
+    pub field_id: u64,
+
+    /// The answer, if one was given
+    pub value: Option<String>,
+}
+
+/// A rider registered for an event
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EventParticipant {
+    /// Participant ID
+    pub id: u64,
+
+    /// User ID of the rider
+    pub user_id: Option<u64>,
+
+    /// Rider's name
+    pub name: Option<String>,
+
+    /// Rider's email address (visible to organizers only)
+    pub email: Option<String>,
+
+    /// Registration status, such as `"registered"` or `"waitlisted"`
+    pub status: Option<String>,
+
+    /// Registration timestamp
+    pub registered_at: Option<String>,
+
+    /// ID of the [`Wave`](crate::Wave) the participant starts in
+    pub wave_id: Option<u64>,
+
+    /// Whether the participant has checked in on the day of the event
+    pub checked_in: Option<bool>,
+
+    /// Check-in timestamp
+    pub checked_in_at: Option<String>,
+
+    /// Answers to the event's registration fields
+    pub answers: Option<Vec<RegistrationAnswer>>,
+}
+
+impl EventParticipant {
+    /// The answer to a registration field, if one was given
+    pub fn answer(&self, field_id: u64) -> Option<&str> {
+        self.answers
+            .iter()
+            .flatten()
+            .find(|a| a.field_id == field_id)
+            .and_then(|a| a.value.as_deref())
+    }
+}
+
+/// Parameters for listing event participants
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
+pub struct ListParticipantsParams {
+    /// Filter by registration status
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+
+    /// Filter by whether participants have checked in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checked_in: Option<bool>,
+
+    /// Page number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+
+    /// Page size
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u32>,
+}
+
+setters!(ListParticipantsParams {
+    status: impl Into<String>,
+    checked_in: bool,
+    page: u32,
+    page_size: u32,
+});
+
+impl RideWithGpsClient {
+    /// List the custom registration fields of an event
+    ///
+    /// Note: This endpoint is only available to the event's organizers.
+    ///
+    /// # Arguments
+    ///
+    /// * `event_id` - The event ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// for field in client.list_registration_fields(12345).unwrap() {
+    ///     println!("{}: {:?}", field.id, field.label);
+    /// }
+    /// ```
+    pub fn list_registration_fields(&self, event_id: u64) -> Result<Vec<RegistrationField>> {
+        self.get_wrapped(
+            &format!("/api/v1/events/{}/registration_fields.json", event_id),
+            "registration_fields",
+        )
+    }
+
+    /// List the participants of an event, with their registration answers
+    ///
+    /// Note: Email addresses and answers are only included for the event's
+    /// organizers.
+    ///
+    /// # Arguments
+    ///
+    /// * `event_id` - The event ID
+    /// * `params` - Optional parameters for filtering and pagination
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let fields = client.list_registration_fields(12345).unwrap();
+    /// let jersey = fields
+    ///     .iter()
+    ///     .find(|f| f.label.as_deref() == Some("Jersey size"))
+    ///     .unwrap();
+    ///
+    /// let participants = client.list_event_participants(12345, None).unwrap();
+    /// for participant in participants.results {
+    ///     println!("{:?}: {:?}", participant.name, participant.answer(jersey.id));
+    /// }
+    /// ```
+    pub fn list_event_participants(
+        &self,
+        event_id: u64,
+        params: Option<&ListParticipantsParams>,
+    ) -> Result<PaginatedResponse<EventParticipant>> {
+        let mut url = format!("/api/v1/events/{}/participants.json", event_id);
+
+        if let Some(params) = params {
+            let query = serde_json::to_value(params)?;
+            if let Some(obj) = query.as_object() {
+                if !obj.is_empty() {
+                    let query_str = serde_urlencoded::to_string(obj).map_err(|e| {
+                        crate::Error::ApiError(format!("Failed to encode query: {}", e))
+                    })?;
+                    url.push('?');
+                    url.push_str(&query_str);
+                }
+            }
+        }
+
+        self.get_path(&url)
+    }
+
+    /// Check in a participant on the day of the event
+    ///
+    /// Note: This endpoint is only available to the event's organizers.
+    ///
+    /// # Arguments
+    ///
+    /// * `event_id` - The event ID
+    /// * `participant_id` - The participant ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{ListParticipantsParams, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let participant = client.check_in_participant(12345, 678).unwrap();
+    /// println!("{:?} checked in at {:?}", participant.name, participant.checked_in_at);
+    ///
+    /// let params = ListParticipantsParams::new().checked_in(false);
+    /// let missing = client.list_event_participants(12345, Some(&params)).unwrap();
+    /// println!("{} still to arrive", missing.results.len());
+    /// ```
+    pub fn check_in_participant(
+        &self,
+        event_id: u64,
+        participant_id: u64,
+    ) -> Result<EventParticipant> {
+        self.post_wrapped(
+            &format!(
+                "/api/v1/events/{}/participants/{}/check_in.json",
+                event_id, participant_id
+            ),
+            "participant",
+            &serde_json::json!({}),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_participant_answers() {
+        let json = r#"{
+            "id": 1,
+            "name": "Jo",
+            "status": "registered",
+            "answers": [
+                {"field_id": 10, "value": "M"},
+                {"field_id": 11, "value": null}
+            ]
+        }"#;
+
+        let participant: EventParticipant = serde_json::from_str(json).unwrap();
+        assert_eq!(participant.answer(10), Some("M"));
+        assert_eq!(participant.answer(11), None);
+        assert_eq!(participant.answer(12), None);
+    }
+
+    #[test]
+    fn test_checked_in_filter() {
+        let params = ListParticipantsParams::new().checked_in(false);
+        let query = serde_urlencoded::to_string(serde_json::to_value(&params).unwrap()).unwrap();
+        assert_eq!(query, "checked_in=false");
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_check_in_participant() {
+        use crate::testing::FakeServer;
+
+        let server = FakeServer::start();
+        let client = server.client();
+        server.mock(
+            "POST",
+            "/api/v1/events/{id}/participants/678/check_in.json",
+            200,
+            serde_json::json!({"participant": {
+                "id": 678,
+                "checked_in": true,
+                "checked_in_at": "2025-06-01T07:45:00Z"
+            }}),
+        );
+
+        let participant = client.check_in_participant(12, 678).unwrap();
+        assert_eq!(participant.checked_in, Some(true));
+        assert_eq!(
+            server.requests().last().unwrap().path,
+            "/api/v1/events/12/participants/678/check_in.json"
+        );
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Note: These endpoints are only available to organization accounts.
 
+use crate::spatial::SegmentIndex;
 use crate::{PaginatedResponse, Result, RideWithGpsClient};
 use serde::{Deserialize, Serialize};
 
@@ -68,6 +69,7 @@ pub struct PointOfInterest {
 
 /// Parameters for listing POIs
 #[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
 pub struct ListPointsOfInterestParams {
     /// Filter by POI name
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -86,8 +88,16 @@ pub struct ListPointsOfInterestParams {
     pub page_size: Option<u32>,
 }
 
+setters!(ListPointsOfInterestParams {
+    name: impl Into<String>,
+    poi_type: impl Into<String>,
+    page: u32,
+    page_size: u32,
+});
+
 /// Request to create or update a POI
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
 pub struct PointOfInterestRequest {
     /// POI name
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -126,6 +136,18 @@ pub struct PointOfInterestRequest {
     pub website: Option<String>,
 }
 
+setters!(PointOfInterestRequest {
+    name: impl Into<String>,
+    description: impl Into<String>,
+    latitude: f64,
+    longitude: f64,
+    poi_type: impl Into<String>,
+    icon: impl Into<String>,
+    address: impl Into<String>,
+    phone: impl Into<String>,
+    website: impl Into<String>,
+});
+
 impl RideWithGpsClient {
     /// List points of interest
     ///
@@ -168,7 +190,7 @@ impl RideWithGpsClient {
             }
         }
 
-        self.get(&url)
+        self.get_path(&url)
     }
 
     /// Create a new point of interest
@@ -190,17 +212,13 @@ impl RideWithGpsClient {
     ///     Some("your-auth-token")
     /// );
     ///
-    /// let poi_req = PointOfInterestRequest {
-    ///     name: Some("Coffee Shop".to_string()),
-    ///     description: Some("Great coffee stop".to_string()),
-    ///     latitude: Some(37.7749),
-    ///     longitude: Some(-122.4194),
-    ///     poi_type: Some("cafe".to_string()),
-    ///     icon: Some("coffee".to_string()),
-    ///     address: None,
-    ///     phone: None,
-    ///     website: None,
-    /// };
+    /// let poi_req = PointOfInterestRequest::new()
+    ///     .name("Coffee Shop")
+    ///     .description("Great coffee stop")
+    ///     .latitude(37.7749)
+    ///     .longitude(-122.4194)
+    ///     .poi_type("cafe")
+    ///     .icon("coffee");
     ///
     /// let poi = client.create_point_of_interest(&poi_req).unwrap();
     /// println!("Created POI: {}", poi.id);
@@ -209,13 +227,7 @@ impl RideWithGpsClient {
         &self,
         poi: &PointOfInterestRequest,
     ) -> Result<PointOfInterest> {
-        #[derive(Deserialize)]
-        struct PoiWrapper {
-            point_of_interest: PointOfInterest,
-        }
-
-        let wrapper: PoiWrapper = self.post("/api/v1/points_of_interest.json", poi)?;
-        Ok(wrapper.point_of_interest)
+        self.post_wrapped("/api/v1/points_of_interest.json", "point_of_interest", poi)
     }
 
     /// Get a specific point of interest by ID
@@ -241,13 +253,7 @@ impl RideWithGpsClient {
     /// println!("POI: {:?}", poi);
     /// ```
     pub fn get_point_of_interest(&self, id: u64) -> Result<PointOfInterest> {
-        #[derive(Deserialize)]
-        struct PoiWrapper {
-            point_of_interest: PointOfInterest,
-        }
-
-        let wrapper: PoiWrapper = self.get(&format!("/api/v1/points_of_interest/{}.json", id))?;
-        Ok(wrapper.point_of_interest)
+        self.get::<PointOfInterest>(id)
     }
 
     /// Update a point of interest
@@ -270,17 +276,7 @@ impl RideWithGpsClient {
     ///     Some("your-auth-token")
     /// );
     ///
-    /// let poi_req = PointOfInterestRequest {
-    ///     name: Some("Updated Coffee Shop".to_string()),
-    ///     description: None,
-    ///     latitude: None,
-    ///     longitude: None,
-    ///     poi_type: None,
-    ///     icon: None,
-    ///     address: None,
-    ///     phone: None,
-    ///     website: None,
-    /// };
+    /// let poi_req = PointOfInterestRequest::new().name("Updated Coffee Shop");
     ///
     /// let poi = client.update_point_of_interest(12345, &poi_req).unwrap();
     /// println!("Updated POI: {:?}", poi);
@@ -290,14 +286,11 @@ impl RideWithGpsClient {
         id: u64,
         poi: &PointOfInterestRequest,
     ) -> Result<PointOfInterest> {
-        #[derive(Deserialize)]
-        struct PoiWrapper {
-            point_of_interest: PointOfInterest,
-        }
-
-        let wrapper: PoiWrapper =
-            self.put(&format!("/api/v1/points_of_interest/{}.json", id), poi)?;
-        Ok(wrapper.point_of_interest)
+        self.put_wrapped(
+            &format!("/api/v1/points_of_interest/{}.json", id),
+            "point_of_interest",
+            poi,
+        )
     }
 
     /// Delete a point of interest
@@ -322,7 +315,7 @@ impl RideWithGpsClient {
     /// client.delete_point_of_interest(12345).unwrap();
     /// ```
     pub fn delete_point_of_interest(&self, id: u64) -> Result<()> {
-        self.delete(&format!("/api/v1/points_of_interest/{}.json", id))
+        self.delete::<PointOfInterest>(id)
     }
 
     /// Associate a point of interest with a route
@@ -395,7 +388,95 @@ impl RideWithGpsClient {
             "/api/v1/points_of_interest/{}/routes/{}.json",
             poi_id, route_id
         );
-        self.delete(&url)
+        self.delete_path(&url)
+    }
+
+    /// Associate all points of interest near a route with it
+    ///
+    /// Fetches the route geometry and every page of the organization's POIs,
+    /// keeps the POIs within `radius_m` meters of the route for which
+    /// `filter` returns `true`, and associates them with the route in a
+    /// single request. POIs without coordinates are skipped. Returns the
+    /// associated POIs.
+    ///
+    /// Note: This endpoint is only available to organization accounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `route_id` - The route ID
+    /// * `radius_m` - Maximum distance from the route in meters
+    /// * `filter` - Predicate selecting which nearby POIs to associate
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let pois = client
+    ///     .associate_pois_near_route(67890, 200.0, |poi| {
+    ///         poi.r#type.as_deref() == Some("cafe")
+    ///     })
+    ///     .unwrap();
+    /// println!("Associated {} cafés", pois.len());
+    /// ```
+    pub fn associate_pois_near_route(
+        &self,
+        route_id: u64,
+        radius_m: f64,
+        filter: impl Fn(&PointOfInterest) -> bool,
+    ) -> Result<Vec<PointOfInterest>> {
+        #[derive(Serialize)]
+        struct AssociationRequest {
+            point_of_interest_ids: Vec<u64>,
+        }
+
+        let route = self.get_route(route_id)?;
+        let coords: Vec<_> = route
+            .track_points
+            .iter()
+            .flatten()
+            .filter_map(|p| p.coord())
+            .collect();
+        if coords.is_empty() {
+            return Ok(Vec::new());
+        }
+        let index = SegmentIndex::new(&coords, radius_m);
+
+        let mut nearby = Vec::new();
+        let mut params = ListPointsOfInterestParams::new().page(1);
+        loop {
+            let response = self.list_points_of_interest(Some(&params))?;
+            let done = response.results.is_empty() || response.pagination.next_page_url.is_none();
+            nearby.extend(response.results.into_iter().filter(|poi| {
+                poi.coord().is_some_and(|c| index.within(&c, radius_m)) && filter(poi)
+            }));
+            if done {
+                break;
+            }
+            params.page = params.page.map(|page| page + 1);
+        }
+
+        if !nearby.is_empty() {
+            let url = self.base_url.join(&format!(
+                "/api/v1/routes/{}/points_of_interest.json",
+                route_id
+            ))?;
+            let body = serde_json::to_vec(&AssociationRequest {
+                point_of_interest_ids: nearby.iter().map(|poi| poi.id).collect(),
+            })?;
+            let response = self.execute(reqwest::Method::POST, url, Some(body), true)?;
+            if !matches!(response.status, 200 | 201 | 204) {
+                return Err(self.error_from_status(response.status, &response.text()));
+            }
+        }
+
+        Ok(nearby)
     }
 }
 
@@ -510,4 +591,82 @@ mod tests {
         assert_eq!(poi1.lng, Some(-122.5));
         assert_eq!(poi1.r#type.as_deref(), Some("water"));
     }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_associate_pois_near_route() {
+        use crate::testing::FakeServer;
+
+        let server = FakeServer::start();
+        let client = server.client();
+        // About 1.1 km due east along the equator
+        server.mock(
+            "GET",
+            "/api/v1/routes/{id}.json",
+            200,
+            serde_json::json!({"route": {"id": 7, "track_points": [
+                {"x": 0.0, "y": 0.0},
+                {"x": 0.01, "y": 0.0}
+            ]}}),
+        );
+        let page = |pois: serde_json::Value, next: Option<&str>| serde_json::json!({"results": pois, "next_page_url": next});
+        // Later mocks are used first
+        let path = "/api/v1/points_of_interest.json";
+        server.mock_once(
+            "GET",
+            path,
+            200,
+            page(
+                serde_json::json!([{"id": 3, "lat": 0.0003, "lng": 0.005, "type": "cafe"}]),
+                None,
+            ),
+        );
+        server.mock_once(
+            "GET",
+            path,
+            200,
+            page(
+                serde_json::json!([
+                    {"id": 1, "lat": 0.0001, "lng": 0.002, "type": "cafe"},
+                    {"id": 2, "lat": 0.01, "lng": 0.005, "type": "cafe"},
+                    {"id": 4, "lat": 0.0, "lng": 0.004, "type": "toilet"},
+                    {"id": 5, "type": "cafe"}
+                ]),
+                Some("/api/v1/points_of_interest.json?page=2"),
+            ),
+        );
+        server.mock_raw(
+            "POST",
+            "/api/v1/routes/{id}/points_of_interest.json",
+            204,
+            "",
+        );
+
+        let pois = client
+            .associate_pois_near_route(7, 50.0, |poi| poi.r#type.as_deref() == Some("cafe"))
+            .unwrap();
+        let ids: Vec<u64> = pois.iter().map(|poi| poi.id).collect();
+        assert_eq!(ids, [1, 3]);
+
+        let requests = server.requests();
+        let paths: Vec<&str> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/api/v1/routes/7.json",
+                "/api/v1/points_of_interest.json?page=1",
+                "/api/v1/points_of_interest.json?page=2",
+                "/api/v1/routes/7/points_of_interest.json"
+            ]
+        );
+        let body: serde_json::Value = serde_json::from_slice(&requests[3].body).unwrap();
+        assert_eq!(body, serde_json::json!({"point_of_interest_ids": [1, 3]}));
+
+        // Nothing to associate, so nothing is posted
+        server.mock("GET", path, 200, page(serde_json::json!([]), None));
+        let pois = client.associate_pois_near_route(7, 50.0, |_| true).unwrap();
+        assert!(pois.is_empty());
+        assert_eq!(server.requests().len(), 6);
+        assert_eq!(server.requests().last().unwrap().method, "GET");
+    }
 }
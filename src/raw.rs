@@ -0,0 +1,227 @@
+//! Untyped responses, arbitrary requests and response metadata
+//!
+//! For debugging, and for endpoints and fields the typed models do not cover
+//! yet.
+
+use crate::resource::Wrapped;
+use crate::{Resource, Result, RideWithGpsClient};
+use reqwest::header::HeaderMap;
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::ops::Deref;
+use std::time::{Duration, Instant};
+
+/// Metadata of the HTTP response a result was decoded from
+#[derive(Debug, Clone)]
+pub struct ResponseMeta {
+    /// HTTP status code
+    pub status: u16,
+
+    /// Response headers
+    pub headers: HeaderMap,
+
+    /// Time from sending the request until the response body was read,
+    /// including any retries
+    pub elapsed: Duration,
+}
+
+/// A result together with the metadata of its response
+///
+/// Dereferences to the result, so it can mostly be used in its place.
+#[derive(Debug, Clone)]
+pub struct WithMeta<T> {
+    /// The decoded result
+    pub value: T,
+
+    /// Metadata of the response
+    pub meta: ResponseMeta,
+}
+
+impl<T> WithMeta<T> {
+    /// Discard the metadata
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for WithMeta<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl RideWithGpsClient {
+    /// Get any API path as untyped JSON
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path, such as `/api/v1/routes/12345.json`
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     None
+    /// );
+    ///
+    /// let json = client.get_raw("/api/v1/routes/12345.json").unwrap();
+    /// println!("{}", json["route"]["name"]);
+    /// ```
+    pub fn get_raw(&self, path: &str) -> Result<serde_json::Value> {
+        self.get_raw_with_meta(path).map(WithMeta::into_inner)
+    }
+
+    /// Get any API path as untyped JSON, with the response metadata
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path, such as `/api/v1/routes/12345.json`
+    pub fn get_raw_with_meta(&self, path: &str) -> Result<WithMeta<serde_json::Value>> {
+        self.get_path_with_meta(path)
+    }
+
+    /// Get a resource of any type by ID, with the response metadata
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The resource ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{RideWithGpsClient, Route};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     None
+    /// );
+    ///
+    /// let route = client.get_with_meta::<Route>(12345).unwrap();
+    /// println!("{:?} took {:?}", route.name, route.meta.elapsed);
+    /// ```
+    pub fn get_with_meta<R: Resource>(&self, id: impl Into<R::Id>) -> Result<WithMeta<R>> {
+        let wrapper: WithMeta<Wrapped<R>> =
+            self.get_path_with_meta(&format!("{}/{}.json", R::PATH, id.into()))?;
+        Ok(WithMeta {
+            value: wrapper.value.0,
+            meta: wrapper.meta,
+        })
+    }
+
+    /// Send a request to any API path
+    ///
+    /// Goes through the same authentication, retries, circuit breaker and
+    /// error mapping as the wrapped endpoints. The body, if any, is sent as
+    /// JSON. A response without a body decodes as JSON `null`, so use `()`
+    /// or an `Option` as `T` for endpoints answering `204 No Content`.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The HTTP method
+    /// * `path` - The path, such as `/api/v1/routes/12345.json`
+    /// * `body` - Optional request body
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{Method, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let badges: serde_json::Value = client
+    ///     .request(Method::GET, "/api/v1/users/current/badges.json", None::<&()>)
+    ///     .unwrap();
+    ///
+    /// let body = serde_json::json!({"route": {"name": "Renamed"}});
+    /// let _: serde_json::Value = client
+    ///     .request(Method::PATCH, "/api/v1/routes/12345.json", Some(&body))
+    ///     .unwrap();
+    ///
+    /// let () = client
+    ///     .request(Method::DELETE, "/api/v1/routes/12345.json", None::<&()>)
+    ///     .unwrap();
+    /// ```
+    pub fn request<T: DeserializeOwned, B: Serialize>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T> {
+        let url = self.base_url.join(path)?;
+        let body = body.map(serde_json::to_vec).transpose()?;
+        let response = self.execute(method, url, body, true)?;
+
+        match response.status {
+            200..=299 if response.body.iter().all(u8::is_ascii_whitespace) => {
+                self.decode(&response.endpoint, b"null")
+            }
+            200..=299 => self.decode(&response.endpoint, &response.body),
+            status => Err(self.error_from_status(status, &response.text())),
+        }
+    }
+
+    /// Execute a GET request, keeping the response metadata
+    fn get_path_with_meta<T: DeserializeOwned>(&self, path: &str) -> Result<WithMeta<T>> {
+        let url = self.base_url.join(path)?;
+        let start = Instant::now();
+        let response = self.execute(Method::GET, url, None, true)?;
+        let meta = ResponseMeta {
+            status: response.status,
+            headers: response.headers.clone(),
+            elapsed: start.elapsed(),
+        };
+
+        Ok(WithMeta {
+            value: self.handle_response(response)?,
+            meta,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "testing")]
+    use super::*;
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_get_raw_and_meta() {
+        use crate::testing::FakeServer;
+        use crate::Route;
+
+        let server = FakeServer::start();
+        let client = server.client();
+
+        let json = client.get_raw("/api/v1/routes/5.json").unwrap();
+        assert_eq!(json["route"]["id"], 5);
+
+        let route = client.get_with_meta::<Route>(7).unwrap();
+        assert_eq!(route.id, 7);
+        assert_eq!(route.meta.status, 200);
+
+        let () = client
+            .request(Method::DELETE, "/api/v1/routes/7.json", None::<&()>)
+            .unwrap();
+        let body = serde_json::json!({"route": {"name": "Renamed"}});
+        let updated: serde_json::Value = client
+            .request(Method::PUT, "/api/v1/routes/7.json", Some(&body))
+            .unwrap();
+        assert_eq!(updated["route"]["id"], 7);
+        assert_eq!(
+            server.requests().last().unwrap().body,
+            body.to_string().as_bytes()
+        );
+    }
+}
@@ -0,0 +1,336 @@
+//! Recording and replaying API interactions for tests
+
+use crate::{Error, RawResponse, Result, RideWithGpsClient};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+/// JSON fields whose values are always replaced when recording
+const REDACTED_FIELDS: &[&str] = &["password", "auth_token", "api_key", "email"];
+
+const REDACTED: &str = "REDACTED";
+
+/// A request or response body as stored in a cassette
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Body {
+    Json(Value),
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl Body {
+    fn new(data: &[u8], secrets: &[Option<&str>]) -> Self {
+        if let Ok(mut value) = serde_json::from_slice::<Value>(data) {
+            redact_value(&mut value, secrets);
+            Body::Json(value)
+        } else if let Ok(text) = std::str::from_utf8(data) {
+            Body::Text(redact_str(text, secrets))
+        } else {
+            Body::Bytes(data.to_vec())
+        }
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        match self {
+            Body::Json(value) => serde_json::to_vec(&value).unwrap_or_default(),
+            Body::Text(text) => text.into_bytes(),
+            Body::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct RecordedRequest {
+    method: String,
+    url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<Body>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    body: Body,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug)]
+enum Mode {
+    Record,
+    Replay { used: Vec<bool> },
+}
+
+/// A file of recorded API interactions
+///
+/// In record mode, every request the client makes is sent to the API as
+/// usual and the request and response are appended to the cassette file.
+/// API keys, auth tokens, and fields such as `password` and `email` are
+/// redacted before anything is written. In replay mode, no requests are
+/// sent; each request is answered with the first unused recorded
+/// interaction with the same method, URL and body.
+///
+/// Attach a cassette with [`RideWithGpsClient::with_cassette`].
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    state: Mutex<(Mode, Vec<Interaction>)>,
+}
+
+impl Cassette {
+    /// Record interactions to a new cassette file, replacing any existing one
+    pub fn record(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            state: Mutex::new((Mode::Record, Vec::new())),
+        }
+    }
+
+    /// Replay the interactions stored in a cassette file
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self> {
+        let data = std::fs::read(path.as_ref())?;
+        let interactions: Vec<Interaction> = serde_json::from_slice(&data)?;
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            state: Mutex::new((
+                Mode::Replay {
+                    used: vec![false; interactions.len()],
+                },
+                interactions,
+            )),
+        })
+    }
+
+    /// Record to the cassette file if it does not exist yet, otherwise replay it
+    ///
+    /// This lets a test run against the real API once to create its cassette
+    /// and offline afterwards.
+    pub fn record_or_replay(path: impl AsRef<Path>) -> Result<Self> {
+        if path.as_ref().exists() {
+            Self::replay(path)
+        } else {
+            Ok(Self::record(path))
+        }
+    }
+
+    pub(crate) fn is_replaying(&self) -> bool {
+        matches!(self.state.lock().unwrap().0, Mode::Replay { .. })
+    }
+
+    pub(crate) fn record_interaction(
+        &self,
+        method: &Method,
+        url: &Url,
+        base_url: &Url,
+        body: Option<&[u8]>,
+        response: &RawResponse,
+        secrets: &[Option<&str>],
+    ) -> Result<()> {
+        let headers = response
+            .headers
+            .iter()
+            .filter(|(name, _)| *name != reqwest::header::SET_COOKIE)
+            .filter_map(|(name, value)| {
+                let value = redact_str(value.to_str().ok()?, secrets);
+                Some((name.to_string(), value))
+            })
+            .collect();
+        let interaction = Interaction {
+            request: RecordedRequest {
+                method: method.to_string(),
+                url: redact_str(&relative_url(url, base_url), secrets),
+                body: body.map(|b| Body::new(b, secrets)),
+            },
+            response: RecordedResponse {
+                status: response.status,
+                headers,
+                body: Body::new(&response.body, secrets),
+            },
+        };
+
+        let mut state = self.state.lock().unwrap();
+        state.1.push(interaction);
+        std::fs::write(&self.path, serde_json::to_vec_pretty(&state.1)?)?;
+        Ok(())
+    }
+
+    pub(crate) fn replay_interaction(
+        &self,
+        method: &Method,
+        url: &Url,
+        base_url: &Url,
+        body: Option<&[u8]>,
+    ) -> Result<RawResponse> {
+        let endpoint = url.path().to_string();
+        let url = relative_url(url, base_url);
+        // Redact the request body the same way it was when it was recorded
+        let body = body.map(|b| Body::new(b, &[]));
+
+        let mut state = self.state.lock().unwrap();
+        let (Mode::Replay { used }, interactions) = &mut *state else {
+            unreachable!("replay called on a recording cassette");
+        };
+        let index = interactions
+            .iter()
+            .enumerate()
+            .position(|(i, interaction)| {
+                !used[i]
+                    && interaction.request.method == method.as_str()
+                    && interaction.request.url == url
+                    && interaction.request.body == body
+            })
+            .ok_or_else(|| {
+                Error::ApiError(format!(
+                    "No recorded interaction for {} {} in {}",
+                    method,
+                    url,
+                    self.path.display()
+                ))
+            })?;
+        used[index] = true;
+
+        let recorded = interactions[index].response.clone();
+        let mut headers = HeaderMap::new();
+        for (name, value) in recorded.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+        Ok(RawResponse {
+            endpoint,
+            status: recorded.status,
+            headers,
+            body: recorded.body.into_bytes(),
+        })
+    }
+}
+
+impl RideWithGpsClient {
+    /// Record or replay this client's API interactions using a cassette
+    ///
+    /// Requires the `replay` feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{Cassette, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// )
+    /// .with_cassette(Cassette::record_or_replay("tests/cassettes/get_route.json").unwrap());
+    ///
+    /// let route = client.get_route(12345).unwrap();
+    /// ```
+    pub fn with_cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(Arc::new(cassette));
+        self
+    }
+}
+
+/// The URL relative to the base URL, or the full URL for other hosts
+fn relative_url(url: &Url, base_url: &Url) -> String {
+    if url.origin() == base_url.origin() {
+        match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        }
+    } else {
+        url.to_string()
+    }
+}
+
+fn redact_str(value: &str, secrets: &[Option<&str>]) -> String {
+    secrets
+        .iter()
+        .flatten()
+        .filter(|secret| !secret.is_empty())
+        .fold(value.to_string(), |value, secret| {
+            value.replace(secret, REDACTED)
+        })
+}
+
+fn redact_value(value: &mut Value, secrets: &[Option<&str>]) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if REDACTED_FIELDS.contains(&key.as_str()) && value.is_string() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(value, secrets);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(|v| redact_value(v, secrets)),
+        Value::String(s) => *s = redact_str(s, secrets),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redaction() {
+        let body = Body::new(
+            br#"{"auth_token": "abc", "user": {"name": "Jo", "note": "key k3y"}}"#,
+            &[Some("k3y"), None],
+        );
+        assert_eq!(
+            body,
+            Body::Json(serde_json::json!({
+                "auth_token": "REDACTED",
+                "user": {"name": "Jo", "note": "key REDACTED"}
+            }))
+        );
+    }
+
+    #[test]
+    fn test_replay() {
+        let path = std::env::temp_dir().join(format!("rwgps-cassette-{}.json", std::process::id()));
+        let cassette = serde_json::json!([
+            {
+                "request": {"method": "GET", "url": "/api/v1/routes/1.json"},
+                "response": {
+                    "status": 200,
+                    "headers": {"content-type": "application/json"},
+                    "body": {"json": {"route": {"id": 1, "name": "Recorded"}}}
+                }
+            },
+            {
+                "request": {"method": "DELETE", "url": "/api/v1/routes/1.json"},
+                "response": {"status": 204, "body": {"text": ""}}
+            }
+        ]);
+        std::fs::write(&path, cassette.to_string()).unwrap();
+
+        let client = RideWithGpsClient::new("https://ridewithgps.com", "key", None)
+            .with_cassette(Cassette::replay(&path).unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        let route = client.get_route(1).unwrap();
+        assert_eq!(route.name.as_deref(), Some("Recorded"));
+        client.delete_route(1).unwrap();
+        // Each interaction is only replayed once
+        assert!(matches!(client.delete_route(1), Err(Error::ApiError(_))));
+        assert!(matches!(client.get_route(2), Err(Error::ApiError(_))));
+    }
+}
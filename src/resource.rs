@@ -0,0 +1,332 @@
+//! Generic access to API resources
+
+use crate::{
+    Collection, CollectionId, Event, EventId, ListCollectionsParams, ListEventsParams,
+    ListPointsOfInterestParams, ListRoutesParams, ListTripsParams, PaginatedResponse,
+    PointOfInterest, RequestOptions, Result, RideWithGpsClient, Route, RouteId, Trip, TripId,
+};
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Number of requests [`RideWithGpsClient::get_many`] runs at the same time
+pub(crate) const BATCH_CONCURRENCY: usize = 4;
+
+/// A kind of object the API serves at `<PATH>.json` and `<PATH>/<id>.json`
+///
+/// Implemented for the crate's main types, and implementable for endpoints
+/// the crate does not cover yet, to use them with [`RideWithGpsClient::get`],
+/// [`RideWithGpsClient::list`] and [`RideWithGpsClient::delete`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ridewithgps_client::{Resource, RideWithGpsClient};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize)]
+/// struct Badge {
+///     id: u64,
+///     name: String,
+/// }
+///
+/// #[derive(Default, Serialize)]
+/// struct ListBadgesParams {
+///     page: Option<u32>,
+/// }
+///
+/// impl Resource for Badge {
+///     const PATH: &'static str = "/api/v1/badges";
+///     const KEY: &'static str = "badge";
+///     type Id = u64;
+///     type ListParams = ListBadgesParams;
+/// }
+///
+/// let client = RideWithGpsClient::new(
+///     "https://ridewithgps.com",
+///     "your-api-key",
+///     Some("your-auth-token")
+/// );
+///
+/// let badge = client.get::<Badge>(7u64).unwrap();
+/// println!("{}: {}", badge.id, badge.name);
+/// ```
+pub trait Resource: DeserializeOwned {
+    /// Path of the collection of resources, without the `.json` suffix
+    const PATH: &'static str;
+
+    /// Key wrapping a single resource in responses, such as `"route"`
+    const KEY: &'static str;
+
+    /// Type of the resource's ID
+    type Id: fmt::Display;
+
+    /// Query parameters for listing resources
+    type ListParams: Serialize;
+}
+
+impl Resource for Route {
+    const PATH: &'static str = "/api/v1/routes";
+    const KEY: &'static str = "route";
+    type Id = RouteId;
+    type ListParams = ListRoutesParams;
+}
+
+impl Resource for Trip {
+    const PATH: &'static str = "/api/v1/trips";
+    const KEY: &'static str = "trip";
+    type Id = TripId;
+    type ListParams = ListTripsParams;
+}
+
+impl Resource for Event {
+    const PATH: &'static str = "/api/v1/events";
+    const KEY: &'static str = "event";
+    type Id = EventId;
+    type ListParams = ListEventsParams;
+}
+
+impl Resource for Collection {
+    const PATH: &'static str = "/api/v1/collections";
+    const KEY: &'static str = "collection";
+    type Id = CollectionId;
+    type ListParams = ListCollectionsParams;
+}
+
+impl Resource for PointOfInterest {
+    const PATH: &'static str = "/api/v1/points_of_interest";
+    const KEY: &'static str = "point_of_interest";
+    type Id = u64;
+    type ListParams = ListPointsOfInterestParams;
+}
+
+/// A resource wrapped in an object under its [`Resource::KEY`]
+pub(crate) struct Wrapped<R>(pub(crate) R);
+
+impl<'de, R: Resource> Deserialize<'de> for Wrapped<R> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Envelope::new(R::KEY).deserialize(deserializer).map(Wrapped)
+    }
+}
+
+/// Decodes a model wrapped in an object under `key`, such as the route of
+/// `{"route": {...}}`; other fields of the object are ignored
+pub(crate) struct Envelope<T> {
+    key: &'static str,
+    model: PhantomData<T>,
+}
+
+impl<T> Envelope<T> {
+    pub(crate) fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            model: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> DeserializeSeed<'de> for Envelope<T> {
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<T, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for Envelope<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an object with a `{}` field", self.key)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<T, A::Error> {
+        let mut value = None;
+        while let Some(key) = map.next_key::<String>()? {
+            if key == self.key {
+                value = Some(map.next_value()?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        value.ok_or_else(|| de::Error::missing_field(self.key))
+    }
+}
+
+impl RideWithGpsClient {
+    /// Get a resource of any type by ID
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The resource ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{RideWithGpsClient, Route, Trip};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let route = client.get::<Route>(12345).unwrap();
+    /// let trip: Trip = client.get(67890).unwrap();
+    /// ```
+    pub fn get<R: Resource>(&self, id: impl Into<R::Id>) -> Result<R> {
+        self.get_resource_with(id.into(), &RequestOptions::default())
+    }
+
+    /// Get a resource by ID, with per-call options
+    pub(crate) fn get_resource_with<R: Resource>(
+        &self,
+        id: R::Id,
+        options: &RequestOptions,
+    ) -> Result<R> {
+        self.get_wrapped_with(&format!("{}/{}.json", R::PATH, id), R::KEY, options)
+    }
+
+    /// Get many resources by ID, a few at a time
+    ///
+    /// Duplicate IDs are only fetched once. A failure to fetch one resource
+    /// does not affect the others.
+    pub(crate) fn get_many<R>(&self, ids: &[R::Id]) -> BTreeMap<R::Id, Result<R>>
+    where
+        R: Resource + Send,
+        R::Id: Ord + Clone + Send + Sync,
+    {
+        let mut ids = ids.to_vec();
+        ids.sort();
+        ids.dedup();
+
+        let next = AtomicUsize::new(0);
+        let worker = || {
+            let mut results = Vec::new();
+            loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(id) = ids.get(i) else {
+                    break results;
+                };
+                results.push((id.clone(), self.get::<R>(id.clone())));
+            }
+        };
+
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..BATCH_CONCURRENCY.min(ids.len()))
+                .map(|_| scope.spawn(worker))
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|w| w.join().expect("batch worker panicked"))
+                .collect()
+        })
+    }
+
+    /// List resources of any type
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Optional parameters for filtering and pagination
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{ListTripsParams, RideWithGpsClient, Trip};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let trips = client.list::<Trip>(Some(&ListTripsParams::new().page(2))).unwrap();
+    /// println!("Found {} trips", trips.results.len());
+    /// ```
+    pub fn list<R: Resource>(
+        &self,
+        params: Option<&R::ListParams>,
+    ) -> Result<PaginatedResponse<R>> {
+        let mut url = format!("{}.json", R::PATH);
+
+        if let Some(params) = params {
+            let query = serde_json::to_value(params)?;
+            if let Some(obj) = query.as_object() {
+                if !obj.is_empty() {
+                    let query_str = serde_urlencoded::to_string(obj).map_err(|e| {
+                        crate::Error::ApiError(format!("Failed to encode query: {}", e))
+                    })?;
+                    url.push('?');
+                    url.push_str(&query_str);
+                }
+            }
+        }
+
+        self.get_path(&url)
+    }
+
+    /// Delete a resource of any type by ID
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The resource ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{Event, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// client.delete::<Event>(12345).unwrap();
+    /// ```
+    pub fn delete<R: Resource>(&self, id: impl Into<R::Id>) -> Result<()> {
+        self.delete_path(&format!("{}/{}.json", R::PATH, id.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_get_many() {
+        use crate::testing::FakeServer;
+
+        let server = FakeServer::start();
+        server.mock("GET", "/api/v1/routes/3.json", 404, serde_json::json!({}));
+        let client = server.client();
+
+        let routes = client.get_routes(&[RouteId(1), RouteId(2), RouteId(3), RouteId(1)]);
+        assert_eq!(routes.len(), 3);
+        assert_eq!(routes[&RouteId(2)].as_ref().unwrap().id, 2);
+        assert!(matches!(
+            routes[&RouteId(3)],
+            Err(crate::Error::NotFound(_))
+        ));
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[test]
+    fn test_wrapped() {
+        let wrapped: Wrapped<Event> =
+            serde_json::from_str(r#"{"meta": {"x": 1}, "event": {"id": 9}}"#).unwrap();
+        assert_eq!(wrapped.0.id, 9);
+
+        let err = serde_json::from_str::<Wrapped<Event>>(r#"{"route": {"id": 9}}"#)
+            .map(|w| w.0.id)
+            .unwrap_err();
+        assert!(err.to_string().contains("missing field `event`"));
+    }
+}
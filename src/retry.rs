@@ -0,0 +1,328 @@
+//! Retrying requests that failed for transient reasons
+
+use crate::{Error, RawResponse, Result};
+use reqwest::Method;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+/// Header carrying the idempotency key of a POST request
+pub(crate) const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// How much randomness to add to backoff delays
+///
+/// Randomizing the delays keeps many clients that were throttled at the
+/// same moment from retrying in lockstep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Jitter {
+    /// Wait exactly the backoff
+    #[default]
+    None,
+
+    /// Wait a random time between zero and the backoff
+    Full,
+
+    /// Wait half the backoff plus a random time up to the other half
+    Equal,
+}
+
+/// When and how often to retry requests that failed for transient reasons
+///
+/// Connection failures, timeouts, and responses with status 429, 502, 503
+/// or 504 are retried with exponential backoff, honoring the server's
+/// `Retry-After` header when present. POST requests are sent with an
+/// `Idempotency-Key` header that stays the same across attempts, so a
+/// creation that succeeded server-side before a timeout is not duplicated
+/// by the retry.
+///
+/// Enable with [`ClientBuilder::retry`](crate::ClientBuilder::retry).
+///
+/// # Example
+///
+/// ```rust
+/// use ridewithgps_client::{Jitter, Method, RetryPolicy};
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::new()
+///     .max_retries(8)
+///     .jitter(Jitter::Full)
+///     .budget(Duration::from_secs(300))
+///     .method_max_retries(Method::DELETE, 0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the first attempt
+    pub max_retries: u32,
+
+    /// Delay before the first retry; doubled for each further retry
+    pub initial_backoff: Duration,
+
+    /// Upper bound for the delay between attempts
+    pub max_backoff: Duration,
+
+    /// Randomness added to backoff delays; delays asked for by the server
+    /// with `Retry-After` are kept as they are
+    pub jitter: Jitter,
+
+    /// Longest time a request may take including its retries, counted from
+    /// the first attempt; no retry is made that would wait past it
+    pub budget: Option<Duration>,
+
+    /// Maximum number of retries for specific HTTP methods, instead of
+    /// [`max_retries`](Self::max_retries)
+    pub method_max_retries: Vec<(Method, u32)>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: Jitter::None,
+            budget: None,
+            method_max_retries: Vec::new(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy with the default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set [`max_retries`](Self::max_retries)
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set [`initial_backoff`](Self::initial_backoff)
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Set [`max_backoff`](Self::max_backoff)
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Set [`jitter`](Self::jitter)
+    pub fn jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set [`budget`](Self::budget)
+    pub fn budget(mut self, budget: Duration) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Retry requests with `method` at most `max_retries` times, such as 0
+    /// to never retry DELETE requests
+    pub fn method_max_retries(mut self, method: Method, max_retries: u32) -> Self {
+        self.method_max_retries.retain(|(m, _)| *m != method);
+        self.method_max_retries.push((method, max_retries));
+        self
+    }
+
+    /// How long to wait before retrying after `attempt` (counting from 0)
+    /// of a `method` request produced `result`, `elapsed` after the first
+    /// attempt started, or `None` if it should not be retried
+    pub(crate) fn retry_delay(
+        &self,
+        method: &Method,
+        attempt: u32,
+        elapsed: Duration,
+        result: &Result<RawResponse>,
+    ) -> Option<Duration> {
+        let max_retries = self
+            .method_max_retries
+            .iter()
+            .find(|(m, _)| m == method)
+            .map_or(self.max_retries, |(_, n)| *n);
+        if attempt >= max_retries {
+            return None;
+        }
+
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff);
+        let backoff = match self.jitter {
+            Jitter::None => backoff,
+            Jitter::Full => backoff.mul_f64(random_fraction()),
+            Jitter::Equal => backoff / 2 + (backoff / 2).mul_f64(random_fraction()),
+        };
+
+        let delay = match result {
+            Err(Error::Http(e)) if e.is_timeout() || e.is_connect() => backoff,
+            Ok(response) if matches!(response.status, 429 | 502 | 503 | 504) => {
+                let retry_after = response
+                    .headers
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.trim().parse::<u64>().ok())
+                    .map(Duration::from_secs);
+                retry_after.unwrap_or(backoff).min(self.max_backoff)
+            }
+            _ => return None,
+        };
+
+        match self.budget {
+            Some(budget) if elapsed.saturating_add(delay) > budget => None,
+            _ => Some(delay),
+        }
+    }
+}
+
+/// A random number in `[0, 1)`
+fn random_fraction() -> f64 {
+    (random_u64() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+fn random_u64() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    // RandomState is seeded randomly per instance
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    if let Ok(elapsed) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+        hasher.write_u128(elapsed.as_nanos());
+    }
+    hasher.finish()
+}
+
+/// Generate a random idempotency key formatted like a UUID
+pub(crate) fn new_idempotency_key() -> String {
+    let hex = format!("{:016x}{:016x}", random_u64(), random_u64());
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+
+    fn response(status: u16, retry_after: Option<&'static str>) -> Result<RawResponse> {
+        let mut headers = HeaderMap::new();
+        if let Some(value) = retry_after {
+            headers.insert(RETRY_AFTER, HeaderValue::from_static(value));
+        }
+        Ok(RawResponse {
+            endpoint: String::new(),
+            status,
+            headers,
+            body: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let policy = RetryPolicy::new()
+            .initial_backoff(Duration::from_secs(1))
+            .max_backoff(Duration::from_secs(5));
+        let delay =
+            |attempt, result| policy.retry_delay(&Method::GET, attempt, Duration::ZERO, &result);
+
+        assert_eq!(delay(0, response(503, None)), Some(Duration::from_secs(1)));
+        assert_eq!(delay(2, response(502, None)), Some(Duration::from_secs(4)));
+        assert_eq!(
+            delay(2, response(429, Some("2"))),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            delay(1, response(429, Some("600"))),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(delay(3, response(503, None)), None);
+        assert_eq!(delay(0, response(500, None)), None);
+        assert_eq!(delay(0, response(200, None)), None);
+        assert_eq!(delay(0, Err(Error::NotFound(String::new()))), None);
+    }
+
+    #[test]
+    fn test_retry_jitter_budget_and_methods() {
+        let policy = RetryPolicy::new()
+            .initial_backoff(Duration::from_secs(4))
+            .jitter(Jitter::Equal)
+            .budget(Duration::from_secs(10))
+            .method_max_retries(Method::DELETE, 0);
+
+        for _ in 0..20 {
+            let delay = policy
+                .retry_delay(&Method::GET, 0, Duration::ZERO, &response(503, None))
+                .unwrap();
+            assert!(delay >= Duration::from_secs(2) && delay <= Duration::from_secs(4));
+        }
+        let full = policy.clone().jitter(Jitter::Full);
+        let delay = full.retry_delay(&Method::GET, 0, Duration::ZERO, &response(503, None));
+        assert!(delay.unwrap() <= Duration::from_secs(4));
+
+        // Retry-After is honored exactly, and counted against the budget
+        let throttled = response(429, Some("3"));
+        assert_eq!(
+            policy.retry_delay(&Method::GET, 0, Duration::from_secs(7), &throttled),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(
+            policy.retry_delay(&Method::GET, 0, Duration::from_secs(8), &throttled),
+            None
+        );
+
+        assert_eq!(
+            policy.retry_delay(&Method::DELETE, 0, Duration::ZERO, &throttled),
+            None
+        );
+        let policy = policy.method_max_retries(Method::DELETE, 1);
+        assert_eq!(policy.method_max_retries.len(), 1);
+        assert!(policy
+            .retry_delay(&Method::DELETE, 0, Duration::ZERO, &throttled)
+            .is_some());
+    }
+
+    #[test]
+    fn test_idempotency_keys_are_unique() {
+        let a = new_idempotency_key();
+        let b = new_idempotency_key();
+        assert_ne!(a, b);
+        assert_eq!(a.len(), 36);
+        assert_eq!(a.matches('-').count(), 4);
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_post_is_retried_with_same_idempotency_key() {
+        use crate::testing::{FakeServer, FAKE_API_KEY};
+        use crate::{RideWithGpsClient, RouteRequest};
+
+        let server = FakeServer::start();
+        server.mock_once("POST", "/api/v1/routes.json", 503, serde_json::json!({}));
+        let client = RideWithGpsClient::builder(&server.url(), FAKE_API_KEY)
+            .retry(RetryPolicy::new().initial_backoff(Duration::from_millis(1)))
+            .build()
+            .unwrap();
+
+        client
+            .create_route(&RouteRequest::new().name("Retried"))
+            .unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        let key = requests[0].header(IDEMPOTENCY_KEY_HEADER).unwrap();
+        assert_eq!(requests[1].header(IDEMPOTENCY_KEY_HEADER), Some(key));
+    }
+}
@@ -0,0 +1,136 @@
+//! Route ratings and reviews
+
+use crate::{Error, PaginatedResponse, Result, RideWithGpsClient};
+use serde::{Deserialize, Serialize};
+
+/// A rating and review of a route
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RouteReview {
+    /// Review ID
+    pub id: u64,
+
+    /// ID of the reviewed route
+    pub route_id: Option<u64>,
+
+    /// ID of the reviewer
+    pub user_id: Option<u64>,
+
+    /// Display name of the reviewer
+    pub user_name: Option<String>,
+
+    /// Rating from 1 to 5
+    pub rating: Option<u8>,
+
+    /// Review text
+    pub text: Option<String>,
+
+    /// Created timestamp
+    pub created_at: Option<String>,
+
+    /// Updated timestamp
+    pub updated_at: Option<String>,
+}
+
+impl RideWithGpsClient {
+    /// List the reviews of a route
+    ///
+    /// # Arguments
+    ///
+    /// * `route_id` - The route ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     None
+    /// );
+    ///
+    /// let reviews = client.list_route_reviews(12345).unwrap();
+    /// for review in reviews.results {
+    ///     println!("{:?}/5: {}", review.rating, review.text.unwrap_or_default());
+    /// }
+    /// ```
+    pub fn list_route_reviews(&self, route_id: u64) -> Result<PaginatedResponse<RouteReview>> {
+        self.get_path(&format!("/api/v1/routes/{}/reviews.json", route_id))
+    }
+
+    /// Rate and review a route
+    ///
+    /// # Arguments
+    ///
+    /// * `route_id` - The route ID
+    /// * `rating` - Rating from 1 to 5
+    /// * `text` - Review text
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let review = client
+    ///     .create_route_review(12345, 5, "Quiet roads and a great café halfway")
+    ///     .unwrap();
+    /// println!("Created review {}", review.id);
+    /// ```
+    pub fn create_route_review(
+        &self,
+        route_id: u64,
+        rating: u8,
+        text: &str,
+    ) -> Result<RouteReview> {
+        #[derive(Serialize)]
+        struct ReviewRequest<'a> {
+            rating: u8,
+            text: &'a str,
+        }
+
+        if !(1..=5).contains(&rating) {
+            return Err(Error::ValidationError(format!(
+                "Rating must be between 1 and 5, got {}",
+                rating
+            )));
+        }
+
+        self.post_wrapped(
+            &format!("/api/v1/routes/{}/reviews.json", route_id),
+            "review",
+            &ReviewRequest { rating, text },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review_deserialization() {
+        let json = r#"{
+            "id": 3,
+            "route_id": 12345,
+            "user_name": "Jo",
+            "rating": 4,
+            "text": "Nice climb"
+        }"#;
+
+        let review: RouteReview = serde_json::from_str(json).unwrap();
+        assert_eq!(review.rating, Some(4));
+        assert_eq!(review.text.as_deref(), Some("Nice climb"));
+
+        let client = RideWithGpsClient::new("https://ridewithgps.com", "key", None);
+        assert!(matches!(
+            client.create_route_review(12345, 6, "Too good"),
+            Err(Error::ValidationError(_))
+        ));
+    }
+}
@@ -0,0 +1,403 @@
+//! Exporting the participants of an event as a roster
+
+use crate::cuesheet::csv_field;
+use crate::{EventParticipant, PageCursor, Result, RideWithGpsClient};
+use std::collections::HashMap;
+use std::io::Write;
+
+/// Page size of PDF rosters, in points (A4 landscape)
+const PAGE_WIDTH: f64 = 842.0;
+const PAGE_HEIGHT: f64 = 595.0;
+
+/// Page margin of PDF rosters, in points
+const MARGIN: f64 = 36.0;
+
+/// Font size and line height of PDF rosters, in points
+const FONT_SIZE: f64 = 8.0;
+const LINE_HEIGHT: f64 = 11.0;
+
+/// File format of an event roster
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RosterFormat {
+    /// Comma-separated values with a header row
+    Csv,
+
+    /// Printable table, on as many A4 landscape pages as needed
+    Pdf,
+}
+
+/// Sensitive columns to leave out of an event roster
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RosterRedaction {
+    /// Leave out email addresses
+    pub email: bool,
+
+    /// Leave out the answers to these registration fields
+    pub fields: Vec<u64>,
+}
+
+impl RideWithGpsClient {
+    /// Write the participants of an event as a roster
+    ///
+    /// Every page of participants is fetched. The roster has a row per
+    /// participant with their name, email address, registration status,
+    /// wave and check-in state, followed by a column per registration field
+    /// with their answers. PDF rosters are titled with the event name.
+    ///
+    /// Note: This is only available to the event's organizers.
+    ///
+    /// # Arguments
+    ///
+    /// * `event_id` - The event ID
+    /// * `format` - The file format to write
+    /// * `redaction` - Columns to leave out
+    /// * `writer` - Where to write the roster, such as a file
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{RideWithGpsClient, RosterFormat, RosterRedaction};
+    /// use std::fs::File;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let redaction = RosterRedaction {
+    ///     email: true,
+    ///     ..Default::default()
+    /// };
+    /// let mut file = File::create("roster.pdf").unwrap();
+    /// client
+    ///     .export_event_roster(12345, RosterFormat::Pdf, &redaction, &mut file)
+    ///     .unwrap();
+    /// ```
+    pub fn export_event_roster<W: Write>(
+        &self,
+        event_id: u64,
+        format: RosterFormat,
+        redaction: &RosterRedaction,
+        writer: &mut W,
+    ) -> Result<()> {
+        let fields: Vec<_> = self
+            .list_registration_fields(event_id)?
+            .into_iter()
+            .filter(|f| !redaction.fields.contains(&f.id))
+            .collect();
+        let waves: HashMap<u64, String> = self
+            .list_waves(event_id)?
+            .into_iter()
+            .map(|w| (w.id, w.name.unwrap_or_else(|| w.id.to_string())))
+            .collect();
+        let participants = self
+            .resume::<EventParticipant>(PageCursor {
+                path: format!("/api/v1/events/{}/participants.json", event_id),
+                page: 1,
+                offset: 0,
+                finished: false,
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut header = vec!["Name".to_string()];
+        if !redaction.email {
+            header.push("Email".to_string());
+        }
+        header.extend(["Status", "Wave", "Checked in"].map(String::from));
+        header.extend(
+            fields
+                .iter()
+                .map(|f| f.label.clone().unwrap_or_else(|| format!("Field {}", f.id))),
+        );
+
+        let row = |p: &EventParticipant| {
+            let text = |value: &Option<String>| value.clone().unwrap_or_default();
+            let mut row = vec![text(&p.name)];
+            if !redaction.email {
+                row.push(text(&p.email));
+            }
+            row.push(text(&p.status));
+            row.push(
+                p.wave_id
+                    .map(|id| waves.get(&id).cloned().unwrap_or_else(|| id.to_string()))
+                    .unwrap_or_default(),
+            );
+            row.push(match p.checked_in {
+                Some(true) => "yes".to_string(),
+                Some(false) => "no".to_string(),
+                None => String::new(),
+            });
+            row.extend(
+                fields
+                    .iter()
+                    .map(|f| p.answer(f.id).unwrap_or_default().to_string()),
+            );
+            row
+        };
+        let rows: Vec<Vec<String>> = participants.iter().map(row).collect();
+
+        match format {
+            RosterFormat::Csv => write_csv(writer, &header, &rows)?,
+            RosterFormat::Pdf => {
+                let event = self.get_event(event_id)?;
+                let title = format!(
+                    "Roster: {}",
+                    event.name.unwrap_or_else(|| event_id.to_string())
+                );
+                write_pdf(writer, &title, &header, &rows)?
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write a table as CSV
+///
+/// Values starting with `=`, `+`, `-` or `@` are prefixed with `'`, so
+/// spreadsheets show answers such as `=HYPERLINK(...)` as text instead of
+/// running them as formulas.
+fn write_csv<W: Write>(writer: &mut W, header: &[String], rows: &[Vec<String>]) -> Result<()> {
+    for row in std::iter::once(header).chain(rows.iter().map(Vec::as_slice)) {
+        let fields: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+        writeln!(writer, "{}", fields.join(","))?;
+    }
+    Ok(())
+}
+
+/// Write a table as a PDF, using the standard Helvetica fonts so no fonts
+/// need embedding
+fn write_pdf<W: Write>(
+    writer: &mut W,
+    title: &str,
+    header: &[String],
+    rows: &[Vec<String>],
+) -> Result<()> {
+    let column_width = (PAGE_WIDTH - 2.0 * MARGIN) / header.len().max(1) as f64;
+    // Helvetica averages about half the font size per character
+    let max_chars = ((column_width - 4.0) / (FONT_SIZE * 0.5)).max(1.0) as usize;
+    let rows_per_page = ((PAGE_HEIGHT - 2.0 * MARGIN) / LINE_HEIGHT) as usize - 3;
+
+    let mut pages = Vec::new();
+    let chunks: Vec<&[Vec<String>]> = if rows.is_empty() {
+        vec![&[]]
+    } else {
+        rows.chunks(rows_per_page).collect()
+    };
+    for (number, chunk) in chunks.iter().enumerate() {
+        let mut content = String::new();
+        let mut text = |x: f64, y: f64, font: &str, text: &str| {
+            content.push_str(&format!(
+                "BT /{} {} Tf {:.1} {:.1} Td ({}) Tj ET\n",
+                font,
+                FONT_SIZE,
+                x,
+                y,
+                pdf_string(text)
+            ));
+        };
+        let mut y = PAGE_HEIGHT - MARGIN;
+        let page_title = format!("{} (page {} of {})", title, number + 1, chunks.len());
+        text(MARGIN, y, "F2", &page_title);
+        y -= LINE_HEIGHT;
+        for (font, cells) in
+            std::iter::once(("F2", header)).chain(chunk.iter().map(|r| ("F1", r.as_slice())))
+        {
+            y -= LINE_HEIGHT;
+            for (i, cell) in cells.iter().enumerate() {
+                let cell: String = cell.chars().take(max_chars).collect();
+                text(MARGIN + i as f64 * column_width, y, font, &cell);
+            }
+        }
+        pages.push(content);
+    }
+
+    // Objects 1-4 are the catalog, page tree and fonts; each page then has
+    // a page object followed by its content stream
+    let page_ids: Vec<usize> = (0..pages.len()).map(|i| 5 + 2 * i).collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            page_ids
+                .iter()
+                .map(|id| format!("{} 0 R", id))
+                .collect::<Vec<_>>()
+                .join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    ];
+    for (content, id) in pages.iter().zip(&page_ids) {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            PAGE_WIDTH,
+            PAGE_HEIGHT,
+            id + 1
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}\nendstream",
+            content.len(),
+            content
+        ));
+    }
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).into_bytes());
+    }
+    let xref = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+    for offset in offsets {
+        pdf.extend(format!("{:010} 00000 n \n", offset).into_bytes());
+    }
+    pdf.extend(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        )
+        .into_bytes(),
+    );
+    writer.write_all(&pdf)?;
+    Ok(())
+}
+
+/// Escape text for a PDF string literal in WinAnsiEncoding
+///
+/// Characters outside Latin-1 are replaced by `?`.
+fn pdf_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' '..='~' => escaped.push(c),
+            '\u{a0}'..='\u{ff}' => escaped.push_str(&format!("\\{:03o}", c as u32)),
+            _ => escaped.push('?'),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pdf_string() {
+        assert_eq!(pdf_string("Jo (A) \\ B"), "Jo \\(A\\) \\\\ B");
+        assert_eq!(pdf_string("Zoë 🚲"), "Zo\\353 ?");
+    }
+
+    #[test]
+    fn test_write_csv_formulas() {
+        let header = vec!["Name".to_string(), "Phone".to_string()];
+        let rows = vec![vec![
+            "=HYPERLINK(\"http://x\", \"Jo\")".to_string(),
+            "+31 20 555 0100".to_string(),
+        ]];
+        let mut csv = Vec::new();
+        write_csv(&mut csv, &header, &rows).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "Name,Phone\n\"'=HYPERLINK(\"\"http://x\"\", \"\"Jo\"\")\",'+31 20 555 0100\n"
+        );
+    }
+
+    #[test]
+    fn test_write_pdf() {
+        let header = vec!["Name".to_string(), "Wave".to_string()];
+        let rows: Vec<Vec<String>> = (0..60)
+            .map(|i| vec![format!("Rider {}", i), "A".to_string()])
+            .collect();
+        let mut pdf = Vec::new();
+        write_pdf(&mut pdf, "Roster: Gran Fondo", &header, &rows).unwrap();
+        let pdf = String::from_utf8(pdf).unwrap();
+
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("/Count 2"));
+        assert!(pdf.contains("(Rider 59) Tj"));
+        assert!(pdf.contains("(Roster: Gran Fondo \\(page 2 of 2\\)) Tj"));
+
+        let startxref: usize = pdf.lines().rev().nth(1).unwrap().parse().unwrap();
+        assert!(pdf[startxref..].starts_with("xref\n0 9\n"));
+        let offset: usize = pdf[startxref..].lines().nth(3).unwrap()[..10]
+            .parse()
+            .unwrap();
+        assert!(pdf[offset..].starts_with("1 0 obj\n<< /Type /Catalog"));
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_export_event_roster() {
+        use crate::testing::FakeServer;
+
+        let server = FakeServer::start();
+        let client = server.client();
+        server.mock(
+            "GET",
+            "/api/v1/events/{id}/registration_fields.json",
+            200,
+            serde_json::json!({"registration_fields": [
+                {"id": 10, "label": "Jersey size"},
+                {"id": 11, "label": "Emergency contact"}
+            ]}),
+        );
+        server.mock(
+            "GET",
+            "/api/v1/events/{id}/waves.json",
+            200,
+            serde_json::json!({"waves": [{"id": 3, "name": "A group"}]}),
+        );
+        server.mock(
+            "GET",
+            "/api/v1/events/{id}/participants.json",
+            200,
+            serde_json::json!({
+                "results": [
+                    {
+                        "id": 1, "name": "Jo", "email": "jo@example.com",
+                        "status": "registered", "wave_id": 3, "checked_in": true,
+                        "answers": [{"field_id": 10, "value": "M"}, {"field_id": 11, "value": "555-0100"}]
+                    },
+                    {"id": 2, "name": "Sam, Jr.", "status": "waitlisted"}
+                ],
+                "record_count": 2,
+                "page_count": 1
+            }),
+        );
+
+        let redaction = RosterRedaction {
+            email: true,
+            fields: vec![11],
+        };
+        let mut csv = Vec::new();
+        client
+            .export_event_roster(7, RosterFormat::Csv, &redaction, &mut csv)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "Name,Status,Wave,Checked in,Jersey size\n\
+             Jo,registered,A group,yes,M\n\
+             \"Sam, Jr.\",waitlisted,,,\n"
+        );
+
+        let mut pdf = Vec::new();
+        client
+            .export_event_roster(7, RosterFormat::Pdf, &RosterRedaction::default(), &mut pdf)
+            .unwrap();
+        let pdf = String::from_utf8(pdf).unwrap();
+        assert!(pdf.contains("(jo@example.com) Tj"));
+        assert!(pdf.contains("(555-0100) Tj"));
+    }
+}
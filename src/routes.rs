@@ -1,7 +1,12 @@
 //! Route-related types and methods
 
-use crate::{PaginatedResponse, PointOfInterest, Result, RideWithGpsClient};
+use crate::corridor::CorridorCache;
+use crate::{
+    ActivityType, CueType, LatLng, Meters, PaginatedResponse, PointOfInterest, RequestOptions,
+    Result, ResultExt, RideWithGpsClient, RouteId, Surface, TrackType,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Visibility setting for a route
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
@@ -34,7 +39,7 @@ pub struct TrackPoint {
 
     /// Surface type
     #[serde(rename = "S")]
-    pub surface: Option<i32>,
+    pub surface: Option<Surface>,
 
     /// Highway tag
     #[serde(rename = "R")]
@@ -42,7 +47,7 @@ pub struct TrackPoint {
 }
 
 /// Course point (turn-by-turn cue) on a route
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct CoursePoint {
     /// Longitude
     pub x: Option<f64>,
@@ -54,12 +59,28 @@ pub struct CoursePoint {
     pub d: Option<f64>,
 
     /// Cue type
-    pub t: Option<String>,
+    pub t: Option<CueType>,
 
     /// Cue text/description
     pub n: Option<String>,
 }
 
+impl CoursePoint {
+    /// Create a course point at a distance along the route
+    ///
+    /// The coordinates are left unset; [`Route::insert_course_point`] fills
+    /// them in from the route's track points.
+    pub fn new(distance: f64, cue_type: impl Into<CueType>, text: &str) -> Self {
+        Self {
+            x: None,
+            y: None,
+            d: Some(distance),
+            t: Some(cue_type.into()),
+            n: Some(text.to_string()),
+        }
+    }
+}
+
 /// Photo attached to a route or trip
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Photo {
@@ -128,7 +149,7 @@ pub struct Route {
     pub country_code: Option<String>,
 
     /// Track type
-    pub track_type: Option<String>,
+    pub track_type: Option<TrackType>,
 
     /// Whether the route has course points
     pub has_course_points: Option<bool>,
@@ -173,7 +194,22 @@ pub struct Route {
     pub archived: Option<bool>,
 
     /// Activity types
-    pub activity_types: Option<Vec<String>>,
+    pub activity_types: Option<Vec<ActivityType>>,
+
+    /// Tags attached to the route
+    pub tag_names: Option<Vec<String>>,
+
+    /// Number of likes
+    pub likes_count: Option<u64>,
+
+    /// Whether the authenticated user likes this route
+    pub liked_by_me: Option<bool>,
+
+    /// Average review rating from 1 to 5
+    pub rating: Option<f64>,
+
+    /// Number of reviews
+    pub reviews_count: Option<u64>,
 
     /// Track points (included when fetching a specific route)
     pub track_points: Option<Vec<TrackPoint>>,
@@ -186,6 +222,10 @@ pub struct Route {
 
     /// Photos (included when fetching a specific route)
     pub photos: Option<Vec<Photo>>,
+
+    /// Spatial index for [`project`](Self::project), built on first use
+    #[serde(skip)]
+    pub(crate) projection: CorridorCache,
 }
 
 /// Polyline data for a route
@@ -203,6 +243,7 @@ pub struct Polyline {
 
 /// Parameters for listing routes
 #[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
 pub struct ListRoutesParams {
     /// Filter by route name
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -228,6 +269,10 @@ pub struct ListRoutesParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_elevation_gain: Option<f64>,
 
+    /// Filter by tag
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+
     /// Page number
     #[serde(skip_serializing_if = "Option::is_none")]
     pub page: Option<u32>,
@@ -237,6 +282,489 @@ pub struct ListRoutesParams {
     pub page_size: Option<u32>,
 }
 
+setters!(ListRoutesParams {
+    name: impl Into<String>,
+    visibility: Visibility,
+    min_distance: f64,
+    max_distance: f64,
+    min_elevation_gain: f64,
+    max_elevation_gain: f64,
+    tag: impl Into<String>,
+    page: u32,
+    page_size: u32,
+});
+
+impl ListRoutesParams {
+    /// Set [`min_distance`](Self::min_distance) in kilometers
+    pub fn min_distance_km(self, km: impl Into<f64>) -> Self {
+        self.min_distance(Meters::from_km(km.into()).0)
+    }
+
+    /// Set [`max_distance`](Self::max_distance) in kilometers
+    pub fn max_distance_km(self, km: impl Into<f64>) -> Self {
+        self.max_distance(Meters::from_km(km.into()).0)
+    }
+}
+
+impl Route {
+    /// Insert a course point, keeping the cues ordered by distance
+    ///
+    /// The cue goes right after the last cue with a known distance up to
+    /// its own; cues without a distance stay where they are. A cue without a
+    /// distance is added at the end. If the course point has no
+    /// coordinates, they are taken from the track point closest to its
+    /// distance. Returns the index of the inserted cue.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{CoursePoint, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let mut route = client.get_route(12345).unwrap();
+    /// route.insert_course_point(CoursePoint::new(42000.0, "food", "Lunch stop"));
+    /// client
+    ///     .update_route_course_points(route.id, route.course_points.as_deref().unwrap())
+    ///     .unwrap();
+    /// ```
+    pub fn insert_course_point(&mut self, mut course_point: CoursePoint) -> usize {
+        if course_point.x.is_none() || course_point.y.is_none() {
+            if let Some(point) = course_point.d.and_then(|d| self.track_point_near(d)) {
+                course_point.x = point.x;
+                course_point.y = point.y;
+            }
+        }
+
+        let course_points = self.course_points.get_or_insert_with(Vec::new);
+        let index = match course_point.d {
+            Some(distance) => course_points
+                .iter()
+                .rposition(|cp| cp.d.is_some_and(|d| d <= distance))
+                .map_or(0, |i| i + 1),
+            None => course_points.len(),
+        };
+        course_points.insert(index, course_point);
+        index
+    }
+
+    /// Move a course point to a new distance along the route
+    ///
+    /// The cue's coordinates are updated from the track point closest to the
+    /// new distance. Returns the new index of the cue, or `None` if `index`
+    /// is out of range.
+    pub fn move_course_point(&mut self, index: usize, distance: f64) -> Option<usize> {
+        let mut course_point = self.remove_course_point(index)?;
+        course_point.d = Some(distance);
+        course_point.x = None;
+        course_point.y = None;
+        Some(self.insert_course_point(course_point))
+    }
+
+    /// Remove a course point, returning it if `index` was in range
+    pub fn remove_course_point(&mut self, index: usize) -> Option<CoursePoint> {
+        let course_points = self.course_points.as_mut()?;
+        (index < course_points.len()).then(|| course_points.remove(index))
+    }
+
+    fn track_point_near(&self, distance: f64) -> Option<&TrackPoint> {
+        self.track_points
+            .iter()
+            .flatten()
+            .filter(|p| p.d.is_some())
+            .min_by(|a, b| {
+                let da = (a.d.unwrap_or_default() - distance).abs();
+                let db = (b.d.unwrap_or_default() - distance).abs();
+                da.total_cmp(&db)
+            })
+    }
+}
+
+/// Request to create a route
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
+pub struct RouteRequest {
+    /// Route name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Route description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Route visibility
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Visibility>,
+
+    /// Track points
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_points: Option<Vec<TrackPoint>>,
+
+    /// Course points/cues
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub course_points: Option<Vec<CoursePoint>>,
+}
+
+setters!(RouteRequest {
+    name: impl Into<String>,
+    description: impl Into<String>,
+    visibility: Visibility,
+    track_points: Vec<TrackPoint>,
+    course_points: Vec<CoursePoint>,
+});
+
+impl From<&Route> for RouteRequest {
+    fn from(route: &Route) -> Self {
+        Self {
+            name: route.name.clone(),
+            description: route.description.clone(),
+            visibility: route.visibility,
+            track_points: route.track_points.clone(),
+            course_points: route.course_points.clone(),
+        }
+    }
+}
+
+impl Route {
+    /// Return a copy of this route ridden in the opposite direction
+    ///
+    /// Track points and course points are reversed and their distances
+    /// recomputed from the new start, elevation gain and loss are swapped,
+    /// and the start/end coordinates are exchanged. Turn cues are mirrored
+    /// ("left" becomes "right" and vice versa); cue text is left untouched,
+    /// since street names generally differ when approaching a junction from
+    /// the other side.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let route = client.get_route(12345).unwrap();
+    /// let back = route.reversed();
+    /// println!("Return leg climbs {:?} m", back.elevation_gain);
+    /// ```
+    pub fn reversed(&self) -> Route {
+        let mut route = self.clone();
+        let total = self
+            .track_points
+            .iter()
+            .flatten()
+            .filter_map(|p| p.d)
+            .fold(self.distance.unwrap_or_default(), f64::max);
+
+        if let Some(points) = route.track_points.as_mut() {
+            points.reverse();
+            for point in points.iter_mut() {
+                point.d = point.d.map(|d| total - d);
+            }
+        }
+
+        if let Some(course_points) = route.course_points.as_mut() {
+            course_points.reverse();
+            for cp in course_points.iter_mut() {
+                cp.d = cp.d.map(|d| total - d);
+                cp.t = cp.t.as_ref().map(CueType::mirrored);
+            }
+        }
+
+        std::mem::swap(&mut route.elevation_gain, &mut route.elevation_loss);
+        std::mem::swap(&mut route.first_lat, &mut route.last_lat);
+        std::mem::swap(&mut route.first_lng, &mut route.last_lng);
+
+        route
+    }
+}
+
+impl Route {
+    /// Split the route into two routes at a distance along it
+    ///
+    /// A track point is interpolated at the split distance so that both
+    /// halves meet exactly. Distances in the second half are re-offset to
+    /// start from zero, cues are assigned to the half they fall in, and the
+    /// summary statistics of both halves are recomputed from their track
+    /// points.
+    ///
+    /// Returns `None` if the route has no track points or `distance` does
+    /// not fall strictly between the distances of its first and last track
+    /// points, as one of the halves would be empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let tour = client.get_route(12345).unwrap();
+    /// let (day1, day2) = tour.split_at_distance(120_000.0).unwrap();
+    /// println!("Day 1: {:?} m, day 2: {:?} m", day1.distance, day2.distance);
+    /// ```
+    pub fn split_at_distance(&self, distance: f64) -> Option<(Route, Route)> {
+        let points = self.track_points.as_ref()?;
+        let start = points.iter().find_map(|p| p.d)?;
+        let end = points.iter().rev().find_map(|p| p.d)?;
+        if !(start < distance && distance < end) {
+            return None;
+        }
+        let index = points
+            .iter()
+            .position(|p| p.d.is_some_and(|d| d >= distance))?;
+
+        let junction = interpolate_track_point(&points[index - 1], &points[index], distance);
+        let mut first_points = points[..index].to_vec();
+        if points[index].d != Some(distance) {
+            first_points.push(junction.clone());
+        } else {
+            first_points.push(points[index].clone());
+        }
+        let mut second_points = vec![junction];
+        second_points.extend(
+            points[index..]
+                .iter()
+                .skip_while(|p| p.d == Some(distance))
+                .cloned(),
+        );
+        for point in second_points.iter_mut() {
+            point.d = point.d.map(|d| d - distance);
+        }
+
+        let (first_cues, second_cues): (Vec<_>, Vec<_>) = self
+            .course_points
+            .iter()
+            .flatten()
+            .cloned()
+            .partition(|cp| cp.d.is_some_and(|d| d < distance));
+        let second_cues = second_cues
+            .into_iter()
+            .map(|mut cp| {
+                cp.d = cp.d.map(|d| d - distance);
+                cp
+            })
+            .collect();
+
+        let mut first = self.clone();
+        first.track_points = Some(first_points);
+        first.course_points = self.course_points.as_ref().map(|_| first_cues);
+        first.recompute_stats();
+
+        let mut second = self.clone();
+        second.track_points = Some(second_points);
+        second.course_points = self.course_points.as_ref().map(|_| second_cues);
+        second.recompute_stats();
+
+        Some((first, second))
+    }
+
+    /// Append another route to the end of this one
+    ///
+    /// The track points and cues of `other` are appended with their
+    /// distances offset by the length of this route plus the straight-line
+    /// gap between the two routes. The summary statistics of the combined
+    /// route are recomputed from its track points; all other metadata is
+    /// taken from `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let day1 = client.get_route(12345).unwrap();
+    /// let day2 = client.get_route(67890).unwrap();
+    /// let tour = day1.concat(&day2);
+    /// println!("Tour: {:?} m", tour.distance);
+    /// ```
+    pub fn concat(&self, other: &Route) -> Route {
+        let mut route = self.clone();
+        let points = route.track_points.get_or_insert_with(Vec::new);
+        let last = points.last().cloned();
+        let mut offset = last
+            .as_ref()
+            .and_then(|p| p.d)
+            .or(self.distance)
+            .unwrap_or_default();
+
+        let mut appended = other.track_points.iter().flatten().cloned().peekable();
+        if let (Some(last), Some(first)) = (last.as_ref(), appended.peek()) {
+            if let (Some(a), Some(b)) = (last.coord(), first.coord()) {
+                offset += a.distance_to(&b);
+            }
+            if last.coord() == first.coord() {
+                appended.next();
+            }
+        }
+        let start = other
+            .track_points
+            .iter()
+            .flatten()
+            .find_map(|p| p.d)
+            .unwrap_or_default();
+        points.extend(appended.map(|mut p| {
+            p.d = p.d.map(|d| d - start + offset);
+            p
+        }));
+
+        if self.course_points.is_some() || other.course_points.is_some() {
+            let course_points = route.course_points.get_or_insert_with(Vec::new);
+            course_points.extend(other.course_points.iter().flatten().cloned().map(|mut cp| {
+                cp.d = cp.d.map(|d| d - start + offset);
+                cp
+            }));
+        }
+
+        route.recompute_stats();
+        route
+    }
+
+    /// Recompute distance, elevation and extent statistics from track points
+    fn recompute_stats(&mut self) {
+        let Some(points) = self.track_points.as_ref() else {
+            return;
+        };
+
+        self.distance = points.iter().rev().find_map(|p| p.d).or(self.distance);
+
+        if points.iter().any(|p| p.e.is_some()) {
+            let (gain, loss) = crate::analysis::elevation_gain_loss(points);
+            self.elevation_gain = Some(gain);
+            self.elevation_loss = Some(loss);
+        }
+
+        let coords: Vec<_> = points.iter().filter_map(|p| p.coord()).collect();
+        if let (Some(first), Some(last)) = (coords.first(), coords.last()) {
+            self.first_lat = Some(first.lat);
+            self.first_lng = Some(first.lng);
+            self.last_lat = Some(last.lat);
+            self.last_lng = Some(last.lng);
+            self.sw_lat = coords.iter().map(|c| c.lat).reduce(f64::min);
+            self.sw_lng = coords.iter().map(|c| c.lng).reduce(f64::min);
+            self.ne_lat = coords.iter().map(|c| c.lat).reduce(f64::max);
+            self.ne_lng = coords.iter().map(|c| c.lng).reduce(f64::max);
+        }
+    }
+}
+
+impl Route {
+    /// The position at a distance along the route
+    ///
+    /// Interpolated between the surrounding track points, found by binary
+    /// search over their distances, so this is cheap enough to call for every
+    /// GPS fix in a navigation app. Returns `None` if the route has no track
+    /// points or `distance` lies beyond either end.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     None
+    /// );
+    ///
+    /// let route = client.get_route(12345).unwrap();
+    /// let position = 32_000.0;
+    /// let ahead = route.point_at_distance(position + 10_000.0).unwrap();
+    /// println!("In 10 km: {:?} m above sea level", ahead.e);
+    /// ```
+    pub fn point_at_distance(&self, distance: f64) -> Option<TrackPoint> {
+        let points = self.track_points.as_ref()?;
+        let index = points.partition_point(|p| p.d.is_some_and(|d| d < distance));
+        let next = points.get(index)?;
+        if next.d == Some(distance) {
+            return Some(next.clone());
+        }
+        let previous = points.get(index.checked_sub(1)?)?;
+        Some(interpolate_track_point(previous, next, distance))
+    }
+
+    /// The first cue strictly after a distance along the route
+    ///
+    /// Cues are expected to be ordered by distance, as kept by
+    /// [`insert_course_point`](Self::insert_course_point).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     None
+    /// );
+    ///
+    /// let route = client.get_route(12345).unwrap();
+    /// let position = 32_000.0;
+    /// if let Some(cue) = route.next_cue_after(position) {
+    ///     println!("In {:?} m: {:?}", cue.d.map(|d| d - position), cue.n);
+    /// }
+    /// ```
+    pub fn next_cue_after(&self, distance: f64) -> Option<&CoursePoint> {
+        let course_points = self.course_points.as_ref()?;
+        let index = course_points.partition_point(|cp| cp.d.is_some_and(|d| d <= distance));
+        course_points.get(index).filter(|cp| cp.d.is_some())
+    }
+}
+
+/// Linearly interpolate a track point at `distance` between two neighbours
+fn interpolate_track_point(a: &TrackPoint, b: &TrackPoint, distance: f64) -> TrackPoint {
+    let (da, db) = (a.d.unwrap_or_default(), b.d.unwrap_or_default());
+    let ratio = if db > da {
+        (distance - da) / (db - da)
+    } else {
+        0.0
+    };
+    let lerp = |x: Option<f64>, y: Option<f64>| match (x, y) {
+        (Some(x), Some(y)) => Some(x + (y - x) * ratio),
+        (x, y) => x.or(y),
+    };
+
+    TrackPoint {
+        x: lerp(a.x, b.x),
+        y: lerp(a.y, b.y),
+        d: Some(distance),
+        e: lerp(a.e, b.e),
+        surface: if ratio < 0.5 { a.surface } else { b.surface },
+        highway: if ratio < 0.5 { a.highway } else { b.highway },
+    }
+}
+
+/// Request and response body of the route tag endpoints
+#[derive(Deserialize, Serialize)]
+struct TagsWrapper {
+    tag_names: Vec<String>,
+}
+
+impl TagsWrapper {
+    fn new(tags: &[&str]) -> Self {
+        Self {
+            tag_names: tags.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+}
+
 impl RideWithGpsClient {
     /// List routes for the authenticated user
     ///
@@ -255,10 +783,7 @@ impl RideWithGpsClient {
     ///     Some("your-auth-token")
     /// );
     ///
-    /// let params = ListRoutesParams {
-    ///     min_distance: Some(10000.0), // 10km
-    ///     ..Default::default()
-    /// };
+    /// let params = ListRoutesParams::new().min_distance_km(10);
     ///
     /// let routes = client.list_routes(Some(&params)).unwrap();
     /// println!("Found {} routes", routes.results.len());
@@ -282,7 +807,89 @@ impl RideWithGpsClient {
             }
         }
 
-        self.get(&url)
+        self.get_path(&url)
+    }
+
+    /// List routes within a bounding box
+    ///
+    /// Intended for map-driven UIs that need the routes visible in the current
+    /// viewport. Pages are fetched until the results are exhausted or
+    /// `max_results` routes have been collected, so a zoomed-out viewport
+    /// cannot trigger an unbounded number of requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `sw` - South-west corner of the bounding box
+    /// * `ne` - North-east corner of the bounding box
+    /// * `params` - Optional additional filters; `page` sets the first page to fetch
+    /// * `max_results` - Maximum number of routes to return
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{LatLng, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let routes = client
+    ///     .list_routes_in_bbox(
+    ///         LatLng::new(45.4, -122.8),
+    ///         LatLng::new(45.6, -122.5),
+    ///         None,
+    ///         200,
+    ///     )
+    ///     .unwrap();
+    /// println!("{} routes in view", routes.len());
+    /// ```
+    pub fn list_routes_in_bbox(
+        &self,
+        sw: LatLng,
+        ne: LatLng,
+        params: Option<&ListRoutesParams>,
+        max_results: usize,
+    ) -> Result<Vec<Route>> {
+        let mut query = match params {
+            Some(params) => serde_json::to_value(params)?,
+            None => serde_json::json!({}),
+        };
+        let obj = query
+            .as_object_mut()
+            .ok_or_else(|| crate::Error::ApiError("Invalid query parameters".to_string()))?;
+        obj.insert("sw_lat".to_string(), sw.lat.into());
+        obj.insert("sw_lng".to_string(), sw.lng.into());
+        obj.insert("ne_lat".to_string(), ne.lat.into());
+        obj.insert("ne_lng".to_string(), ne.lng.into());
+
+        let mut page = params.and_then(|p| p.page).unwrap_or(1);
+        let mut routes = Vec::new();
+
+        while routes.len() < max_results {
+            obj.insert("page".to_string(), page.into());
+            let query_str = serde_urlencoded::to_string(&*obj)
+                .map_err(|e| crate::Error::ApiError(format!("Failed to encode query: {}", e)))?;
+            let response: PaginatedResponse<Route> =
+                self.get_path(&format!("/api/v1/routes.json?{}", query_str))?;
+
+            let exhausted = response.results.is_empty()
+                || response
+                    .pagination
+                    .page_count
+                    .map_or(response.pagination.next_page_url.is_none(), |count| {
+                        u64::from(page) >= count
+                    });
+            routes.extend(response.results);
+            if exhausted {
+                break;
+            }
+            page += 1;
+        }
+
+        routes.truncate(max_results);
+        Ok(routes)
     }
 
     /// Get a specific route by ID
@@ -306,13 +913,96 @@ impl RideWithGpsClient {
     /// println!("Route: {:?}", route);
     /// ```
     pub fn get_route(&self, id: u64) -> Result<Route> {
-        #[derive(Deserialize)]
-        struct RouteWrapper {
-            route: Route,
-        }
+        self.get_route_with(id, &RequestOptions::default())
+    }
 
-        let wrapper: RouteWrapper = self.get(&format!("/api/v1/routes/{}.json", id))?;
-        Ok(wrapper.route)
+    /// Get many routes by ID
+    ///
+    /// The routes are fetched a few at a time rather than one by one, which
+    /// makes this suitable for materializing the changes reported by
+    /// [`sync`](Self::sync). Each ID maps to its own result, so one missing
+    /// route does not prevent fetching the others.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The route IDs; duplicates are fetched once
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{RideWithGpsClient, RouteId};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let routes = client.get_routes(&[RouteId(1), RouteId(2), RouteId(3)]);
+    /// for (id, route) in routes {
+    ///     match route {
+    ///         Ok(route) => println!("{}: {:?}", id, route.name),
+    ///         Err(e) => eprintln!("{}: {}", id, e),
+    ///     }
+    /// }
+    /// ```
+    pub fn get_routes(&self, ids: &[RouteId]) -> BTreeMap<RouteId, Result<Route>> {
+        self.get_many(ids)
+    }
+
+    /// Get a specific route by ID, or `None` if it does not exist
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The route ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// match client.get_route_opt(12345).unwrap() {
+    ///     Some(route) => println!("Found: {:?}", route.name),
+    ///     None => println!("No such route"),
+    /// }
+    /// ```
+    pub fn get_route_opt(&self, id: u64) -> Result<Option<Route>> {
+        self.get_route(id).optional()
+    }
+
+    /// Get a specific route by ID, with per-call options
+    ///
+    /// Routes with long tracks can take a while to download, so this is
+    /// useful to allow a longer timeout than usual.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The route ID
+    /// * `options` - Settings overriding the client's defaults for this call
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{RequestOptions, RideWithGpsClient};
+    /// use std::time::Duration;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let options = RequestOptions::new().timeout(Duration::from_secs(120));
+    /// let route = client.get_route_with(12345, &options).unwrap();
+    /// ```
+    pub fn get_route_with(&self, id: u64, options: &RequestOptions) -> Result<Route> {
+        self.get_resource_with::<Route>(id.into(), options)
     }
 
     /// Get the polyline for a specific route
@@ -336,7 +1026,69 @@ impl RideWithGpsClient {
     /// println!("Polyline: {}", polyline.polyline);
     /// ```
     pub fn get_route_polyline(&self, id: u64) -> Result<Polyline> {
-        self.get(&format!("/api/v1/routes/{}/polyline.json", id))
+        self.get_path(&format!("/api/v1/routes/{}/polyline.json", id))
+    }
+
+    /// Create a new route
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - The route data
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{RideWithGpsClient, RouteRequest, Visibility};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let route = client.get_route(12345).unwrap();
+    /// let mut request = RouteRequest::from(&route);
+    /// request.name = Some("Copy of my route".to_string());
+    /// request.visibility = Some(Visibility::Private);
+    ///
+    /// let copy = client.create_route(&request).unwrap();
+    /// println!("Created route: {}", copy.id);
+    /// ```
+    pub fn create_route(&self, route: &RouteRequest) -> Result<Route> {
+        self.post_wrapped("/api/v1/routes.json", "route", route)
+    }
+
+    /// Create a new route that is the reverse of an existing one
+    ///
+    /// The route is fetched, reversed with [`Route::reversed`] and uploaded
+    /// as a new route named `"<original name> (reversed)"`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the route to reverse
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let reversed = client.create_reversed_route(12345).unwrap();
+    /// println!("Created reversed route: {}", reversed.id);
+    /// ```
+    pub fn create_reversed_route(&self, id: u64) -> Result<Route> {
+        let route = self.get_route(id)?.reversed();
+        let mut request = RouteRequest::from(&route);
+        request.name = Some(format!(
+            "{} (reversed)",
+            route.name.as_deref().unwrap_or("Route")
+        ));
+        self.create_route(&request)
     }
 
     /// Delete a route
@@ -359,7 +1111,113 @@ impl RideWithGpsClient {
     /// client.delete_route(12345).unwrap();
     /// ```
     pub fn delete_route(&self, id: u64) -> Result<()> {
-        self.delete(&format!("/api/v1/routes/{}.json", id))
+        self.delete::<Route>(id)
+    }
+
+    /// Add tags to a route
+    ///
+    /// Returns the route's tags after the change.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The route ID
+    /// * `tags` - Tags to add; tags the route already has are ignored
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let tags = client.add_route_tags(12345, &["gravel", "commute"]).unwrap();
+    /// println!("Tags: {}", tags.join(", "));
+    /// ```
+    pub fn add_route_tags(&self, id: u64, tags: &[&str]) -> Result<Vec<String>> {
+        let wrapper: TagsWrapper = self.post(
+            &format!("/api/v1/routes/{}/tags.json", id),
+            &TagsWrapper::new(tags),
+        )?;
+        Ok(wrapper.tag_names)
+    }
+
+    /// Remove tags from a route
+    ///
+    /// Returns the route's tags after the change.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The route ID
+    /// * `tags` - Tags to remove; tags the route does not have are ignored
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// client.remove_route_tags(12345, &["commute"]).unwrap();
+    /// ```
+    pub fn remove_route_tags(&self, id: u64, tags: &[&str]) -> Result<Vec<String>> {
+        let url = self
+            .base_url
+            .join(&format!("/api/v1/routes/{}/tags.json", id))?;
+        let body = serde_json::to_vec(&TagsWrapper::new(tags))?;
+        let response = self.execute(reqwest::Method::DELETE, url, Some(body), true)?;
+
+        let wrapper: TagsWrapper = self.handle_response(response)?;
+        Ok(wrapper.tag_names)
+    }
+
+    /// Replace the course points (cues) of a route
+    ///
+    /// # Arguments
+    ///
+    /// * `route_id` - The route ID
+    /// * `course_points` - The complete, ordered list of cues for the route
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let mut route = client.get_route(12345).unwrap();
+    /// route.remove_course_point(0);
+    /// let route = client
+    ///     .update_route_course_points(12345, route.course_points.as_deref().unwrap_or_default())
+    ///     .unwrap();
+    /// println!("Route now has {:?} cues", route.course_points.map(|c| c.len()));
+    /// ```
+    pub fn update_route_course_points(
+        &self,
+        route_id: u64,
+        course_points: &[CoursePoint],
+    ) -> Result<Route> {
+        #[derive(Serialize)]
+        struct CoursePointsRequest<'a> {
+            course_points: &'a [CoursePoint],
+        }
+
+        self.put_wrapped(
+            &format!("/api/v1/routes/{}.json", route_id),
+            "route",
+            &CoursePointsRequest { course_points },
+        )
     }
 }
 
@@ -413,6 +1271,21 @@ mod tests {
         assert!(json.get("min_distance").is_some());
     }
 
+    #[test]
+    fn test_list_routes_params_builder() {
+        let params = ListRoutesParams::new()
+            .name("test")
+            .min_distance_km(50)
+            .tag("gravel")
+            .page_size(100);
+
+        assert_eq!(params.name.as_deref(), Some("test"));
+        assert_eq!(params.tag.as_deref(), Some("gravel"));
+        assert_eq!(params.min_distance, Some(50000.0));
+        assert_eq!(params.page_size, Some(100));
+        assert!(params.visibility.is_none());
+    }
+
     #[test]
     fn test_route_wrapper_deserialization() {
         let json = r#"{
@@ -450,7 +1323,7 @@ mod tests {
         assert_eq!(track_point.y, Some(37.7749));
         assert_eq!(track_point.d, Some(1234.5));
         assert_eq!(track_point.e, Some(100.0));
-        assert_eq!(track_point.surface, Some(2));
+        assert_eq!(track_point.surface, Some(Surface::Unpaved));
         assert_eq!(track_point.highway, Some(3));
     }
 
@@ -469,7 +1342,7 @@ mod tests {
         assert_eq!(course_point.y, Some(37.8));
         assert_eq!(course_point.d, Some(5000.0));
         assert_eq!(course_point.n.as_deref(), Some("Water Stop"));
-        assert_eq!(course_point.t.as_deref(), Some("water"));
+        assert_eq!(course_point.t, Some(CueType::Water));
     }
 
     #[test]
@@ -494,6 +1367,186 @@ mod tests {
         assert_eq!(route.course_points.as_ref().unwrap().len(), 1);
     }
 
+    #[test]
+    fn test_course_point_editing() {
+        let mut route: Route = serde_json::from_str(
+            r#"{
+                "id": 1,
+                "track_points": [
+                    {"x": -122.0, "y": 37.0, "d": 0.0},
+                    {"x": -122.1, "y": 37.1, "d": 1000.0},
+                    {"x": -122.2, "y": 37.2, "d": 2000.0}
+                ],
+                "course_points": [
+                    {"d": 0.0, "t": "generic", "n": "Start"},
+                    {"d": 2000.0, "t": "generic", "n": "End"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let index = route.insert_course_point(CoursePoint::new(900.0, "water", "Fountain"));
+        assert_eq!(index, 1);
+        let cue = &route.course_points.as_ref().unwrap()[1];
+        assert_eq!((cue.x, cue.y), (Some(-122.1), Some(37.1)));
+
+        assert_eq!(route.move_course_point(1, 2500.0), Some(2));
+        let names: Vec<_> = route
+            .course_points
+            .iter()
+            .flatten()
+            .map(|cp| cp.n.as_deref().unwrap())
+            .collect();
+        assert_eq!(names, vec!["Start", "End", "Fountain"]);
+
+        let removed = route.remove_course_point(0).unwrap();
+        assert_eq!(removed.n.as_deref(), Some("Start"));
+        assert!(route.remove_course_point(5).is_none());
+
+        // Cues without a distance do not decide where others go
+        let mut route: Route = serde_json::from_str(
+            r#"{"id": 1, "course_points": [{"d": 0.0}, {"n": "Unplaced"}, {"d": 2000.0}, {"n": "Note"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            route.insert_course_point(CoursePoint::new(1500.0, "water", "Fountain")),
+            1
+        );
+        assert_eq!(
+            route.insert_course_point(CoursePoint::new(2500.0, "food", "Cafe")),
+            4
+        );
+        let mut note = CoursePoint::new(0.0, "generic", "Later");
+        note.d = None;
+        assert_eq!(route.insert_course_point(note), 6);
+    }
+
+    #[test]
+    fn test_reversed_route() {
+        let route: Route = serde_json::from_str(
+            r#"{
+                "id": 1,
+                "distance": 2000.0,
+                "elevation_gain": 100.0,
+                "elevation_loss": 20.0,
+                "first_lat": 37.0,
+                "last_lat": 37.2,
+                "track_points": [
+                    {"x": -122.0, "y": 37.0, "d": 0.0, "e": 10.0},
+                    {"x": -122.1, "y": 37.1, "d": 500.0, "e": 50.0},
+                    {"x": -122.2, "y": 37.2, "d": 2000.0, "e": 90.0}
+                ],
+                "course_points": [
+                    {"d": 500.0, "t": "Slight Left", "n": "Bear left onto Oak St"},
+                    {"d": 1500.0, "t": "right", "n": "Turn right"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let reversed = route.reversed();
+        let points = reversed.track_points.as_ref().unwrap();
+        assert_eq!(points[0].e, Some(90.0));
+        assert_eq!(points[0].d, Some(0.0));
+        assert_eq!(points[1].d, Some(1500.0));
+        assert_eq!(points[2].d, Some(2000.0));
+
+        let cues = reversed.course_points.as_ref().unwrap();
+        assert_eq!(cues[0].d, Some(500.0));
+        assert_eq!(cues[0].t, Some(CueType::Left));
+        assert_eq!(cues[1].d, Some(1500.0));
+        assert_eq!(cues[1].t, Some(CueType::SlightRight));
+
+        assert_eq!(reversed.elevation_gain, Some(20.0));
+        assert_eq!(reversed.elevation_loss, Some(100.0));
+        assert_eq!(reversed.first_lat, Some(37.2));
+        assert_eq!(reversed.last_lat, Some(37.0));
+    }
+
+    fn straight_route() -> Route {
+        serde_json::from_str(
+            r#"{
+                "id": 1,
+                "track_points": [
+                    {"x": 0.0, "y": 0.0, "d": 0.0, "e": 0.0},
+                    {"x": 0.0, "y": 0.01, "d": 1000.0, "e": 100.0},
+                    {"x": 0.0, "y": 0.02, "d": 2000.0, "e": 50.0}
+                ],
+                "course_points": [
+                    {"d": 0.0, "n": "Start"},
+                    {"d": 1500.0, "n": "Halfway"}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_split_at_distance() {
+        let (first, second) = straight_route().split_at_distance(500.0).unwrap();
+
+        let first_points = first.track_points.as_ref().unwrap();
+        assert_eq!(first_points.len(), 2);
+        assert_eq!(first_points[1].d, Some(500.0));
+        assert_eq!(first_points[1].e, Some(50.0));
+        assert_eq!(first.distance, Some(500.0));
+        assert_eq!(first.elevation_gain, Some(50.0));
+        assert_eq!(first.course_points.as_ref().unwrap().len(), 1);
+
+        let second_points = second.track_points.as_ref().unwrap();
+        assert_eq!(second_points.len(), 3);
+        assert_eq!(second_points[0].d, Some(0.0));
+        assert_eq!(second_points[2].d, Some(1500.0));
+        assert_eq!(second.distance, Some(1500.0));
+        assert_eq!(second.elevation_gain, Some(50.0));
+        assert_eq!(second.elevation_loss, Some(50.0));
+        assert_eq!(second.course_points.as_ref().unwrap()[0].d, Some(1000.0));
+
+        assert!(straight_route().split_at_distance(0.0).is_none());
+        assert!(straight_route().split_at_distance(2000.0).is_none());
+        assert!(straight_route().split_at_distance(5000.0).is_none());
+    }
+
+    #[test]
+    fn test_split_and_concat_roundtrip() {
+        let route = straight_route();
+        let (first, second) = route.split_at_distance(1000.0).unwrap();
+        let joined = first.concat(&second);
+
+        let points = joined.track_points.as_ref().unwrap();
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[2].d, Some(2000.0));
+        assert_eq!(joined.distance, Some(2000.0));
+        assert_eq!(joined.elevation_gain, Some(100.0));
+        assert_eq!(joined.course_points.as_ref().unwrap()[1].d, Some(1500.0));
+        assert_eq!(joined.ne_lat, Some(0.02));
+    }
+
+    #[test]
+    fn test_lookup_by_distance() {
+        let route = straight_route();
+
+        let point = route.point_at_distance(1250.0).unwrap();
+        assert_eq!(point.d, Some(1250.0));
+        assert_eq!(point.e, Some(87.5));
+        assert_eq!(route.point_at_distance(1000.0).unwrap().e, Some(100.0));
+        assert_eq!(route.point_at_distance(0.0).unwrap().e, Some(0.0));
+        assert!(route.point_at_distance(-1.0).is_none());
+        assert!(route.point_at_distance(2000.5).is_none());
+
+        let next = |d| route.next_cue_after(d).and_then(|cp| cp.n.as_deref());
+        assert_eq!(next(-1.0), Some("Start"));
+        assert_eq!(next(0.0), Some("Halfway"));
+        assert_eq!(next(1500.0), None);
+    }
+
+    #[test]
+    fn test_mirror_cue_type() {
+        assert_eq!(CueType::from("left").mirrored(), CueType::Right);
+        assert_eq!(CueType::from("Sharp Right").mirrored(), CueType::SharpLeft);
+        assert_eq!(CueType::from("water").mirrored(), CueType::Water);
+    }
+
     #[test]
     fn test_photo_deserialization() {
         let json = r#"{
@@ -0,0 +1,276 @@
+//! Segment-related types and methods
+
+use crate::{PaginatedResponse, Result, RideWithGpsClient, TrackPoint};
+use serde::{Deserialize, Serialize};
+
+/// A segment (a named stretch of road or trail with a leaderboard)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Segment {
+    /// Segment ID
+    pub id: u64,
+
+    /// Segment name
+    pub name: Option<String>,
+
+    /// Segment description
+    pub description: Option<String>,
+
+    /// Distance in meters
+    pub distance: Option<f64>,
+
+    /// Elevation gain in meters
+    pub elevation_gain: Option<f64>,
+
+    /// Elevation loss in meters
+    pub elevation_loss: Option<f64>,
+
+    /// Average grade in percent
+    pub avg_grade: Option<f64>,
+
+    /// Maximum grade in percent
+    pub max_grade: Option<f64>,
+
+    /// User ID of the segment creator
+    pub user_id: Option<u64>,
+
+    /// API URL
+    pub url: Option<String>,
+
+    /// HTML/web URL
+    pub html_url: Option<String>,
+
+    /// Created timestamp
+    pub created_at: Option<String>,
+
+    /// Updated timestamp
+    pub updated_at: Option<String>,
+
+    /// Locality/location
+    pub locality: Option<String>,
+
+    /// Administrative area
+    pub administrative_area: Option<String>,
+
+    /// Country code
+    pub country_code: Option<String>,
+
+    /// Start point latitude
+    pub first_lat: Option<f64>,
+
+    /// Start point longitude
+    pub first_lng: Option<f64>,
+
+    /// End point latitude
+    pub last_lat: Option<f64>,
+
+    /// End point longitude
+    pub last_lng: Option<f64>,
+
+    /// Number of recorded efforts
+    pub effort_count: Option<u64>,
+
+    /// Track points (included when fetching a specific segment)
+    pub track_points: Option<Vec<TrackPoint>>,
+}
+
+/// A single ride over a segment
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SegmentEffort {
+    /// Effort ID
+    pub id: u64,
+
+    /// Segment ID
+    pub segment_id: Option<u64>,
+
+    /// Segment name
+    pub segment_name: Option<String>,
+
+    /// Trip ID the effort was recorded on
+    pub trip_id: Option<u64>,
+
+    /// User ID of the rider
+    pub user_id: Option<u64>,
+
+    /// Elapsed time in seconds
+    pub elapsed_time: Option<f64>,
+
+    /// Moving time in seconds
+    pub moving_time: Option<f64>,
+
+    /// Average speed in m/s
+    pub avg_speed: Option<f64>,
+
+    /// Average heart rate (BPM)
+    pub avg_hr: Option<f64>,
+
+    /// Average power (watts)
+    pub avg_watts: Option<f64>,
+
+    /// Index of the first trip track point on the segment
+    pub start_index: Option<u64>,
+
+    /// Index of the last trip track point on the segment
+    pub end_index: Option<u64>,
+
+    /// Leaderboard rank
+    pub rank: Option<u32>,
+
+    /// When the effort started
+    pub started_at: Option<String>,
+
+    /// Created timestamp
+    pub created_at: Option<String>,
+}
+
+/// Parameters for listing segment efforts
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
+pub struct ListSegmentEffortsParams {
+    /// Only include efforts by this user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<u64>,
+
+    /// Page number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+
+    /// Page size
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u32>,
+}
+
+setters!(ListSegmentEffortsParams {
+    user_id: u64,
+    page: u32,
+    page_size: u32,
+});
+
+impl RideWithGpsClient {
+    /// Get a specific segment by ID
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The segment ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     None
+    /// );
+    ///
+    /// let segment = client.get_segment(12345).unwrap();
+    /// println!("Segment: {:?}", segment.name);
+    /// ```
+    pub fn get_segment(&self, id: u64) -> Result<Segment> {
+        self.get_wrapped(&format!("/api/v1/segments/{}.json", id), "segment")
+    }
+
+    /// List efforts (leaderboard entries) on a segment
+    ///
+    /// # Arguments
+    ///
+    /// * `segment_id` - The segment ID
+    /// * `params` - Optional parameters for filtering and pagination
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let efforts = client.list_segment_efforts(12345, None).unwrap();
+    /// for effort in efforts.results {
+    ///     println!("#{:?}: {:?} s", effort.rank, effort.elapsed_time);
+    /// }
+    /// ```
+    pub fn list_segment_efforts(
+        &self,
+        segment_id: u64,
+        params: Option<&ListSegmentEffortsParams>,
+    ) -> Result<PaginatedResponse<SegmentEffort>> {
+        let mut url = format!("/api/v1/segments/{}/efforts.json", segment_id);
+
+        if let Some(params) = params {
+            let query = serde_json::to_value(params)?;
+            if let Some(obj) = query.as_object() {
+                if !obj.is_empty() {
+                    let query_str = serde_urlencoded::to_string(obj).map_err(|e| {
+                        crate::Error::ApiError(format!("Failed to encode query: {}", e))
+                    })?;
+                    url.push('?');
+                    url.push_str(&query_str);
+                }
+            }
+        }
+
+        self.get_path(&url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_deserialization() {
+        let json = r#"{
+            "id": 42,
+            "name": "Hawk Hill",
+            "distance": 2400.0,
+            "elevation_gain": 150.0,
+            "avg_grade": 6.2,
+            "effort_count": 10234
+        }"#;
+
+        let segment: Segment = serde_json::from_str(json).unwrap();
+        assert_eq!(segment.id, 42);
+        assert_eq!(segment.name.as_deref(), Some("Hawk Hill"));
+        assert_eq!(segment.avg_grade, Some(6.2));
+        assert_eq!(segment.effort_count, Some(10234));
+    }
+
+    #[test]
+    fn test_segment_effort_deserialization() {
+        let json = r#"{
+            "id": 7,
+            "segment_id": 42,
+            "trip_id": 555,
+            "elapsed_time": 512.0,
+            "avg_watts": 280.0,
+            "start_index": 120,
+            "end_index": 610,
+            "rank": 3
+        }"#;
+
+        let effort: SegmentEffort = serde_json::from_str(json).unwrap();
+        assert_eq!(effort.segment_id, Some(42));
+        assert_eq!(effort.trip_id, Some(555));
+        assert_eq!(effort.elapsed_time, Some(512.0));
+        assert_eq!(effort.rank, Some(3));
+    }
+
+    #[test]
+    fn test_trip_with_segment_efforts() {
+        let json = r#"{
+            "id": 555,
+            "segment_efforts": [
+                {"id": 7, "segment_id": 42, "segment_name": "Hawk Hill"}
+            ]
+        }"#;
+
+        let trip: crate::Trip = serde_json::from_str(json).unwrap();
+        let efforts = trip.segment_efforts.unwrap();
+        assert_eq!(efforts.len(), 1);
+        assert_eq!(efforts[0].segment_name.as_deref(), Some("Hawk Hill"));
+    }
+}
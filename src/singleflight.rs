@@ -0,0 +1,187 @@
+//! Coalescing concurrent identical GET requests
+
+use crate::{RawResponse, RequestOptions, Result};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex};
+
+/// A request in flight, and its response once it has one
+///
+/// The outer option is unset while the request is running; the inner one
+/// is unset if it failed without a response.
+#[derive(Default)]
+struct Call {
+    response: Mutex<Option<Option<RawResponse>>>,
+    done: Condvar,
+}
+
+/// Requests currently in flight, by key
+#[derive(Default)]
+pub(crate) struct InFlight {
+    calls: Mutex<HashMap<String, Arc<Call>>>,
+}
+
+impl InFlight {
+    /// Run `fetch`, or wait for an identical request already running and
+    /// share its response
+    ///
+    /// Waiting callers send their own request if the shared one failed
+    /// without a response.
+    pub(crate) fn run(
+        &self,
+        key: String,
+        fetch: impl FnOnce() -> Result<RawResponse>,
+    ) -> Result<RawResponse> {
+        let mut calls = self.calls.lock().unwrap();
+        if let Some(call) = calls.get(&key).cloned() {
+            drop(calls);
+            let mut response = call.response.lock().unwrap();
+            while response.is_none() {
+                response = call.done.wait(response).unwrap();
+            }
+            if let Some(Some(raw)) = &*response {
+                return Ok(raw.clone());
+            }
+            drop(response);
+            return fetch();
+        }
+
+        let call = Arc::new(Call::default());
+        calls.insert(key.clone(), call.clone());
+        drop(calls);
+
+        let leader = Leader {
+            inflight: self,
+            key,
+            call,
+        };
+        let result = fetch();
+        *leader.call.response.lock().unwrap() = Some(result.as_ref().ok().cloned());
+        drop(leader);
+        result
+    }
+}
+
+/// Key under which a GET shares its response with identical requests
+///
+/// `credentials` are the API key and auth token sent with the request, if
+/// any. They are hashed so the table of requests in flight does not hold
+/// them in the clear.
+pub(crate) fn request_key(
+    credentials: Option<(&str, Option<&str>)>,
+    options: &RequestOptions,
+    url: &url::Url,
+) -> String {
+    let credentials = credentials.map(|(api_key, auth_token)| {
+        let mut hasher = Sha256::new();
+        hasher.update(api_key);
+        hasher.update([0]);
+        if let Some(auth_token) = auth_token {
+            hasher.update([1]);
+            hasher.update(auth_token);
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>()
+    });
+    format!("{:?} {:?} {}", credentials, options, url)
+}
+
+/// Removes a call when its request finishes, waking the callers waiting on
+/// it even if the request panicked
+struct Leader<'a> {
+    inflight: &'a InFlight,
+    key: String,
+    call: Arc<Call>,
+}
+
+impl Drop for Leader<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut calls) = self.inflight.calls.lock() {
+            calls.remove(&self.key);
+        }
+        if let Ok(mut response) = self.call.response.lock() {
+            response.get_or_insert(None);
+        }
+        self.call.done.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn response(body: &str) -> RawResponse {
+        RawResponse {
+            endpoint: "/api/v1/routes/1.json".to_string(),
+            status: 200,
+            headers: HeaderMap::new(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_concurrent_calls_share_response() {
+        let inflight = InFlight::default();
+        let fetches = AtomicUsize::new(0);
+
+        let bodies: Vec<Vec<u8>> = std::thread::scope(|scope| {
+            let threads: Vec<_> = (0..5)
+                .map(|_| {
+                    scope.spawn(|| {
+                        inflight
+                            .run("GET /api/v1/routes/1.json".to_string(), || {
+                                fetches.fetch_add(1, Ordering::SeqCst);
+                                std::thread::sleep(Duration::from_millis(200));
+                                Ok(response("{}"))
+                            })
+                            .unwrap()
+                            .body
+                    })
+                })
+                .collect();
+            threads.into_iter().map(|t| t.join().unwrap()).collect()
+        });
+
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert!(bodies.iter().all(|b| b == b"{}"));
+        assert!(inflight.calls.lock().unwrap().is_empty());
+
+        // Finished calls are not reused
+        inflight
+            .run("GET /api/v1/routes/1.json".to_string(), || {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                Ok(response("{}"))
+            })
+            .unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_request_key() {
+        let url = url::Url::parse("https://ridewithgps.com/api/v1/users/current.json").unwrap();
+        let options = RequestOptions::default();
+        let key = |credentials| request_key(credentials, &options, &url);
+
+        assert_eq!(
+            key(Some(("key", Some("alice")))),
+            key(Some(("key", Some("alice"))))
+        );
+        assert_ne!(
+            key(Some(("key", Some("alice")))),
+            key(Some(("key", Some("bob"))))
+        );
+        assert_ne!(key(Some(("key", None))), key(Some(("key", Some("")))));
+        assert_ne!(key(Some(("key", None))), key(None));
+        assert!(!key(Some(("key", Some("alice")))).contains("alice"));
+        assert_ne!(
+            key(None),
+            request_key(None, &RequestOptions::new().retry(false), &url)
+        );
+    }
+}
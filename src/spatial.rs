@@ -0,0 +1,236 @@
+//! Grid-based spatial index over the segments of a track
+
+use crate::{LatLng, EARTH_RADIUS};
+use std::collections::HashMap;
+
+/// Index answering "is this point within a distance of the track?" queries
+///
+/// Coordinates are projected onto a local equirectangular plane centered on
+/// the track, which is accurate to well under a percent for the extent of a
+/// route. Each segment is registered in every grid cell its bounding box
+/// overlaps, so a query only has to inspect the cells around the point.
+pub(crate) struct SegmentIndex {
+    cos_lat: f64,
+    cell_size: f64,
+    points: Vec<(f64, f64)>,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+
+    /// Lowest and highest cell coordinates holding any segment
+    bounds: ((i64, i64), (i64, i64)),
+}
+
+impl SegmentIndex {
+    /// Build an index over consecutive coordinates, sized for queries of
+    /// about `radius` meters
+    pub(crate) fn new(coords: &[LatLng], radius: f64) -> Self {
+        let mean_lat = coords.iter().map(|c| c.lat).sum::<f64>() / coords.len().max(1) as f64;
+        let mut index = SegmentIndex {
+            cos_lat: mean_lat.to_radians().cos(),
+            cell_size: radius.max(1.0),
+            points: Vec::new(),
+            cells: HashMap::new(),
+            bounds: ((0, 0), (0, 0)),
+        };
+        index.points = coords.iter().map(|c| index.project(c)).collect();
+        if let Some(&(x, y)) = index.points.first() {
+            let ((min_x, min_y), (max_x, max_y)) = index.points.iter().fold(
+                ((x, y), (x, y)),
+                |((min_x, min_y), (max_x, max_y)), &(x, y)| {
+                    ((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y)))
+                },
+            );
+            index.bounds = (
+                (index.cell(min_x), index.cell(min_y)),
+                (index.cell(max_x), index.cell(max_y)),
+            );
+        }
+
+        for i in 0..index.points.len().saturating_sub(1) {
+            let (a, b) = (index.points[i], index.points[i + 1]);
+            let (min_x, max_x) = (index.cell(a.0.min(b.0)), index.cell(a.0.max(b.0)));
+            let (min_y, max_y) = (index.cell(a.1.min(b.1)), index.cell(a.1.max(b.1)));
+            for x in min_x..=max_x {
+                for y in min_y..=max_y {
+                    index.cells.entry((x, y)).or_default().push(i);
+                }
+            }
+        }
+
+        index
+    }
+
+    /// Whether `point` lies within `radius` meters of any segment
+    pub(crate) fn within(&self, point: &LatLng, radius: f64) -> bool {
+        let p = self.project(point);
+        if self.points.len() == 1 {
+            return distance(p, self.points[0]) <= radius;
+        }
+
+        for x in self.cell(p.0 - radius)..=self.cell(p.0 + radius) {
+            for y in self.cell(p.1 - radius)..=self.cell(p.1 + radius) {
+                let Some(segments) = self.cells.get(&(x, y)) else {
+                    continue;
+                };
+                if segments
+                    .iter()
+                    .any(|&i| segment_distance(p, self.points[i], self.points[i + 1]) <= radius)
+                {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Distance in meters from `point` to the nearest segment, or `None` if
+    /// the track has no points
+    pub(crate) fn distance(&self, point: &LatLng) -> Option<f64> {
+        self.nearest(point).map(|(_, _, d)| d)
+    }
+
+    /// The point of the track nearest to `point`, as the index of its
+    /// segment, the fraction of the way along that segment, and the
+    /// distance in meters; `None` if the track has no points
+    pub(crate) fn nearest(&self, point: &LatLng) -> Option<(usize, f64, f64)> {
+        let p = self.project(point);
+        match self.points.len() {
+            0 => return None,
+            1 => return Some((0, 0.0, distance(p, self.points[0]))),
+            _ => {}
+        }
+        let on_segment = |i: usize| {
+            let (t, d) = segment_projection(p, self.points[i], self.points[i + 1]);
+            (i, t, d)
+        };
+        let closest = |best: Option<(usize, f64, f64)>, candidate: (usize, f64, f64)| match best {
+            Some(best) if best.2 <= candidate.2 => Some(best),
+            _ => Some(candidate),
+        };
+
+        // Search rings of cells of growing size around the point. A segment
+        // up to `ring` cells away is registered in a cell of one of the rings
+        // searched so far, so the search stops once the nearest segment
+        // found is that close, or once the rings cover the whole track.
+        let (cx, cy) = (self.cell(p.0), self.cell(p.1));
+        let ((min_x, min_y), (max_x, max_y)) = self.bounds;
+        // Rings nearer to the point than the track's bounds hold no segments
+        let mut ring = [min_x - cx, cx - max_x, min_y - cy, cy - max_y, 0]
+            .into_iter()
+            .max()
+            .unwrap_or_default();
+        let mut best = None;
+        loop {
+            for y in (cy - ring).max(min_y)..=(cy + ring).min(max_y) {
+                // Whole rows at the top and bottom, only the ends in between
+                let xs = if y == cy - ring || y == cy + ring {
+                    ((cx - ring).max(min_x)..=(cx + ring).min(max_x)).step_by(1)
+                } else {
+                    (cx - ring..=cx + ring).step_by(2 * ring as usize)
+                };
+                best = xs
+                    .filter(|x| (min_x..=max_x).contains(x))
+                    .filter_map(|x| self.cells.get(&(x, y)))
+                    .flatten()
+                    .map(|&i| on_segment(i))
+                    .fold(best, closest);
+            }
+            let covered = cx - ring <= min_x
+                && cx + ring >= max_x
+                && cy - ring <= min_y
+                && cy + ring >= max_y;
+            match best {
+                Some(best) if best.2 <= ring as f64 * self.cell_size => return Some(best),
+                _ if covered => return best,
+                _ => ring += 1,
+            }
+        }
+    }
+
+    fn project(&self, c: &LatLng) -> (f64, f64) {
+        (
+            c.lng.to_radians() * self.cos_lat * EARTH_RADIUS,
+            c.lat.to_radians() * EARTH_RADIUS,
+        )
+    }
+
+    fn cell(&self, v: f64) -> i64 {
+        (v / self.cell_size).floor() as i64
+    }
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+/// Distance from `p` to the segment from `a` to `b`
+fn segment_distance(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    segment_projection(p, a, b).1
+}
+
+/// The point of the segment from `a` to `b` nearest to `p`, as the fraction
+/// of the way from `a` to `b`, and its distance from `p`
+fn segment_projection(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_sq = dx * dx + dy * dy;
+    if length_sq == 0.0 {
+        return (0.0, distance(p, a));
+    }
+    let t = (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_sq).clamp(0.0, 1.0);
+    (t, distance(p, (a.0 + t * dx, a.1 + t * dy)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_within() {
+        // About 1.1 km due east along the equator
+        let track = [LatLng::new(0.0, 0.0), LatLng::new(0.0, 0.01)];
+        let index = SegmentIndex::new(&track, 50.0);
+
+        assert!(index.within(&LatLng::new(0.0003, 0.005), 50.0));
+        assert!(!index.within(&LatLng::new(0.001, 0.005), 50.0));
+        assert!(!index.within(&LatLng::new(0.0, 0.011), 50.0));
+    }
+
+    #[test]
+    fn test_distance() {
+        let track = [LatLng::new(0.0, 0.0), LatLng::new(0.0, 0.01)];
+        let index = SegmentIndex::new(&track, 50.0);
+
+        let near = index.distance(&LatLng::new(0.0003, 0.005)).unwrap();
+        assert!((near - 33.4).abs() < 0.5, "{}", near);
+        let far = index.distance(&LatLng::new(0.01, 0.005)).unwrap();
+        assert!((far - 1112.0).abs() < 5.0, "{}", far);
+        assert_eq!(SegmentIndex::new(&[], 50.0).distance(&track[0]), None);
+
+        let (segment, t, _) = index.nearest(&LatLng::new(0.01, 0.0025)).unwrap();
+        assert_eq!(segment, 0);
+        assert!((t - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_nearest_matches_scan() {
+        // A zigzag of a few kilometers, with small cells
+        let track: Vec<LatLng> = (0..40)
+            .map(|i| LatLng::new((i % 5) as f64 * 0.003, i as f64 * 0.001))
+            .collect();
+        let index = SegmentIndex::new(&track, 20.0);
+
+        for (lat, lng) in [
+            (0.0, 0.0),
+            (0.005, 0.02),
+            (-0.05, 0.01),
+            (0.2, -0.3),
+            (0.01, 0.1),
+        ] {
+            let p = index.project(&LatLng::new(lat, lng));
+            let scan = (0..track.len() - 1)
+                .map(|i| segment_distance(p, index.points[i], index.points[i + 1]))
+                .fold(f64::INFINITY, f64::min);
+            let (_, _, d) = index.nearest(&LatLng::new(lat, lng)).unwrap();
+            assert!((d - scan).abs() < 1e-6, "{} != {}", d, scan);
+        }
+    }
+}
@@ -0,0 +1,309 @@
+//! Static map images of routes, stitched from basemap tiles
+//!
+//! Requires the `staticmap` feature.
+
+use crate::svg::{mercator, TILE_SIZE};
+use crate::{Error, LatLng, PointOfInterest, Result, Route};
+use tiny_skia::{
+    Color, FillRule, LineCap, LineJoin, Paint, PathBuilder, Pixmap, PixmapPaint, Stroke, Transform,
+};
+
+/// Highest zoom level chosen automatically
+const MAX_ZOOM: u8 = 17;
+
+/// Renders a route and points of interest onto a map image
+///
+/// Tiles are fetched from any slippy map tile server serving 256 pixel PNG
+/// tiles. Check the usage policy of the server you use; the default
+/// OpenStreetMap server is fine for occasional reports, but not for bulk
+/// rendering.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ridewithgps_client::{RideWithGpsClient, StaticMap};
+///
+/// let client = RideWithGpsClient::new(
+///     "https://ridewithgps.com",
+///     "your-api-key",
+///     None
+/// );
+///
+/// let route = client.get_route(12345).unwrap();
+/// let pois = route.points_of_interest.clone().unwrap_or_default();
+/// let png = StaticMap::new(800, 600).render(&route, &pois).unwrap();
+/// std::fs::write("route.png", png).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct StaticMap {
+    tile_url: String,
+    width: u32,
+    height: u32,
+    padding: u32,
+    zoom: Option<u8>,
+    line_color: [u8; 3],
+    line_width: f32,
+    client: reqwest::blocking::Client,
+}
+
+impl StaticMap {
+    /// OpenStreetMap's standard tile layer
+    pub const OSM_TILE_URL: &'static str = "https://tile.openstreetmap.org/{z}/{x}/{y}.png";
+
+    /// Create a renderer for images of `width` by `height` pixels
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            tile_url: Self::OSM_TILE_URL.to_string(),
+            width,
+            height,
+            padding: 20,
+            zoom: None,
+            line_color: [0xe8, 0x43, 0x1d],
+            line_width: 4.0,
+            client: reqwest::blocking::Client::builder()
+                .user_agent(concat!("ridewithgps-client/", env!("CARGO_PKG_VERSION")))
+                .build()
+                .expect("Failed to build HTTP client"),
+        }
+    }
+
+    /// Fetch tiles from another server, with `{z}`, `{x}` and `{y}` in the
+    /// template replaced by the tile coordinates
+    pub fn tile_url(mut self, template: impl Into<String>) -> Self {
+        self.tile_url = template.into();
+        self
+    }
+
+    /// Minimum margin in pixels between the route and the image border when
+    /// choosing the zoom level (default 20)
+    pub fn padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Use a fixed zoom level instead of the highest one that fits the route
+    pub fn zoom(mut self, zoom: u8) -> Self {
+        self.zoom = Some(zoom);
+        self
+    }
+
+    /// Color of the route line (default RideWithGPS orange)
+    pub fn line_color(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.line_color = [r, g, b];
+        self
+    }
+
+    /// Width of the route line in pixels (default 4)
+    pub fn line_width(mut self, width: f32) -> Self {
+        self.line_width = width;
+        self
+    }
+
+    /// Render the route and points of interest as a PNG image
+    ///
+    /// The map is centered on the route, or on the points of interest if
+    /// the route has no track points.
+    pub fn render(&self, route: &Route, pois: &[PointOfInterest]) -> Result<Vec<u8>> {
+        self.render_with(route, pois, |z, x, y| self.fetch_tile(z, x, y))
+    }
+
+    fn fetch_tile(&self, z: u8, x: u32, y: u32) -> Result<Pixmap> {
+        let url = self
+            .tile_url
+            .replace("{z}", &z.to_string())
+            .replace("{x}", &x.to_string())
+            .replace("{y}", &y.to_string());
+        let response = self.client.get(&url).send()?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(Error::ApiError(format!(
+                "Tile request {} failed with {}",
+                url, status
+            )));
+        }
+        Pixmap::decode_png(&response.bytes()?)
+            .map_err(|e| Error::ApiError(format!("Invalid tile image {}: {}", url, e)))
+    }
+
+    /// Render with tiles from `tile`, called with the zoom, column and row
+    fn render_with(
+        &self,
+        route: &Route,
+        pois: &[PointOfInterest],
+        mut tile: impl FnMut(u8, u32, u32) -> Result<Pixmap>,
+    ) -> Result<Vec<u8>> {
+        let track: Vec<LatLng> = route
+            .track_points
+            .iter()
+            .flatten()
+            .filter_map(|p| p.coord())
+            .collect();
+        let markers: Vec<LatLng> = pois.iter().filter_map(|p| p.coord()).collect();
+        let extent = if track.is_empty() { &markers } else { &track };
+        if extent.is_empty() {
+            return Err(Error::ValidationError(
+                "Nothing to draw: the route has no track points".to_string(),
+            ));
+        }
+
+        let zoom = self.zoom.unwrap_or_else(|| self.fit_zoom(extent));
+        let (min_x, min_y, max_x, max_y) = bounds(extent, zoom);
+        let left = (min_x + max_x) / 2.0 - f64::from(self.width) / 2.0;
+        let top = (min_y + max_y) / 2.0 - f64::from(self.height) / 2.0;
+
+        let mut pixmap = Pixmap::new(self.width, self.height)
+            .ok_or_else(|| Error::ValidationError("Image size must not be zero".to_string()))?;
+        pixmap.fill(Color::from_rgba8(0xee, 0xee, 0xee, 0xff));
+
+        let tiles = 1i64 << zoom;
+        let first_column = (left / TILE_SIZE).floor() as i64;
+        let last_column = ((left + f64::from(self.width)) / TILE_SIZE).floor() as i64;
+        let first_row = (top / TILE_SIZE).floor().max(0.0) as i64;
+        let last_row = (((top + f64::from(self.height)) / TILE_SIZE).floor() as i64).min(tiles - 1);
+        for row in first_row..=last_row {
+            for column in first_column..=last_column {
+                let image = tile(zoom, column.rem_euclid(tiles) as u32, row as u32)?;
+                pixmap.draw_pixmap(
+                    (column as f64 * TILE_SIZE - left).round() as i32,
+                    (row as f64 * TILE_SIZE - top).round() as i32,
+                    image.as_ref(),
+                    &PixmapPaint::default(),
+                    Transform::identity(),
+                    None,
+                );
+            }
+        }
+
+        let to_pixel = |c: &LatLng| {
+            let (x, y) = mercator(c, zoom);
+            ((x - left) as f32, (y - top) as f32)
+        };
+
+        let mut line = PathBuilder::new();
+        for (i, (x, y)) in track.iter().map(to_pixel).enumerate() {
+            if i == 0 {
+                line.move_to(x, y);
+            } else {
+                line.line_to(x, y);
+            }
+        }
+        if let Some(path) = line.finish() {
+            let mut paint = Paint::default();
+            let [r, g, b] = self.line_color;
+            paint.set_color_rgba8(r, g, b, 0xff);
+            paint.anti_alias = true;
+            let stroke = Stroke {
+                width: self.line_width,
+                line_cap: LineCap::Round,
+                line_join: LineJoin::Round,
+                ..Stroke::default()
+            };
+            pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+        }
+
+        let mut fill = Paint::default();
+        fill.set_color_rgba8(0x1f, 0x6f, 0xd1, 0xff);
+        fill.anti_alias = true;
+        let mut outline = Paint::default();
+        outline.set_color_rgba8(0xff, 0xff, 0xff, 0xff);
+        outline.anti_alias = true;
+        let border = Stroke {
+            width: 2.0,
+            ..Stroke::default()
+        };
+        for (x, y) in markers.iter().map(to_pixel) {
+            if let Some(circle) = PathBuilder::from_circle(x, y, 6.0) {
+                let identity = Transform::identity();
+                pixmap.fill_path(&circle, &fill, FillRule::Winding, identity, None);
+                pixmap.stroke_path(&circle, &outline, &border, identity, None);
+            }
+        }
+
+        pixmap
+            .encode_png()
+            .map_err(|e| Error::ApiError(format!("Failed to encode PNG: {}", e)))
+    }
+
+    /// Highest zoom level at which `coords` fit inside the padded image
+    fn fit_zoom(&self, coords: &[LatLng]) -> u8 {
+        let width = f64::from(self.width.saturating_sub(2 * self.padding));
+        let height = f64::from(self.height.saturating_sub(2 * self.padding));
+        (0..=MAX_ZOOM)
+            .rev()
+            .find(|&zoom| {
+                let (min_x, min_y, max_x, max_y) = bounds(coords, zoom);
+                max_x - min_x <= width && max_y - min_y <= height
+            })
+            .unwrap_or(0)
+    }
+}
+
+/// Pixel bounding box of coordinates at a zoom level, as (min x, min y,
+/// max x, max y)
+fn bounds(coords: &[LatLng], zoom: u8) -> (f64, f64, f64, f64) {
+    coords.iter().map(|c| mercator(c, zoom)).fold(
+        (
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ),
+        |(min_x, min_y, max_x, max_y), (x, y)| {
+            (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route() -> Route {
+        serde_json::from_str(
+            r#"{"id": 1, "track_points": [{"x": 4.89, "y": 52.37}, {"x": 4.95, "y": 52.37}]}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_fit_zoom() {
+        let map = StaticMap::new(400, 300);
+        let coords: Vec<_> = route()
+            .track_points
+            .unwrap()
+            .iter()
+            .filter_map(|p| p.coord())
+            .collect();
+
+        // 0.06° of longitude is about 350 px at zoom 13
+        assert_eq!(map.fit_zoom(&coords), 13);
+        assert_eq!(map.padding(190).fit_zoom(&coords), 8);
+    }
+
+    #[test]
+    fn test_render_with() {
+        let map = StaticMap::new(200, 100).zoom(12).line_color(255, 0, 0);
+        let mut requested = Vec::new();
+        let png = map
+            .render_with(&route(), &[], |z, x, y| {
+                requested.push((z, x, y));
+                let mut tile = Pixmap::new(256, 256).unwrap();
+                tile.fill(Color::WHITE);
+                Ok(tile)
+            })
+            .unwrap();
+
+        assert!(requested.iter().all(|&(z, _, _)| z == 12));
+        assert!(requested.contains(&(12, 2103, 1346)));
+
+        let image = Pixmap::decode_png(&png).unwrap();
+        assert_eq!((image.width(), image.height()), (200, 100));
+        let center = image.pixel(100, 50).unwrap();
+        assert_eq!((center.red(), center.green(), center.blue()), (255, 0, 0));
+        let corner = image.pixel(0, 0).unwrap();
+        assert_eq!(
+            (corner.red(), corner.green(), corner.blue()),
+            (255, 255, 255)
+        );
+    }
+}
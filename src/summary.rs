@@ -0,0 +1,168 @@
+//! One-line human-readable summaries of routes, trips, events and collections
+
+use crate::{format, Collection, Event, Meters, Route, Seconds, Trip, UnitSystem};
+use std::fmt;
+
+fn distance(meters: f64, units: UnitSystem) -> String {
+    format::distance(Meters(meters), units)
+}
+
+fn elevation(meters: f64, units: UnitSystem) -> String {
+    format!("{} ↑", format::elevation(Meters(meters), units))
+}
+
+fn duration(seconds: f64) -> String {
+    let minutes = Seconds(seconds).minutes().round() as u64;
+    if minutes >= 60 {
+        format!("{}h{:02}", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+fn join(title: String, parts: Vec<String>) -> String {
+    if parts.is_empty() {
+        title
+    } else {
+        format!("{} — {}", title, parts.join(", "))
+    }
+}
+
+impl Route {
+    /// One-line summary such as "Hawk Hill loop — 42.3 km, 512 m ↑"
+    pub fn summary(&self, units: UnitSystem) -> String {
+        let title = self
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("Route {}", self.id));
+        let parts = [
+            self.distance.map(|d| distance(d, units)),
+            self.elevation_gain.map(|e| elevation(e, units)),
+        ];
+        join(title, parts.into_iter().flatten().collect())
+    }
+}
+
+impl Trip {
+    /// One-line summary such as "Morning Ride — 42.3 km, 512 m ↑, 1h42"
+    ///
+    /// The duration shown is the moving time if known, otherwise the total
+    /// duration.
+    pub fn summary(&self, units: UnitSystem) -> String {
+        let title = self
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("Trip {}", self.id));
+        let parts = [
+            self.distance.map(|d| distance(d, units)),
+            self.elevation_gain.map(|e| elevation(e, units)),
+            self.moving_time.or(self.duration).map(duration),
+        ];
+        join(title, parts.into_iter().flatten().collect())
+    }
+}
+
+impl Event {
+    /// One-line summary such as "Gran Fondo — 2025-06-01T09:00:00, Portland, OR"
+    pub fn summary(&self) -> String {
+        let title = self
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("Event {}", self.id));
+        let parts = [
+            self.starts_at.clone().or_else(|| self.start_date.clone()),
+            self.location.clone(),
+        ];
+        join(title, parts.into_iter().flatten().collect())
+    }
+}
+
+impl Collection {
+    /// One-line summary such as "Favorite climbs — 15 routes"
+    pub fn summary(&self) -> String {
+        let title = self
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("Collection {}", self.id));
+        let route_count = self
+            .route_count
+            .map(|n| n as usize)
+            .or_else(|| self.routes.as_ref().map(Vec::len));
+        let trip_count = self.trips.as_ref().map(Vec::len).filter(|&n| n > 0);
+        let plural =
+            |n: usize, what: &str| format!("{} {}{}", n, what, if n == 1 { "" } else { "s" });
+        let parts = [
+            route_count.map(|n| plural(n, "route")),
+            trip_count.map(|n| plural(n, "trip")),
+        ];
+        join(title, parts.into_iter().flatten().collect())
+    }
+}
+
+impl fmt::Display for Route {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.summary(UnitSystem::Metric))
+    }
+}
+
+impl fmt::Display for Trip {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.summary(UnitSystem::Metric))
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.summary())
+    }
+}
+
+impl fmt::Display for Collection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.summary())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trip_summary() {
+        let trip: Trip = serde_json::from_str(
+            r#"{
+                "id": 1,
+                "name": "Morning Ride",
+                "distance": 42300.0,
+                "elevation_gain": 512.0,
+                "moving_time": 6120.0
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(trip.to_string(), "Morning Ride — 42.3 km, 512 m ↑, 1h42");
+        assert_eq!(
+            trip.summary(UnitSystem::Imperial),
+            "Morning Ride — 26.3 mi, 1680 ft ↑, 1h42"
+        );
+    }
+
+    #[test]
+    fn test_route_summary_without_details() {
+        let route: Route = serde_json::from_str(r#"{"id": 7}"#).unwrap();
+        assert_eq!(route.to_string(), "Route 7");
+    }
+
+    #[test]
+    fn test_event_and_collection_summaries() {
+        let event: Event = serde_json::from_str(
+            r#"{"id": 3, "name": "Gran Fondo", "start_date": "2025-06-01", "location": "Portland, OR"}"#,
+        )
+        .unwrap();
+        assert_eq!(event.to_string(), "Gran Fondo — 2025-06-01, Portland, OR");
+
+        let collection: Collection =
+            serde_json::from_str(r#"{"id": 4, "name": "Climbs", "route_count": 1}"#).unwrap();
+        assert_eq!(collection.to_string(), "Climbs — 1 route");
+    }
+}
@@ -0,0 +1,372 @@
+//! Road surface classification of route track points
+
+use crate::Route;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Surface type of a track point, decoded from RideWithGPS's numeric `S` code
+///
+/// Codes this crate does not know are kept in [`Surface::Other`], so they
+/// round-trip unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(from = "i32", into = "i32")]
+pub enum Surface {
+    /// Surface not known (code 0)
+    Unknown,
+
+    /// Asphalt or concrete (code 1)
+    Paved,
+
+    /// Unpaved, without further detail (code 2)
+    Unpaved,
+
+    /// Gravel or compacted gravel (code 3)
+    Gravel,
+
+    /// Dirt or earth (code 4)
+    Dirt,
+
+    /// Grass (code 5)
+    Grass,
+
+    /// Sand (code 6)
+    Sand,
+
+    /// Cobblestones or paving stones (code 7)
+    Cobblestone,
+
+    /// Any other code
+    Other(i32),
+}
+
+impl Surface {
+    /// Whether the surface counts as paved, or `None` if it is not known
+    pub fn is_paved(&self) -> Option<bool> {
+        match self {
+            Surface::Paved | Surface::Cobblestone => Some(true),
+            Surface::Unpaved | Surface::Gravel | Surface::Dirt | Surface::Grass | Surface::Sand => {
+                Some(false)
+            }
+            Surface::Unknown | Surface::Other(_) => None,
+        }
+    }
+}
+
+impl From<i32> for Surface {
+    fn from(code: i32) -> Self {
+        match code {
+            0 => Surface::Unknown,
+            1 => Surface::Paved,
+            2 => Surface::Unpaved,
+            3 => Surface::Gravel,
+            4 => Surface::Dirt,
+            5 => Surface::Grass,
+            6 => Surface::Sand,
+            7 => Surface::Cobblestone,
+            code => Surface::Other(code),
+        }
+    }
+}
+
+impl From<Surface> for i32 {
+    fn from(surface: Surface) -> Self {
+        match surface {
+            Surface::Unknown => 0,
+            Surface::Paved => 1,
+            Surface::Unpaved => 2,
+            Surface::Gravel => 3,
+            Surface::Dirt => 4,
+            Surface::Grass => 5,
+            Surface::Sand => 6,
+            Surface::Cobblestone => 7,
+            Surface::Other(code) => code,
+        }
+    }
+}
+
+/// A stretch of a route along a single road class (highway code)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SurfaceSegment {
+    /// Distance along the route where the segment starts, in meters
+    pub start: f64,
+
+    /// Distance along the route where the segment ends, in meters
+    pub end: f64,
+
+    /// Highway code (`R`) of the segment
+    pub highway: Option<i32>,
+
+    /// Distance per surface within the segment, in meters
+    pub surfaces: BTreeMap<Surface, f64>,
+
+    /// Percentage of the segment's known-surface distance that is unpaved
+    pub unpaved_pct: Option<f64>,
+}
+
+/// A contiguous unpaved stretch of a route, from [`Route::unpaved_segments`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnpavedSegment {
+    /// Distance along the route where the segment starts, in meters
+    pub start: f64,
+
+    /// Distance along the route where the segment ends, in meters
+    pub end: f64,
+
+    /// Surface of the segment
+    pub surface: Surface,
+}
+
+impl UnpavedSegment {
+    /// Length of the segment in meters
+    pub fn length(&self) -> f64 {
+        self.end - self.start
+    }
+}
+
+/// Distance per surface type along a route, from [`Route::surface_breakdown`]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SurfaceBreakdown {
+    /// Distance per surface over the whole route, in meters
+    pub totals: BTreeMap<Surface, f64>,
+
+    /// Percentage of the route's known-surface distance that is unpaved
+    pub unpaved_pct: Option<f64>,
+
+    /// Consecutive stretches sharing the same highway code
+    pub segments: Vec<SurfaceSegment>,
+}
+
+/// Percentage of unpaved distance among the distance with a known surface
+fn unpaved_pct(surfaces: &BTreeMap<Surface, f64>) -> Option<f64> {
+    let (mut paved, mut unpaved) = (0.0, 0.0);
+    for (surface, distance) in surfaces {
+        match surface.is_paved() {
+            Some(true) => paved += distance,
+            Some(false) => unpaved += distance,
+            None => {}
+        }
+    }
+    let known = paved + unpaved;
+    (known > 0.0).then(|| unpaved / known * 100.0)
+}
+
+impl Route {
+    /// Aggregate the per-point surface and highway codes into distances
+    ///
+    /// Each interval between consecutive track points is attributed to the
+    /// surface and highway code of its first point; points without a surface
+    /// count as [`Surface::Unknown`]. Points without a distance are skipped.
+    /// Returns an empty breakdown if the route has no track points.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{RideWithGpsClient, Surface};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     None
+    /// );
+    ///
+    /// let route = client.get_route(12345).unwrap();
+    /// let breakdown = route.surface_breakdown();
+    /// println!("Unpaved: {:?} %", breakdown.unpaved_pct);
+    /// for (surface, meters) in &breakdown.totals {
+    ///     println!("{:?}: {:.1} km", surface, meters / 1000.0);
+    /// }
+    /// ```
+    pub fn surface_breakdown(&self) -> SurfaceBreakdown {
+        let mut breakdown = SurfaceBreakdown::default();
+        let points: Vec<_> = self
+            .track_points
+            .iter()
+            .flatten()
+            .filter(|p| p.d.is_some())
+            .collect();
+
+        for pair in points.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let (start, end) = (a.d.unwrap_or_default(), b.d.unwrap_or_default());
+            let length = (end - start).max(0.0);
+            let surface = a.surface.unwrap_or(Surface::Unknown);
+
+            *breakdown.totals.entry(surface).or_default() += length;
+
+            match breakdown.segments.last_mut() {
+                Some(segment) if segment.highway == a.highway => segment.end = end,
+                _ => breakdown.segments.push(SurfaceSegment {
+                    start,
+                    end,
+                    highway: a.highway,
+                    surfaces: BTreeMap::new(),
+                    unpaved_pct: None,
+                }),
+            }
+            if let Some(segment) = breakdown.segments.last_mut() {
+                *segment.surfaces.entry(surface).or_default() += length;
+            }
+        }
+
+        breakdown.unpaved_pct = unpaved_pct(&breakdown.totals);
+        for segment in &mut breakdown.segments {
+            segment.unpaved_pct = unpaved_pct(&segment.surfaces);
+        }
+
+        breakdown
+    }
+
+    /// List the unpaved stretches of the route, such as gravel sectors
+    ///
+    /// Intervals between track points are attributed to the surface of their
+    /// first point, as in [`surface_breakdown`](Self::surface_breakdown).
+    /// Consecutive intervals on the same unpaved surface are merged into one
+    /// segment; a change to another unpaved surface starts a new one.
+    /// Surfaces that are not known are not counted as unpaved.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     None
+    /// );
+    ///
+    /// let route = client.get_route(12345).unwrap();
+    /// let sectors = route.unpaved_segments();
+    /// for sector in &sectors {
+    ///     println!(
+    ///         "{:?} from km {:.1} to {:.1}",
+    ///         sector.surface,
+    ///         sector.start / 1000.0,
+    ///         sector.end / 1000.0
+    ///     );
+    /// }
+    /// let total: f64 = sectors.iter().map(|s| s.length()).sum();
+    /// println!("{:.1} km unpaved", total / 1000.0);
+    /// ```
+    pub fn unpaved_segments(&self) -> Vec<UnpavedSegment> {
+        let points: Vec<_> = self
+            .track_points
+            .iter()
+            .flatten()
+            .filter(|p| p.d.is_some())
+            .collect();
+
+        let mut segments: Vec<UnpavedSegment> = Vec::new();
+        let mut previous_unpaved = false;
+        for pair in points.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let surface = a.surface.unwrap_or(Surface::Unknown);
+            if surface.is_paved() != Some(false) {
+                previous_unpaved = false;
+                continue;
+            }
+            let (start, end) = (a.d.unwrap_or_default(), b.d.unwrap_or_default());
+            match segments.last_mut() {
+                Some(segment) if previous_unpaved && segment.surface == surface => {
+                    segment.end = end
+                }
+                _ => segments.push(UnpavedSegment {
+                    start,
+                    end,
+                    surface,
+                }),
+            }
+            previous_unpaved = true;
+        }
+
+        segments
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_surface_codes_round_trip() {
+        let surfaces: Vec<Surface> = serde_json::from_str("[0, 1, 3, 42]").unwrap();
+        assert_eq!(
+            surfaces,
+            vec![
+                Surface::Unknown,
+                Surface::Paved,
+                Surface::Gravel,
+                Surface::Other(42)
+            ]
+        );
+        assert_eq!(serde_json::to_string(&surfaces).unwrap(), "[0,1,3,42]");
+    }
+
+    #[test]
+    fn test_surface_breakdown() {
+        let route: Route = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "track_points": [
+                {"d": 0.0, "S": 1, "R": 1},
+                {"d": 300.0, "S": 3, "R": 1},
+                {"d": 400.0, "S": 1, "R": 2},
+                {"d": 1000.0, "S": 0, "R": 2},
+                {"d": 1200.0}
+            ]
+        }))
+        .unwrap();
+
+        let breakdown = route.surface_breakdown();
+        assert_eq!(breakdown.totals[&Surface::Paved], 900.0);
+        assert_eq!(breakdown.totals[&Surface::Gravel], 100.0);
+        assert_eq!(breakdown.totals[&Surface::Unknown], 200.0);
+        assert_eq!(breakdown.unpaved_pct, Some(10.0));
+
+        assert_eq!(breakdown.segments.len(), 2);
+        assert_eq!(breakdown.segments[0].end, 400.0);
+        assert_eq!(breakdown.segments[0].unpaved_pct, Some(25.0));
+        assert_eq!(breakdown.segments[1].start, 400.0);
+        assert_eq!(breakdown.segments[1].unpaved_pct, Some(0.0));
+    }
+
+    #[test]
+    fn test_unpaved_segments() {
+        let route: Route = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "track_points": [
+                {"d": 0.0, "S": 1},
+                {"d": 100.0, "S": 3},
+                {"d": 250.0, "S": 3},
+                {"d": 400.0, "S": 4},
+                {"d": 500.0, "S": 0},
+                {"d": 600.0, "S": 3},
+                {"d": 700.0, "S": 1},
+                {"d": 800.0}
+            ]
+        }))
+        .unwrap();
+
+        let segments = route.unpaved_segments();
+        assert_eq!(
+            segments,
+            vec![
+                UnpavedSegment {
+                    start: 100.0,
+                    end: 400.0,
+                    surface: Surface::Gravel
+                },
+                UnpavedSegment {
+                    start: 400.0,
+                    end: 500.0,
+                    surface: Surface::Dirt
+                },
+                UnpavedSegment {
+                    start: 600.0,
+                    end: 700.0,
+                    surface: Surface::Gravel
+                },
+            ]
+        );
+        assert_eq!(segments.iter().map(|s| s.length()).sum::<f64>(), 500.0);
+    }
+}
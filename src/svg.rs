@@ -0,0 +1,303 @@
+//! SVG rendering of route geometry
+
+use crate::gpx::escape;
+use crate::{LatLng, Route, TrackPoint};
+use std::f64::consts::PI;
+use std::fmt::Write;
+
+/// Size in pixels of a Web Mercator tile, and of the whole world at zoom 0
+pub(crate) const TILE_SIZE: f64 = 256.0;
+
+/// Default stroke color, in the orange-red RideWithGPS uses for routes
+const DEFAULT_COLOR: &str = "#e8431d";
+
+/// Default stroke width in pixels
+const DEFAULT_STROKE_WIDTH: f64 = 3.0;
+
+/// How coordinates are mapped to SVG pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    /// Scale the route to fit a `width` by `height` pixel box, keeping its
+    /// aspect ratio and leaving `padding` pixels on every side
+    Fit {
+        /// Width of the box in pixels
+        width: f64,
+
+        /// Height of the box in pixels
+        height: f64,
+
+        /// Margin around the route in pixels
+        padding: f64,
+    },
+
+    /// Absolute Web Mercator pixel coordinates at a zoom level, matching
+    /// the usual 256 pixel slippy map tiles, for overlays on a basemap
+    WebMercator {
+        /// Zoom level
+        zoom: u8,
+    },
+}
+
+/// How the route is colored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SvgColoring {
+    /// A single color
+    #[default]
+    Solid,
+
+    /// By climbing gradient, from green (under 3%) through yellow, orange and
+    /// red to dark red (12% and steeper); descents are colored as flat
+    Gradient,
+
+    /// Gray on paved and brown on unpaved surfaces
+    Surface,
+}
+
+/// Styling of [`Route::to_svg_path`]
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct SvgStyle {
+    /// Stroke color as a CSS color, used for the whole route with
+    /// [`SvgColoring::Solid`] and wherever the gradient or surface is unknown
+    pub color: Option<String>,
+
+    /// Stroke width in pixels (default 3)
+    pub stroke_width: Option<f64>,
+
+    /// How to color the route (default solid)
+    pub coloring: Option<SvgColoring>,
+}
+
+setters!(SvgStyle {
+    color: impl Into<String>,
+    stroke_width: f64,
+    coloring: SvgColoring,
+});
+
+/// Web Mercator pixel coordinates of a position at a zoom level
+pub(crate) fn mercator(coord: &LatLng, zoom: u8) -> (f64, f64) {
+    let size = TILE_SIZE * f64::from(1u32 << zoom);
+    let lat = coord.lat.clamp(-85.0511, 85.0511).to_radians();
+    let x = (coord.lng + 180.0) / 360.0 * size;
+    let y = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0 * size;
+    (x, y)
+}
+
+impl Projection {
+    /// Project coordinates into SVG pixels
+    fn apply(&self, coords: &[LatLng]) -> Vec<(f64, f64)> {
+        match *self {
+            Projection::WebMercator { zoom } => coords.iter().map(|c| mercator(c, zoom)).collect(),
+            Projection::Fit {
+                width,
+                height,
+                padding,
+            } => {
+                let points: Vec<_> = coords.iter().map(|c| mercator(c, 0)).collect();
+                let min_x = points.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+                let max_x = points.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+                let min_y = points.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+                let max_y = points.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+
+                let (inner_w, inner_h) = (width - 2.0 * padding, height - 2.0 * padding);
+                let scale = match (max_x - min_x, max_y - min_y) {
+                    (0.0, 0.0) => 1.0,
+                    (dx, dy) => (inner_w / dx).min(inner_h / dy),
+                };
+                let offset_x = padding + (inner_w - (max_x - min_x) * scale) / 2.0;
+                let offset_y = padding + (inner_h - (max_y - min_y) * scale) / 2.0;
+                points
+                    .iter()
+                    .map(|p| {
+                        (
+                            offset_x + (p.0 - min_x) * scale,
+                            offset_y + (p.1 - min_y) * scale,
+                        )
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Color of the gradient between two track points, if known
+fn gradient_color(a: &TrackPoint, b: &TrackPoint) -> Option<&'static str> {
+    let run = b.d? - a.d?;
+    if run <= 0.0 {
+        return None;
+    }
+    let grade = (b.e? - a.e?) / run * 100.0;
+    Some(match grade {
+        g if g < 3.0 => "#2ca02c",
+        g if g < 6.0 => "#f2c80f",
+        g if g < 9.0 => "#ff7f0e",
+        g if g < 12.0 => "#d62728",
+        _ => "#7b1113",
+    })
+}
+
+/// Color of the surface of a track point, if known
+fn surface_color(point: &TrackPoint) -> Option<&'static str> {
+    match point.surface?.is_paved()? {
+        true => Some("#555555"),
+        false => Some("#a0522d"),
+    }
+}
+
+impl Route {
+    /// Render the route's track as SVG `<path>` elements
+    ///
+    /// Returns one path for a solid color, or one per run of equally
+    /// colored segments when coloring by gradient or surface. Wrap the result
+    /// in an `<svg>` element, or insert it into an existing drawing. Track
+    /// points without coordinates are skipped; a route without track points
+    /// renders as an empty string.
+    ///
+    /// # Arguments
+    ///
+    /// * `projection` - How to map coordinates to pixels
+    /// * `style` - Stroke color, width and coloring
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{Projection, RideWithGpsClient, SvgColoring, SvgStyle};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     None
+    /// );
+    ///
+    /// let route = client.get_route(12345).unwrap();
+    /// let paths = route.to_svg_path(
+    ///     Projection::Fit { width: 800.0, height: 600.0, padding: 20.0 },
+    ///     &SvgStyle::new().coloring(SvgColoring::Gradient),
+    /// );
+    /// let svg = format!(
+    ///     r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 800 600">{}</svg>"#,
+    ///     paths
+    /// );
+    /// std::fs::write("route.svg", svg).unwrap();
+    /// ```
+    pub fn to_svg_path(&self, projection: Projection, style: &SvgStyle) -> String {
+        let points: Vec<&TrackPoint> = self
+            .track_points
+            .iter()
+            .flatten()
+            .filter(|p| p.coord().is_some())
+            .collect();
+        let coords: Vec<LatLng> = points.iter().filter_map(|p| p.coord()).collect();
+        if coords.is_empty() {
+            return String::new();
+        }
+        let pixels = projection.apply(&coords);
+
+        let default_color = style.color.as_deref().unwrap_or(DEFAULT_COLOR);
+        let coloring = style.coloring.unwrap_or_default();
+        let color = |i: usize| -> &str {
+            let color = match (coloring, i) {
+                (SvgColoring::Solid, _) | (_, 0) => None,
+                (SvgColoring::Gradient, i) => gradient_color(points[i - 1], points[i]),
+                (SvgColoring::Surface, i) => surface_color(points[i - 1]),
+            };
+            color.unwrap_or(default_color)
+        };
+
+        // Runs of consecutive segments sharing a color, as (color, first
+        // point, last point)
+        let mut runs: Vec<(&str, usize, usize)> = Vec::new();
+        for i in 1..pixels.len() {
+            match runs.last_mut() {
+                Some((run_color, _, end)) if *run_color == color(i) => *end = i,
+                _ => runs.push((color(i), i - 1, i)),
+            }
+        }
+        if runs.is_empty() {
+            runs.push((default_color, 0, 0));
+        }
+
+        let stroke_width = style.stroke_width.unwrap_or(DEFAULT_STROKE_WIDTH);
+        let mut svg = String::new();
+        for (color, start, end) in runs {
+            let mut d = String::new();
+            for (i, (x, y)) in pixels[start..=end].iter().enumerate() {
+                let command = if i == 0 { 'M' } else { 'L' };
+                let _ = write!(d, "{}{:.1} {:.1} ", command, x, y);
+            }
+            let _ = write!(
+                svg,
+                r#"<path d="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linecap="round" stroke-linejoin="round"/>"#,
+                d.trim_end(),
+                escape(color),
+                stroke_width
+            );
+        }
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route() -> Route {
+        serde_json::from_str(
+            r#"{
+                "id": 1,
+                "track_points": [
+                    {"x": 0.0, "y": 0.0, "d": 0, "e": 0, "S": 1},
+                    {"x": 0.01, "y": 0.0, "d": 1000, "e": 10, "S": 1},
+                    {"x": 0.02, "y": 0.0, "d": 2000, "e": 150, "S": 3},
+                    {"x": 0.02, "y": 0.01, "d": 3000, "e": 50, "S": 3}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mercator() {
+        let (x, y) = mercator(&LatLng::new(0.0, 0.0), 1);
+        assert!((x - 256.0).abs() < 1e-9 && (y - 256.0).abs() < 1e-9);
+        let (x, _) = mercator(&LatLng::new(0.0, 180.0), 0);
+        assert!((x - 256.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_to_svg_path_fit() {
+        let projection = Projection::Fit {
+            width: 200.0,
+            height: 200.0,
+            padding: 10.0,
+        };
+        let svg = route().to_svg_path(projection, &SvgStyle::new().color("black"));
+
+        assert_eq!(svg.matches("<path").count(), 1);
+        assert!(svg.contains(r#"d="M10.0 145.0 L100.0 145.0 L190.0 145.0 L190.0 55.0""#));
+        assert!(svg.contains(r#"stroke="black" stroke-width="3""#));
+
+        let svg = route().to_svg_path(projection, &SvgStyle::new().color(r#"red" onload="x"#));
+        assert!(svg.contains(r#"stroke="red&quot; onload=&quot;x""#));
+    }
+
+    #[test]
+    fn test_to_svg_path_colored() {
+        let projection = Projection::WebMercator { zoom: 12 };
+        let route = route();
+
+        let gradient =
+            route.to_svg_path(projection, &SvgStyle::new().coloring(SvgColoring::Gradient));
+        let colors: Vec<_> = gradient
+            .split(r#"stroke=""#)
+            .skip(1)
+            .map(|s| &s[..7])
+            .collect();
+        assert_eq!(colors, vec!["#2ca02c", "#7b1113", "#2ca02c"]);
+
+        let surface =
+            route.to_svg_path(projection, &SvgStyle::new().coloring(SvgColoring::Surface));
+        assert_eq!(surface.matches("<path").count(), 2);
+        assert!(surface.contains("#555555") && surface.contains("#a0522d"));
+    }
+}
@@ -1,8 +1,10 @@
 //! Sync-related types and methods
 
 use crate::{Result, RideWithGpsClient};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
 
 /// Types of items that can be synchronized
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
@@ -22,7 +24,7 @@ pub enum ItemType {
 }
 
 /// A synchronized item
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct SyncItem {
     /// Item ID
     pub id: u64,
@@ -47,11 +49,93 @@ pub struct SyncResponse {
     pub server_datetime: DateTime<Utc>,
 }
 
+/// Where [`RideWithGpsClient::sync_with_store`] continues from
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SyncState {
+    /// Server datetime of the last sync
+    pub since: DateTime<Utc>,
+
+    /// Items already handled that changed within the overlap before `since`
+    #[serde(default)]
+    pub recent: Vec<SyncItem>,
+}
+
+/// Storage for a [`SyncState`] between runs
+///
+/// Implement it on top of whatever the application already persists to,
+/// such as a database table; [`FileSyncStateStore`] keeps it in a file.
+pub trait SyncStateStore {
+    /// The saved state, or `None` before the first sync
+    fn load(&self) -> Result<Option<SyncState>>;
+
+    /// Replace the saved state
+    fn save(&self, state: &SyncState) -> Result<()>;
+}
+
+/// A [`SyncStateStore`] keeping the state in a JSON file
+#[derive(Debug, Clone)]
+pub struct FileSyncStateStore {
+    path: PathBuf,
+}
+
+impl FileSyncStateStore {
+    /// Keep the state in the file at `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl SyncStateStore for FileSyncStateStore {
+    fn load(&self) -> Result<Option<SyncState>> {
+        match std::fs::read(&self.path) {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, state: &SyncState) -> Result<()> {
+        // Write a new file and rename it over the old one, so a crash
+        // never leaves a truncated state behind
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(state)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Settings for [`RideWithGpsClient::sync_with_store`]
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct SyncOptions {
+    /// Ask for changes this long before the last sync as well, to catch
+    /// changes the server recorded late; items already handled are skipped
+    pub overlap: Option<Duration>,
+
+    /// Handle at most this span of the change history per call, starting
+    /// at the oldest change, so a store that is far behind catches up in
+    /// bounded batches over several calls
+    pub window: Option<Duration>,
+
+    /// Where to start when the store is empty, instead of the beginning of
+    /// the account's history
+    pub initial_since: Option<DateTime<Utc>>,
+}
+
+setters!(SyncOptions {
+    overlap: Duration,
+    window: Duration,
+    initial_since: DateTime<Utc>,
+});
+
 impl RideWithGpsClient {
     /// Get items that have changed since a specific datetime
     ///
     /// This endpoint is useful for efficiently synchronizing a local library
-    /// with the server by only fetching items that have changed.
+    /// with the server by only fetching items that have changed. Pass the
+    /// `server_datetime` of one response as `since` of the next, rather than
+    /// a time from the local clock, which may be off; or let
+    /// [`sync_with_store`](Self::sync_with_store) keep track of it.
     ///
     /// # Arguments
     ///
@@ -88,7 +172,94 @@ impl RideWithGpsClient {
             "/api/v1/sync.json?since={}",
             urlencoding::encode(&since_str)
         );
-        self.get(&url)
+        self.get_path(&url)
+    }
+
+    /// Fetch the changes since the last sync recorded in `store`, and hand
+    /// them to `handle`
+    ///
+    /// The next sync starts from the `server_datetime` of this one, so the
+    /// local clock is never used. The store is only updated once `handle`
+    /// has succeeded; if it fails, the same changes are fetched again next
+    /// time. With an [`overlap`](SyncOptions::overlap), items already handled
+    /// in the overlap are left out. With a [`window`](SyncOptions::window),
+    /// only the changes within the window after the oldest one are handled,
+    /// and the next sync starts at the end of the window; the window is
+    /// measured in server time as well. Returns the changes that were
+    /// handled.
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - Where the sync state is kept between runs
+    /// * `options` - Overlap, window and starting point
+    /// * `handle` - Called once with the new changes
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{FileSyncStateStore, RideWithGpsClient, SyncOptions};
+    /// use std::time::Duration;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let store = FileSyncStateStore::new("sync-state.json");
+    /// let options = SyncOptions::new().overlap(Duration::from_secs(300));
+    /// client
+    ///     .sync_with_store(&store, &options, |items| {
+    ///         for item in items {
+    ///             println!("{:?} {} changed", item.item_type, item.id);
+    ///         }
+    ///         Ok(())
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn sync_with_store(
+        &self,
+        store: &dyn SyncStateStore,
+        options: &SyncOptions,
+        handle: impl FnOnce(&[SyncItem]) -> Result<()>,
+    ) -> Result<SyncResponse> {
+        let overlap = options.overlap.map_or(TimeDelta::zero(), |o| {
+            TimeDelta::from_std(o).unwrap_or(TimeDelta::MAX)
+        });
+        let state = store.load()?;
+        let since = match &state {
+            Some(state) => state.since.checked_sub_signed(overlap).unwrap_or_default(),
+            None => options.initial_since.unwrap_or_default(),
+        };
+
+        let mut response = self.sync(&since)?;
+        let previous = state.map(|s| s.recent).unwrap_or_default();
+        response.items.retain(|item| !previous.contains(item));
+
+        let mut next_since = response.server_datetime;
+        let oldest = response.items.iter().map(|item| item.updated_at).min();
+        if let (Some(window), Some(oldest)) = (options.window, oldest) {
+            let end = TimeDelta::from_std(window)
+                .ok()
+                .and_then(|window| oldest.checked_add_signed(window));
+            if let Some(end) = end.filter(|end| *end < next_since) {
+                response.items.retain(|item| item.updated_at <= end);
+                next_since = end;
+            }
+        }
+        handle(&response.items)?;
+
+        let cutoff = next_since.checked_sub_signed(overlap);
+        let recent = previous
+            .into_iter()
+            .chain(response.items.iter().cloned())
+            .filter(|item| !overlap.is_zero() && cutoff.is_none_or(|c| item.updated_at >= c))
+            .collect();
+        store.save(&SyncState {
+            since: next_since,
+            recent,
+        })?;
+        Ok(response)
     }
 }
 
@@ -150,4 +321,144 @@ mod tests {
         );
         assert_eq!(serde_json::to_string(&ItemType::Trip).unwrap(), r#""trip""#);
     }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_sync_with_store() {
+        use crate::testing::FakeServer;
+        use std::cell::RefCell;
+
+        #[derive(Default)]
+        struct MemoryStore(RefCell<Option<SyncState>>);
+
+        impl SyncStateStore for MemoryStore {
+            fn load(&self) -> Result<Option<SyncState>> {
+                Ok(self.0.borrow().clone())
+            }
+
+            fn save(&self, state: &SyncState) -> Result<()> {
+                *self.0.borrow_mut() = Some(state.clone());
+                Ok(())
+            }
+        }
+
+        let server = FakeServer::start();
+        let client = server.client();
+        let store = MemoryStore::default();
+        let options = SyncOptions::new().overlap(Duration::from_secs(86400));
+
+        // A failing handler leaves the store alone
+        let result = client.sync_with_store(&store, &options, |_| {
+            Err(crate::Error::ApiError("disk full".to_string()))
+        });
+        assert!(result.is_err());
+        assert!(store.load().unwrap().is_none());
+
+        let first = client
+            .sync_with_store(&store, &options, |_| Ok(()))
+            .unwrap();
+        assert_eq!(first.items.len(), 3);
+        assert!(server
+            .requests()
+            .last()
+            .unwrap()
+            .path
+            .contains("since=1970-01-01T00%3A00%3A00%2B00%3A00"));
+        let state = store.load().unwrap().unwrap();
+        assert_eq!(
+            state.since,
+            Utc.with_ymd_and_hms(2025, 1, 4, 0, 0, 0).unwrap()
+        );
+        // Only the trips changed within a day of the server datetime
+        assert_eq!(state.recent.len(), 2);
+
+        // The overlap asks for the last day again, but skips what was handled
+        server.mock_raw(
+            "GET",
+            "/api/v1/sync.json",
+            200,
+            r#"{"items": [
+                {"id": 201, "item_type": "trip", "updated_at": "2025-01-03T08:00:00Z", "deleted": false},
+                {"id": 199, "item_type": "trip", "updated_at": "2025-01-03T09:00:00Z", "deleted": true},
+                {"id": 202, "item_type": "trip", "updated_at": "2025-01-03T23:59:00Z", "deleted": false}
+            ], "server_datetime": "2025-01-05T00:00:00Z"}"#,
+        );
+        let second = client
+            .sync_with_store(&store, &options, |_| Ok(()))
+            .unwrap();
+        assert_eq!(second.items.len(), 1);
+        assert_eq!(second.items[0].id, 202);
+        assert!(server
+            .requests()
+            .last()
+            .unwrap()
+            .path
+            .contains("since=2025-01-03T00%3A00%3A00%2B00%3A00"));
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_sync_with_store_window() {
+        use crate::testing::FakeServer;
+
+        let dir = std::env::temp_dir().join(format!("rwgps-sync-window-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let store = FileSyncStateStore::new(dir.join("state.json"));
+        let server = FakeServer::start();
+        let client = server.client();
+        let options = SyncOptions::new().window(Duration::from_secs(12 * 3600));
+
+        // The window starts at the oldest change, not at the epoch
+        let first = client
+            .sync_with_store(&store, &options, |_| Ok(()))
+            .unwrap();
+        assert_eq!(first.items.len(), 1);
+        assert_eq!(first.items[0].id, 101);
+        assert_eq!(
+            store.load().unwrap().unwrap().since,
+            Utc.with_ymd_and_hms(2025, 1, 2, 20, 0, 0).unwrap()
+        );
+
+        // The next window starts at the oldest change not handled yet
+        server.mock_raw(
+            "GET",
+            "/api/v1/sync.json",
+            200,
+            r#"{"items": [
+                {"id": 201, "item_type": "trip", "updated_at": "2025-01-03T08:00:00Z", "deleted": false},
+                {"id": 199, "item_type": "trip", "updated_at": "2025-01-03T09:00:00Z", "deleted": true}
+            ], "server_datetime": "2025-01-04T00:00:00Z"}"#,
+        );
+        let second = client
+            .sync_with_store(&store, &options, |_| Ok(()))
+            .unwrap();
+        assert_eq!(second.items.len(), 2);
+        assert!(server
+            .requests()
+            .last()
+            .unwrap()
+            .path
+            .contains("since=2025-01-02T20%3A00%3A00%2B00%3A00"));
+        assert_eq!(
+            store.load().unwrap().unwrap().since,
+            Utc.with_ymd_and_hms(2025, 1, 3, 20, 0, 0).unwrap()
+        );
+
+        // A window reaching past the server datetime ends there
+        server.mock_raw(
+            "GET",
+            "/api/v1/sync.json",
+            200,
+            r#"{"items": [], "server_datetime": "2025-01-04T00:00:00Z"}"#,
+        );
+        let third = client
+            .sync_with_store(&store, &options, |_| Ok(()))
+            .unwrap();
+        assert!(third.items.is_empty());
+        assert_eq!(
+            store.load().unwrap().unwrap().since,
+            Utc.with_ymd_and_hms(2025, 1, 4, 0, 0, 0).unwrap()
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
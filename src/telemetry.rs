@@ -0,0 +1,109 @@
+//! Reporting failed requests to the application
+
+use crate::{Error, Method};
+use std::fmt;
+use std::sync::Arc;
+
+/// A request attempt that failed, passed to the hook installed with
+/// [`ClientBuilder::on_error`](crate::ClientBuilder::on_error)
+#[derive(Debug)]
+pub struct FailedRequest<'a> {
+    /// HTTP method of the request
+    pub method: &'a Method,
+
+    /// Path of the endpoint, such as `/api/v1/routes/123.json`
+    pub endpoint: &'a str,
+
+    /// HTTP status of the response, or `None` if no response was received
+    pub status: Option<u16>,
+
+    /// Attempt number, counting from 1; higher for retries
+    pub attempt: u32,
+
+    /// Whether the client will retry the request
+    pub retrying: bool,
+
+    /// What went wrong
+    pub error: &'a Error,
+}
+
+/// Callback receiving every failed request attempt
+#[derive(Clone)]
+pub(crate) struct ErrorHook(Arc<dyn Fn(&FailedRequest) + Send + Sync>);
+
+impl ErrorHook {
+    pub(crate) fn new(hook: impl Fn(&FailedRequest) + Send + Sync + 'static) -> Self {
+        ErrorHook(Arc::new(hook))
+    }
+
+    pub(crate) fn call(&self, failed: &FailedRequest) {
+        (self.0)(failed)
+    }
+}
+
+impl fmt::Debug for ErrorHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ErrorHook")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_on_error() {
+        use crate::testing::{FakeServer, FAKE_API_KEY};
+        use crate::{Error, RetryPolicy, RideWithGpsClient};
+        use std::sync::{Arc, Mutex};
+        use std::time::Duration;
+
+        let server = FakeServer::start();
+        server.mock_once(
+            "GET",
+            "/api/v1/routes/{id}.json",
+            503,
+            serde_json::json!({}),
+        );
+        server.mock("GET", "/api/v1/trips/{id}.json", 404, serde_json::json!({}));
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let client = RideWithGpsClient::builder(&server.url(), FAKE_API_KEY)
+            .retry(RetryPolicy::new().initial_backoff(Duration::from_millis(1)))
+            .on_error(move |failed| {
+                log.lock().unwrap().push((
+                    failed.endpoint.to_string(),
+                    failed.status,
+                    failed.attempt,
+                    failed.retrying,
+                    matches!(failed.error, Error::NotFound(_)),
+                ))
+            })
+            .build()
+            .unwrap();
+
+        client.get_route(1).unwrap();
+        assert!(client.get_trip(2).is_err());
+        client.get_event(3).unwrap();
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                (
+                    "/api/v1/routes/1.json".to_string(),
+                    Some(503),
+                    1,
+                    true,
+                    false
+                ),
+                (
+                    "/api/v1/trips/2.json".to_string(),
+                    Some(404),
+                    1,
+                    false,
+                    true
+                ),
+            ]
+        );
+    }
+}
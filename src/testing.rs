@@ -0,0 +1,578 @@
+//! A local fake RideWithGPS server for end-to-end tests
+//!
+//! [`FakeServer`] listens on a random local port and answers the auth, user,
+//! route, trip, event and sync endpoints with realistic canned responses, so
+//! code built on [`RideWithGpsClient`] can be exercised without network
+//! access:
+//!
+//! ```rust
+//! use ridewithgps_client::testing::FakeServer;
+//!
+//! let server = FakeServer::start();
+//! let client = server.client();
+//!
+//! let route = client.get_route(42).unwrap();
+//! assert_eq!(route.id, 42);
+//! ```
+//!
+//! Other endpoints, such as event check-in, reordering collections or bulk
+//! visibility changes, answer `404 Not Found` until given a response with
+//! [`FakeServer::mock`], which also replaces the canned responses. The
+//! requests received are available from [`FakeServer::requests`]. Requires
+//! the `testing` feature.
+
+use crate::RideWithGpsClient;
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+/// API key accepted by [`FakeServer`]; requests without an API key get a 401
+pub const FAKE_API_KEY: &str = "fake-api-key";
+
+/// Auth token handed out by the fake `POST /api/v1/auth_tokens` endpoint
+pub const FAKE_AUTH_TOKEN: &str = "fake-auth-token";
+
+const USER: &str = r#"{"id": 1, "email": "rider@example.com", "name": "Fake Rider", "first_name": "Fake", "last_name": "Rider", "created_at": "2020-01-01T00:00:00Z"}"#;
+
+const ROUTE: &str = r#"{
+    "id": {{id}},
+    "name": "Fake loop",
+    "description": "A canned route served by FakeServer",
+    "distance": 12000.0,
+    "elevation_gain": 150.0,
+    "elevation_loss": 150.0,
+    "visibility": "public",
+    "user_id": 1,
+    "first_lat": 45.5,
+    "first_lng": -122.6,
+    "last_lat": 45.5,
+    "last_lng": -122.6,
+    "created_at": "2025-01-01T08:00:00Z",
+    "updated_at": "2025-01-02T08:00:00Z",
+    "track_points": [
+        {"x": -122.6, "y": 45.5, "d": 0.0, "e": 30.0},
+        {"x": -122.55, "y": 45.53, "d": 6000.0, "e": 180.0},
+        {"x": -122.6, "y": 45.5, "d": 12000.0, "e": 30.0}
+    ],
+    "course_points": [
+        {"x": -122.6, "y": 45.5, "d": 0.0, "t": "generic", "n": "Start"},
+        {"x": -122.55, "y": 45.53, "d": 6000.0, "t": "left", "n": "Turn left at the summit"}
+    ]
+}"#;
+
+const TRIP: &str = r#"{
+    "id": {{id}},
+    "name": "Fake morning ride",
+    "distance": 12100.0,
+    "elevation_gain": 155.0,
+    "elevation_loss": 150.0,
+    "duration": 2700.0,
+    "moving_time": 2400.0,
+    "avg_speed": 5.0,
+    "max_speed": 14.0,
+    "visibility": "private",
+    "user_id": 1,
+    "departed_at": "2025-01-03T07:00:00Z",
+    "created_at": "2025-01-03T08:00:00Z",
+    "updated_at": "2025-01-03T08:00:00Z",
+    "track_points": [
+        {"x": -122.6, "y": 45.5, "d": 0.0, "e": 30.0, "t": 1735887600, "s": 0.0},
+        {"x": -122.55, "y": 45.53, "d": 6050.0, "e": 182.0, "t": 1735888800, "s": 18.0},
+        {"x": -122.6, "y": 45.5, "d": 12100.0, "e": 30.0, "t": 1735890300, "s": 0.0}
+    ]
+}"#;
+
+const EVENT: &str = r#"{
+    "id": {{id}},
+    "name": "Fake gran fondo",
+    "description": "A canned event served by FakeServer",
+    "location": "Portland, OR",
+    "visibility": "public",
+    "starts_at": "2025-06-01T09:00:00Z",
+    "ends_at": "2025-06-01T17:00:00Z",
+    "created_at": "2025-01-01T00:00:00Z",
+    "updated_at": "2025-01-01T00:00:00Z"
+}"#;
+
+/// A request received by [`FakeServer`]
+#[derive(Debug, Clone)]
+pub struct FakeRequest {
+    /// HTTP method
+    pub method: String,
+
+    /// Path, including the query string if any
+    pub path: String,
+
+    /// Request headers, with lowercase names
+    pub headers: Vec<(String, String)>,
+
+    /// Request body
+    pub body: Vec<u8>,
+}
+
+impl FakeRequest {
+    /// The value of a header, looked up case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_ascii_lowercase();
+        self.headers
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The body parsed as JSON
+    pub fn json(&self) -> Option<Value> {
+        serde_json::from_slice(&self.body).ok()
+    }
+}
+
+/// A canned response for requests matching a method and path pattern
+#[derive(Debug, Clone)]
+struct Fixture {
+    method: String,
+    pattern: String,
+    status: u16,
+    body: String,
+    once: bool,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    fixtures: Vec<Fixture>,
+    requests: Vec<FakeRequest>,
+}
+
+/// A local HTTP server imitating the RideWithGPS API
+///
+/// The server stops when dropped.
+pub struct FakeServer {
+    addr: SocketAddr,
+    shared: Arc<Mutex<Shared>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FakeServer {
+    /// Start a server with the default fixtures on a random local port
+    ///
+    /// # Panics
+    ///
+    /// Panics if no local port can be bound.
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind fake server");
+        let addr = listener.local_addr().expect("fake server has no address");
+        let shared = Arc::new(Mutex::new(Shared {
+            fixtures: default_fixtures(),
+            requests: Vec::new(),
+        }));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let shared = shared.clone();
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        // A malformed request only affects its own connection
+                        let _ = handle_connection(stream, &shared);
+                    }
+                }
+            })
+        };
+
+        Self {
+            addr,
+            shared,
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    /// Base URL of the server, for [`RideWithGpsClient::new`]
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Address the server listens on, for
+    /// [`ClientBuilder::resolve`](crate::ClientBuilder::resolve)
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// A client pointed at this server, authenticated with the fake API key
+    /// and auth token
+    pub fn client(&self) -> RideWithGpsClient {
+        RideWithGpsClient::new(&self.url(), FAKE_API_KEY, Some(FAKE_AUTH_TOKEN))
+    }
+
+    /// Serve `body` with `status` for requests matching `method` and `path`
+    ///
+    /// `path` is matched against the request path without its query string;
+    /// `{id}` matches a numeric path segment (or part of one, as in
+    /// `/api/v1/routes/{id}.json`), and every `{{id}}` in the body is
+    /// replaced with the matched ID. Mocks take precedence over the default
+    /// fixtures and over earlier mocks for the same endpoint.
+    pub fn mock(&self, method: &str, path: &str, status: u16, body: Value) {
+        self.mock_raw(method, path, status, &body.to_string());
+    }
+
+    /// Like [`mock`](Self::mock), but with a raw response body
+    pub fn mock_raw(&self, method: &str, path: &str, status: u16, body: &str) {
+        self.shared.lock().unwrap().fixtures.push(Fixture {
+            method: method.to_ascii_uppercase(),
+            pattern: path.to_string(),
+            status,
+            body: body.to_string(),
+            once: false,
+        });
+    }
+
+    /// Like [`mock`](Self::mock), but only for the next matching request
+    ///
+    /// Later requests fall through to the earlier mocks and fixtures, which
+    /// makes it possible to test how the client handles transient failures.
+    pub fn mock_once(&self, method: &str, path: &str, status: u16, body: Value) {
+        self.shared.lock().unwrap().fixtures.push(Fixture {
+            method: method.to_ascii_uppercase(),
+            pattern: path.to_string(),
+            status,
+            body: body.to_string(),
+            once: true,
+        });
+    }
+
+    /// Requests received so far, oldest first
+    pub fn requests(&self) -> Vec<FakeRequest> {
+        self.shared.lock().unwrap().requests.clone()
+    }
+}
+
+impl Drop for FakeServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wake up the accept loop so it notices the stop flag
+        let _ = TcpStream::connect(self.addr);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn default_fixtures() -> Vec<Fixture> {
+    let list = |item: &str, ids: &[u64]| {
+        let items: Vec<String> = ids
+            .iter()
+            .map(|id| item.replace("{{id}}", &id.to_string()))
+            .collect();
+        format!(
+            r#"{{"results": [{}], "record_count": {}, "page_count": 1, "page_size": 20, "next_page_url": null}}"#,
+            items.join(","),
+            ids.len()
+        )
+    };
+    let wrap = |name: &str, item: &str| format!(r#"{{"{}": {}}}"#, name, item);
+    let polyline = |parent: &str| {
+        format!(
+            r#"{{"polyline": "_p~iF~ps|U_ulLnnqC_mqNvxq`@", "parent_type": "{}", "parent_id": {{{{id}}}}}}"#,
+            parent
+        )
+    };
+
+    let fixtures = [
+        (
+            "POST",
+            "/api/v1/auth_tokens",
+            201,
+            format!(
+                r#"{{"auth_token": "{}", "user_id": 1, "user": {}}}"#,
+                FAKE_AUTH_TOKEN, USER
+            ),
+        ),
+        ("GET", "/api/v1/auth_tokens.json", 200, list(r#"{"id": {{id}}, "token_prefix": "fake", "name": "FakeServer"}"#, &[1])),
+        ("DELETE", "/api/v1/auth_tokens/current.json", 204, String::new()),
+        ("DELETE", "/api/v1/auth_tokens/{id}.json", 204, String::new()),
+        ("GET", "/api/v1/ping.json", 200, r#"{"status": "ok"}"#.to_string()),
+        ("GET", "/api/v1/users/current.json", 200, wrap("user", USER)),
+        ("GET", "/api/v1/routes.json", 200, list(ROUTE, &[101, 102])),
+        ("POST", "/api/v1/routes.json", 201, wrap("route", &ROUTE.replace("{{id}}", "103"))),
+        ("GET", "/api/v1/routes/{id}.json", 200, wrap("route", ROUTE)),
+        ("PUT", "/api/v1/routes/{id}.json", 200, wrap("route", ROUTE)),
+        ("DELETE", "/api/v1/routes/{id}.json", 204, String::new()),
+        ("GET", "/api/v1/routes/{id}/polyline.json", 200, polyline("route")),
+        ("GET", "/api/v1/trips.json", 200, list(TRIP, &[201, 202])),
+        ("GET", "/api/v1/trips/{id}.json", 200, wrap("trip", TRIP)),
+        ("DELETE", "/api/v1/trips/{id}.json", 204, String::new()),
+        ("GET", "/api/v1/trips/{id}/polyline.json", 200, polyline("trip")),
+        ("GET", "/api/v1/events.json", 200, list(EVENT, &[301])),
+        ("GET", "/api/v1/organizations/{id}/events.json", 200, list(EVENT, &[303])),
+        ("POST", "/api/v1/events.json", 201, wrap("event", &EVENT.replace("{{id}}", "302"))),
+        ("GET", "/api/v1/events/{id}.json", 200, wrap("event", EVENT)),
+        ("PUT", "/api/v1/events/{id}.json", 200, wrap("event", EVENT)),
+        ("DELETE", "/api/v1/events/{id}.json", 204, String::new()),
+        (
+            "GET",
+            "/api/v1/sync.json",
+            200,
+            r#"{"items": [
+                {"id": 101, "item_type": "route", "updated_at": "2025-01-02T08:00:00Z", "deleted": false},
+                {"id": 201, "item_type": "trip", "updated_at": "2025-01-03T08:00:00Z", "deleted": false},
+                {"id": 199, "item_type": "trip", "updated_at": "2025-01-03T09:00:00Z", "deleted": true}
+            ], "server_datetime": "2025-01-04T00:00:00Z"}"#
+                .to_string(),
+        ),
+    ];
+
+    fixtures
+        .into_iter()
+        .map(|(method, pattern, status, body)| Fixture {
+            method: method.to_string(),
+            pattern: pattern.to_string(),
+            status,
+            body,
+            once: false,
+        })
+        .collect()
+}
+
+/// Match a path against a pattern, returning the `{id}` value if any
+fn match_path(pattern: &str, path: &str) -> Option<Option<String>> {
+    let pattern_segments: Vec<&str> = pattern.split('/').collect();
+    let path_segments: Vec<&str> = path.split('/').collect();
+    if pattern_segments.len() != path_segments.len() {
+        return None;
+    }
+
+    let mut id = None;
+    for (pattern, segment) in pattern_segments.iter().zip(&path_segments) {
+        match pattern.split_once("{id}") {
+            Some((prefix, suffix)) => {
+                let value = segment.strip_prefix(prefix)?.strip_suffix(suffix)?;
+                if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                id = Some(value.to_string());
+            }
+            None if pattern == segment => {}
+            None => return None,
+        }
+    }
+    Some(id)
+}
+
+fn handle_connection(stream: TcpStream, shared: &Mutex<Shared>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    let content_length = headers
+        .iter()
+        .find(|(name, _)| name == "content-length")
+        .and_then(|(_, value)| value.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let request = FakeRequest {
+        method,
+        path,
+        headers,
+        body,
+    };
+    let (status, body) = respond(&request, shared);
+
+    let reason = match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        _ => "Unknown",
+    };
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+fn respond(request: &FakeRequest, shared: &Mutex<Shared>) -> (u16, String) {
+    let mut shared = shared.lock().unwrap();
+    shared.requests.push(request.clone());
+
+    let path = request.path.split('?').next().unwrap_or_default();
+
+    // Every endpoint but the health check requires an API key, so tests
+    // notice when it is not sent. Other files, such as photos, are
+    // downloaded without one.
+    let api = path.starts_with("/api/") && path != "/api/v1/ping.json";
+    if request.header("x-rwgps-api-key").is_none() && api {
+        return (401, r#"{"error": "Missing API key"}"#.to_string());
+    }
+
+    let found = shared
+        .fixtures
+        .iter()
+        .enumerate()
+        .rev()
+        .find_map(|(index, fixture)| {
+            if fixture.method != request.method {
+                return None;
+            }
+            let id = match_path(&fixture.pattern, path)?;
+            let body = match id {
+                Some(id) => fixture.body.replace("{{id}}", &id),
+                None => fixture.body.clone(),
+            };
+            Some((index, fixture.status, body))
+        });
+    if let Some((index, _, _)) = found {
+        if shared.fixtures[index].once {
+            shared.fixtures.remove(index);
+        }
+    }
+    let found = found.map(|(_, status, body)| (status, body));
+
+    found.unwrap_or_else(|| {
+        (
+            404,
+            format!(
+                r#"{{"error": "No fixture for {} {}"}}"#,
+                request.method, path
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, ItemType, ListRoutesParams};
+
+    #[test]
+    fn test_match_path() {
+        assert_eq!(
+            match_path("/api/v1/routes/{id}.json", "/api/v1/routes/42.json"),
+            Some(Some("42".to_string()))
+        );
+        assert_eq!(
+            match_path("/api/v1/routes.json", "/api/v1/routes.json"),
+            Some(None)
+        );
+        assert_eq!(
+            match_path("/api/v1/routes/{id}.json", "/api/v1/routes/pinned.json"),
+            None
+        );
+        assert_eq!(
+            match_path(
+                "/api/v1/routes/{id}.json",
+                "/api/v1/routes/42/polyline.json"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_default_fixtures() {
+        let server = FakeServer::start();
+        let client = server.client();
+
+        let routes = client
+            .list_routes(Some(&ListRoutesParams::new().page(1)))
+            .unwrap();
+        assert_eq!(routes.results.len(), 2);
+
+        let route = client.get_route(42).unwrap();
+        assert_eq!(route.id, 42);
+        assert_eq!(route.track_points.unwrap().len(), 3);
+
+        let trip = client.get_trip(7).unwrap();
+        assert_eq!(trip.moving_time, Some(2400.0));
+
+        assert_eq!(client.get_event(5).unwrap().id, 5);
+        client.delete_event(5).unwrap();
+        let events = client.list_organization_events(9, None).unwrap();
+        assert_eq!(events.results[0].id, 303);
+
+        let changes = client.sync(&chrono::Utc::now()).unwrap();
+        assert_eq!(changes.items[2].item_type, ItemType::Trip);
+
+        let user = client.get_current_user().unwrap();
+        assert_eq!(user.id, 1);
+
+        let requests = server.requests();
+        assert_eq!(requests[1].path, "/api/v1/routes/42.json");
+        assert_eq!(requests[1].header("X-RWGPS-API-KEY"), Some(FAKE_API_KEY));
+    }
+
+    #[test]
+    fn test_fixtures_match_schema() {
+        let server = FakeServer::start();
+        let client = RideWithGpsClient::builder(&server.url(), FAKE_API_KEY)
+            .auth_token(FAKE_AUTH_TOKEN)
+            .strict(true)
+            .build()
+            .unwrap();
+
+        client.list_routes(None).unwrap();
+        client.get_route(1).unwrap();
+        client.get_route_polyline(1).unwrap();
+        client.list_trips(None).unwrap();
+        client.get_trip(1).unwrap();
+        client.list_events(None).unwrap();
+        client.get_event(1).unwrap();
+        client.get_current_user().unwrap();
+        client.list_auth_tokens().unwrap();
+        client.sync(&chrono::Utc::now()).unwrap();
+    }
+
+    #[test]
+    fn test_mock_overrides_fixture() {
+        let server = FakeServer::start();
+        server.mock(
+            "GET",
+            "/api/v1/routes/{id}.json",
+            404,
+            serde_json::json!({"error": "gone"}),
+        );
+
+        let client = server.client();
+        assert!(matches!(client.get_route(1), Err(Error::NotFound(_))));
+    }
+
+    #[test]
+    fn test_login() {
+        let server = FakeServer::start();
+        let client =
+            RideWithGpsClient::with_credentials(&server.url(), FAKE_API_KEY, "a@b.c", "pw")
+                .unwrap();
+        assert_eq!(client.auth_token(), Some(FAKE_AUTH_TOKEN));
+
+        let login = &server.requests()[0];
+        assert_eq!(login.json().unwrap()["password"], "pw");
+    }
+}
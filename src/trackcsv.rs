@@ -0,0 +1,173 @@
+//! CSV export of trip telemetry
+
+use crate::Trip;
+use chrono::{DateTime, SecondsFormat};
+use std::io::Write;
+
+/// Which columns [`Trip::write_telemetry_csv`] writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnSelection {
+    /// Time of the point, in UTC
+    pub time: bool,
+
+    /// Latitude
+    pub lat: bool,
+
+    /// Longitude
+    pub lng: bool,
+
+    /// Elevation in meters
+    pub elevation: bool,
+
+    /// Speed in km/h
+    pub speed: bool,
+
+    /// Heart rate in BPM
+    pub heart_rate: bool,
+
+    /// Cadence in RPM
+    pub cadence: bool,
+
+    /// Power in watts
+    pub power: bool,
+}
+
+impl Default for ColumnSelection {
+    fn default() -> Self {
+        Self {
+            time: true,
+            lat: true,
+            lng: true,
+            elevation: true,
+            speed: true,
+            heart_rate: true,
+            cadence: true,
+            power: true,
+        }
+    }
+}
+
+impl Trip {
+    /// Write the trip's track points as CSV, one row per point
+    ///
+    /// Times are written in RFC 3339 format. Missing readings are left
+    /// empty. The trip must have been fetched with
+    /// [`get_trip`](crate::RideWithGpsClient::get_trip) for the track points
+    /// to be present; otherwise only the header is written.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{ColumnSelection, RideWithGpsClient};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let trip = client.get_trip(12345).unwrap();
+    /// let mut file = std::fs::File::create("trip.csv").unwrap();
+    /// let columns = ColumnSelection {
+    ///     lat: false,
+    ///     lng: false,
+    ///     ..Default::default()
+    /// };
+    /// trip.write_telemetry_csv(&mut file, columns).unwrap();
+    /// ```
+    pub fn write_telemetry_csv<W: Write>(
+        &self,
+        writer: &mut W,
+        columns: ColumnSelection,
+    ) -> std::io::Result<()> {
+        let selected = [
+            (columns.time, "time"),
+            (columns.lat, "lat"),
+            (columns.lng, "lng"),
+            (columns.elevation, "elevation_m"),
+            (columns.speed, "speed_kmh"),
+            (columns.heart_rate, "heart_rate_bpm"),
+            (columns.cadence, "cadence_rpm"),
+            (columns.power, "power_w"),
+        ];
+        let header: Vec<&str> = selected
+            .iter()
+            .filter(|(selected, _)| *selected)
+            .map(|(_, name)| *name)
+            .collect();
+        writeln!(writer, "{}", header.join(","))?;
+
+        let number = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+        for point in self.track_points.iter().flatten() {
+            let time = point
+                .t
+                .and_then(|t| DateTime::from_timestamp(t, 0))
+                .map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true))
+                .unwrap_or_default();
+            let values = [
+                time,
+                number(point.y),
+                number(point.x),
+                number(point.e),
+                number(point.s),
+                number(point.h),
+                number(point.c),
+                number(point.p),
+            ];
+            let row: Vec<String> = selected
+                .iter()
+                .zip(values)
+                .filter(|((selected, _), _)| *selected)
+                .map(|(_, value)| value)
+                .collect();
+            writeln!(writer, "{}", row.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_telemetry_csv() {
+        let trip: Trip = serde_json::from_str(
+            r#"{
+                "id": 1,
+                "track_points": [
+                    {"x": -122.6, "y": 45.5, "e": 12.5, "t": 1735718400, "s": 24.1, "h": 131, "c": 88, "p": 210},
+                    {"x": -122.59, "y": 45.5, "t": 1735718401, "s": 24.3}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let mut out = Vec::new();
+        trip.write_telemetry_csv(&mut out, ColumnSelection::default())
+            .unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "time,lat,lng,elevation_m,speed_kmh,heart_rate_bpm,cadence_rpm,power_w",
+                "2025-01-01T08:00:00Z,45.5,-122.6,12.5,24.1,131,88,210",
+                "2025-01-01T08:00:01Z,45.5,-122.59,,24.3,,,",
+            ]
+        );
+
+        let mut out = Vec::new();
+        let columns = ColumnSelection {
+            time: false,
+            lat: false,
+            lng: false,
+            elevation: false,
+            ..Default::default()
+        };
+        trip.write_telemetry_csv(&mut out, columns).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .starts_with("speed_kmh,heart_rate_bpm,cadence_rpm,power_w\n24.1,131,88,210\n"));
+    }
+}
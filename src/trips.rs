@@ -1,7 +1,11 @@
 //! Trip-related types and methods
 
-use crate::{PaginatedResponse, Photo, Polyline, Result, RideWithGpsClient, Visibility};
+use crate::{
+    ActivityType, Meters, PaginatedResponse, Photo, Polyline, RequestOptions, Result, ResultExt,
+    RideWithGpsClient, SegmentEffort, TrackType, TripId, Visibility,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Track point on a trip with telemetry data
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -50,6 +54,69 @@ pub struct TripTrackPoint {
     pub m: Option<bool>,
 }
 
+/// A position in a trip's track, used with [`RideWithGpsClient::crop_trip`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CropPoint {
+    /// Index into the trip's track points
+    Index(usize),
+
+    /// Unix timestamp, as in [`TripTrackPoint::t`]
+    Time(i64),
+}
+
+impl From<usize> for CropPoint {
+    fn from(index: usize) -> Self {
+        CropPoint::Index(index)
+    }
+}
+
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for CropPoint {
+    fn from(time: chrono::DateTime<Tz>) -> Self {
+        CropPoint::Time(time.timestamp())
+    }
+}
+
+/// A change to the flags of one trip track point
+///
+/// Used with [`RideWithGpsClient::update_trip_track_points`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TrackPointEdit {
+    /// Index of the point in the trip's track points
+    pub index: usize,
+
+    /// Exclude the point from metrics, see [`TripTrackPoint::k`]
+    #[serde(rename = "k", skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<bool>,
+
+    /// Mark the point as user modified, see [`TripTrackPoint::m`]
+    #[serde(rename = "m", skip_serializing_if = "Option::is_none")]
+    pub modified: Option<bool>,
+}
+
+impl TrackPointEdit {
+    /// Create an edit of the point at `index` that changes nothing yet
+    pub fn new(index: usize) -> Self {
+        Self {
+            index,
+            exclude: None,
+            modified: None,
+        }
+    }
+
+    /// Set [`exclude`](Self::exclude)
+    pub fn exclude(mut self, exclude: bool) -> Self {
+        self.exclude = Some(exclude);
+        self
+    }
+
+    /// Set [`modified`](Self::modified)
+    pub fn modified(mut self, modified: bool) -> Self {
+        self.modified = Some(modified);
+        self
+    }
+}
+
 /// Gear/equipment used for a trip
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Gear {
@@ -172,7 +239,7 @@ pub struct Trip {
     pub country_code: Option<String>,
 
     /// Activity type
-    pub activity_type: Option<String>,
+    pub activity_type: Option<ActivityType>,
 
     /// FIT file sport type
     pub fit_sport: Option<i32>,
@@ -184,7 +251,7 @@ pub struct Trip {
     pub stationary: Option<bool>,
 
     /// Track type
-    pub track_type: Option<String>,
+    pub track_type: Option<TrackType>,
 
     /// Terrain rating
     pub terrain: Option<i32>,
@@ -216,6 +283,12 @@ pub struct Trip {
     /// Northeast corner longitude (bounding box)
     pub ne_lng: Option<f64>,
 
+    /// Number of likes
+    pub likes_count: Option<u64>,
+
+    /// Whether the authenticated user likes this trip
+    pub liked_by_me: Option<bool>,
+
     /// Track points with telemetry (included when fetching a specific trip)
     pub track_points: Option<Vec<TripTrackPoint>>,
 
@@ -224,10 +297,14 @@ pub struct Trip {
 
     /// Photos (included when fetching a specific trip)
     pub photos: Option<Vec<Photo>>,
+
+    /// Segment efforts matched on this trip (included when fetching a specific trip)
+    pub segment_efforts: Option<Vec<SegmentEffort>>,
 }
 
 /// Parameters for listing trips
 #[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
 pub struct ListTripsParams {
     /// Filter by trip name
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -262,6 +339,29 @@ pub struct ListTripsParams {
     pub page_size: Option<u32>,
 }
 
+setters!(ListTripsParams {
+    name: impl Into<String>,
+    visibility: Visibility,
+    min_distance: f64,
+    max_distance: f64,
+    min_elevation_gain: f64,
+    max_elevation_gain: f64,
+    page: u32,
+    page_size: u32,
+});
+
+impl ListTripsParams {
+    /// Set [`min_distance`](Self::min_distance) in kilometers
+    pub fn min_distance_km(self, km: impl Into<f64>) -> Self {
+        self.min_distance(Meters::from_km(km.into()).0)
+    }
+
+    /// Set [`max_distance`](Self::max_distance) in kilometers
+    pub fn max_distance_km(self, km: impl Into<f64>) -> Self {
+        self.max_distance(Meters::from_km(km.into()).0)
+    }
+}
+
 impl RideWithGpsClient {
     /// List trips for the authenticated user
     ///
@@ -280,10 +380,7 @@ impl RideWithGpsClient {
     ///     Some("your-auth-token")
     /// );
     ///
-    /// let params = ListTripsParams {
-    ///     min_distance: Some(20000.0), // 20km
-    ///     ..Default::default()
-    /// };
+    /// let params = ListTripsParams::new().min_distance_km(20);
     ///
     /// let trips = client.list_trips(Some(&params)).unwrap();
     /// println!("Found {} trips", trips.results.len());
@@ -304,7 +401,7 @@ impl RideWithGpsClient {
             }
         }
 
-        self.get(&url)
+        self.get_path(&url)
     }
 
     /// Get a specific trip by ID
@@ -328,13 +425,96 @@ impl RideWithGpsClient {
     /// println!("Trip: {:?}", trip);
     /// ```
     pub fn get_trip(&self, id: u64) -> Result<Trip> {
-        #[derive(Deserialize)]
-        struct TripWrapper {
-            trip: Trip,
-        }
+        self.get_trip_with(id, &RequestOptions::default())
+    }
 
-        let wrapper: TripWrapper = self.get(&format!("/api/v1/trips/{}.json", id))?;
-        Ok(wrapper.trip)
+    /// Get many trips by ID
+    ///
+    /// The trips are fetched a few at a time rather than one by one, which
+    /// makes this suitable for materializing the changes reported by
+    /// [`sync`](Self::sync). Each ID maps to its own result, so one missing
+    /// trip does not prevent fetching the others.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The trip IDs; duplicates are fetched once
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{RideWithGpsClient, TripId};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let trips = client.get_trips(&[TripId(1), TripId(2), TripId(3)]);
+    /// for (id, trip) in trips {
+    ///     match trip {
+    ///         Ok(trip) => println!("{}: {:?}", id, trip.name),
+    ///         Err(e) => eprintln!("{}: {}", id, e),
+    ///     }
+    /// }
+    /// ```
+    pub fn get_trips(&self, ids: &[TripId]) -> BTreeMap<TripId, Result<Trip>> {
+        self.get_many(ids)
+    }
+
+    /// Get a specific trip by ID, or `None` if it does not exist
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The trip ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// match client.get_trip_opt(12345).unwrap() {
+    ///     Some(trip) => println!("Found: {:?}", trip.name),
+    ///     None => println!("No such trip"),
+    /// }
+    /// ```
+    pub fn get_trip_opt(&self, id: u64) -> Result<Option<Trip>> {
+        self.get_trip(id).optional()
+    }
+
+    /// Get a specific trip by ID, with per-call options
+    ///
+    /// Trips with long tracks can take a while to download, so this is
+    /// useful to allow a longer timeout than usual.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The trip ID
+    /// * `options` - Settings overriding the client's defaults for this call
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{RequestOptions, RideWithGpsClient};
+    /// use std::time::Duration;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let options = RequestOptions::new().timeout(Duration::from_secs(120));
+    /// let trip = client.get_trip_with(12345, &options).unwrap();
+    /// ```
+    pub fn get_trip_with(&self, id: u64, options: &RequestOptions) -> Result<Trip> {
+        self.get_resource_with::<Trip>(id.into(), options)
     }
 
     /// Get the polyline for a specific trip
@@ -358,7 +538,114 @@ impl RideWithGpsClient {
     /// println!("Polyline: {}", polyline.polyline);
     /// ```
     pub fn get_trip_polyline(&self, id: u64) -> Result<Polyline> {
-        self.get(&format!("/api/v1/trips/{}/polyline.json", id))
+        self.get_path(&format!("/api/v1/trips/{}/polyline.json", id))
+    }
+
+    /// Crop a trip to the part between two points of its track
+    ///
+    /// Everything before `start` and after `end` is removed on the server,
+    /// which then recomputes distance, time and the other derived stats.
+    /// This is useful to trim the drive home recorded because the GPS was
+    /// left running.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The trip ID
+    /// * `start` - First point to keep, as a track point index or a time
+    /// * `end` - Last point to keep, as a track point index or a time
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let trip = client.get_trip(12345).unwrap();
+    /// let points = trip.track_points.unwrap_or_default();
+    /// // Drop the last 500 points
+    /// let cropped = client.crop_trip(12345, 0, points.len().saturating_sub(501)).unwrap();
+    /// println!("Now {:?} m", cropped.distance);
+    /// ```
+    pub fn crop_trip(
+        &self,
+        id: u64,
+        start: impl Into<CropPoint>,
+        end: impl Into<CropPoint>,
+    ) -> Result<Trip> {
+        #[derive(Serialize)]
+        struct CropRequest {
+            start: CropPoint,
+            end: CropPoint,
+        }
+
+        let request = CropRequest {
+            start: start.into(),
+            end: end.into(),
+        };
+        let empty = match (request.start, request.end) {
+            (CropPoint::Index(start), CropPoint::Index(end)) => start >= end,
+            (CropPoint::Time(start), CropPoint::Time(end)) => start >= end,
+            _ => false,
+        };
+        if empty {
+            return Err(crate::Error::ValidationError(
+                "Crop start must come before its end".to_string(),
+            ));
+        }
+
+        self.put_wrapped(&format!("/api/v1/trips/{}/crop.json", id), "trip", &request)
+    }
+
+    /// Change the exclude and user-modified flags of trip track points
+    ///
+    /// Excluded points are ignored when the server computes the trip's
+    /// metrics, which makes it possible to drop power or heart rate spikes.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The trip ID
+    /// * `edits` - The points to change; points not listed are left alone
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::{RideWithGpsClient, TrackPointEdit};
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let trip = client.get_trip(12345).unwrap();
+    /// let edits: Vec<_> = trip
+    ///     .track_points
+    ///     .iter()
+    ///     .flatten()
+    ///     .enumerate()
+    ///     .filter(|(_, p)| p.p.is_some_and(|watts| watts > 2000.0))
+    ///     .map(|(i, _)| TrackPointEdit::new(i).exclude(true))
+    ///     .collect();
+    /// client.update_trip_track_points(12345, &edits).unwrap();
+    /// ```
+    pub fn update_trip_track_points(&self, id: u64, edits: &[TrackPointEdit]) -> Result<Trip> {
+        #[derive(Serialize)]
+        struct TrackPointsRequest<'a> {
+            track_points: &'a [TrackPointEdit],
+        }
+
+        self.put_wrapped(
+            &format!("/api/v1/trips/{}/track_points.json", id),
+            "trip",
+            &TrackPointsRequest {
+                track_points: edits,
+            },
+        )
     }
 
     /// Delete a trip
@@ -381,7 +668,7 @@ impl RideWithGpsClient {
     /// client.delete_trip(12345).unwrap();
     /// ```
     pub fn delete_trip(&self, id: u64) -> Result<()> {
-        self.delete(&format!("/api/v1/trips/{}.json", id))
+        self.delete::<Trip>(id)
     }
 }
 
@@ -389,6 +676,31 @@ impl RideWithGpsClient {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_crop_point() {
+        let time = chrono::DateTime::parse_from_rfc3339("2025-01-15T08:00:00+01:00").unwrap();
+        assert_eq!(CropPoint::from(time), CropPoint::Time(1736924400));
+        assert_eq!(
+            serde_json::to_value(CropPoint::from(12)).unwrap(),
+            serde_json::json!({"index": 12})
+        );
+
+        let client = RideWithGpsClient::new("https://ridewithgps.com", "key", None);
+        assert!(matches!(
+            client.crop_trip(1, 10, 5),
+            Err(crate::Error::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_track_point_edit() {
+        let edit = TrackPointEdit::new(3).exclude(true);
+        assert_eq!(
+            serde_json::to_value(&edit).unwrap(),
+            serde_json::json!({"index": 3, "k": true})
+        );
+    }
+
     #[test]
     fn test_trip_deserialization() {
         let json = r#"{
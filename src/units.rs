@@ -0,0 +1,298 @@
+//! Unit newtypes and unit-aware accessors
+//!
+//! The API reports distances in meters, durations in seconds and summary
+//! speeds in m/s, while track point speeds are in km/h. These types make the
+//! unit explicit so it cannot be confused.
+
+use crate::{Route, Trip, TripTrackPoint};
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Div, Sub};
+use std::time::Duration;
+
+const METERS_PER_MILE: f64 = 1609.344;
+const METERS_PER_FOOT: f64 = 0.3048;
+
+/// System of units used when presenting quantities to people
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    /// Kilometers, meters and km/h
+    #[default]
+    Metric,
+
+    /// Miles, feet and mph
+    Imperial,
+}
+
+/// A distance or elevation in meters
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Meters(pub f64);
+
+impl Meters {
+    /// Value in kilometers
+    pub fn km(self) -> f64 {
+        self.0 / 1000.0
+    }
+
+    /// Value in statute miles
+    pub fn miles(self) -> f64 {
+        self.0 / METERS_PER_MILE
+    }
+
+    /// Value in feet
+    pub fn feet(self) -> f64 {
+        self.0 / METERS_PER_FOOT
+    }
+
+    /// Create from a value in kilometers
+    pub fn from_km(km: f64) -> Self {
+        Self(km * 1000.0)
+    }
+
+    /// Create from a value in statute miles
+    pub fn from_miles(miles: f64) -> Self {
+        Self(miles * METERS_PER_MILE)
+    }
+
+    /// Create from a value in feet
+    pub fn from_feet(feet: f64) -> Self {
+        Self(feet * METERS_PER_FOOT)
+    }
+}
+
+/// A duration in seconds
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Seconds(pub f64);
+
+impl Seconds {
+    /// Value in minutes
+    pub fn minutes(self) -> f64 {
+        self.0 / 60.0
+    }
+
+    /// Value in hours
+    pub fn hours(self) -> f64 {
+        self.0 / 3600.0
+    }
+
+    /// Convert to a [`Duration`], clamping negative values to zero
+    pub fn to_duration(self) -> Duration {
+        Duration::from_secs_f64(self.0.max(0.0))
+    }
+}
+
+impl From<Duration> for Seconds {
+    fn from(d: Duration) -> Self {
+        Self(d.as_secs_f64())
+    }
+}
+
+/// A speed in meters per second
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct MetersPerSecond(pub f64);
+
+impl MetersPerSecond {
+    /// Value in kilometers per hour
+    pub fn kmh(self) -> f64 {
+        self.0 * 3.6
+    }
+
+    /// Value in miles per hour
+    pub fn mph(self) -> f64 {
+        self.0 * 3600.0 / METERS_PER_MILE
+    }
+
+    /// Create from a value in kilometers per hour
+    pub fn from_kmh(kmh: f64) -> Self {
+        Self(kmh / 3.6)
+    }
+
+    /// Create from a value in miles per hour
+    pub fn from_mph(mph: f64) -> Self {
+        Self(mph * METERS_PER_MILE / 3600.0)
+    }
+}
+
+impl Add for Meters {
+    type Output = Meters;
+
+    fn add(self, rhs: Meters) -> Meters {
+        Meters(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Meters {
+    type Output = Meters;
+
+    fn sub(self, rhs: Meters) -> Meters {
+        Meters(self.0 - rhs.0)
+    }
+}
+
+impl Add for Seconds {
+    type Output = Seconds;
+
+    fn add(self, rhs: Seconds) -> Seconds {
+        Seconds(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Seconds {
+    type Output = Seconds;
+
+    fn sub(self, rhs: Seconds) -> Seconds {
+        Seconds(self.0 - rhs.0)
+    }
+}
+
+impl Div<Seconds> for Meters {
+    type Output = MetersPerSecond;
+
+    fn div(self, rhs: Seconds) -> MetersPerSecond {
+        MetersPerSecond(self.0 / rhs.0)
+    }
+}
+
+impl Route {
+    /// Distance as [`Meters`]
+    pub fn distance_meters(&self) -> Option<Meters> {
+        self.distance.map(Meters)
+    }
+
+    /// Distance in kilometers
+    pub fn distance_km(&self) -> Option<f64> {
+        self.distance_meters().map(Meters::km)
+    }
+
+    /// Distance in statute miles
+    pub fn distance_miles(&self) -> Option<f64> {
+        self.distance_meters().map(Meters::miles)
+    }
+
+    /// Elevation gain as [`Meters`]
+    pub fn elevation_gain_meters(&self) -> Option<Meters> {
+        self.elevation_gain.map(Meters)
+    }
+
+    /// Elevation gain in feet
+    pub fn elevation_gain_feet(&self) -> Option<f64> {
+        self.elevation_gain_meters().map(Meters::feet)
+    }
+}
+
+impl Trip {
+    /// Distance as [`Meters`]
+    pub fn distance_meters(&self) -> Option<Meters> {
+        self.distance.map(Meters)
+    }
+
+    /// Distance in kilometers
+    pub fn distance_km(&self) -> Option<f64> {
+        self.distance_meters().map(Meters::km)
+    }
+
+    /// Distance in statute miles
+    pub fn distance_miles(&self) -> Option<f64> {
+        self.distance_meters().map(Meters::miles)
+    }
+
+    /// Elevation gain as [`Meters`]
+    pub fn elevation_gain_meters(&self) -> Option<Meters> {
+        self.elevation_gain.map(Meters)
+    }
+
+    /// Elevation gain in feet
+    pub fn elevation_gain_feet(&self) -> Option<f64> {
+        self.elevation_gain_meters().map(Meters::feet)
+    }
+
+    /// Total duration as [`Seconds`]
+    pub fn duration_seconds(&self) -> Option<Seconds> {
+        self.duration.map(Seconds)
+    }
+
+    /// Moving time as [`Seconds`]
+    pub fn moving_time_seconds(&self) -> Option<Seconds> {
+        self.moving_time.map(Seconds)
+    }
+
+    /// Average speed as [`MetersPerSecond`]
+    pub fn avg_speed_mps(&self) -> Option<MetersPerSecond> {
+        self.avg_speed.map(MetersPerSecond)
+    }
+
+    /// Average speed in kilometers per hour
+    pub fn avg_speed_kmh(&self) -> Option<f64> {
+        self.avg_speed_mps().map(MetersPerSecond::kmh)
+    }
+
+    /// Average speed in miles per hour
+    pub fn avg_speed_mph(&self) -> Option<f64> {
+        self.avg_speed_mps().map(MetersPerSecond::mph)
+    }
+
+    /// Maximum speed as [`MetersPerSecond`]
+    pub fn max_speed_mps(&self) -> Option<MetersPerSecond> {
+        self.max_speed.map(MetersPerSecond)
+    }
+
+    /// Maximum speed in kilometers per hour
+    pub fn max_speed_kmh(&self) -> Option<f64> {
+        self.max_speed_mps().map(MetersPerSecond::kmh)
+    }
+
+    /// Maximum speed in miles per hour
+    pub fn max_speed_mph(&self) -> Option<f64> {
+        self.max_speed_mps().map(MetersPerSecond::mph)
+    }
+}
+
+impl TripTrackPoint {
+    /// Speed as [`MetersPerSecond`]
+    ///
+    /// Track point speeds are reported in km/h, unlike the m/s used by trip
+    /// summaries; this accessor converts accordingly.
+    pub fn speed(&self) -> Option<MetersPerSecond> {
+        self.s.map(MetersPerSecond::from_kmh)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(Meters(1500.0).km(), 1.5);
+        assert!((Meters::from_miles(1.0).0 - 1609.344).abs() < 1e-9);
+        assert!((Meters(0.3048).feet() - 1.0).abs() < 1e-9);
+        assert_eq!(Seconds(5400.0).hours(), 1.5);
+        assert_eq!(Seconds(90.0).to_duration(), Duration::from_secs(90));
+        assert!((MetersPerSecond(10.0).kmh() - 36.0).abs() < 1e-9);
+        assert!((MetersPerSecond::from_mph(10.0).mph() - 10.0).abs() < 1e-9);
+        assert_eq!(Meters(100.0) / Seconds(20.0), MetersPerSecond(5.0));
+    }
+
+    #[test]
+    fn test_trip_accessors() {
+        let trip: Trip = serde_json::from_str(
+            r#"{"id": 1, "distance": 42195.0, "avg_speed": 5.0, "moving_time": 7200.0}"#,
+        )
+        .unwrap();
+
+        assert_eq!(trip.distance_km(), Some(42.195));
+        assert!((trip.distance_miles().unwrap() - 26.219).abs() < 0.001);
+        assert_eq!(trip.avg_speed_kmh(), Some(18.0));
+        assert_eq!(trip.moving_time_seconds().map(Seconds::hours), Some(2.0));
+        assert_eq!(trip.max_speed_kmh(), None);
+    }
+
+    #[test]
+    fn test_track_point_speed_is_converted_from_kmh() {
+        let point: TripTrackPoint = serde_json::from_str(r#"{"s": 36.0}"#).unwrap();
+        assert_eq!(point.speed(), Some(MetersPerSecond(10.0)));
+    }
+}
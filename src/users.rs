@@ -1,6 +1,7 @@
 //! User-related types and methods
 
 use crate::{Result, RideWithGpsClient};
+use reqwest::header::HeaderMap;
 use serde::{Deserialize, Serialize};
 
 /// User information
@@ -43,6 +44,67 @@ pub struct User {
     pub updated_at: Option<String>,
 }
 
+/// Plan, limits and API quota of the authenticated account, from
+/// [`get_account_status`](RideWithGpsClient::get_account_status)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AccountStatus {
+    /// Subscription plan, such as "free", "basic" or "premium"
+    pub plan: Option<String>,
+
+    /// Storage used, in bytes
+    pub storage_used: Option<u64>,
+
+    /// Storage allowed by the plan, in bytes
+    pub storage_limit: Option<u64>,
+
+    /// Number of routes in the account
+    pub route_count: Option<u64>,
+
+    /// Number of routes allowed by the plan
+    pub route_limit: Option<u64>,
+
+    /// API tier of the credentials
+    pub api_tier: Option<String>,
+
+    /// Number of requests allowed per rate-limit window
+    pub rate_limit: Option<u64>,
+
+    /// Number of requests left in the current rate-limit window
+    pub rate_limit_remaining: Option<u64>,
+}
+
+impl AccountStatus {
+    /// Number of routes that can still be created, if the plan has a limit
+    pub fn routes_remaining(&self) -> Option<u64> {
+        Some(
+            self.route_limit?
+                .saturating_sub(self.route_count.unwrap_or(0)),
+        )
+    }
+
+    /// Storage left in bytes, if the plan has a limit
+    pub fn storage_remaining(&self) -> Option<u64> {
+        Some(
+            self.storage_limit?
+                .saturating_sub(self.storage_used.unwrap_or(0)),
+        )
+    }
+
+    /// Fill in the API tier and quota from rate-limit response headers
+    fn merge_headers(&mut self, headers: &HeaderMap) {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        if self.api_tier.is_none() {
+            self.api_tier = header("x-rwgps-api-tier").map(str::to_string);
+        }
+        if let Some(limit) = header("x-ratelimit-limit").and_then(|v| v.parse().ok()) {
+            self.rate_limit = Some(limit);
+        }
+        if let Some(remaining) = header("x-ratelimit-remaining").and_then(|v| v.parse().ok()) {
+            self.rate_limit_remaining = Some(remaining);
+        }
+    }
+}
+
 impl RideWithGpsClient {
     /// Get the current authenticated user's information
     ///
@@ -63,13 +125,43 @@ impl RideWithGpsClient {
     /// println!("User: {:?}", user);
     /// ```
     pub fn get_current_user(&self) -> Result<User> {
-        #[derive(Deserialize)]
-        struct UserWrapper {
-            user: User,
-        }
+        self.get_wrapped("/api/v1/users/current.json", "user")
+    }
 
-        let wrapper: UserWrapper = self.get("/api/v1/users/current.json")?;
-        Ok(wrapper.user)
+    /// Get the plan, limits and remaining API quota of the authenticated
+    /// account
+    ///
+    /// The API quota is read from the rate-limit headers of the response,
+    /// so it reflects the requests made so far, including this one. Requires
+    /// an auth token to be set.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let files = 250;
+    /// let status = client.get_account_status().unwrap();
+    /// if status.routes_remaining().is_some_and(|n| n < files) {
+    ///     eprintln!("Only room for {:?} more routes", status.routes_remaining());
+    /// }
+    /// if status.rate_limit_remaining.is_some_and(|n| n < files) {
+    ///     eprintln!("Not enough API quota left for this upload");
+    /// }
+    /// ```
+    pub fn get_account_status(&self) -> Result<AccountStatus> {
+        let (mut status, headers) = self.get_wrapped_with_headers::<AccountStatus>(
+            "/api/v1/users/current/account.json",
+            "account",
+        )?;
+        status.merge_headers(&headers);
+        Ok(status)
     }
 }
 
@@ -141,4 +233,32 @@ mod tests {
         assert_eq!(user.description.as_deref(), Some("Avid cyclist"));
         assert_eq!(user.premium, Some(true));
     }
+
+    #[test]
+    fn test_account_status() {
+        let mut status: AccountStatus = serde_json::from_str(
+            r#"{
+                "plan": "basic",
+                "storage_used": 800,
+                "storage_limit": 1000,
+                "route_count": 120,
+                "route_limit": 100
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(status.routes_remaining(), Some(0));
+        assert_eq!(status.storage_remaining(), Some(200));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-rwgps-api-tier", "standard".parse().unwrap());
+        headers.insert("x-ratelimit-limit", "1000".parse().unwrap());
+        headers.insert("x-ratelimit-remaining", "42".parse().unwrap());
+        status.merge_headers(&headers);
+        assert_eq!(status.api_tier.as_deref(), Some("standard"));
+        assert_eq!(status.rate_limit, Some(1000));
+        assert_eq!(status.rate_limit_remaining, Some(42));
+
+        let unlimited: AccountStatus = serde_json::from_str(r#"{"plan": "premium"}"#).unwrap();
+        assert_eq!(unlimited.routes_remaining(), None);
+    }
 }
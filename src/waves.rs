@@ -0,0 +1,117 @@
+//! Event waves (start groups)
+
+use crate::{EventParticipant, Result, RideWithGpsClient};
+use serde::{Deserialize, Serialize};
+
+/// A group of participants starting an event together
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Wave {
+    /// Wave ID
+    pub id: u64,
+
+    /// ID of the event
+    pub event_id: Option<u64>,
+
+    /// Wave name, such as `"A group"`
+    pub name: Option<String>,
+
+    /// Start time
+    pub starts_at: Option<String>,
+
+    /// Maximum number of participants
+    pub capacity: Option<u32>,
+
+    /// Number of participants assigned
+    pub participants_count: Option<u32>,
+}
+
+impl RideWithGpsClient {
+    /// List the waves of an event
+    ///
+    /// # Arguments
+    ///
+    /// * `event_id` - The event ID
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// for wave in client.list_waves(12345).unwrap() {
+    ///     println!("{:?} starts at {:?}", wave.name, wave.starts_at);
+    /// }
+    /// ```
+    pub fn list_waves(&self, event_id: u64) -> Result<Vec<Wave>> {
+        self.get_wrapped(&format!("/api/v1/events/{}/waves.json", event_id), "waves")
+    }
+
+    /// Assign a participant to a wave, or remove them from their wave
+    ///
+    /// Note: This endpoint is only available to the event's organizers.
+    ///
+    /// # Arguments
+    ///
+    /// * `event_id` - The event ID
+    /// * `participant_id` - The participant ID
+    /// * `wave_id` - The wave to start in, or `None` to unassign
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ridewithgps_client::RideWithGpsClient;
+    ///
+    /// let client = RideWithGpsClient::new(
+    ///     "https://ridewithgps.com",
+    ///     "your-api-key",
+    ///     Some("your-auth-token")
+    /// );
+    ///
+    /// let participant = client.assign_participant_wave(12345, 678, Some(3)).unwrap();
+    /// println!("{:?} is in wave {:?}", participant.name, participant.wave_id);
+    /// ```
+    pub fn assign_participant_wave(
+        &self,
+        event_id: u64,
+        participant_id: u64,
+        wave_id: Option<u64>,
+    ) -> Result<EventParticipant> {
+        #[derive(Serialize)]
+        struct WaveAssignment {
+            wave_id: Option<u64>,
+        }
+
+        self.put_wrapped(
+            &format!(
+                "/api/v1/events/{}/participants/{}.json",
+                event_id, participant_id
+            ),
+            "participant",
+            &WaveAssignment { wave_id },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wave_deserialization() {
+        let json = r#"{"waves": [{"id": 3, "name": "A group", "starts_at": "2025-06-01T08:00:00Z", "capacity": 50}]}"#;
+
+        #[derive(Deserialize)]
+        struct WavesWrapper {
+            waves: Vec<Wave>,
+        }
+
+        let wrapper: WavesWrapper = serde_json::from_str(json).unwrap();
+        assert_eq!(wrapper.waves[0].name.as_deref(), Some("A group"));
+        assert_eq!(wrapper.waves[0].capacity, Some(50));
+    }
+}
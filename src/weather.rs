@@ -0,0 +1,349 @@
+//! Weather conditions along a recorded trip
+//!
+//! Requires the `weather` feature.
+
+use crate::{Error, LatLng, Result, Trip};
+use serde::Deserialize;
+
+/// Ride time between weather samples, in seconds
+const WEATHER_INTERVAL: i64 = 15 * 60;
+
+/// Weather conditions at a place and time
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct WeatherConditions {
+    /// Air temperature in Celsius
+    pub temperature: Option<f64>,
+
+    /// Wind speed in m/s
+    pub wind_speed: Option<f64>,
+
+    /// Direction the wind blows from, in degrees clockwise from north
+    pub wind_direction: Option<f64>,
+}
+
+/// A source of historical weather data
+///
+/// [`OpenMeteo`] is provided as a reference implementation; implement this
+/// trait to use another weather service or a local archive.
+pub trait WeatherProvider {
+    /// Look up the conditions at each location and Unix timestamp
+    ///
+    /// Returns one entry per query.
+    fn conditions(&self, queries: &[(LatLng, i64)]) -> Result<Vec<WeatherConditions>>;
+}
+
+/// [`WeatherProvider`] backed by the free [Open-Meteo](https://open-meteo.com) API
+///
+/// Conditions are taken from the hourly series, using the hour closest to
+/// each timestamp. All queries are sent in a single request.
+#[derive(Debug, Clone)]
+pub struct OpenMeteo {
+    base_url: String,
+    client: reqwest::blocking::Client,
+}
+
+impl OpenMeteo {
+    /// Historical weather API, which lags a few days behind the present
+    pub const ARCHIVE_URL: &'static str = "https://archive-api.open-meteo.com/v1/archive";
+
+    /// Forecast API, which also covers the past three months
+    pub const FORECAST_URL: &'static str = "https://api.open-meteo.com/v1/forecast";
+
+    /// Create a provider using the historical weather API
+    pub fn new() -> Self {
+        Self::with_base_url(Self::ARCHIVE_URL)
+    }
+
+    /// Create a provider using another endpoint, such as
+    /// [`FORECAST_URL`](Self::FORECAST_URL) for rides from the last few days
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.to_string(),
+            client: reqwest::blocking::Client::new(),
+        }
+    }
+}
+
+impl Default for OpenMeteo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hourly series of a single location in an Open-Meteo response
+#[derive(Debug, Deserialize)]
+struct OpenMeteoLocation {
+    hourly: OpenMeteoHourly,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoHourly {
+    time: Vec<i64>,
+    temperature_2m: Vec<Option<f64>>,
+    wind_speed_10m: Vec<Option<f64>>,
+    wind_direction_10m: Vec<Option<f64>>,
+}
+
+/// Open-Meteo returns an object for one location and an array for several
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OpenMeteoResponse {
+    One(OpenMeteoLocation),
+    Many(Vec<OpenMeteoLocation>),
+}
+
+impl OpenMeteoHourly {
+    /// Conditions for the hour closest to `timestamp`
+    fn at(&self, timestamp: i64) -> WeatherConditions {
+        let Some(index) = (0..self.time.len()).min_by_key(|&i| (self.time[i] - timestamp).abs())
+        else {
+            return WeatherConditions::default();
+        };
+        let value = |series: &[Option<f64>]| series.get(index).copied().flatten();
+        WeatherConditions {
+            temperature: value(&self.temperature_2m),
+            wind_speed: value(&self.wind_speed_10m),
+            wind_direction: value(&self.wind_direction_10m),
+        }
+    }
+}
+
+impl WeatherProvider for OpenMeteo {
+    fn conditions(&self, queries: &[(LatLng, i64)]) -> Result<Vec<WeatherConditions>> {
+        if queries.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let date = |timestamp: i64| {
+            chrono::DateTime::from_timestamp(timestamp, 0)
+                .map(|t| t.format("%Y-%m-%d").to_string())
+                .ok_or_else(|| Error::ValidationError(format!("Invalid timestamp {}", timestamp)))
+        };
+        let join = |f: fn(&LatLng) -> f64| {
+            queries
+                .iter()
+                .map(|(c, _)| format!("{:.4}", f(c)))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let start = queries.iter().map(|(_, t)| *t).min().unwrap_or_default();
+        let end = queries.iter().map(|(_, t)| *t).max().unwrap_or_default();
+
+        let response = self
+            .client
+            .get(&self.base_url)
+            .query(&[
+                ("latitude", join(|c| c.lat)),
+                ("longitude", join(|c| c.lng)),
+                ("start_date", date(start)?),
+                ("end_date", date(end)?),
+                (
+                    "hourly",
+                    "temperature_2m,wind_speed_10m,wind_direction_10m".to_string(),
+                ),
+                ("wind_speed_unit", "ms".to_string()),
+                ("timeformat", "unixtime".to_string()),
+                ("timezone", "GMT".to_string()),
+            ])
+            .send()?;
+        let status = response.status();
+        let body = response.text()?;
+        if !status.is_success() {
+            return Err(Error::ApiError(format!(
+                "Open-Meteo request failed with {}: {}",
+                status, body
+            )));
+        }
+
+        let locations = match serde_json::from_str(&body)? {
+            OpenMeteoResponse::One(location) => vec![location],
+            OpenMeteoResponse::Many(locations) => locations,
+        };
+        if locations.len() != queries.len() {
+            return Err(Error::ApiError(format!(
+                "Expected {} locations from Open-Meteo, got {}",
+                queries.len(),
+                locations.len()
+            )));
+        }
+
+        Ok(locations
+            .iter()
+            .zip(queries)
+            .map(|(location, (_, timestamp))| location.hourly.at(*timestamp))
+            .collect())
+    }
+}
+
+/// Weather at a point along a trip, from [`enrich_trip_weather`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TripWeather {
+    /// Distance along the trip in meters, if known
+    pub distance: Option<f64>,
+
+    /// Unix timestamp
+    pub time: i64,
+
+    /// Position
+    pub position: LatLng,
+
+    /// Conditions at this place and time
+    pub conditions: WeatherConditions,
+}
+
+/// Look up the weather along a trip using [`OpenMeteo`]
+///
+/// See [`enrich_trip_weather_with`] for details.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ridewithgps_client::{enrich_trip_weather, RideWithGpsClient};
+///
+/// let client = RideWithGpsClient::new(
+///     "https://ridewithgps.com",
+///     "your-api-key",
+///     Some("your-auth-token")
+/// );
+///
+/// let trip = client.get_trip(12345).unwrap();
+/// for sample in enrich_trip_weather(&trip).unwrap() {
+///     println!(
+///         "{:?} m: {:?} °C, wind {:?} m/s",
+///         sample.distance, sample.conditions.temperature, sample.conditions.wind_speed
+///     );
+/// }
+/// ```
+pub fn enrich_trip_weather(trip: &Trip) -> Result<Vec<TripWeather>> {
+    enrich_trip_weather_with(trip, &OpenMeteo::new())
+}
+
+/// Look up the weather along a trip using any [`WeatherProvider`]
+///
+/// The trip is sampled at its first track point with a timestamp and
+/// position, then every 15 minutes of elapsed time, and at its last point.
+/// Returns an empty list if the trip has no such track points, e.g. because
+/// it was fetched from a listing rather than with
+/// [`get_trip`](crate::RideWithGpsClient::get_trip).
+///
+/// # Example
+///
+/// ```rust
+/// use ridewithgps_client::{
+///     enrich_trip_weather_with, LatLng, Result, Trip, WeatherConditions, WeatherProvider,
+/// };
+///
+/// /// Always a mild, calm day
+/// struct Mild;
+///
+/// impl WeatherProvider for Mild {
+///     fn conditions(&self, queries: &[(LatLng, i64)]) -> Result<Vec<WeatherConditions>> {
+///         let mild = WeatherConditions {
+///             temperature: Some(18.0),
+///             ..Default::default()
+///         };
+///         Ok(vec![mild; queries.len()])
+///     }
+/// }
+///
+/// let trip: Trip = serde_json::from_str(
+///     r#"{"id": 1, "track_points": [{"x": 4.9, "y": 52.4, "t": 1700000000}]}"#,
+/// ).unwrap();
+/// let weather = enrich_trip_weather_with(&trip, &Mild).unwrap();
+/// assert_eq!(weather[0].conditions.temperature, Some(18.0));
+/// ```
+pub fn enrich_trip_weather_with(
+    trip: &Trip,
+    provider: &(impl WeatherProvider + ?Sized),
+) -> Result<Vec<TripWeather>> {
+    let points: Vec<_> = trip
+        .track_points
+        .iter()
+        .flatten()
+        .filter_map(|p| Some((p.t?, p.coord()?, p.d)))
+        .collect();
+
+    let mut samples: Vec<(i64, LatLng, Option<f64>)> = Vec::new();
+    for (i, &point) in points.iter().enumerate() {
+        let due = samples
+            .last()
+            .is_none_or(|&(last, _, _)| point.0 - last >= WEATHER_INTERVAL);
+        if due || i == points.len() - 1 {
+            samples.push(point);
+        }
+    }
+    if samples.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let queries: Vec<(LatLng, i64)> = samples.iter().map(|&(t, c, _)| (c, t)).collect();
+    let conditions = provider.conditions(&queries)?;
+    if conditions.len() != samples.len() {
+        return Err(Error::ApiError(format!(
+            "Expected {} weather samples, got {}",
+            samples.len(),
+            conditions.len()
+        )));
+    }
+
+    Ok(samples
+        .into_iter()
+        .zip(conditions)
+        .map(|((time, position, distance), conditions)| TripWeather {
+            distance,
+            time,
+            position,
+            conditions,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Hourly;
+
+    impl WeatherProvider for Hourly {
+        fn conditions(&self, queries: &[(LatLng, i64)]) -> Result<Vec<WeatherConditions>> {
+            Ok(queries
+                .iter()
+                .map(|(_, t)| WeatherConditions {
+                    temperature: Some(*t as f64 / 3600.0),
+                    ..Default::default()
+                })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_enrich_trip_weather_samples_every_interval() {
+        let points: Vec<serde_json::Value> = (0..=10)
+            .map(|i| serde_json::json!({"x": 5.0, "y": 52.0, "d": i * 1000, "t": i * 360}))
+            .collect();
+        let trip: Trip =
+            serde_json::from_value(serde_json::json!({"id": 1, "track_points": points})).unwrap();
+
+        let weather = enrich_trip_weather_with(&trip, &Hourly).unwrap();
+        let times: Vec<i64> = weather.iter().map(|w| w.time).collect();
+        assert_eq!(times, vec![0, 1080, 2160, 3240, 3600]);
+        assert_eq!(weather[4].distance, Some(10000.0));
+        assert_eq!(weather[4].conditions.temperature, Some(1.0));
+    }
+
+    #[test]
+    fn test_open_meteo_response() {
+        let json = r#"[
+            {"hourly": {"time": [0, 3600], "temperature_2m": [10.5, 12.0], "wind_speed_10m": [3.0, null], "wind_direction_10m": [270, 280]}},
+            {"hourly": {"time": [0, 3600], "temperature_2m": [9.0, 11.0], "wind_speed_10m": [2.0, 2.5], "wind_direction_10m": [180, 190]}}
+        ]"#;
+
+        let OpenMeteoResponse::Many(locations) = serde_json::from_str(json).unwrap() else {
+            panic!("expected several locations");
+        };
+        let conditions = locations[0].hourly.at(3000);
+        assert_eq!(conditions.temperature, Some(12.0));
+        assert_eq!(conditions.wind_speed, None);
+        assert_eq!(locations[1].hourly.at(600).wind_direction, Some(180.0));
+    }
+}
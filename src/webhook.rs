@@ -0,0 +1,319 @@
+//! Receiving RideWithGPS webhook notifications
+//!
+//! RideWithGPS POSTs a JSON document listing changed routes and trips to an
+//! API client's webhook URL, signed with the client's secret: the
+//! `x-rwgps-signature` header holds the hex-encoded HMAC-SHA256 of the raw
+//! request body. [`verify_signature`] checks it for any web framework; with
+//! the `axum` feature, the [`RwgpsWebhook`] extractor checks it and
+//! deserializes the body in one go.
+
+use crate::kinds::string_enum;
+use crate::ItemType;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fmt;
+
+/// Header carrying the signature of a webhook request
+pub const SIGNATURE_HEADER: &str = "x-rwgps-signature";
+
+string_enum!(
+    /// What happened to the item of a [`WebhookNotification`]
+    WebhookAction {
+        /// The item was created
+        Created => "created",
+        /// The item was changed
+        Updated => "updated",
+        /// The item was deleted
+        Deleted => "deleted",
+    }
+);
+
+/// A change to a route or trip, as reported to a webhook
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WebhookNotification {
+    /// Kind of item that changed
+    pub item_type: ItemType,
+
+    /// ID of the item
+    pub item_id: u64,
+
+    /// What happened to the item
+    pub action: WebhookAction,
+
+    /// ID of the user owning the item
+    pub user_id: Option<u64>,
+
+    /// API URL of the item
+    pub item_url: Option<String>,
+}
+
+/// Body of a webhook request
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WebhookPayload {
+    /// The changes being reported
+    pub notifications: Vec<WebhookNotification>,
+}
+
+/// Check the signature of a webhook request
+///
+/// # Arguments
+///
+/// * `secret` - Secret of the API client the webhook belongs to
+/// * `body` - Raw request body, as received
+/// * `signature` - Value of the [`SIGNATURE_HEADER`] header
+///
+/// # Example
+///
+/// ```rust
+/// use ridewithgps_client::webhook;
+///
+/// let body = br#"{"notifications": []}"#;
+/// assert!(!webhook::verify_signature("client-secret", body, "00ff"));
+/// ```
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(signature) = decode_hex(signature.trim()) else {
+        return false;
+    };
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    // Compares in constant time
+    mac.verify_slice(&signature).is_ok()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Why a webhook request was rejected by [`verify_signature`] or the
+/// [`RwgpsWebhook`] extractor
+#[derive(Debug)]
+pub enum WebhookError {
+    /// The request has no [`SIGNATURE_HEADER`] header
+    MissingSignature,
+
+    /// The signature does not match the body
+    InvalidSignature,
+
+    /// The body could not be read
+    Body(String),
+
+    /// The body is not a valid payload
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WebhookError::MissingSignature => write!(f, "Missing {} header", SIGNATURE_HEADER),
+            WebhookError::InvalidSignature => write!(f, "Invalid webhook signature"),
+            WebhookError::Body(e) => write!(f, "Could not read webhook body: {}", e),
+            WebhookError::Json(e) => write!(f, "Invalid webhook payload: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for WebhookError {}
+
+/// Check the signature of a webhook request and deserialize its body
+///
+/// `signature` is the value of the [`SIGNATURE_HEADER`] header, if present.
+pub fn parse_webhook<T: for<'de> Deserialize<'de>>(
+    secret: &str,
+    body: &[u8],
+    signature: Option<&str>,
+) -> std::result::Result<T, WebhookError> {
+    let signature = signature.ok_or(WebhookError::MissingSignature)?;
+    if !verify_signature(secret, body, signature) {
+        return Err(WebhookError::InvalidSignature);
+    }
+    serde_json::from_slice(body).map_err(WebhookError::Json)
+}
+
+/// Secret used by the [`RwgpsWebhook`] extractor to check signatures
+///
+/// The extractor takes it from the router state, through
+/// [`FromRef`](axum::extract::FromRef).
+#[cfg(feature = "axum")]
+#[derive(Clone)]
+pub struct WebhookSecret(pub String);
+
+#[cfg(feature = "axum")]
+impl fmt::Debug for WebhookSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WebhookSecret(..)")
+    }
+}
+
+/// Axum extractor for a signed webhook request
+///
+/// Rejects requests whose signature does not match with
+/// `401 Unauthorized`, and bodies that do not deserialize into `T` with
+/// `400 Bad Request`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use axum::{routing::post, Router};
+/// use ridewithgps_client::webhook::{RwgpsWebhook, WebhookPayload, WebhookSecret};
+///
+/// async fn handle(RwgpsWebhook(payload): RwgpsWebhook<WebhookPayload>) {
+///     for notification in payload.notifications {
+///         println!("{:?} {} {}", notification.item_type, notification.item_id, notification.action);
+///     }
+/// }
+///
+/// let app: Router = Router::new()
+///     .route("/rwgps", post(handle))
+///     .with_state(WebhookSecret("client-secret".to_string()));
+/// ```
+#[cfg(feature = "axum")]
+#[derive(Debug, Clone)]
+pub struct RwgpsWebhook<T = WebhookPayload>(pub T);
+
+#[cfg(feature = "axum")]
+impl<S, T> axum::extract::FromRequest<S> for RwgpsWebhook<T>
+where
+    S: Send + Sync,
+    T: for<'de> Deserialize<'de>,
+    WebhookSecret: axum::extract::FromRef<S>,
+{
+    type Rejection = WebhookError;
+
+    async fn from_request(
+        request: axum::extract::Request,
+        state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        use axum::extract::FromRef;
+
+        let secret = WebhookSecret::from_ref(state);
+        let signature = request
+            .headers()
+            .get(SIGNATURE_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = axum::body::Bytes::from_request(request, state)
+            .await
+            .map_err(|e| WebhookError::Body(e.body_text()))?;
+        parse_webhook(&secret.0, &body, signature.as_deref()).map(RwgpsWebhook)
+    }
+}
+
+#[cfg(feature = "axum")]
+impl axum::response::IntoResponse for WebhookError {
+    fn into_response(self) -> axum::response::Response {
+        use axum::http::StatusCode;
+
+        let status = match self {
+            WebhookError::MissingSignature | WebhookError::InvalidSignature => {
+                StatusCode::UNAUTHORIZED
+            }
+            WebhookError::Body(_) | WebhookError::Json(_) => StatusCode::BAD_REQUEST,
+        };
+        (status, self.to_string()).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &[u8] = br#"{"notifications": [{"item_type": "trip", "item_id": 42, "action": "created", "user_id": 7, "item_url": "https://ridewithgps.com/api/v1/trips/42.json"}]}"#;
+
+    fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        // RFC 4231 test case 2
+        assert!(verify_signature(
+            "Jefe",
+            b"what do ya want for nothing?",
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        ));
+        assert!(!verify_signature("Jefe", b"what do ya want?", "5bdc"));
+        assert!(!verify_signature("Jefe", b"", "not hex"));
+    }
+
+    #[test]
+    fn test_parse_webhook() {
+        let signature = sign("secret", BODY);
+        let payload: WebhookPayload = parse_webhook("secret", BODY, Some(&signature)).unwrap();
+        assert_eq!(payload.notifications[0].item_type, ItemType::Trip);
+        assert_eq!(payload.notifications[0].action, WebhookAction::Created);
+
+        assert!(matches!(
+            parse_webhook::<WebhookPayload>("other", BODY, Some(&signature)),
+            Err(WebhookError::InvalidSignature)
+        ));
+        assert!(matches!(
+            parse_webhook::<WebhookPayload>("secret", BODY, None),
+            Err(WebhookError::MissingSignature)
+        ));
+        let signature = sign("secret", b"{}");
+        assert!(matches!(
+            parse_webhook::<WebhookPayload>("secret", b"{}", Some(&signature)),
+            Err(WebhookError::Json(_))
+        ));
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn test_extractor() {
+        use axum::extract::FromRequest;
+        use axum::response::IntoResponse;
+        use std::future::Future;
+        use std::task::{Context, Poll, Waker};
+
+        // The body is already in memory, so the future never has to wait
+        fn ready<F: Future>(future: F) -> F::Output {
+            let mut future = std::pin::pin!(future);
+            match future
+                .as_mut()
+                .poll(&mut Context::from_waker(Waker::noop()))
+            {
+                Poll::Ready(output) => output,
+                Poll::Pending => panic!("extractor did not complete"),
+            }
+        }
+        let request = |signature: &str| {
+            axum::http::Request::builder()
+                .method("POST")
+                .header(SIGNATURE_HEADER, signature)
+                .body(axum::body::Body::from(BODY))
+                .unwrap()
+        };
+        let secret = WebhookSecret("secret".to_string());
+
+        let RwgpsWebhook(payload) = ready(RwgpsWebhook::<WebhookPayload>::from_request(
+            request(&sign("secret", BODY)),
+            &secret,
+        ))
+        .unwrap();
+        assert_eq!(payload.notifications[0].item_id, 42);
+
+        let rejection = ready(RwgpsWebhook::<WebhookPayload>::from_request(
+            request("00"),
+            &secret,
+        ))
+        .unwrap_err();
+        assert_eq!(
+            rejection.into_response().status(),
+            axum::http::StatusCode::UNAUTHORIZED
+        );
+    }
+}